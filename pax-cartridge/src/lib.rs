@@ -38,4 +38,8 @@ impl DefinitionToInstanceTraverser {
     pub fn get_manifest(&self) -> &pax_manifest::PaxManifest {
         unreachable!("{}", PLACEHOLDER_ERROR)
     }
+
+    pub fn get_manifest_mut(&mut self) -> &mut pax_manifest::PaxManifest {
+        unreachable!("{}", PLACEHOLDER_ERROR)
+    }
 }
@@ -3,6 +3,8 @@
 extern crate core;
 
 use std::ffi::c_void;
+use std::sync::OnceLock;
+use std::time::Instant;
 
 use std::mem::{transmute, ManuallyDrop};
 
@@ -41,9 +43,8 @@ pub extern "C" fn pax_init() -> *mut PaxEngineContainer {
     let mut definition_to_instance_traverser =
         pax_cartridge::DefinitionToInstanceTraverser::new(manifest);
     let main_component_instance = definition_to_instance_traverser.get_main_component();
-    let expression_table = ExpressionTable {
-        table: pax_cartridge::instantiate_expression_table(),
-    };
+    let expression_table =
+        ExpressionTable::from_table(pax_cartridge::instantiate_expression_table());
 
     //Initialize a ManuallyDrop-contained PaxEngine, so that a pointer to that
     //engine can be passed back to Swift via the C (FFI) bridge
@@ -126,6 +127,12 @@ pub extern "C" fn pax_interrupt(
                 _ => {}
             };
         }
+        // routed by the engine, which tracks gestures
+        NativeInterrupt::TouchStart(_)
+        | NativeInterrupt::TouchMove(_)
+        | NativeInterrupt::TouchEnd(_) => {
+            engine.handle_native_interrupt(&interrupt);
+        }
         NativeInterrupt::Scroll(_args) => {}
         NativeInterrupt::Image(args) => match args {
             ImageLoadInterruptArgs::Reference(_ref_args) => {
@@ -165,7 +172,7 @@ pub extern "C" fn pax_tick(
     (*engine).set_viewport_size((width as f64, height as f64));
     render_context.add_context("0", CoreGraphicsContext::new_y_up(ctx, height as f64, None));
 
-    let messages = (*engine).tick();
+    let messages = (*engine).tick_at(now_ms());
     engine.render(&mut render_context as &mut dyn RenderContext);
 
     let wrapped_queue = MessageQueue { messages };
@@ -192,6 +199,14 @@ pub extern "C" fn pax_tick(
     queue_container
 }
 
+/// Milliseconds on a monotonic clock, which unlike the system time doesn't jump
+/// when the system clock is adjusted. The engine only uses differences between
+/// ticks, so the origin is arbitrary.
+fn now_ms() -> f64 {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    ORIGIN.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
 /// Required manual cleanup callback from Swift after reading a frame's message queue.
 /// If this is not called after `pax_tick` is invoked, we will have a memory leak.
 #[no_mangle] //Exposed to Swift via PaxCartridge.h
@@ -0,0 +1,295 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// Environment variable that, when set, causes [`Frame::compare_to_golden`] to overwrite
+/// golden images with the current frame instead of comparing against them.
+pub const UPDATE_GOLDENS_ENV_VAR: &str = "PAX_UPDATE_GOLDENS";
+
+/// A rendered frame as a tightly packed buffer of non-premultiplied RGBA8 pixels, row-major.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+/// The result of comparing two frames pixel-by-pixel.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameDiff {
+    /// Whether both frames have the same width and height. If not, all other fields are zero.
+    pub dimensions_match: bool,
+    /// Number of pixels where at least one channel differs by more than the allowed tolerance
+    pub mismatched_pixels: usize,
+    /// Largest absolute difference seen in any single channel
+    pub max_channel_delta: u8,
+}
+
+impl FrameDiff {
+    pub fn is_match(&self) -> bool {
+        self.dimensions_match && self.mismatched_pixels == 0
+    }
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Result<Self, String> {
+        if pixels.len() != width * height * 4 {
+            return Err(format!(
+                "expected {} bytes for a {}x{} RGBA frame, got {}",
+                width * height * 4,
+                width,
+                height,
+                pixels.len()
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the RGBA value of the pixel at (x, y), or None if out of bounds
+    pub fn pixel(&self, x: usize, y: usize) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y * self.width + x) * 4;
+        Some([
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ])
+    }
+
+    /// Encodes this frame as an 8-bit RGBA PNG at `path`
+    pub fn write_png(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+        }
+        let file = File::create(path)
+            .map_err(|e| format!("failed to create {}: {}", path.display(), e))?;
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|e| format!("failed to encode {}: {}", path.display(), e))
+    }
+
+    /// Decodes a PNG at `path` into a frame, expanding grayscale, palette and RGB images to RGBA8
+    pub fn read_png(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| format!("failed to decode {}: {}", path.display(), e))?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buf)
+            .map_err(|e| format!("failed to decode {}: {}", path.display(), e))?;
+        buf.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buf,
+            png::ColorType::Rgb => buf
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buf
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(format!(
+                    "failed to decode {}: unexpanded indexed color",
+                    path.display()
+                ))
+            }
+        };
+        Self::new(info.width as usize, info.height as usize, pixels)
+    }
+
+    /// Compares this frame against `other`. Channel differences up to and including
+    /// `tolerance` are not counted as mismatches, which absorbs antialiasing noise
+    /// between rasterizer versions.
+    pub fn diff(&self, other: &Frame, tolerance: u8) -> FrameDiff {
+        if self.width != other.width || self.height != other.height {
+            return FrameDiff::default();
+        }
+        let mut diff = FrameDiff {
+            dimensions_match: true,
+            ..Default::default()
+        };
+        for (a, b) in self
+            .pixels
+            .chunks_exact(4)
+            .zip(other.pixels.chunks_exact(4))
+        {
+            let delta = a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| a.abs_diff(*b))
+                .max()
+                .unwrap_or(0);
+            diff.max_channel_delta = diff.max_channel_delta.max(delta);
+            if delta > tolerance {
+                diff.mismatched_pixels += 1;
+            }
+        }
+        diff
+    }
+
+    /// Compares this frame against the golden image at `golden_path`.
+    ///
+    /// If [`UPDATE_GOLDENS_ENV_VAR`] is set, the golden is (re)written from this frame instead.
+    /// A missing golden is an error otherwise, so that a golden that was never committed can't
+    /// make a test pass. On mismatch, this frame is written next to the golden with an
+    /// `.actual.png` extension so that it can be inspected or promoted, and an error
+    /// describing the difference is returned.
+    pub fn compare_to_golden(
+        &self,
+        golden_path: impl AsRef<Path>,
+        tolerance: u8,
+    ) -> Result<FrameDiff, String> {
+        let update = std::env::var_os(UPDATE_GOLDENS_ENV_VAR).is_some();
+        self.compare_to_golden_or_update(golden_path, tolerance, update)
+    }
+
+    /// Like [`Frame::compare_to_golden`], with `update` in place of reading
+    /// [`UPDATE_GOLDENS_ENV_VAR`]
+    pub fn compare_to_golden_or_update(
+        &self,
+        golden_path: impl AsRef<Path>,
+        tolerance: u8,
+        update: bool,
+    ) -> Result<FrameDiff, String> {
+        let golden_path = golden_path.as_ref();
+        if update {
+            self.write_png(golden_path)?;
+            return Ok(self.diff(self, tolerance));
+        }
+        if !golden_path.exists() {
+            let actual_path = actual_path_for(golden_path);
+            self.write_png(&actual_path)?;
+            return Err(format!(
+                "golden {} doesn't exist (actual written to {}, set {} to create it)",
+                golden_path.display(),
+                actual_path.display(),
+                UPDATE_GOLDENS_ENV_VAR
+            ));
+        }
+
+        let golden = Self::read_png(golden_path)?;
+        let diff = self.diff(&golden, tolerance);
+        if diff.is_match() {
+            return Ok(diff);
+        }
+
+        let actual_path = actual_path_for(golden_path);
+        self.write_png(&actual_path)?;
+        if !diff.dimensions_match {
+            return Err(format!(
+                "frame is {}x{} but golden {} is {}x{} (actual written to {})",
+                self.width,
+                self.height,
+                golden_path.display(),
+                golden.width,
+                golden.height,
+                actual_path.display()
+            ));
+        }
+        Err(format!(
+            "{} pixels differ from golden {} (max channel delta {}, tolerance {}; actual written to {})",
+            diff.mismatched_pixels,
+            golden_path.display(),
+            diff.max_channel_delta,
+            tolerance,
+            actual_path.display()
+        ))
+    }
+}
+
+fn actual_path_for(golden_path: &Path) -> PathBuf {
+    let stem = golden_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    golden_path.with_file_name(format!("{}.actual.png", stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: usize, height: usize, rgba: [u8; 4]) -> Frame {
+        Frame::new(width, height, rgba.repeat(width * height)).unwrap()
+    }
+
+    #[test]
+    fn diff_respects_tolerance() {
+        let a = solid(4, 4, [10, 20, 30, 255]);
+        let b = solid(4, 4, [12, 20, 30, 255]);
+        assert!(a.diff(&b, 2).is_match());
+        let diff = a.diff(&b, 1);
+        assert_eq!(diff.mismatched_pixels, 16);
+        assert_eq!(diff.max_channel_delta, 2);
+    }
+
+    #[test]
+    fn diff_reports_dimension_mismatch() {
+        let diff = solid(4, 4, [0; 4]).diff(&solid(4, 5, [0; 4]), 0);
+        assert!(!diff.dimensions_match);
+        assert!(!diff.is_match());
+    }
+
+    #[test]
+    fn missing_golden_fails_unless_updating_goldens() {
+        let dir = std::env::temp_dir().join("pax-chassis-headless-missing-golden");
+        let _ = std::fs::remove_dir_all(&dir);
+        let golden_path = dir.join("frame.png");
+        let frame = solid(2, 2, [0, 0, 255, 255]);
+
+        let compare = |update| frame.compare_to_golden_or_update(&golden_path, 0, update);
+        assert!(compare(false).is_err());
+        assert!(!golden_path.exists());
+        assert_eq!(
+            Frame::read_png(dir.join("frame.actual.png")).unwrap(),
+            frame
+        );
+
+        assert!(compare(true).unwrap().is_match());
+        assert!(compare(false).unwrap().is_match());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let path = std::env::temp_dir().join("pax-chassis-headless-round-trip.png");
+        let frame = Frame::new(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        frame.write_png(&path).unwrap();
+        assert_eq!(Frame::read_png(&path).unwrap(), frame);
+        let _ = std::fs::remove_file(&path);
+    }
+}
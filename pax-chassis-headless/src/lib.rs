@@ -0,0 +1,215 @@
+//! Headless chassis: runs a Pax cartridge without a window or browser, rasterizing frames on
+//! the CPU through `piet-common`'s bitmap target. Native elements (text, form controls, frames)
+//! are not drawn; instead the emitted `NativeMessage`s are replayed into an in-memory
+//! [`NativeElements`] model that can be inspected. Intended for CI rendering and golden-image tests.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use pax_message::{NativeInterrupt, NativeMessage};
+use pax_runtime::api::{borrow, borrow_mut, use_RefCell, Platform, RenderContext, OS};
+use pax_runtime::{ComponentInstance, ExpressionTable, PaxEngine, Renderer};
+use piet::ImageFormat;
+use piet_common::Device;
+use_RefCell!();
+
+mod frame;
+mod native;
+
+pub use frame::{Frame, FrameDiff, UPDATE_GOLDENS_ENV_VAR};
+pub use native::{NativeElement, NativeElementKind, NativeElements};
+
+struct ImageData {
+    rgba: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+pub struct PaxChassisHeadless {
+    engine: Rc<RefCell<PaxEngine>>,
+    viewport_size: (f64, f64),
+    pix_scale: f64,
+    /// One device per occlusion layer; layers are rasterized separately and composited in order
+    devices: Vec<Device>,
+    images: HashMap<String, ImageData>,
+    assets_root: PathBuf,
+    native_elements: NativeElements,
+    native_message_log: Vec<NativeMessage>,
+}
+
+impl PaxChassisHeadless {
+    /// Instantiates the attached cartridge's main component with a viewport of the given size
+    pub fn new(width: f64, height: f64) -> Self {
+        let manifest = serde_json::from_str(pax_cartridge::INITIAL_MANIFEST).unwrap();
+        let mut definition_to_instance_traverser =
+            pax_cartridge::DefinitionToInstanceTraverser::new(manifest);
+        let main_component_instance = definition_to_instance_traverser.get_main_component();
+        let expression_table =
+            ExpressionTable::from_table(pax_cartridge::instantiate_expression_table());
+        Self::new_with_main_component(main_component_instance, expression_table, (width, height))
+    }
+
+    /// Runs an already-instantiated main component, e.g. one built by hand in a test
+    pub fn new_with_main_component(
+        main_component_instance: Rc<ComponentInstance>,
+        expression_table: ExpressionTable,
+        viewport_size: (f64, f64),
+    ) -> Self {
+        let engine = PaxEngine::new(
+            main_component_instance,
+            expression_table,
+            viewport_size,
+            Platform::Unknown,
+            OS::default(),
+        );
+        Self {
+            engine: Rc::new(RefCell::new(engine)),
+            viewport_size,
+            pix_scale: 1.0,
+            devices: Vec::new(),
+            images: HashMap::new(),
+            assets_root: PathBuf::from("."),
+            native_elements: NativeElements::default(),
+            native_message_log: Vec::new(),
+        }
+    }
+
+    pub fn engine(&self) -> &Rc<RefCell<PaxEngine>> {
+        &self.engine
+    }
+
+    /// Directory that image paths requested by the engine are resolved against
+    pub fn set_assets_root(&mut self, assets_root: impl Into<PathBuf>) {
+        self.assets_root = assets_root.into();
+    }
+
+    /// Device pixel ratio used when rasterizing; frames are `viewport * pix_scale` pixels large
+    pub fn set_pix_scale(&mut self, pix_scale: f64) {
+        self.pix_scale = pix_scale;
+    }
+
+    pub fn send_viewport_update(&mut self, width: f64, height: f64) {
+        self.viewport_size = (width, height);
+        borrow_mut!(self.engine).set_viewport_size((width, height));
+    }
+
+    pub fn interrupt(&mut self, native_interrupt: NativeInterrupt) -> bool {
+        borrow!(self.engine).handle_native_interrupt(&native_interrupt)
+    }
+
+    /// Advances the engine by one tick, applying the emitted native messages to the native
+    /// element model and loading any requested images from the assets root.
+    pub fn tick(&mut self) {
+        let messages = borrow_mut!(self.engine).tick();
+        for message in &messages {
+            self.native_elements.apply(message);
+        }
+        self.native_message_log.extend(messages);
+        for path in self.native_elements.take_pending_image_loads() {
+            if let Err(e) = self.load_image_from_assets(&path) {
+                log::warn!("{}", e);
+            }
+        }
+    }
+
+    pub fn tick_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    pub fn native_elements(&self) -> &NativeElements {
+        &self.native_elements
+    }
+
+    /// All native messages emitted since the last call, in order
+    pub fn take_native_messages(&mut self) -> Vec<NativeMessage> {
+        std::mem::take(&mut self.native_message_log)
+    }
+
+    /// Registers non-premultiplied RGBA image data under `path`, as if the platform had
+    /// finished loading it
+    pub fn load_image(&mut self, path: &str, rgba: Vec<u8>, width: usize, height: usize) {
+        self.images.insert(
+            path.to_owned(),
+            ImageData {
+                rgba,
+                width,
+                height,
+            },
+        );
+    }
+
+    fn load_image_from_assets(&mut self, path: &str) -> Result<(), String> {
+        if self.images.contains_key(path) {
+            return Ok(());
+        }
+        let image = Frame::read_png(self.assets_root.join(path))?;
+        let (width, height) = (image.width(), image.height());
+        self.load_image(path, image.pixels().to_vec(), width, height);
+        Ok(())
+    }
+
+    /// Rasterizes the current state of the engine into a frame. Each occlusion layer is
+    /// drawn into its own bitmap, then layers are composited bottom to top.
+    pub fn render(&mut self) -> Result<Frame, String> {
+        let width = (self.viewport_size.0 * self.pix_scale).ceil().max(1.0) as usize;
+        let height = (self.viewport_size.1 * self.pix_scale).ceil().max(1.0) as usize;
+
+        let layer_count = self.layer_count();
+        while self.devices.len() < layer_count {
+            self.devices
+                .push(Device::new().map_err(|e| format!("failed to create device: {}", e))?);
+        }
+
+        let mut targets = self.devices[..layer_count]
+            .iter_mut()
+            .map(|device| device.bitmap_target(width, height, self.pix_scale))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("failed to create bitmap target: {}", e))?;
+
+        {
+            let mut renderer = Renderer::new();
+            for (i, target) in targets.iter_mut().enumerate() {
+                renderer.add_context(&i.to_string(), target.render_context());
+            }
+            for (path, image) in &self.images {
+                renderer.load_image(path, &image.rgba, image.width, image.height);
+            }
+            borrow_mut!(self.engine).render(&mut renderer as &mut dyn RenderContext);
+            renderer
+                .finish()
+                .map_err(|e| format!("failed to finish rendering: {}", e))?;
+        }
+
+        let mut composite = vec![0u8; width * height * 4];
+        for target in targets.iter_mut() {
+            let layer = target
+                .to_image_buf(ImageFormat::RgbaPremul)
+                .map_err(|e| format!("failed to read back layer: {}", e))?;
+            composite_over(&mut composite, layer.raw_pixels());
+        }
+        piet::util::unpremultiply_rgba(&mut composite);
+        Frame::new(width, height, composite)
+    }
+
+    fn layer_count(&self) -> usize {
+        let engine = borrow!(self.engine);
+        let mut max_layer = 0;
+        engine.root_node.recurse_visit_postorder(&mut |node| {
+            max_layer = max_layer.max(*borrow!(node.occlusion_id));
+        });
+        max_layer as usize + 1
+    }
+}
+
+/// Source-over compositing of premultiplied RGBA8 `src` onto `dst`
+fn composite_over(dst: &mut [u8], src: &[u8]) {
+    for (d, s) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+        let inv_alpha = 255 - s[3] as u16;
+        for c in 0..4 {
+            d[c] = (s[c] as u16 + (d[c] as u16 * inv_alpha + 127) / 255).min(255) as u8;
+        }
+    }
+}
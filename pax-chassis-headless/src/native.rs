@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use pax_message::{AnyCreatePatch, NativeMessage};
+use serde_json::{Map, Value};
+
+/// The kind of native element a chassis would otherwise create a platform view for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NativeElementKind {
+    Text,
+    Frame,
+    Checkbox,
+    Textbox,
+    Slider,
+    Dropdown,
+    RadioSet,
+    Button,
+    Scroller,
+}
+
+/// In-memory stand-in for a native element (text, form control, clipping frame, ...).
+/// Properties hold the accumulated state of every `*Update` patch received for this
+/// element, keyed by patch field name, e.g. `"content"` for text or `"value"` for sliders.
+#[derive(Clone, Debug)]
+pub struct NativeElement {
+    pub id: u32,
+    pub kind: NativeElementKind,
+    pub parent_frame: Option<u32>,
+    pub occlusion_layer_id: u32,
+    pub properties: Map<String, Value>,
+}
+
+impl NativeElement {
+    fn new(kind: NativeElementKind, id: u32) -> Self {
+        Self {
+            id,
+            kind,
+            parent_frame: None,
+            occlusion_layer_id: 0,
+            properties: Map::new(),
+        }
+    }
+
+    pub fn get(&self, property: &str) -> Option<&Value> {
+        self.properties.get(property)
+    }
+
+    pub fn get_str(&self, property: &str) -> Option<&str> {
+        self.get(property).and_then(Value::as_str)
+    }
+
+    pub fn get_f64(&self, property: &str) -> Option<f64> {
+        self.get(property).and_then(Value::as_f64)
+    }
+
+    pub fn get_bool(&self, property: &str) -> Option<bool> {
+        self.get(property).and_then(Value::as_bool)
+    }
+}
+
+/// Model of all native elements that are currently alive, built by replaying the
+/// [`NativeMessage`] stream emitted by the engine.
+#[derive(Default, Debug)]
+pub struct NativeElements {
+    elements: BTreeMap<u32, NativeElement>,
+    pending_image_loads: Vec<String>,
+}
+
+impl NativeElements {
+    pub fn get(&self, id: u32) -> Option<&NativeElement> {
+        self.elements.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NativeElement> {
+        self.elements.values()
+    }
+
+    pub fn of_kind(&self, kind: NativeElementKind) -> impl Iterator<Item = &NativeElement> {
+        self.elements.values().filter(move |e| e.kind == kind)
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Image paths requested by the engine since the last call
+    pub fn take_pending_image_loads(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_image_loads)
+    }
+
+    pub fn apply(&mut self, message: &NativeMessage) {
+        use NativeElementKind as K;
+        match message {
+            NativeMessage::TextCreate(p) => self.create(K::Text, p),
+            NativeMessage::TextUpdate(p) => self.update(K::Text, p.id, p),
+            NativeMessage::TextDelete(id) => self.delete(*id),
+            NativeMessage::FrameCreate(p) => self.create(K::Frame, p),
+            NativeMessage::FrameUpdate(p) => self.update(K::Frame, p.id, p),
+            NativeMessage::FrameDelete(id) => self.delete(*id),
+            NativeMessage::CheckboxCreate(p) => self.create(K::Checkbox, p),
+            NativeMessage::CheckboxUpdate(p) => self.update(K::Checkbox, p.id, p),
+            NativeMessage::CheckboxDelete(id) => self.delete(*id),
+            NativeMessage::TextboxCreate(p) => self.create(K::Textbox, p),
+            NativeMessage::TextboxUpdate(p) => self.update(K::Textbox, p.id, p),
+            NativeMessage::TextboxDelete(id) => self.delete(*id),
+            NativeMessage::SliderCreate(p) => self.create(K::Slider, p),
+            NativeMessage::SliderUpdate(p) => self.update(K::Slider, p.id, p),
+            NativeMessage::SliderDelete(id) => self.delete(*id),
+            NativeMessage::DropdownCreate(p) => self.create(K::Dropdown, p),
+            NativeMessage::DropdownUpdate(p) => self.update(K::Dropdown, p.id, p),
+            NativeMessage::DropdownDelete(id) => self.delete(*id),
+            NativeMessage::RadioSetCreate(p) => self.create(K::RadioSet, p),
+            NativeMessage::RadioSetUpdate(p) => self.update(K::RadioSet, p.id, p),
+            NativeMessage::RadioSetDelete(id) => self.delete(*id),
+            NativeMessage::ButtonCreate(p) => self.create(K::Button, p),
+            NativeMessage::ButtonUpdate(p) => self.update(K::Button, p.id, p),
+            NativeMessage::ButtonDelete(id) => self.delete(*id),
+            NativeMessage::ScrollerCreate(p) => self.create(K::Scroller, p),
+            NativeMessage::ScrollerUpdate(p) => self.update(K::Scroller, p.id, p),
+            NativeMessage::ScrollerDelete(id) => self.delete(*id),
+            NativeMessage::ImageLoad(p) => {
+                if let Some(path) = &p.path {
+                    self.pending_image_loads.push(path.clone());
+                }
+            }
+            NativeMessage::OcclusionUpdate(p) => {
+                if let Some(element) = self.elements.get_mut(&p.id) {
+                    element.occlusion_layer_id = p.occlusion_layer_id;
+                }
+            }
+            NativeMessage::LayerAdd(_) => {}
+        }
+    }
+
+    fn create(&mut self, kind: NativeElementKind, patch: &AnyCreatePatch) {
+        let mut element = NativeElement::new(kind, patch.id);
+        element.parent_frame = patch.parent_frame;
+        element.occlusion_layer_id = patch.occlusion_layer_id;
+        self.elements.insert(patch.id, element);
+    }
+
+    /// Merges the fields set on `patch` into the element's properties; unset (`None`)
+    /// fields leave the previous value untouched, mirroring how chassis apply patches.
+    fn update(&mut self, kind: NativeElementKind, id: u32, patch: &impl serde::Serialize) {
+        let element = self
+            .elements
+            .entry(id)
+            .or_insert_with(|| NativeElement::new(kind, id));
+        let Ok(Value::Object(fields)) = serde_json::to_value(patch) else {
+            log::warn!("failed to serialize patch for native element {}", id);
+            return;
+        };
+        for (key, value) in fields {
+            if key != "id" && !value.is_null() {
+                element.properties.insert(key, value);
+            }
+        }
+    }
+
+    fn delete(&mut self, id: u32) {
+        self.elements.remove(&id);
+    }
+}
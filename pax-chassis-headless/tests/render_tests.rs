@@ -0,0 +1,90 @@
+use std::rc::Rc;
+
+use pax_chassis_headless::PaxChassisHeadless;
+use pax_runtime::api::{borrow, Layer, RenderContext};
+use pax_runtime::testing::TestNode;
+use pax_runtime::{
+    BaseInstance, ExpandedNode, ExpressionTable, InstanceFlags, InstanceNode, InstantiationArgs,
+    RuntimeContext,
+};
+use piet::kurbo::{Affine, Rect, Shape};
+use piet::Color;
+
+/// Fills its bounds with red
+struct Swatch {
+    base: BaseInstance,
+}
+
+impl InstanceNode for Swatch {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Canvas,
+                    is_component: false,
+                },
+            ),
+        })
+    }
+
+    fn render(
+        &self,
+        expanded_node: &ExpandedNode,
+        _context: &Rc<RuntimeContext>,
+        rc: &mut dyn RenderContext,
+    ) {
+        let t_and_b = expanded_node.transform_and_bounds.get();
+        let (width, height) = t_and_b.bounds;
+        let path = Into::<Affine>::into(t_and_b.transform)
+            * Rect::new(0.0, 0.0, width, height).to_path(0.1);
+        let layer_id = format!("{}", borrow!(expanded_node.occlusion_id));
+        rc.fill(&layer_id, path, &Color::rgb8(255, 0, 0).into());
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("Swatch").finish()
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+}
+
+/// A 10x10 swatch at (5, 5) in a 20x20 viewport
+fn build_chassis() -> PaxChassisHeadless {
+    let swatch = TestNode::new()
+        .position(5.0, 5.0)
+        .size(10.0, 10.0)
+        .instantiate::<Swatch>();
+    let main = TestNode::new().component(vec![swatch as Rc<dyn InstanceNode>]);
+    PaxChassisHeadless::new_with_main_component(main, ExpressionTable::new(), (20.0, 20.0))
+}
+
+#[test]
+fn engine_renders_into_frames() {
+    let mut chassis = build_chassis();
+    chassis.tick();
+    let frame = chassis.render().unwrap();
+    assert_eq!((frame.width(), frame.height()), (20, 20));
+    assert_eq!(frame.pixel(10, 10), Some([255, 0, 0, 255]));
+    assert_eq!(frame.pixel(2, 2).map(|pixel| pixel[3]), Some(0));
+    assert_eq!(frame.pixel(17, 17).map(|pixel| pixel[3]), Some(0));
+}
+
+#[test]
+fn pix_scale_scales_frames() {
+    let mut chassis = build_chassis();
+    chassis.set_pix_scale(2.0);
+    chassis.tick();
+    let frame = chassis.render().unwrap();
+    assert_eq!((frame.width(), frame.height()), (40, 40));
+    assert_eq!(frame.pixel(20, 20), Some([255, 0, 0, 255]));
+    assert_eq!(frame.pixel(8, 8).map(|pixel| pixel[3]), Some(0));
+}
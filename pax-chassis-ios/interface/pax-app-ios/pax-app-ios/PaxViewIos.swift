@@ -40,6 +40,9 @@ struct PaxViewIos: View {
                                       -deltaX,
                                       -deltaY)
                     sendInterrupt(with: json)
+                    sendTouchInterrupt("TouchMove", at: dragGesture.location, delta: CGPoint(x: deltaX, y: deltaY))
+                } else {
+                    sendTouchInterrupt("TouchStart", at: dragGesture.location, delta: .zero)
                 }
                 
                 self.previousScrollLocation = dragGesture.location
@@ -47,6 +50,7 @@ struct PaxViewIos: View {
             .onEnded { dragGesture in
                 //Reset scroll tracking position
                 self.previousScrollLocation = nil
+                sendTouchInterrupt("TouchEnd", at: dragGesture.location, delta: .zero)
                 
                 // Handle "Click" events — note that we should probably check to ensure that a maximum distance has not been crossed
                 // to rightly handle this as a "click".  Currently this is more of a `touchend`.
@@ -56,6 +60,17 @@ struct PaxViewIos: View {
         )
     }
 
+    /// Sends a touch interrupt for the single touch a `DragGesture` tracks
+    func sendTouchInterrupt(_ type: String, at location: CGPoint, delta: CGPoint) {
+        let json = String(format: "{\"%@\": {\"touches\": [{\"x\": %f, \"y\": %f, \"identifier\": 0, \"delta_x\": %f, \"delta_y\": %f}] } }",
+                          type,
+                          location.x,
+                          location.y,
+                          delta.x,
+                          delta.y)
+        sendInterrupt(with: json)
+    }
+
     func sendInterrupt(with json: String) {
         let buffer = try! FlexBufferBuilder.fromJSON(json)
         buffer.data.withUnsafeBytes { ptr in
@@ -78,12 +78,28 @@ async function startRenderLoop(extensionlessUrl: string, mount: Element) {
     try {
         let {chassis, get_latest_memory} = await loadWasmModule(extensionlessUrl);
         nativePool.attach(chassis, mount);
+        connectHotReload(chassis);
         requestAnimationFrame(renderLoop.bind(renderLoop, chassis, mount, get_latest_memory));
     } catch (error) {
         console.error("Failed to load or instantiate Wasm module:", error);
     }
 }
 
+// Served by `pax-cli run --watch`, which pushes template patches when `.pax` files change
+const HOT_RELOAD_PATH = "/__pax/hot-reload";
+
+function connectHotReload(chassis: PaxChassisWeb) {
+    // The dev server only ever binds to loopback; skip the attempt for deployed builds
+    if (!["localhost", "127.0.0.1"].includes(window.location.hostname)) {
+        return;
+    }
+    let protocol = window.location.protocol === "https:" ? "wss:" : "ws:";
+    let socket = new WebSocket(`${protocol}//${window.location.host}${HOT_RELOAD_PATH}`);
+    socket.onmessage = (event: MessageEvent) => {
+        chassis.apply_template_patch(event.data);
+    };
+}
+
 function renderLoop (chassis: PaxChassisWeb, mount: Element, get_latest_memory: ()=>any) {
     nativePool.clearCanvases();
 
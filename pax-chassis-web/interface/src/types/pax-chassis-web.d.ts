@@ -58,6 +58,10 @@ export class PaxChassisWeb {
 /**
 */
   render(): void;
+/**
+* @param {string} patch_json
+*/
+  apply_template_patch(patch_json: string): void;
 }
 
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
@@ -77,6 +81,7 @@ export interface InitOutput {
   readonly paxchassisweb_interrupt: (a: number, b: number, c: number, d: number) => void;
   readonly paxchassisweb_deallocate: (a: number, b: number) => void;
   readonly paxchassisweb_tick: (a: number) => number;
+  readonly paxchassisweb_apply_template_patch: (a: number, b: number, c: number) => void;
   readonly __wbg_memoryslice_free: (a: number) => void;
   readonly memoryslice_ptr: (a: number) => number;
   readonly memoryslice_len: (a: number) => number;
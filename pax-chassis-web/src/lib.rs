@@ -3,6 +3,8 @@
 use js_sys::Uint8Array;
 use log::Level;
 use pax_manifest::PaxManifest;
+#[cfg(not(feature = "designtime"))]
+use pax_manifest::TemplatePatch;
 use pax_message::ImageLoadInterruptArgs;
 use pax_runtime::api::borrow;
 use pax_runtime::api::math::Point2;
@@ -31,9 +33,8 @@ use pax_runtime::{PaxEngine, Renderer};
 
 use pax_message::NativeInterrupt;
 use pax_runtime::api::{
-    Clap, Click, ContextMenu, DoubleClick, Drop, KeyDown, KeyPress, KeyUp, KeyboardEventArgs,
-    ModifierKey, MouseButton, MouseDown, MouseEventArgs, MouseMove, MouseOut, MouseOver, MouseUp,
-    Touch, TouchEnd, TouchMove, TouchStart, Wheel,
+    Clap, Click, ContextMenu, DoubleClick, Drop, ModifierKey, MouseButton, MouseEventArgs,
+    MouseOut, MouseOver, Wheel,
 };
 use serde_json;
 
@@ -57,7 +58,6 @@ pub fn wasm_memory() -> JsValue {
 pub struct PaxChassisWeb {
     drawing_contexts: Renderer<WebRenderContext<'static>>,
     engine: Rc<RefCell<PaxEngine>>,
-    #[cfg(feature = "designtime")]
     definition_to_instance_traverser: pax_cartridge::DefinitionToInstanceTraverser,
     #[cfg(feature = "designtime")]
     designtime_manager: Rc<RefCell<DesigntimeManager>>,
@@ -154,7 +154,35 @@ impl PaxChassisWeb {
         Self {
             engine: engine_container,
             drawing_contexts: Renderer::new(),
+            definition_to_instance_traverser,
+        }
+    }
+
+    /// Applies a serialized `TemplatePatch` pushed by `pax-cli run --watch`, re-instantiating
+    /// the main component's template while keeping its state. Expressions without compiled
+    /// code are interpreted.
+    #[cfg(not(feature = "designtime"))]
+    pub fn apply_template_patch(&mut self, patch_json: String) {
+        let patch: TemplatePatch = match serde_json::from_str(&patch_json) {
+            Ok(patch) => patch,
+            Err(err) => {
+                log::error!("failed to deserialize template patch: {}", err);
+                return;
+            }
+        };
+        let expression_table = borrow!(self.engine).runtime_context.expression_table();
+        for (vtable_id, paxel) in &patch.interpreted_expressions {
+            if let Err(err) = pax_paxel_interp::override_entry(&expression_table, *vtable_id, paxel)
+            {
+                log::error!("failed to apply template patch: {}", err);
+                return;
+            }
         }
+        self.definition_to_instance_traverser
+            .get_manifest_mut()
+            .apply_template_patch(patch);
+        let main_component_instance = self.definition_to_instance_traverser.get_main_component();
+        borrow_mut!(self.engine).reload_main_template(main_component_instance);
     }
 
     fn init_common() -> (f64, f64, OS, ExpressionTable) {
@@ -177,9 +205,8 @@ impl PaxChassisWeb {
         let width = window.inner_width().unwrap().as_f64().unwrap();
         let height = window.inner_height().unwrap().as_f64().unwrap();
 
-        let expression_table = ExpressionTable {
-            table: pax_cartridge::instantiate_expression_table(),
-        };
+        let expression_table =
+            ExpressionTable::from_table(pax_cartridge::instantiate_expression_table());
         (width, height, os_info, expression_table)
     }
 
@@ -225,8 +252,7 @@ impl PaxChassisWeb {
         let x: NativeInterrupt = serde_json::from_str(&native_interrupt).unwrap();
 
         let engine = borrow_mut!(self.engine);
-        let ctx = &engine.runtime_context;
-        let globals = ctx.globals();
+        let engine = borrow!(self.engine);
         let prevent_default = match &x {
             NativeInterrupt::DropFile(args) => {
                 let data = Uint8Array::new(additional_payload).to_vec();
@@ -241,42 +267,15 @@ impl PaxChassisWeb {
                         mime_type: args.mime_type.clone(),
                         data,
                     };
-                    topmost_node.dispatch_drop(args_drop, &globals, &engine.runtime_context)
+                    topmost_node.dispatch_drop(
+                        args_drop,
+                        &engine.runtime_context.globals(),
+                        &engine.runtime_context,
+                    )
                 } else {
                     false
                 }
             }
-            NativeInterrupt::FormRadioSetChange(args) => {
-                let node = engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
-                if let Some(node) = node {
-                    borrow!(node.instance_node).handle_native_interrupt(&node, &x);
-                }
-                false
-            }
-            NativeInterrupt::FormSliderChange(args) => {
-                let node = engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
-                if let Some(node) = node {
-                    borrow!(node.instance_node).handle_native_interrupt(&node, &x);
-                }
-                false
-            }
-            NativeInterrupt::FormDropdownChange(args) => {
-                let node = engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
-                if let Some(node) = node {
-                    borrow!(node.instance_node).handle_native_interrupt(&node, &x);
-                }
-                false
-            }
-            NativeInterrupt::ChassisResizeRequestCollection(collection) => {
-                for args in collection {
-                    let node =
-                        engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
-                    if let Some(node) = node {
-                        node.chassis_resize_request(args.width, args.height);
-                    }
-                }
-                false
-            }
             NativeInterrupt::Image(args) => match args {
                 ImageLoadInterruptArgs::Reference(_ref_args) => false,
                 ImageLoadInterruptArgs::Data(data_args) => {
@@ -290,399 +289,7 @@ impl PaxChassisWeb {
                     false
                 }
             },
-            NativeInterrupt::FormButtonClick(args) => {
-                let node = engine
-                    .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
-                    .expect("button node exists in engine");
-                node.dispatch_button_click(ButtonClick {}, &globals, &engine.runtime_context)
-            }
-            NativeInterrupt::FormTextboxInput(args) => {
-                let node = engine
-                    .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
-                    .expect("textbox node exists in engine");
-                node.dispatch_textbox_input(
-                    TextboxInput {
-                        text: args.text.clone(),
-                    },
-                    &globals,
-                    &engine.runtime_context,
-                )
-            }
-            NativeInterrupt::TextInput(args) => {
-                let node = engine
-                    .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
-                    .expect("text node exists in engine");
-                borrow!(node.instance_node).handle_text_change(&node, args.text.clone());
-                node.dispatch_text_input(
-                    TextInput {
-                        text: args.text.clone(),
-                    },
-                    &globals,
-                    &engine.runtime_context,
-                )
-            }
-            NativeInterrupt::FormTextboxChange(args) => {
-                let node = engine
-                    .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
-                    .expect("textbox node exists in engine");
-                node.dispatch_textbox_change(
-                    TextboxChange {
-                        text: args.text.clone(),
-                    },
-                    &globals,
-                    &engine.runtime_context,
-                )
-            }
-            NativeInterrupt::FormCheckboxToggle(args) => {
-                let node = engine
-                    .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
-                    .expect("checkbox node exists in engine");
-                node.dispatch_checkbox_change(
-                    CheckboxChange {
-                        checked: args.state,
-                    },
-                    &globals,
-                    &engine.runtime_context,
-                )
-            }
-
-            NativeInterrupt::AddedLayer(_args) => false,
-            NativeInterrupt::Click(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_click = Click {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_click(args_click, &globals, &engine.runtime_context)
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::Scroll(_args) => false,
-            NativeInterrupt::Clap(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_clap = Clap {
-                        x: args.x,
-                        y: args.y,
-                    };
-                    topmost_node.dispatch_clap(args_clap, &globals, &engine.runtime_context)
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::TouchStart(args) => {
-                let first_touch = args.touches.get(0).unwrap();
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(first_touch.x, first_touch.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let touches = args.touches.iter().map(|x| Touch::from(x)).collect();
-                    let args_touch_start = TouchStart { touches };
-                    topmost_node.dispatch_touch_start(
-                        args_touch_start,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::TouchMove(args) => {
-                let first_touch = args.touches.get(0).unwrap();
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(first_touch.x, first_touch.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let touches = args.touches.iter().map(|x| Touch::from(x)).collect();
-                    let args_touch_move = TouchMove { touches };
-                    topmost_node.dispatch_touch_move(
-                        args_touch_move,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::TouchEnd(args) => {
-                let first_touch = args.touches.get(0).unwrap();
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(first_touch.x, first_touch.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let touches = args.touches.iter().map(|x| Touch::from(x)).collect();
-                    let args_touch_end = TouchEnd { touches };
-                    topmost_node.dispatch_touch_end(
-                        args_touch_end,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::KeyDown(args) => {
-                let modifiers = args
-                    .modifiers
-                    .iter()
-                    .map(|x| ModifierKey::from(x))
-                    .collect();
-                let args_key_down = KeyDown {
-                    keyboard: KeyboardEventArgs {
-                        key: args.key.clone(),
-                        modifiers,
-                        is_repeat: args.is_repeat,
-                    },
-                };
-                engine.global_dispatch_key_down(args_key_down);
-                false
-            }
-            NativeInterrupt::KeyUp(args) => {
-                let modifiers = args
-                    .modifiers
-                    .iter()
-                    .map(|x| ModifierKey::from(x))
-                    .collect();
-                let args_key_up = KeyUp {
-                    keyboard: KeyboardEventArgs {
-                        key: args.key.clone(),
-                        modifiers,
-                        is_repeat: args.is_repeat,
-                    },
-                };
-                engine.global_dispatch_key_up(args_key_up);
-                false
-            }
-            NativeInterrupt::KeyPress(args) => {
-                let modifiers = args
-                    .modifiers
-                    .iter()
-                    .map(|x| ModifierKey::from(x))
-                    .collect();
-                let args_key_press = KeyPress {
-                    keyboard: KeyboardEventArgs {
-                        key: args.key.clone(),
-                        modifiers,
-                        is_repeat: args.is_repeat,
-                    },
-                };
-                engine.global_dispatch_key_press(args_key_press);
-                false
-            }
-            NativeInterrupt::DoubleClick(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_double_click = DoubleClick {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_double_click(
-                        args_double_click,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::MouseMove(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_mouse_move = MouseMove {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_mouse_move(
-                        args_mouse_move,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::Wheel(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let modifiers = args
-                        .modifiers
-                        .iter()
-                        .map(|x| ModifierKey::from(x))
-                        .collect();
-                    let args_wheel = Wheel {
-                        x: args.x,
-                        y: args.y,
-                        delta_x: args.delta_x,
-                        delta_y: args.delta_y,
-                        modifiers,
-                    };
-                    topmost_node.dispatch_wheel(args_wheel, &globals, &engine.runtime_context)
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::MouseDown(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_mouse_down = MouseDown {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_mouse_down(
-                        args_mouse_down,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::MouseUp(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_mouse_up = MouseUp {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_mouse_up(args_mouse_up, &globals, &engine.runtime_context)
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::MouseOver(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_mouse_over = MouseOver {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_mouse_over(
-                        args_mouse_over,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::MouseOut(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_mouse_out = MouseOut {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_mouse_out(
-                        args_mouse_out,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
-            NativeInterrupt::ContextMenu(args) => {
-                let prospective_hit = engine
-                    .runtime_context
-                    .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
-                if let Some(topmost_node) = prospective_hit {
-                    let args_context_menu = ContextMenu {
-                        mouse: MouseEventArgs {
-                            x: args.x,
-                            y: args.y,
-                            button: MouseButton::from(args.button.clone()),
-                            modifiers: args
-                                .modifiers
-                                .iter()
-                                .map(|x| ModifierKey::from(x))
-                                .collect(),
-                        },
-                    };
-                    topmost_node.dispatch_context_menu(
-                        args_context_menu,
-                        &globals,
-                        &engine.runtime_context,
-                    )
-                } else {
-                    false
-                }
-            }
+            _ => dispatch_interrupt(&engine, &x),
         };
 
         InterruptResult { prevent_default }
@@ -754,7 +361,7 @@ impl PaxChassisWeb {
         #[cfg(feature = "designtime")]
         self.designtime_tick();
 
-        let message_queue = borrow_mut!(self.engine).tick();
+        let message_queue = borrow_mut!(self.engine).tick_at(now_ms());
 
         // Serialize data to a JSON string
         let json_string = serde_json::to_string(&message_queue).unwrap();
@@ -786,6 +393,279 @@ impl PaxChassisWeb {
     }
 }
 
+/// Milliseconds on the page's monotonic clock, which unlike `Date::now()` doesn't
+/// jump when the system clock is adjusted
+fn now_ms() -> f64 {
+    window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+/// Dispatches the interrupts that don't need the payload or the drawing contexts
+/// of the chassis to the nodes they target
+fn dispatch_interrupt(engine: &PaxEngine, interrupt: &NativeInterrupt) -> bool {
+    let globals = engine.runtime_context.globals();
+    match interrupt {
+        NativeInterrupt::FormRadioSetChange(args) => {
+            let node = engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
+            if let Some(node) = node {
+                borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+            }
+            false
+        }
+        NativeInterrupt::FormSliderChange(args) => {
+            let node = engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
+            if let Some(node) = node {
+                borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+            }
+            false
+        }
+        NativeInterrupt::FormDropdownChange(args) => {
+            let node = engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
+            if let Some(node) = node {
+                borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+            }
+            false
+        }
+        NativeInterrupt::ChassisResizeRequestCollection(collection) => {
+            for args in collection {
+                let node = engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id));
+                if let Some(node) = node {
+                    node.chassis_resize_request(args.width, args.height);
+                }
+            }
+            false
+        }
+        NativeInterrupt::FormButtonClick(args) => {
+            let node = engine
+                .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
+                .expect("button node exists in engine");
+            node.dispatch_button_click(ButtonClick {}, &globals, &engine.runtime_context)
+        }
+        NativeInterrupt::FormTextboxInput(args) => {
+            let node = engine
+                .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
+                .expect("textbox node exists in engine");
+            borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+            node.dispatch_textbox_input(
+                TextboxInput {
+                    text: args.text.clone(),
+                },
+                &globals,
+                &engine.runtime_context,
+            )
+        }
+        NativeInterrupt::TextInput(args) => {
+            let node = engine
+                .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
+                .expect("text node exists in engine");
+            borrow!(node.instance_node).handle_text_change(&node, args.text.clone());
+            node.dispatch_text_input(
+                TextInput {
+                    text: args.text.clone(),
+                },
+                &globals,
+                &engine.runtime_context,
+            )
+        }
+        NativeInterrupt::FormTextboxChange(args) => {
+            let node = engine
+                .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
+                .expect("textbox node exists in engine");
+            node.dispatch_textbox_change(
+                TextboxChange {
+                    text: args.text.clone(),
+                },
+                &globals,
+                &engine.runtime_context,
+            )
+        }
+        NativeInterrupt::FormCheckboxToggle(args) => {
+            let node = engine
+                .get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
+                .expect("checkbox node exists in engine");
+            borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+            node.dispatch_checkbox_change(
+                CheckboxChange {
+                    checked: args.state,
+                },
+                &globals,
+                &engine.runtime_context,
+            )
+        }
+
+        NativeInterrupt::AddedLayer(_args) => false,
+        NativeInterrupt::Click(args) => {
+            let prospective_hit = engine
+                .runtime_context
+                .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+            if let Some(topmost_node) = prospective_hit {
+                let args_click = Click {
+                    mouse: MouseEventArgs {
+                        x: args.x,
+                        y: args.y,
+                        button: MouseButton::from(args.button.clone()),
+                        modifiers: args
+                            .modifiers
+                            .iter()
+                            .map(|x| ModifierKey::from(x))
+                            .collect(),
+                    },
+                };
+                topmost_node.dispatch_click(args_click, &globals, &engine.runtime_context)
+            } else {
+                false
+            }
+        }
+        NativeInterrupt::Scroll(_args) => false,
+        NativeInterrupt::Clap(args) => {
+            let prospective_hit = engine
+                .runtime_context
+                .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+            if let Some(topmost_node) = prospective_hit {
+                let args_clap = Clap {
+                    x: args.x,
+                    y: args.y,
+                };
+                topmost_node.dispatch_clap(args_clap, &globals, &engine.runtime_context)
+            } else {
+                false
+            }
+        }
+        NativeInterrupt::DoubleClick(args) => {
+            let prospective_hit = engine
+                .runtime_context
+                .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+            if let Some(topmost_node) = prospective_hit {
+                let args_double_click = DoubleClick {
+                    mouse: MouseEventArgs {
+                        x: args.x,
+                        y: args.y,
+                        button: MouseButton::from(args.button.clone()),
+                        modifiers: args
+                            .modifiers
+                            .iter()
+                            .map(|x| ModifierKey::from(x))
+                            .collect(),
+                    },
+                };
+                topmost_node.dispatch_double_click(
+                    args_double_click,
+                    &globals,
+                    &engine.runtime_context,
+                )
+            } else {
+                false
+            }
+        }
+        // routed by the engine, which tracks pointer capture, drag and drop, gestures
+        // and keyboard focus
+        NativeInterrupt::MouseMove(_)
+        | NativeInterrupt::MouseDown(_)
+        | NativeInterrupt::MouseUp(_)
+        | NativeInterrupt::TouchStart(_)
+        | NativeInterrupt::TouchMove(_)
+        | NativeInterrupt::TouchEnd(_)
+        | NativeInterrupt::KeyDown(_)
+        | NativeInterrupt::KeyUp(_)
+        | NativeInterrupt::KeyPress(_) => engine.handle_native_interrupt(interrupt),
+        NativeInterrupt::Wheel(args) => {
+            let prospective_hit = engine
+                .runtime_context
+                .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+            if let Some(topmost_node) = prospective_hit {
+                let modifiers = args
+                    .modifiers
+                    .iter()
+                    .map(|x| ModifierKey::from(x))
+                    .collect();
+                let args_wheel = Wheel {
+                    x: args.x,
+                    y: args.y,
+                    delta_x: args.delta_x,
+                    delta_y: args.delta_y,
+                    modifiers,
+                };
+                topmost_node.dispatch_wheel(args_wheel, &globals, &engine.runtime_context)
+            } else {
+                false
+            }
+        }
+        NativeInterrupt::MouseOver(args) => {
+            let prospective_hit = engine
+                .runtime_context
+                .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+            if let Some(topmost_node) = prospective_hit {
+                let args_mouse_over = MouseOver {
+                    mouse: MouseEventArgs {
+                        x: args.x,
+                        y: args.y,
+                        button: MouseButton::from(args.button.clone()),
+                        modifiers: args
+                            .modifiers
+                            .iter()
+                            .map(|x| ModifierKey::from(x))
+                            .collect(),
+                    },
+                };
+                topmost_node.dispatch_mouse_over(args_mouse_over, &globals, &engine.runtime_context)
+            } else {
+                false
+            }
+        }
+        NativeInterrupt::MouseOut(args) => {
+            let prospective_hit = engine
+                .runtime_context
+                .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+            if let Some(topmost_node) = prospective_hit {
+                let args_mouse_out = MouseOut {
+                    mouse: MouseEventArgs {
+                        x: args.x,
+                        y: args.y,
+                        button: MouseButton::from(args.button.clone()),
+                        modifiers: args
+                            .modifiers
+                            .iter()
+                            .map(|x| ModifierKey::from(x))
+                            .collect(),
+                    },
+                };
+                topmost_node.dispatch_mouse_out(args_mouse_out, &globals, &engine.runtime_context)
+            } else {
+                false
+            }
+        }
+        NativeInterrupt::ContextMenu(args) => {
+            let prospective_hit = engine
+                .runtime_context
+                .get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+            if let Some(topmost_node) = prospective_hit {
+                let args_context_menu = ContextMenu {
+                    mouse: MouseEventArgs {
+                        x: args.x,
+                        y: args.y,
+                        button: MouseButton::from(args.button.clone()),
+                        modifiers: args
+                            .modifiers
+                            .iter()
+                            .map(|x| ModifierKey::from(x))
+                            .collect(),
+                    },
+                };
+                topmost_node.dispatch_context_menu(
+                    args_context_menu,
+                    &globals,
+                    &engine.runtime_context,
+                )
+            } else {
+                false
+            }
+        }
+        NativeInterrupt::DropFile(_) | NativeInterrupt::Image(_) => false,
+    }
+}
+
 // parsing of user_agent strings could most likely be done more robustly, possibly copy some of the logic
 // used in https://crates.io/crates/woothee (used server side normally, to large dep?)
 // list of common user agent strings: https://deviceatlas.com/blog/list-of-user-agent-strings
@@ -830,3 +710,102 @@ impl MemorySlice {
         self.len
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pax_runtime::api::{NodeContext, Property};
+    use pax_runtime::constants::{
+        DOUBLE_TAP_HANDLERS, TAP_HANDLERS, TOUCH_END_HANDLERS, TOUCH_START_HANDLERS,
+    };
+    use pax_runtime::testing::TestNode;
+    use pax_runtime::InstanceNode;
+    use pax_runtime_api::pax_value::PaxAny;
+
+    thread_local! {
+        static RECEIVED: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+    }
+
+    type HandlerFn = fn(Rc<RefCell<PaxAny>>, &NodeContext, Option<PaxAny>);
+
+    macro_rules! recorder {
+        ($event:literal) => {
+            |_: Rc<RefCell<PaxAny>>, _: &NodeContext, _: Option<PaxAny>| {
+                RECEIVED.with(|received| borrow_mut!(received).push($event))
+            }
+        };
+    }
+
+    /// Dispatches `interrupt` as serialized by the interface's listeners,
+    /// returning whether default behavior should be prevented
+    fn send(engine: &PaxEngine, interrupt: &str) -> bool {
+        dispatch_interrupt(engine, &serde_json::from_str(interrupt).unwrap())
+    }
+
+    fn mounted_engine(node: Rc<dyn InstanceNode>) -> PaxEngine {
+        let main = TestNode::new().component(vec![node]);
+        let mut engine = PaxEngine::new(
+            main,
+            ExpressionTable::new(),
+            (200.0, 200.0),
+            Platform::Web,
+            OS::default(),
+        );
+        engine.tick();
+        engine
+    }
+
+    #[test]
+    fn touches_are_routed_through_the_engine() {
+        let handlers: Vec<(&str, HandlerFn)> = vec![
+            (TOUCH_START_HANDLERS, recorder!("touch_start")),
+            (TOUCH_END_HANDLERS, recorder!("touch_end")),
+            (TAP_HANDLERS, recorder!("tap")),
+            (DOUBLE_TAP_HANDLERS, recorder!("double_tap")),
+        ];
+        let pad = handlers
+            .into_iter()
+            .fold(
+                TestNode::new().size(100.0, 100.0),
+                |node, (event, handler)| node.on(event, handler),
+            )
+            .leaf();
+        let engine = mounted_engine(pad);
+
+        // a tap, as listeners.ts reports it: touches along with claps, followed by
+        // the mouse events browsers emulate, which must not make a second tap
+        let touch = r#"{"x": 10.0, "y": 10.0, "identifier": 0, "delta_x": 0.0, "delta_y": 0.0}"#;
+        let mouse = r#""x": 10.0, "y": 10.0, "button": "Left", "modifiers": []"#;
+        send(
+            &engine,
+            &format!(r#"{{"TouchStart": {{"touches": [{}]}}}}"#, touch),
+        );
+        send(&engine, r#"{"Clap": {"x": 10.0, "y": 10.0}}"#);
+        send(
+            &engine,
+            &format!(r#"{{"TouchEnd": {{"touches": [{}]}}}}"#, touch),
+        );
+        send(&engine, &format!(r#"{{"MouseDown": {{{}}}}}"#, mouse));
+        send(&engine, &format!(r#"{{"MouseUp": {{{}}}}}"#, mouse));
+        send(&engine, &format!(r#"{{"Click": {{{}}}}}"#, mouse));
+        send(&engine, r#"{"Clap": {"x": 10.0, "y": 10.0}}"#);
+        assert_eq!(
+            RECEIVED.with(|received| std::mem::take(&mut *borrow_mut!(received))),
+            vec!["touch_start", "touch_end", "tap"]
+        );
+    }
+
+    #[test]
+    fn tab_prevents_default_when_it_moves_focus() {
+        let tab = r#"{"KeyDown": {"key": "Tab", "modifiers": [], "is_repeat": false}}"#;
+        let engine = mounted_engine(TestNode::new().size(10.0, 10.0).leaf());
+        assert!(!send(&engine, tab));
+
+        let focusable = TestNode::new()
+            .size(10.0, 10.0)
+            .common(|_, common| common.focusable = Property::new(Some(true)))
+            .leaf();
+        let engine = mounted_engine(focusable);
+        assert!(send(&engine, tab));
+    }
+}
@@ -1,7 +1,6 @@
 use clap::{crate_version, App, AppSettings, Arg, ArgMatches};
 use color_eyre::config::HookBuilder;
 use colored::{ColoredString, Colorize};
-use std::io::Write;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{process, thread};
@@ -60,6 +59,13 @@ fn main() -> Result<(), Report> {
         .takes_value(false)
         .help("Build in Release mode, with appropriate platform-specific optimizations.");
 
+    #[allow(non_snake_case)]
+    let ARG_WATCH = Arg::with_name("watch")
+        .short("w")
+        .long("watch")
+        .takes_value(false)
+        .help("Watch `.pax` files and hot reload template changes into the running app, without recompiling.  Web target only.");
+
     #[allow(non_snake_case)]
     let ARG_LIBDEV = Arg::with_name("libdev")
         .long("libdev")
@@ -80,6 +86,7 @@ fn main() -> Result<(), Report> {
                 .arg( ARG_PATH.clone() )
                 .arg( ARG_TARGET.clone() )
                 .arg( ARG_VERBOSE.clone() )
+                .arg( ARG_WATCH.clone() )
                 .arg( ARG_LIBDEV.clone() )
         )
         .subcommand(
@@ -163,6 +170,7 @@ fn perform_nominal_action(
             let path = args.value_of("path").unwrap().to_string(); //default value "."
             let verbose = args.is_present("verbose");
             let is_libdev_mode = args.is_present("libdev");
+            let watch = args.is_present("watch");
 
             let _ = pax_compiler::perform_build(&RunContext {
                 target: RunTarget::from(target.as_str()),
@@ -172,6 +180,7 @@ fn perform_nominal_action(
                 is_libdev_mode,
                 process_child_ids,
                 is_release: false,
+                watch,
             })?;
 
             Ok(())
@@ -191,6 +200,7 @@ fn perform_nominal_action(
                 is_libdev_mode,
                 process_child_ids,
                 is_release,
+                watch: false,
             })?;
 
             Ok(())
@@ -221,15 +231,7 @@ fn perform_nominal_action(
             match args.subcommand() {
                 ("parse", Some(args)) => {
                     let path = args.value_of("path").unwrap().to_string(); //default value "."
-                    let output = &pax_compiler::run_parser_binary(&path, process_child_ids);
-
-                    // Forward both stdout and stderr
-                    std::io::stderr()
-                        .write_all(output.stderr.as_slice())
-                        .unwrap();
-                    std::io::stdout()
-                        .write_all(output.stdout.as_slice())
-                        .unwrap();
+                    println!("{}", pax_compiler::perform_parse(&path)?);
 
                     Ok(())
                 }
@@ -18,7 +18,7 @@ use color_eyre::eyre;
 use tar::Archive;
 
 use crate::{
-    helpers::{copy_dir_recursively, ALL_PKGS, DIR_IGNORE_LIST_MACOS, PKG_DIR_NAME},
+    helpers::{copy_dir_recursively, ALL_PKGS, DIR_IGNORE_LIST_MACOS, PAX_BADGE, PKG_DIR_NAME},
     RunContext, RunTarget,
 };
 
@@ -36,6 +36,7 @@ mod design;
 pub fn build_chassis_with_cartridge(
     pax_dir: &PathBuf,
     ctx: &RunContext,
+    manifest: &PaxManifest,
     process_child_ids: Arc<Mutex<Vec<u64>>>,
 ) -> Result<Option<PathBuf>, eyre::Report> {
     let target: &RunTarget = &ctx.target;
@@ -74,11 +75,17 @@ pub fn build_chassis_with_cartridge(
     //string together a shell call to build our chassis, with cartridge inserted via `patch`
     match target {
         RunTarget::macOS | RunTarget::iOS => {
+            if ctx.watch {
+                eprintln!(
+                    "{} `--watch` is only supported for the web target; ignoring",
+                    *PAX_BADGE
+                );
+            }
             build_apple_chassis_with_cartridge(ctx, &pax_dir, process_child_ids)?;
             Ok(None)
         }
         RunTarget::Web => {
-            let fs = build_web_chassis_with_cartridge(ctx, &pax_dir, process_child_ids)?;
+            let fs = build_web_chassis_with_cartridge(ctx, &pax_dir, manifest, process_child_ids)?;
             Ok(Some(fs))
         }
     }
@@ -115,6 +122,11 @@ pub fn update_type_id_prefixes_in_place(
         if let Some(template) = c.1.template.as_mut() {
             template.fully_qualify_template_type_ids(host_crate_info);
         }
+        for event in c.1.custom_events.iter_mut() {
+            if let Some(payload_type) = event.payload_type.as_mut() {
+                payload_type.fully_qualify_type_id(host_crate_info);
+            }
+        }
 
         let mut key = c.0.clone();
         updated_component_table.insert(
@@ -2,6 +2,7 @@ use crate::helpers::{
     wait_with_output, ASSETS_DIR_NAME, BUILD_DIR_NAME, DIR_IGNORE_LIST_WEB, PAX_BADGE,
     PKG_DIR_NAME, PUBLIC_DIR_NAME,
 };
+use crate::hot_reload::{TemplateWatcher, WatchEvent, HOT_RELOAD_WEBSOCKET_PATH};
 use crate::{copy_dir_recursively, RunContext, RunTarget};
 
 use color_eyre::eyre;
@@ -10,21 +11,26 @@ use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use actix_web::middleware::Logger;
-use actix_web::{App, HttpServer};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
 use env_logger;
 use eyre::eyre;
+use futures::StreamExt;
+use pax_manifest::PaxManifest;
 use std::io::Write;
 use std::net::TcpListener;
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
+use tokio::sync::broadcast;
 
 const IS_DESIGN_TIME_BUILD: bool = cfg!(feature = "designtime");
 
 pub fn build_web_chassis_with_cartridge(
     ctx: &RunContext,
     pax_dir: &PathBuf,
+    manifest: &PaxManifest,
     process_child_ids: Arc<Mutex<Vec<u64>>>,
 ) -> Result<PathBuf, eyre::Report> {
     let target: &RunTarget = &ctx.target;
@@ -126,7 +132,10 @@ pub fn build_web_chassis_with_cartridge(
     // Start local server if this is a `run` rather than a `build`
     if ctx.should_also_run {
         println!("{} 🐇 Running Pax Web...", *PAX_BADGE);
-        let _ = start_static_http_server(interface_path.join(PUBLIC_DIR_NAME));
+        let watcher = ctx
+            .watch
+            .then(|| TemplateWatcher::new(manifest.clone(), &PathBuf::from(&ctx.path)));
+        let _ = start_static_http_server(interface_path.join(PUBLIC_DIR_NAME), watcher);
     } else {
         println!(
             "{} 🗂️ Done: {} build available at {}",
@@ -138,7 +147,82 @@ pub fn build_web_chassis_with_cartridge(
     Ok(build_src)
 }
 
-fn start_static_http_server(fs_path: PathBuf) -> std::io::Result<()> {
+/// How often watched files are checked for changes
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Polls `watcher` on a background thread, broadcasting each resulting template patch
+/// (serialized as JSON) to connected chassis
+fn spawn_template_watcher(mut watcher: TemplateWatcher) -> broadcast::Sender<String> {
+    let (patches, _) = broadcast::channel(16);
+    let sender = patches.clone();
+    println!(
+        "{} 👀 Watching {} .pax file(s) for changes",
+        *PAX_BADGE,
+        watcher.watched_template_count()
+    );
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WATCH_POLL_INTERVAL);
+        match watcher.poll() {
+            Some(WatchEvent::Patch(patch, files)) => {
+                let patch = serde_json::to_string(&patch).unwrap();
+                // sending only fails when no chassis is connected yet
+                let _ = sender.send(patch);
+                for file in files {
+                    println!("{} ♻️  Reloaded {}", *PAX_BADGE, file.to_string_lossy());
+                }
+            }
+            Some(WatchEvent::RequiresRebuild(reason)) => {
+                println!(
+                    "{} 🔁 Can't hot reload ({}); restart `pax-cli run` to rebuild",
+                    *PAX_BADGE, reason
+                );
+            }
+            None => {}
+        }
+    });
+    patches
+}
+
+/// Forwards template patches to a connected chassis until it disconnects
+async fn hot_reload_socket(
+    req: HttpRequest,
+    body: web::Payload,
+    patches: web::Data<broadcast::Sender<String>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    let mut receiver = patches.subscribe();
+    actix_rt::spawn(async move {
+        loop {
+            tokio::select! {
+                patch = receiver.recv() => match patch {
+                    Ok(patch) => {
+                        if session.text(patch).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                message = messages.next() => match message {
+                    Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(actix_ws::Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+        let _ = session.close(None).await;
+    });
+    Ok(response)
+}
+
+fn start_static_http_server(
+    fs_path: PathBuf,
+    watcher: Option<TemplateWatcher>,
+) -> std::io::Result<()> {
     // Initialize logging
 
     std::env::set_var("RUST_LOG", "actix_web=info");
@@ -146,6 +230,8 @@ fn start_static_http_server(fs_path: PathBuf) -> std::io::Result<()> {
         .format(|buf, record| writeln!(buf, "{} 🍱 Served {}", *PAX_BADGE, record.args()))
         .init();
 
+    let patches = watcher.map(spawn_template_watcher);
+
     // Create a Runtime
     let runtime = actix_rt::System::new().block_on(async {
         let mut port = 8080;
@@ -161,8 +247,15 @@ fn start_static_http_server(fs_path: PathBuf) -> std::io::Result<()> {
                 let address_msg = format!("http://127.0.0.1:{}", port).blue();
                 let server_running_at_msg = format!("Server running at {}", address_msg).bold();
                 println!("{} 📠 {}", *PAX_BADGE, server_running_at_msg);
+                let patches = patches.clone();
                 break HttpServer::new(move || {
-                    App::new().wrap(Logger::new("| %s | %U")).service(
+                    let mut app = App::new().wrap(Logger::new("| %s | %U"));
+                    if let Some(patches) = &patches {
+                        app = app
+                            .app_data(web::Data::new(patches.clone()))
+                            .route(HOT_RELOAD_WEBSOCKET_PATH, web::get().to(hot_reload_socket));
+                    }
+                    app.service(
                         actix_files::Files::new("/*", fs_path.clone()).index_file("index.html"),
                     )
                 })
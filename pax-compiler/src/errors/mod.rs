@@ -75,6 +75,7 @@ impl fmt::Display for PaxTemplateError {
             TokenType::IfExpression => "Invalid 'if' expression.",
             TokenType::ForPredicate => "Invalid 'for' predicate.",
             TokenType::ForSource => "Invalid 'for' source.",
+            TokenType::ForKey => "Invalid 'for' key.",
             TokenType::SlotExpression => "Invalid slot expression.",
             TokenType::EventId => "Invalid event ID.",
             TokenType::Handler => "Invalid handler.",
@@ -1,7 +1,7 @@
 use pax_manifest::{
     escape_identifier, ComponentDefinition, ComponentTemplate,
     ControlFlowRepeatPredicateDefinition, ExpressionCompilationInfo, ExpressionSpec,
-    ExpressionSpecInvocation, HostCrateInfo, PaxManifest, PropertyDefinition,
+    ExpressionSpecInvocation, HostCrateInfo, PaxManifest, PaxType, PropertyDefinition,
     PropertyDefinitionFlags, SettingElement, TemplateNodeId, Token, TypeDefinition, TypeId,
     TypeTable, ValueDefinition,
 };
@@ -106,9 +106,19 @@ fn recurse_compile_literal_block<'a>(
             match value {
                 // LiteralValue:       no need to compile literal values
                 // EventBindingTarget: event bindings are handled on a separate compiler pass; no-op here
-                ValueDefinition::LiteralValue(_)
-                | ValueDefinition::EventBindingTarget(_)
-                | ValueDefinition::DoubleBinding(_, _) => {}
+                ValueDefinition::LiteralValue(_) | ValueDefinition::EventBindingTarget(_) => {}
+                ValueDefinition::DoubleBinding(identifier, _) => {
+                    // e.g. the `self.name` in `<Textbox bind:text=self.name />`
+                    // Nothing is compiled: the child's property is replaced at instantiation by the
+                    // very same Property as the bound symbol, so we only check that both sides line up.
+                    validate_double_binding(
+                        ctx,
+                        &current_property_definitions,
+                        &type_id,
+                        token,
+                        identifier,
+                    )?;
+                }
                 ValueDefinition::Block(block) => {
                     let type_def = (current_property_definitions
                         .iter()
@@ -244,6 +254,87 @@ fn recurse_compile_literal_block<'a>(
     })
 }
 
+fn validate_double_binding(
+    ctx: &ExpressionCompilationContext,
+    current_property_definitions: &Vec<PropertyDefinition>,
+    type_id: &TypeId,
+    token: &Token,
+    identifier: &Token,
+) -> Result<(), eyre::Report> {
+    let common_property_type = COMMON_PROPERTIES_TYPE
+        .iter()
+        .find(|cpt| cpt.0 == token.token_value)
+        .map(|cpt| cpt.1);
+    let property_def = if common_property_type.is_some() {
+        None
+    } else {
+        Some(
+            current_property_definitions
+                .iter()
+                .find(|property_def| property_def.name == token.token_value)
+                .ok_or::<eyre::Report>(PaxTemplateError::new(
+                    Some(format!(
+                        "Property `{}` not found on `{}`",
+                        &token.token_value, type_id
+                    )),
+                    token.clone(),
+                ))?,
+        )
+    };
+
+    let bound_def = ctx
+        .resolve_symbol_as_prop_def(&identifier.raw_value, identifier.clone())?
+        .and_then(|mut pds| pds.pop())
+        .ok_or::<eyre::Report>(PaxTemplateError::new(
+            Some(format!(
+                "Failed to resolve symbol `{}` for two-way binding",
+                &identifier.raw_value
+            )),
+            identifier.clone(),
+        ))?;
+
+    if bound_def.flags.is_binding_repeat_i || bound_def.flags.is_binding_repeat_elem {
+        return Err(PaxTemplateError::new(
+            Some(format!(
+                "Cannot bind to `{}`: two-way bindings must target a component property, not a `for` predicate",
+                &identifier.raw_value
+            )),
+            identifier.clone(),
+        ));
+    }
+
+    let (matches, property_type) = match (property_def, common_property_type) {
+        (Some(property_def), _) => (
+            bound_def.type_id == property_def.type_id,
+            property_def.type_id.to_string(),
+        ),
+        // Common properties are `Property<Option<T>>`, and share the bound property as is
+        (None, Some(common_property_type)) => (
+            is_option_of(&bound_def.type_id, common_property_type),
+            format!("std::option::Option<{}>", common_property_type),
+        ),
+        (None, None) => unreachable!(),
+    };
+    if !matches {
+        return Err(PaxTemplateError::new(
+            Some(format!(
+                "Cannot bind `{}` of type `{}` to `{}` of type `{}`; two-way bindings require identical types",
+                &token.token_value, property_type, &identifier.raw_value, bound_def.type_id
+            )),
+            identifier.clone(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `type_id` is `Option<T>`, with `T` the type at `import_path`
+fn is_option_of(type_id: &TypeId, import_path: &str) -> bool {
+    let PaxType::Option { identifier } = type_id.get_pax_type() else {
+        return false;
+    };
+    identifier.strip_prefix("{PREFIX}").unwrap_or(&identifier) == import_path
+}
+
 fn recurse_compile_expressions<'a>(
     mut ctx: ExpressionCompilationContext<'a>,
     mut source_map: &mut SourceMap,
@@ -484,6 +575,43 @@ fn recurse_compile_expressions<'a>(
                     is_repeat_source_iterable_expression: is_repeat_source_iterable,
                 },
             );
+
+            // Handle the optional `item.id` in `for item in self.items key=item.id`, compiled with
+            // the predicate symbols already on the scope_stack so it can be evaluated per element
+            if let Some(key_expression_paxel) = &cfa.repeat_key_expression_paxel {
+                let (output_statement, invocations) =
+                    compile_paxel_to_ril(key_expression_paxel.clone(), &ctx)?;
+                let id = ctx.vtable_uid_gen.next().unwrap();
+
+                let deps = invocations
+                    .iter()
+                    .map(|i| i.root_identifier.clone())
+                    .collect::<Vec<String>>();
+
+                cfa.repeat_key_expression_info = Some(ExpressionCompilationInfo {
+                    vtable_id: id,
+                    dependencies: deps,
+                });
+
+                let mut whitespace_removed_input = key_expression_paxel.clone().token_value;
+                whitespace_removed_input.retain(|c| !c.is_whitespace());
+
+                let source_map_id = source_map.insert(key_expression_paxel.clone());
+                let input_statement =
+                    source_map.generate_mapped_string(whitespace_removed_input, source_map_id);
+
+                ctx.expression_specs.insert(
+                    id,
+                    ExpressionSpec {
+                        id,
+                        invocations,
+                        output_type: "PaxAny".to_string(),
+                        output_statement,
+                        input_statement,
+                        is_repeat_source_iterable_expression: false,
+                    },
+                );
+            }
         } else if let Some(condition_expression_paxel) = &cfa.condition_expression_paxel {
             //Handle `if` boolean expression, e.g. the `num_clicks > 5` in `if num_clicks > 5 { ... }`
             let (output_statement, invocations) =
@@ -839,3 +967,76 @@ impl<'a> ExpressionCompilationContext<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::update_type_id_prefixes_in_place;
+    use crate::helpers::get_host_crate_info;
+    use crate::static_analysis::{analyze_project, tests::host_crate};
+    use std::fs;
+
+    /// Compiles the expressions of a `Form` component with a `Size`, an optional
+    /// `Size` and an optional `units::Size` property, rendering `template`
+    fn compile(name: &str, template: &str) -> eyre::Result<(), Report> {
+        let dir = host_crate(
+            name,
+            &[
+                (
+                    "lib.rs",
+                    r#"
+                    use pax_engine::api::*;
+                    use pax_engine::*;
+                    use pax_std::primitives::*;
+
+                    #[pax]
+                    #[main]
+                    #[file("lib.pax")]
+                    pub struct Form {
+                        pub width: Property<Size>,
+                        pub maybe_width: Property<Option<Size>>,
+                        pub maybe_units: Property<Option<units::Size>>,
+                    }
+
+                    pub mod units {
+                        use pax_engine::*;
+
+                        #[pax]
+                        pub struct Size {
+                            pub em: f64,
+                        }
+                    }
+                    "#,
+                ),
+                ("lib.pax", template),
+            ],
+        );
+        let mut manifest = analyze_project(&dir).unwrap();
+        let host_crate_info = get_host_crate_info(&dir.join("Cargo.toml"));
+        fs::remove_dir_all(&dir).unwrap();
+        update_type_id_prefixes_in_place(&mut manifest, &host_crate_info);
+        compile_all_expressions(&mut manifest, &mut SourceMap::new(), &host_crate_info)
+    }
+
+    #[test]
+    fn common_properties_can_be_bound_to_optional_properties() {
+        compile(
+            "expressions-bind-common-fixture",
+            "<Rectangle bind:width=self.maybe_width/>",
+        )
+        .unwrap();
+        let error = compile(
+            "expressions-bind-common-mismatch-fixture",
+            "<Rectangle bind:width=self.width/>",
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("require identical types"));
+        // same name, but not the type of the common property
+        let error = compile(
+            "expressions-bind-common-other-type-fixture",
+            "<Rectangle bind:width=self.maybe_units/>",
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("require identical types"));
+    }
+}
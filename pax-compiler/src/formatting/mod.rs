@@ -52,3 +52,49 @@ fn format_pax_in_rust_file(path: &Path) -> Result<(), Report> {
     fs::write(path, modified_content)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_else_if_and_else_branches() {
+        let formatted = format_pax_template(
+            "if a {<Rectangle/>} else   if b {<Ellipse/>}\nelse {<Text/>}".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            formatted.trim(),
+            "if a {\n    <Rectangle />\n} else if b {\n    <Ellipse />\n} else {\n    <Text />\n}"
+        );
+        assert_eq!(format_pax_template(formatted.clone()).unwrap(), formatted);
+    }
+
+    #[test]
+    fn formats_timelines_in_settings() {
+        let formatted = format_pax_template(
+            "<Rectangle/>\n@settings { @timeline intro(autoplay,loop){ x:[0ms:0px,1.5s :100px   out_quad] } }"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            formatted.trim(),
+            "<Rectangle />\n\n@settings {\n    @timeline intro (autoplay, loop) {\n        x: [0ms: 0px, 1.5s: 100px out_quad]\n    }\n}"
+        );
+        assert_eq!(format_pax_template(formatted.clone()).unwrap(), formatted);
+    }
+
+    #[test]
+    fn formats_capture_event_bindings() {
+        let formatted = format_pax_template(
+            "<Rectangle @click.capture=self.on_capture @click=self.on_click/>\n@settings { @click.capture : on_capture }"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            formatted.trim(),
+            "<Rectangle @click.capture=self.on_capture @click=self.on_click/>\n\n@settings {\n    @click.capture: on_capture,\n}"
+        );
+        assert_eq!(format_pax_template(formatted.clone()).unwrap(), formatted);
+    }
+}
@@ -88,6 +88,13 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         Rule::settings_block_declaration => vec![Box::new(SettingsBlockDeclarationDefaultRule)],
         Rule::settings_event_binding => vec![Box::new(SettingsEventBindingDefaultRule)],
         Rule::selector_block => vec![Box::new(SelectorBlockDefaultRule)],
+        Rule::settings_timeline => vec![Box::new(SettingsTimelineDefaultRule)],
+        Rule::timeline_options => vec![Box::new(TimelineOptionsDefaultRule)],
+        Rule::timeline_track => vec![
+            Box::new(TimelineTrackMultiLineRule),
+            Box::new(TimelineTrackDefaultRule),
+        ],
+        Rule::timeline_keyframe => vec![Box::new(TimelineKeyframeDefaultRule)],
         Rule::literal_object | Rule::xo_object => vec![Box::new(ObjectDefaultRule)],
         Rule::settings_key_value_pair => vec![Box::new(SettingsKeyValuePairDefaultRule)],
         Rule::literal_function => vec![Box::new(LiteralFunctionDefaultRule)],
@@ -117,7 +124,10 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
             vec![Box::new(XoObjectSettingsKeyValuePairDefaultRule)]
         }
         Rule::statement_for => vec![Box::new(StatementForDefaultRule)],
+        Rule::statement_for_key => vec![Box::new(StatementForKeyDefaultRule)],
         Rule::statement_if => vec![Box::new(StatementIfDefaultRule)],
+        Rule::statement_else_if => vec![Box::new(StatementElseIfDefaultRule)],
+        Rule::statement_else => vec![Box::new(StatementElseDefaultRule)],
         Rule::statement_slot => vec![Box::new(StatementSlotDefaultRule)],
         Rule::any_template_value | Rule::node_inner_content | Rule::settings_value => {
             vec![Box::new(WrapExpressionRule), Box::new(ForwardRule)]
@@ -140,6 +150,8 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         | Rule::xo_symbol
         | Rule::id_binding
         | Rule::literal_color_channel
+        | Rule::timeline_time
+        | Rule::timeline_time_unit
         | Rule::EOI => vec![Box::new(RemoveWhitespaceRule)],
 
         Rule::identifier
@@ -170,6 +182,9 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         | Rule::literal_color_space_func
         | Rule::xo_color_space_func
         | Rule::literal_color_const
+        | Rule::timeline_option
+        | Rule::timeline_easing
+        | Rule::event_capture
         | Rule::xo_range_exclusive => vec![Box::new(PrintRule)],
 
         Rule::expression_wrapped
@@ -357,12 +372,14 @@ impl FormattingRule for SettingsBlockDeclarationDefaultRule {
         enum SettingType {
             Selector,
             Event,
+            Timeline,
             Unknown,
         }
 
         let mut current = SettingType::Unknown;
         let mut unknown_comments: VecDeque<Child> = VecDeque::new();
         let mut handlers: VecDeque<Child> = VecDeque::new();
+        let mut timelines: VecDeque<Child> = VecDeque::new();
         let mut selectors: VecDeque<Child> = VecDeque::new();
 
         for child in children.iter().rev() {
@@ -372,10 +389,14 @@ impl FormattingRule for SettingsBlockDeclarationDefaultRule {
             } else if child.node_type == Rule::settings_event_binding {
                 current = SettingType::Event;
                 handlers.push_front(child.clone());
+            } else if child.node_type == Rule::settings_timeline {
+                current = SettingType::Timeline;
+                timelines.push_front(child.clone());
             } else if child.node_type == Rule::comment {
                 match current {
                     SettingType::Selector => selectors.push_front(child.clone()),
                     SettingType::Event => handlers.push_front(child.clone()),
+                    SettingType::Timeline => timelines.push_front(child.clone()),
                     SettingType::Unknown => unknown_comments.push_front(child.clone()),
                 }
             }
@@ -399,6 +420,16 @@ impl FormattingRule for SettingsBlockDeclarationDefaultRule {
             );
         }
 
+        if !timelines.is_empty() {
+            settings.push(
+                timelines
+                    .iter()
+                    .map(|child| child.formatted_node.clone())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            );
+        }
+
         if !selectors.is_empty() {
             settings.push(
                 selectors
@@ -429,6 +460,90 @@ impl FormattingRule for SelectorBlockDefaultRule {
     }
 }
 
+#[derive(Clone)]
+struct SettingsTimelineDefaultRule;
+
+impl FormattingRule for SettingsTimelineDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let mut formatted_node = format!("@timeline {}", children[0].formatted_node);
+        let mut entries = Vec::new();
+        for child in &children[1..] {
+            if child.node_type == Rule::timeline_options {
+                formatted_node.push_str(&format!(" {}", child.formatted_node));
+            } else {
+                entries.push(child.formatted_node.clone());
+            }
+        }
+        let indented_entries = indent_every_line_of_string(entries.join("\n"));
+        formatted_node.push_str(format!(" {{\n{}\n}}", indented_entries).as_str());
+        formatted_node
+    }
+}
+
+#[derive(Clone)]
+struct TimelineOptionsDefaultRule;
+
+impl FormattingRule for TimelineOptionsDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let options = children
+            .iter()
+            .map(|child| child.formatted_node.clone())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("({})", options)
+    }
+}
+
+#[derive(Clone)]
+struct TimelineTrackMultiLineRule;
+
+impl FormattingRule for TimelineTrackMultiLineRule {
+    fn is_applicable(&self, children: Vec<Child>) -> bool {
+        children_longer_than_line_limit(&children)
+    }
+
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let keyframes = children[1..]
+            .iter()
+            .map(|child| child.formatted_node.clone())
+            .collect::<Vec<String>>()
+            .join(",\n");
+        let indented_keyframes = indent_every_line_of_string(keyframes);
+        format!(
+            "{}: [\n{}\n]",
+            children[0].formatted_node, indented_keyframes
+        )
+    }
+}
+
+#[derive(Clone)]
+struct TimelineTrackDefaultRule;
+
+impl FormattingRule for TimelineTrackDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let keyframes = children[1..]
+            .iter()
+            .map(|child| child.formatted_node.clone())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("{}: [{}]", children[0].formatted_node, keyframes)
+    }
+}
+
+#[derive(Clone)]
+struct TimelineKeyframeDefaultRule;
+
+impl FormattingRule for TimelineKeyframeDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let value = children[1..]
+            .iter()
+            .map(|child| child.formatted_node.clone())
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!("{}: {}", children[0].formatted_node, value)
+    }
+}
+
 #[derive(Clone)]
 struct RemoveWhitespaceRule;
 
@@ -774,11 +889,33 @@ impl FormattingRule for StatementForDefaultRule {
         let mut formatted_node = String::new();
         let sfpd = children[0].formatted_node.clone();
         let sfs = children[1].formatted_node.clone();
-        let inner_nodes = children[2].formatted_node.clone();
+        let (sfk, inner_nodes) = if children[2].node_type == Rule::statement_for_key {
+            (
+                format!(" {}", children[2].formatted_node),
+                children[3].formatted_node.clone(),
+            )
+        } else {
+            (String::new(), children[2].formatted_node.clone())
+        };
         let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
+        formatted_node.push_str(
+            format!(
+                "for {} in {}{} {{\n{}\n}}",
+                sfpd, sfs, sfk, inner_nodes_indented
+            )
+            .as_str(),
+        );
         formatted_node
-            .push_str(format!("for {} in {} {{\n{}\n}}", sfpd, sfs, inner_nodes_indented).as_str());
-        formatted_node
+    }
+}
+
+#[derive(Clone)]
+struct StatementForKeyDefaultRule;
+
+impl FormattingRule for StatementForKeyDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let exp = children[0].formatted_node.clone();
+        format!("key={}", exp)
     }
 }
 
@@ -792,6 +929,37 @@ impl FormattingRule for StatementIfDefaultRule {
         let inner_nodes = children[1].formatted_node.clone();
         let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
         formatted_node.push_str(format!("if {} {{\n{}\n}}", exp, inner_nodes_indented).as_str());
+        for else_branch in &children[2..] {
+            formatted_node.push_str(format!(" {}", else_branch.formatted_node).as_str());
+        }
+        formatted_node
+    }
+}
+
+#[derive(Clone)]
+struct StatementElseIfDefaultRule;
+
+impl FormattingRule for StatementElseIfDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let mut formatted_node = String::new();
+        let exp = children[0].formatted_node.clone();
+        let inner_nodes = children[1].formatted_node.clone();
+        let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
+        formatted_node
+            .push_str(format!("else if {} {{\n{}\n}}", exp, inner_nodes_indented).as_str());
+        formatted_node
+    }
+}
+
+#[derive(Clone)]
+struct StatementElseDefaultRule;
+
+impl FormattingRule for StatementElseDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let mut formatted_node = String::new();
+        let inner_nodes = children[0].formatted_node.clone();
+        let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
+        formatted_node.push_str(format!("else {{\n{}\n}}", inner_nodes_indented).as_str());
         formatted_node
     }
 }
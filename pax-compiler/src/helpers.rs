@@ -36,6 +36,7 @@ pub const ERR_SPAWN: &str = "failed to spawn child";
 pub const ALL_PKGS: &[&str] = &[
     "pax-cartridge",
     "pax-chassis-common",
+    "pax-chassis-headless",
     "pax-chassis-ios",
     "pax-chassis-macos",
     "pax-chassis-web",
@@ -50,6 +51,7 @@ pub const ALL_PKGS: &[&str] = &[
     "pax-component-library",
     "pax-manifest",
     "pax-lang",
+    "pax-paxel-interp",
 ];
 
 #[derive(Debug, Deserialize)]
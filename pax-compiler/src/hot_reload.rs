@@ -0,0 +1,686 @@
+//! # Hot Reload
+//!
+//! Supports `pax-cli run --watch`: the [`TemplateWatcher`] polls the `.pax` files behind the
+//! compiled manifest, re-parses any that changed into a [`ComponentTemplate`] and diffs it
+//! against the compiled template. Expressions that already exist in the running cartridge
+//! keep their compiled code, while new ones are interpreted by the chassis (see
+//! `pax-paxel-interp`). When every event handler in the new template also exists, the result
+//! is a [`TemplatePatch`] that the web chassis applies without recompiling. Anything else
+//! (new handlers, new components, expressions the interpreter can't evaluate, `@settings` or
+//! Rust changes) still requires a full rebuild.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use pax_lang::{parse_pax_str, Pair, Rule};
+use pax_manifest::{
+    ComponentTemplate, ExpressionCompilationInfo, PaxManifest, SettingElement, TemplatePatch,
+    Token, TypeId, ValueDefinition,
+};
+use walkdir::WalkDir;
+
+use crate::parsing::{
+    parse_settings_from_component_definition_string,
+    parse_template_from_component_definition_string, TemplateNodeParseContext,
+};
+use crate::static_analysis::{check_component_capture_bindings, embedded_pax};
+
+/// Route on the local dev server over which template patches are pushed to the chassis
+pub const HOT_RELOAD_WEBSOCKET_PATH: &str = "/__pax/hot-reload";
+
+/// Result of a [`TemplateWatcher::poll`] that found changes
+pub enum WatchEvent {
+    /// Templates that can be applied to the running cartridge as-is, along with the files they came from
+    Patch(TemplatePatch, Vec<PathBuf>),
+    /// The changes can't be hot reloaded, for the given reason
+    RequiresRebuild(String),
+}
+
+struct WatchedTemplate {
+    component: TypeId,
+    modified: Option<SystemTime>,
+    contents: String,
+}
+
+pub struct TemplateWatcher {
+    /// The manifest compiled into the running cartridge. Only expressions and handlers
+    /// from this manifest have generated code, so patches are always diffed against it.
+    compiled: PaxManifest,
+    templates: HashMap<PathBuf, WatchedTemplate>,
+    rust_sources: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+impl TemplateWatcher {
+    /// Watches the `.pax` files of every component in `compiled`, as well as the Rust
+    /// sources under `project_path/src`
+    pub fn new(compiled: PaxManifest, project_path: &Path) -> Self {
+        let mut templates = HashMap::new();
+        for (type_id, component) in &compiled.components {
+            let Some(path) = component.template.as_ref().and_then(|t| t.get_file_path()) else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            if path.extension().is_none_or(|ext| ext != "pax") {
+                // inlined templates live in Rust files
+                continue;
+            }
+            templates.insert(
+                path.clone(),
+                WatchedTemplate {
+                    component: type_id.clone(),
+                    modified: modified_time(&path),
+                    contents: fs::read_to_string(&path).unwrap_or_default(),
+                },
+            );
+        }
+
+        let rust_sources = WalkDir::new(project_path.join("src"))
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+            .map(|e| (e.path().to_path_buf(), modified_time(e.path())))
+            .collect();
+
+        Self {
+            compiled,
+            templates,
+            rust_sources,
+        }
+    }
+
+    pub fn watched_template_count(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// Checks watched files for changes since the last poll. Returns `None` if nothing changed.
+    pub fn poll(&mut self) -> Option<WatchEvent> {
+        let mut rust_changed = false;
+        for (path, modified) in self.rust_sources.iter_mut() {
+            let current = modified_time(path);
+            if current != *modified {
+                *modified = current;
+                rust_changed = true;
+            }
+        }
+
+        let mut changed = vec![];
+        for (path, watched) in self.templates.iter_mut() {
+            let current = modified_time(path);
+            if current == watched.modified {
+                continue;
+            }
+            watched.modified = current;
+            let Ok(contents) = fs::read_to_string(path) else {
+                continue;
+            };
+            if contents != watched.contents {
+                watched.contents = contents;
+                changed.push(path.clone());
+            }
+        }
+
+        if rust_changed {
+            return Some(WatchEvent::RequiresRebuild(
+                "Rust sources changed".to_string(),
+            ));
+        }
+        if changed.is_empty() {
+            return None;
+        }
+
+        let mut patch = TemplatePatch::default();
+        for path in &changed {
+            let watched = &self.templates[path];
+            match reload_template(
+                &self.compiled,
+                &watched.component,
+                &watched.contents,
+                &mut patch.interpreted_expressions,
+            ) {
+                Ok(template) => {
+                    patch.templates.insert(watched.component.clone(), template);
+                }
+                Err(reason) => {
+                    return Some(WatchEvent::RequiresRebuild(format!(
+                        "{}: {}",
+                        path.to_string_lossy(),
+                        reason
+                    )))
+                }
+            }
+        }
+        Some(WatchEvent::Patch(patch, changed))
+    }
+}
+
+/// Re-parses `pax` as the template of the component `type_id` and reuses the vtable ids of
+/// the compiled template for its expressions. New expressions get vtable ids past those of
+/// the cartridge and their source is added to `interpreted`. Returns an error describing why
+/// a rebuild is required if the new template can't be run by the compiled cartridge.
+pub fn reload_template(
+    compiled: &PaxManifest,
+    type_id: &TypeId,
+    pax: &str,
+    interpreted: &mut BTreeMap<usize, String>,
+) -> Result<ComponentTemplate, String> {
+    let component = compiled
+        .components
+        .get(type_id)
+        .ok_or_else(|| format!("unknown component {}", type_id))?;
+    let compiled_template = component
+        .template
+        .as_ref()
+        .ok_or_else(|| format!("{} has no template", type_id))?;
+
+    // framed like compiled templates, so that token locations match theirs
+    let pax = &embedded_pax(pax);
+    let ast = parse_pax_str(Rule::pax_component_definition, pax)?;
+
+    let pascal_identifier_to_type_id_map = known_components(compiled, compiled_template);
+    let mut tags = vec![];
+    collect_tag_identifiers(ast.clone(), &mut tags);
+    if let Some(unknown) = tags
+        .iter()
+        .find(|tag| !pascal_identifier_to_type_id_map.contains_key(*tag))
+    {
+        return Err(format!("<{}> is not part of the compiled app", unknown));
+    }
+
+    let settings = parse_settings_from_component_definition_string(pax, ast.clone())?;
+    if settings != component.settings.clone().unwrap_or_default() {
+        return Err("@settings changed".to_string());
+    }
+
+    let mut ctx = TemplateNodeParseContext {
+        pascal_identifier_to_type_id_map,
+        template: ComponentTemplate::new(type_id.clone(), compiled_template.get_file_path()),
+    };
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        parse_template_from_component_definition_string(&mut ctx, pax, ast)
+    }))
+    .map_err(|_| "failed to parse template".to_string())?;
+    let mut template = ctx.template;
+    check_component_capture_bindings(compiled, component, Some(&template))?;
+
+    template.populate_template_with_known_entities(compiled_template);
+    interpret_uncompiled_expressions(compiled, type_id, &mut template, interpreted)?;
+
+    let compiled_handlers = event_handlers(compiled_template);
+    if let Some((event, handler)) = event_handlers(&template)
+        .into_iter()
+        .find(|h| !compiled_handlers.contains(h))
+    {
+        return Err(format!("new handler `@{}={}`", event, handler));
+    }
+
+    Ok(template)
+}
+
+/// Assigns vtable ids past any in use to the expressions of `template` that have no
+/// compiled code, recording their source in `interpreted`. Fails for expressions the
+/// interpreter can't evaluate, or that read symbols that aren't in scope.
+fn interpret_uncompiled_expressions(
+    compiled: &PaxManifest,
+    type_id: &TypeId,
+    template: &mut ComponentTemplate,
+    interpreted: &mut BTreeMap<usize, String>,
+) -> Result<(), String> {
+    let next_vtable_id = compiled
+        .components
+        .values()
+        .filter_map(|component| component.template.as_ref())
+        .flat_map(vtable_ids)
+        .chain(interpreted.keys().copied())
+        .max()
+        .map_or(0, |id| id + 1);
+    let mut interpreted = Interpreted {
+        next_vtable_id,
+        sources: interpreted,
+    };
+    // like during expression compilation, the component's own properties are in scope everywhere
+    let properties: Vec<String> = compiled
+        .type_table
+        .get(type_id)
+        .map(|type_def| {
+            type_def
+                .property_definitions
+                .iter()
+                .map(|pd| pd.name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // depth first in template order, so that ids follow the source
+    let mut pending: Vec<_> = template
+        .get_root()
+        .into_iter()
+        .rev()
+        .map(|id| (id, properties.clone()))
+        .collect();
+    while let Some((id, scope)) = pending.pop() {
+        let Some(mut node) = template.get_node(&id).cloned() else {
+            continue;
+        };
+        // the symbols of a `for` are in scope for its key and its children
+        let mut inner_scope = scope.clone();
+        if let Some(cfsd) = node.control_flow_settings.as_mut() {
+            if let Some(condition) = &cfsd.condition_expression_paxel {
+                interpreted.assign(condition, &mut cfsd.condition_expression_info, &scope)?;
+            }
+            if let Some(index) = &cfsd.slot_index_expression_paxel {
+                interpreted.assign(index, &mut cfsd.slot_index_expression_info, &scope)?;
+            }
+            if let Some(source) = cfsd.repeat_source_definition.as_mut() {
+                let paxel = source
+                    .range_expression_paxel
+                    .as_ref()
+                    .or(source.symbolic_binding.as_ref());
+                if let Some(paxel) = paxel {
+                    interpreted.assign(paxel, &mut source.expression_info, &scope)?;
+                }
+            }
+            if let Some(predicate) = &cfsd.repeat_predicate_definition {
+                inner_scope.extend(predicate.get_symbols());
+            }
+            if let Some(key) = &cfsd.repeat_key_expression_paxel {
+                interpreted.assign(key, &mut cfsd.repeat_key_expression_info, &inner_scope)?;
+            }
+        }
+        for setting in node.settings.iter_mut().flatten() {
+            if let SettingElement::Setting(key, value) = setting {
+                // `id=some_identifier` and `class=some_identifier` are never compiled
+                if key.token_value != "id" && key.token_value != "class" {
+                    interpreted.assign_value(value, &scope)?;
+                }
+            }
+        }
+        template.set_node(id.clone(), node);
+        for child in template
+            .get_children(&id)
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+        {
+            pending.push((child, inner_scope.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Expressions registered for interpretation while reloading templates, by vtable id
+struct Interpreted<'a> {
+    next_vtable_id: usize,
+    sources: &'a mut BTreeMap<usize, String>,
+}
+
+impl Interpreted<'_> {
+    /// Registers `paxel` unless it has compiled code, i.e. `info`
+    fn assign(
+        &mut self,
+        paxel: &Token,
+        info: &mut Option<ExpressionCompilationInfo>,
+        scope: &[String],
+    ) -> Result<(), String> {
+        if info.is_some() {
+            return Ok(());
+        }
+        let source = &paxel.token_value;
+        let expr = pax_paxel_interp::parse(source)
+            .map_err(|e| format!("new expression `{}`: {}", paxel.raw_value, e))?;
+        if let Some(unsupported) = expr.unsupported() {
+            return Err(format!(
+                "new expression `{}`: `{}` can only be compiled",
+                paxel.raw_value, unsupported
+            ));
+        }
+        let dependencies = expr.symbols();
+        if let Some(unknown) = dependencies.iter().find(|symbol| !scope.contains(symbol)) {
+            return Err(format!(
+                "new expression `{}`: `{}` is not in scope",
+                paxel.raw_value, unknown
+            ));
+        }
+        let vtable_id = self.next_vtable_id;
+        self.next_vtable_id += 1;
+        self.sources.insert(vtable_id, source.clone());
+        *info = Some(ExpressionCompilationInfo {
+            vtable_id,
+            dependencies,
+        });
+        Ok(())
+    }
+
+    fn assign_value(
+        &mut self,
+        value: &mut ValueDefinition,
+        scope: &[String],
+    ) -> Result<(), String> {
+        match value {
+            ValueDefinition::Expression(paxel, info) | ValueDefinition::Identifier(paxel, info) => {
+                self.assign(paxel, info, scope)
+            }
+            ValueDefinition::Block(block) => {
+                for element in block.elements.iter_mut() {
+                    if let SettingElement::Setting(_, value) = element {
+                        self.assign_value(value, scope)?;
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Vtable ids of the compiled expressions in `template`
+fn vtable_ids(template: &ComponentTemplate) -> Vec<usize> {
+    let mut ids: Vec<usize> = template
+        .get_known_expressions()
+        .into_values()
+        .map(|info| info.vtable_id)
+        .collect();
+    for cfsd in template
+        .get_nodes()
+        .into_iter()
+        .filter_map(|node| node.control_flow_settings.as_ref())
+    {
+        let source_info = cfsd
+            .repeat_source_definition
+            .as_ref()
+            .and_then(|source| source.expression_info.as_ref());
+        ids.extend(
+            [
+                &cfsd.condition_expression_info,
+                &cfsd.slot_index_expression_info,
+                &cfsd.repeat_key_expression_info,
+            ]
+            .into_iter()
+            .flatten()
+            .chain(source_info)
+            .map(|info| info.vtable_id),
+        );
+    }
+    ids
+}
+
+/// Components that can be instantiated by pascal identifier. Components already used in the
+/// compiled template take precedence over others in the manifest with the same name.
+fn known_components(
+    compiled: &PaxManifest,
+    compiled_template: &ComponentTemplate,
+) -> HashMap<String, TypeId> {
+    let mut ambiguous = HashSet::new();
+    let mut known: HashMap<String, TypeId> = HashMap::new();
+    for (type_id, component) in &compiled.components {
+        if component.is_struct_only_component {
+            continue;
+        }
+        if let Some(pascal_identifier) = type_id.get_pascal_identifier() {
+            if known
+                .insert(pascal_identifier.clone(), type_id.clone())
+                .is_some()
+            {
+                ambiguous.insert(pascal_identifier);
+            }
+        }
+    }
+    known.retain(|pascal_identifier, _| !ambiguous.contains(pascal_identifier));
+
+    for node in compiled_template.get_nodes() {
+        if node.type_id.import_path().is_none() {
+            // control flow and comments
+            continue;
+        }
+        if let Some(pascal_identifier) = node.type_id.get_pascal_identifier() {
+            known.insert(pascal_identifier, node.type_id.clone());
+        }
+    }
+    known
+}
+
+fn collect_tag_identifiers(pair: Pair<Rule>, tags: &mut Vec<String>) {
+    if matches!(pair.as_rule(), Rule::open_tag | Rule::self_closing_tag) {
+        if let Some(identifier) = pair.clone().into_inner().next() {
+            tags.push(identifier.as_str().to_string());
+        }
+    }
+    for inner in pair.into_inner() {
+        collect_tag_identifiers(inner, tags);
+    }
+}
+
+/// (event, handler) pairs bound inline in `template`, e.g. `(click, self.increment)`
+fn event_handlers(template: &ComponentTemplate) -> HashSet<(String, String)> {
+    let mut handlers = HashSet::new();
+    for node in template.get_nodes() {
+        for setting in node.settings.iter().flatten() {
+            if let SettingElement::Setting(event, ValueDefinition::EventBindingTarget(handler)) =
+                setting
+            {
+                handlers.insert((event.token_value.clone(), handler.raw_value.clone()));
+            }
+        }
+    }
+    handlers
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+
+    use pax_manifest::{
+        ComponentDefinition, ExpressionCompilationInfo, PropertyDefinition, SettingElement,
+        TypeDefinition, ValueDefinition,
+    };
+
+    use super::*;
+
+    fn rectangle() -> TypeId {
+        TypeId::build_singleton("pax_std::primitives::Rectangle", Some("Rectangle"))
+    }
+
+    fn main_component() -> TypeId {
+        TypeId::build_singleton("crate::Example", Some("Example"))
+    }
+
+    /// A manifest for `pax` as if it had gone through expression compilation
+    fn compiled_manifest(pax: &str) -> PaxManifest {
+        let mut ctx = TemplateNodeParseContext {
+            pascal_identifier_to_type_id_map: HashMap::from([(
+                "Rectangle".to_string(),
+                rectangle(),
+            )]),
+            template: ComponentTemplate::new(
+                main_component(),
+                Some("/tmp/example.pax".to_string()),
+            ),
+        };
+        let ast = parse_pax_str(Rule::pax_component_definition, pax).unwrap();
+        parse_template_from_component_definition_string(&mut ctx, pax, ast);
+
+        let mut vtable_id = 0;
+        let mut next_info = || {
+            vtable_id += 1;
+            Some(ExpressionCompilationInfo {
+                vtable_id,
+                dependencies: vec![],
+            })
+        };
+        for node in ctx.template.get_nodes_mut() {
+            for setting in node.settings.iter_mut().flatten() {
+                if let SettingElement::Setting(key, value) = setting {
+                    match value {
+                        ValueDefinition::Expression(_, info) => *info = next_info(),
+                        ValueDefinition::Identifier(_, info) if key.token_value != "id" => {
+                            *info = next_info()
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if let Some(cfsd) = node.control_flow_settings.as_mut() {
+                if cfsd.condition_expression_paxel.is_some() {
+                    cfsd.condition_expression_info = next_info();
+                }
+                if let Some(source) = cfsd.repeat_source_definition.as_mut() {
+                    source.expression_info = next_info();
+                }
+                if cfsd.repeat_key_expression_paxel.is_some() {
+                    cfsd.repeat_key_expression_info = next_info();
+                }
+            }
+        }
+
+        let component =
+            |type_id: TypeId, template: Option<ComponentTemplate>| ComponentDefinition {
+                type_id,
+                is_main_component: template.is_some(),
+                is_primitive: template.is_none(),
+                is_struct_only_component: false,
+                module_path: "crate".to_string(),
+                primitive_instance_import_path: None,
+                template,
+                settings: Some(vec![]),
+                custom_events: vec![],
+            };
+        PaxManifest {
+            components: BTreeMap::from([
+                (
+                    main_component(),
+                    component(main_component(), Some(ctx.template)),
+                ),
+                (rectangle(), component(rectangle(), None)),
+            ]),
+            main_component_type_id: main_component(),
+            expression_specs: None,
+            type_table: HashMap::from([(
+                main_component(),
+                TypeDefinition {
+                    type_id: main_component(),
+                    inner_iterable_type_id: None,
+                    property_definitions: ["size", "count", "visible"]
+                        .map(|name| PropertyDefinition::primitive_with_name("usize", name))
+                        .to_vec(),
+                },
+            )]),
+            import_paths: HashSet::new(),
+        }
+    }
+
+    const COMPILED: &str = r#"
+        <Rectangle id=a width={self.size * 2} @click=self.increment />
+        for i in 0..self.count {
+            <Rectangle fill=color />
+        }
+    "#;
+
+    #[test]
+    fn reuses_compiled_expressions_for_rearranged_templates() {
+        let manifest = compiled_manifest(COMPILED);
+        let vtable_ids = |template: &ComponentTemplate| {
+            template
+                .get_known_expressions()
+                .into_values()
+                .map(|info| info.vtable_id)
+                .collect::<HashSet<_>>()
+        };
+
+        let pax = r#"
+            for i in 0..self.count {
+                <Rectangle fill=color width={self.size * 2} />
+            }
+            <Rectangle id=b @click=self.increment height={self.size * 2} />
+        "#;
+        let mut interpreted = BTreeMap::new();
+        let template =
+            reload_template(&manifest, &main_component(), pax, &mut interpreted).unwrap();
+        assert!(template.get_uncompiled_expressions().is_empty());
+        assert!(interpreted.is_empty());
+        assert_eq!(template.get_nodes().len(), 3);
+        let compiled_template = manifest.components[&main_component()].template.as_ref();
+        assert_eq!(
+            vtable_ids(&template),
+            vtable_ids(compiled_template.unwrap())
+        );
+        assert_eq!(
+            template.get_file_path(),
+            Some("/tmp/example.pax".to_string())
+        );
+    }
+
+    #[test]
+    fn interprets_expressions_without_generated_code() {
+        let manifest = compiled_manifest(COMPILED);
+        let pax = r#"
+            <Rectangle width={self.size * 2} height={self.size * 3} />
+            if self.visible {
+                for j in 0..self.count {
+                    <Rectangle width={j * 10px} />
+                }
+            }
+        "#;
+        let mut interpreted = BTreeMap::new();
+        let template =
+            reload_template(&manifest, &main_component(), pax, &mut interpreted).unwrap();
+        assert!(template.get_uncompiled_expressions().is_empty());
+
+        // numbered after the 3 compiled expressions
+        assert_eq!(
+            interpreted
+                .iter()
+                .map(|(id, paxel)| (*id, paxel.trim()))
+                .collect::<HashSet<_>>(),
+            HashSet::from([
+                (4, "self.size * 3"),
+                (5, "self.visible"),
+                (6, "0..self.count"),
+                (7, "j * 10px"),
+            ])
+        );
+        let known = template.get_known_expressions();
+        assert_eq!(known["{j * 10px}"].dependencies, vec!["j"]);
+        let compiled_template = manifest.components[&main_component()].template.as_ref();
+        assert_eq!(
+            known["{self.size * 2}"],
+            compiled_template.unwrap().get_known_expressions()["{self.size * 2}"]
+        );
+    }
+
+    #[test]
+    fn requires_rebuild_for_changes_without_generated_code() {
+        let manifest = compiled_manifest(COMPILED);
+        let reload = |pax: &str| {
+            reload_template(&manifest, &main_component(), pax, &mut BTreeMap::new()).unwrap_err()
+        };
+
+        assert!(
+            reload("<Rectangle width={self.missing * 3} />").contains("`missing` is not in scope")
+        );
+        assert!(
+            reload("for j in 0..3 { <Rectangle/> } <Rectangle width={j * 10px} />")
+                .contains("`j` is not in scope")
+        );
+        assert!(reload("<Rectangle width={self.size * Foo::bar(1)} />").contains("Foo::bar(1)"));
+        assert!(reload("<Rectangle @click=self.decrement />").contains("self.decrement"));
+        assert!(reload("<Ellipse />").contains("Ellipse"));
+        assert!(reload("<Rectangle /> @settings { #a { width: 10px } }").contains("@settings"));
+        assert!(!reload("<Rectangle").is_empty());
+    }
+
+    #[test]
+    fn rejects_capture_bindings_on_events_without_a_capture_phase() {
+        let manifest = compiled_manifest("<Rectangle @mount=self.increment />");
+        let pax = "<Rectangle\n    @mount.capture=self.increment />";
+        assert_eq!(
+            reload_template(&manifest, &main_component(), pax, &mut BTreeMap::new()).unwrap_err(),
+            "/tmp/example.pax:2:6: `@mount.capture` in `crate::Example`: it is only dispatched to the node itself, so it has no capture phase, bind `@mount` instead"
+        );
+    }
+}
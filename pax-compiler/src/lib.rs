@@ -16,22 +16,20 @@ pub mod errors;
 pub mod expressions;
 pub mod formatting;
 pub mod helpers;
+pub mod hot_reload;
 pub mod parsing;
 mod reexports;
+pub mod static_analysis;
 
 use color_eyre::eyre;
 use color_eyre::eyre::Report;
 use eyre::eyre;
 use fs_extra::dir::{self, CopyOptions};
-use helpers::{copy_dir_recursively, wait_with_output, ERR_SPAWN};
+use helpers::{copy_dir_recursively, wait_with_output};
 use pax_manifest::PaxManifest;
 use std::fs;
-use std::io::Write;
 use std::sync::{Arc, Mutex};
 
-#[cfg(unix)]
-use std::os::unix::process::CommandExt;
-
 use crate::building::{
     build_chassis_with_cartridge, clone_all_to_pkg_dir, update_type_id_prefixes_in_place,
 };
@@ -41,7 +39,7 @@ use crate::errors::source_map::SourceMap;
 use crate::reexports::generate_reexports_partial_rs;
 
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::Command;
 
 use crate::helpers::{
     get_host_crate_info, get_or_create_pax_directory, get_version_of_whitelisted_packages,
@@ -50,8 +48,6 @@ use crate::helpers::{
     PAX_CREATE_TEMPLATE,
 };
 
-const IS_DESIGN_TIME_BUILD: bool = cfg!(feature = "designtime");
-
 pub struct RunContext {
     pub target: RunTarget,
     pub path: String,
@@ -60,6 +56,8 @@ pub struct RunContext {
     pub is_libdev_mode: bool,
     pub process_child_ids: Arc<Mutex<Vec<u64>>>,
     pub is_release: bool,
+    /// Hot reload `.pax` changes into the running app (web only)
+    pub watch: bool,
 }
 
 #[derive(PartialEq)]
@@ -100,8 +98,8 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
         }
     }
 
-    //First we clone dependencies into the .pax/pkg directory.  We must do this before running
-    //the parser binary specifical for libdev in pax-example — see pax-example/Cargo.toml where
+    //First we clone dependencies into the .pax/pkg directory.  We must do this before analyzing
+    //the project's sources, specifically for libdev in pax-example — see pax-example/Cargo.toml where
     //dependency paths are `.pax/pkg/*`.
     let pax_dir = get_or_create_pax_directory(&ctx.path);
 
@@ -120,25 +118,9 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
         set_path_on_pax_dependencies(&full_path);
     }
 
-    println!("{} 🛠️  Building parser binary with `cargo`...", *PAX_BADGE);
-
-    // Run parser bin from host project with `--features parser`
-    let output = run_parser_binary(&ctx.path, Arc::clone(&ctx.process_child_ids));
-
-    // Forward stderr only
-    std::io::stderr()
-        .write_all(output.stderr.as_slice())
-        .unwrap();
-
-    if !output.status.success() {
-        return Err(eyre!(
-            "Parsing failed — there is likely a syntax error in the provided pax"
-        ));
-    }
-
-    let out = String::from_utf8(output.stdout).unwrap();
-    let mut manifest: PaxManifest =
-        serde_json::from_str(&out).expect(&format!("Malformed JSON from parser: {}", &out));
+    println!("{} 🔍 Analyzing Rust sources...", *PAX_BADGE);
+    let mut manifest = static_analysis::analyze_project(Path::new(&ctx.path))
+        .map_err(|e| eyre!("Parsing failed — {}", e))?;
     let host_cargo_toml_path = Path::new(&ctx.path).join("Cargo.toml");
     let host_crate_info = get_host_crate_info(&host_cargo_toml_path);
     update_type_id_prefixes_in_place(&mut manifest, &host_crate_info);
@@ -155,8 +137,12 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
 
     //7. Build the appropriate `chassis` from source, with the patched `Cargo.toml`, Properties Coproduct, and Cartridge from above
     println!("{} 🧱 Building cartridge with `cargo`", *PAX_BADGE);
-    let build_dir =
-        build_chassis_with_cartridge(&pax_dir, &ctx, Arc::clone(&ctx.process_child_ids))?;
+    let build_dir = build_chassis_with_cartridge(
+        &pax_dir,
+        &ctx,
+        &manifest,
+        Arc::clone(&ctx.process_child_ids),
+    )?;
     Ok((manifest, build_dir))
 }
 
@@ -255,36 +241,12 @@ pub fn perform_create(ctx: &CreateContext) {
     );
 }
 
-/// Executes a shell command to run the feature-flagged parser at the specified path
-/// Returns an output object containing bytestreams of stdout/stderr as well as an exit code
-pub fn run_parser_binary(path: &str, process_child_ids: Arc<Mutex<Vec<u64>>>) -> Output {
-    let mut cmd = Command::new("cargo");
-    cmd.current_dir(path)
-        .arg("run")
-        .arg("--release") // --release speeds up Pest parsing substantially
-        .arg("--bin")
-        .arg("parser")
-        .arg("--features")
-        .arg("parser")
-        .arg("--color")
-        .arg("always")
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
-
-    if IS_DESIGN_TIME_BUILD {
-        cmd.arg("--features").arg("designtime");
-    }
-
-    #[cfg(unix)]
-    unsafe {
-        cmd.pre_exec(pre_exec_hook);
-    }
-
-    let child = cmd.spawn().expect(ERR_SPAWN);
-
-    // child.stdin.take().map(drop);
-    let output = wait_with_output(&process_child_ids, child);
-    output
+/// Statically analyzes the Pax project at the specified path and returns its manifest,
+/// serialized to JSON
+pub fn perform_parse(path: &str) -> eyre::Result<String, Report> {
+    let manifest = static_analysis::analyze_project(Path::new(path))
+        .map_err(|e| eyre!("Parsing failed — {}", e))?;
+    Ok(serde_json::to_string_pretty(&manifest)?)
 }
 
 impl From<&str> for RunTarget {
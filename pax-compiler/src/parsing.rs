@@ -2,16 +2,19 @@ use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 
-use pax_lang::{parse_pax_str, Assoc, Op, Pair, Pairs, Parser, PaxParser, PrattParser, Rule, Span};
+use pax_lang::{
+    parse_pax_str, span_error, Assoc, Op, Pair, Pairs, Parser, PaxParser, PrattParser, Rule, Span,
+};
 use pax_manifest::{
     escape_identifier, get_primitive_type_table, ComponentDefinition, ComponentTemplate,
-    ControlFlowRepeatPredicateDefinition, ControlFlowRepeatSourceDefinition,
-    ControlFlowSettingsDefinition, LiteralBlockDefinition, LocationInfo, PropertyDefinition,
-    PropertyDefinitionFlags, SettingElement, SettingsBlockElement, TemplateNodeDefinition,
-    TemplateNodeId, Token, TokenType, TreeLocation, TypeDefinition, TypeId, TypeTable,
-    ValueDefinition,
+    ConditionalBranchDefinition, ControlFlowRepeatPredicateDefinition,
+    ControlFlowRepeatSourceDefinition, ControlFlowSettingsDefinition, KeyframeDefinition,
+    LiteralBlockDefinition, LocationInfo, PropertyDefinition, PropertyDefinitionFlags,
+    SettingElement, SettingsBlockElement, TemplateNodeDefinition, TemplateNodeId,
+    TimelineDefinition, TimelineTrackDefinition, Token, TokenType, TreeLocation, TypeDefinition,
+    TypeId, TypeTable, ValueDefinition,
 };
-use pax_runtime_api::{Color, Fill, Size, Stroke};
+use pax_runtime_api::{Color, EasingCurve, Fill, Size, Stroke};
 
 use crate::expressions::clean_and_split_symbols;
 
@@ -359,6 +362,18 @@ pub fn parse_template_from_component_definition_string(
         });
 }
 
+/// Collects the pascal identifiers of every tag in `pair`, e.g. `Rectangle` for `<Rectangle/>`
+pub fn collect_tag_identifiers(pair: Pair<Rule>, tags: &mut Vec<String>) {
+    if matches!(pair.as_rule(), Rule::open_tag | Rule::self_closing_tag) {
+        if let Some(identifier) = pair.clone().into_inner().next() {
+            tags.push(identifier.as_str().to_string());
+        }
+    }
+    for inner in pair.into_inner() {
+        collect_tag_identifiers(inner, tags);
+    }
+}
+
 pub struct TemplateNodeParseContext {
     pub template: ComponentTemplate,
     pub pascal_identifier_to_type_id_map: HashMap<String, TypeId>,
@@ -447,47 +462,112 @@ fn recurse_visit_tag_pairs_for_template(
             let any_tag_pair = any_tag_pair.into_inner().next().unwrap();
             let _template_node_definition = match any_tag_pair.as_rule() {
                 Rule::statement_if => {
-                    let mut statement_if = any_tag_pair.into_inner();
-                    let expression_body = statement_if.next().unwrap();
-                    let expression_body_location = span_to_location(&expression_body.as_span());
-                    let expression_body_token = Token::new(
-                        expression_body.as_str().to_string(),
-                        TokenType::IfExpression,
-                        expression_body_location,
-                        pax,
-                    );
+                    /* statement_if = {"if" ~ expression_body ~ "{" ~ inner_nodes ~ "}" ~ statement_else_if* ~ statement_else?} */
+
+                    // `else if` / `else` branches are desugared into a chain of sibling `if`s: each branch's
+                    // condition is its own expression conjoined with the negation of every preceding one.
+                    // The branch as written is kept in `conditional_branch`.
+                    let mut preceding_conditions: Vec<String> = vec![];
+                    let else_branches = any_tag_pair.clone().into_inner().filter(|pair| {
+                        matches!(
+                            pair.as_rule(),
+                            Rule::statement_else_if | Rule::statement_else
+                        )
+                    });
+                    let branches = std::iter::once(any_tag_pair.clone()).chain(else_branches);
+
+                    for branch in branches {
+                        let branch_rule = branch.as_rule();
+                        let branch_start = branch.as_span().start();
+                        let mut branch_pairs = branch.into_inner();
+                        let (condition, condition_location) =
+                            if branch_pairs.peek().unwrap().as_rule() == Rule::expression_body {
+                                let expression_body = branch_pairs.next().unwrap();
+                                (
+                                    Some(expression_body.as_str().trim().to_string()),
+                                    span_to_location(&expression_body.as_span()),
+                                )
+                            } else {
+                                // `else` has no condition of its own, so its token is the keyword
+                                let else_keyword =
+                                    Span::new(pax, branch_start, branch_start + "else".len())
+                                        .unwrap();
+                                (None, span_to_location(&else_keyword))
+                            };
+
+                        let conditional_branch = match branch_rule {
+                            Rule::statement_else_if => {
+                                ConditionalBranchDefinition::ElseIf(Token::new(
+                                    condition.clone().unwrap(),
+                                    TokenType::IfExpression,
+                                    condition_location.clone(),
+                                    pax,
+                                ))
+                            }
+                            Rule::statement_else => ConditionalBranchDefinition::Else,
+                            _ => ConditionalBranchDefinition::If,
+                        };
 
-                    //`if` TemplateNodeDefinition
-                    let template_node = TemplateNodeDefinition {
-                        control_flow_settings: Some(ControlFlowSettingsDefinition {
-                            condition_expression_paxel: Some(expression_body_token),
-                            condition_expression_info: None, //This will be written back to this data structure later, during expression compilation
-                            slot_index_expression_paxel: None,
-                            slot_index_expression_info: None,
-                            repeat_predicate_definition: None,
-                            repeat_source_definition: None,
-                        }),
-                        type_id: TypeId::build_if(),
-                        settings: None,
-                        raw_comment_string: None,
-                    };
+                        let mut conjuncts: Vec<String> = preceding_conditions
+                            .iter()
+                            .map(|preceding| format!("!({})", preceding))
+                            .collect();
+                        if let Some(condition) = &condition {
+                            conjuncts.push(format!("({})", condition));
+                        }
+                        let combined_condition = if preceding_conditions.is_empty() {
+                            condition.clone().unwrap()
+                        } else {
+                            conjuncts.join(" && ")
+                        };
+                        let expression_body_token = Token::new(
+                            combined_condition,
+                            TokenType::IfExpression,
+                            condition_location,
+                            pax,
+                        );
 
-                    let id = match location {
-                        TreeLocation::Root => ctx.template.add_root_node_back(template_node),
-                        TreeLocation::Parent(id) => ctx.template.add_child_back(id, template_node),
-                    };
+                        //`if` TemplateNodeDefinition
+                        let template_node = TemplateNodeDefinition {
+                            control_flow_settings: Some(ControlFlowSettingsDefinition {
+                                condition_expression_paxel: Some(expression_body_token),
+                                condition_expression_info: None, //This will be written back to this data structure later, during expression compilation
+                                slot_index_expression_paxel: None,
+                                slot_index_expression_info: None,
+                                repeat_predicate_definition: None,
+                                repeat_source_definition: None,
+                                repeat_key_expression_paxel: None,
+                                repeat_key_expression_info: None,
+                                conditional_branch: Some(conditional_branch),
+                            }),
+                            type_id: TypeId::build_if(),
+                            settings: None,
+                            raw_comment_string: None,
+                        };
 
-                    let prospective_inner_nodes = statement_if.next();
+                        let id = match location.clone() {
+                            TreeLocation::Root => ctx.template.add_root_node_back(template_node),
+                            TreeLocation::Parent(id) => {
+                                ctx.template.add_child_back(id, template_node)
+                            }
+                        };
 
-                    if let Some(inner_nodes) = prospective_inner_nodes {
-                        inner_nodes.into_inner().for_each(|sub_tag_pair| {
-                            recurse_visit_tag_pairs_for_template(
-                                ctx,
-                                sub_tag_pair,
-                                pax,
-                                TreeLocation::Parent(id.clone().get_template_node_id()),
-                            );
-                        })
+                        let prospective_inner_nodes = branch_pairs.next();
+
+                        if let Some(inner_nodes) = prospective_inner_nodes {
+                            inner_nodes.into_inner().for_each(|sub_tag_pair| {
+                                recurse_visit_tag_pairs_for_template(
+                                    ctx,
+                                    sub_tag_pair,
+                                    pax,
+                                    TreeLocation::Parent(id.clone().get_template_node_id()),
+                                );
+                            })
+                        }
+
+                        if let Some(condition) = condition {
+                            preceding_conditions.push(condition);
+                        }
                     }
                 }
                 Rule::statement_for => {
@@ -496,7 +576,22 @@ fn recurse_visit_tag_pairs_for_template(
                     let mut predicate_declaration = for_statement.next().unwrap().into_inner();
                     let source = for_statement.next().unwrap();
 
-                    let prospective_inner_nodes = for_statement.next();
+                    let mut prospective_inner_nodes = for_statement.next();
+                    if let Some(key) = prospective_inner_nodes
+                        .clone()
+                        .filter(|p| p.as_rule() == Rule::statement_for_key)
+                    {
+                        //optional key, like the `item.id` in `for item in self.items key=item.id`
+                        let expression_body = key.into_inner().next().unwrap();
+                        let expression_body_location = span_to_location(&expression_body.as_span());
+                        cfavd.repeat_key_expression_paxel = Some(Token::new(
+                            expression_body.as_str().trim().to_string(),
+                            TokenType::ForKey,
+                            expression_body_location,
+                            pax,
+                        ));
+                        prospective_inner_nodes = for_statement.next();
+                    }
 
                     if predicate_declaration.clone().count() > 1 {
                         //tuple, like the `elem, i` in `for (elem, i) in self.some_list`
@@ -642,6 +737,9 @@ fn recurse_visit_tag_pairs_for_template(
                             slot_index_expression_info: None, //This will be written back to this data structure later, during expression compilation
                             repeat_predicate_definition: None,
                             repeat_source_definition: None,
+                            repeat_key_expression_paxel: None,
+                            repeat_key_expression_info: None,
+                            conditional_branch: None,
                         }),
                         type_id: TypeId::build_slot(),
                         settings: None,
@@ -706,11 +804,18 @@ fn parse_literal_function(literal_function_full: Pair<Rule>, pax: &str) -> Token
 }
 
 fn parse_event_id(event_id_full: Pair<Rule>, pax: &str) -> Token {
-    let event_id = event_id_full.clone().into_inner().next().unwrap();
+    // event_id = ${"@" ~ identifier ~ event_capture?}
+    // capture handlers are registered under e.g. `click.capture`
+    let mut event_id_pairs = event_id_full.clone().into_inner();
+    let event_id = event_id_pairs.next().unwrap();
+    let capture = event_id_pairs
+        .next()
+        .map(|c| c.as_str())
+        .unwrap_or_default();
 
     let event_id_location = span_to_location(&event_id.as_span());
     let event_id_token = Token::new_with_raw_value(
-        event_id.as_str().to_string(),
+        event_id.as_str().to_string() + capture,
         event_id_full.as_str().to_string(),
         TokenType::EventId,
         event_id_location,
@@ -719,6 +824,127 @@ fn parse_event_id(event_id_full: Pair<Rule>, pax: &str) -> Token {
     event_id_token
 }
 
+fn parse_timeline(settings_timeline: Pair<Rule>, pax: &str) -> Result<TimelineDefinition, String> {
+    let mut timeline_pairs = settings_timeline.into_inner();
+    let name = timeline_pairs.next().unwrap();
+    let name_token = Token::new(
+        name.as_str().to_string(),
+        TokenType::Identifier,
+        span_to_location(&name.as_span()),
+        pax,
+    );
+    let mut timeline = TimelineDefinition {
+        name: name_token,
+        autoplay: false,
+        looping: false,
+        tracks: vec![],
+    };
+    for timeline_pair in timeline_pairs {
+        match timeline_pair.as_rule() {
+            Rule::timeline_options => {
+                for option in timeline_pair.into_inner() {
+                    match option.as_str() {
+                        "autoplay" => timeline.autoplay = true,
+                        "loop" => timeline.looping = true,
+                        _ => unreachable!("Parsing error: unknown timeline option {}", option),
+                    }
+                }
+            }
+            Rule::timeline_track => {
+                let mut track_pairs = timeline_pair.into_inner();
+                let property = track_pairs.next().unwrap();
+                let property_token = Token::new(
+                    property.as_str().to_string(),
+                    TokenType::SettingKey,
+                    span_to_location(&property.as_span()),
+                    pax,
+                );
+                let keyframes = track_pairs
+                    .map(|keyframe| parse_keyframe(keyframe, &timeline.name.token_value, pax))
+                    .collect::<Result<_, _>>()?;
+                timeline.tracks.push(TimelineTrackDefinition {
+                    property: property_token,
+                    keyframes,
+                });
+            }
+            Rule::comment => {}
+            _ => unreachable!("Parsing error: {:?}", timeline_pair.as_rule()),
+        }
+    }
+    Ok(timeline)
+}
+
+fn parse_keyframe(
+    keyframe: Pair<Rule>,
+    timeline_name: &str,
+    pax: &str,
+) -> Result<KeyframeDefinition, String> {
+    let mut keyframe_pairs = keyframe.into_inner();
+
+    let time = keyframe_pairs.next().unwrap();
+    let mut time_pairs = time.clone().into_inner();
+    let number = time_pairs.next().unwrap().as_str().parse::<f64>();
+    let millis = match (number, time_pairs.next().unwrap().as_str()) {
+        (Ok(number), "s") => number * 1000.0,
+        (Ok(number), _) => number,
+        (Err(_), _) => {
+            return Err(span_error(
+                time.as_span(),
+                format!("invalid keyframe time in timeline {}", timeline_name),
+            ))
+        }
+    };
+    if millis < 0.0 {
+        return Err(span_error(
+            time.as_span(),
+            format!(
+                "keyframes can't be placed before the start of timeline {}",
+                timeline_name
+            ),
+        ));
+    }
+
+    let value = keyframe_pairs.next().unwrap();
+    let raw_value = value.as_str().trim();
+    //we want to pratt-parse literals, mostly to unpack `px` and `%` (recursively)
+    let (output_string, _) = crate::parsing::run_pratt_parser(raw_value);
+    let value_token = Token::new_with_raw_value(
+        output_string,
+        raw_value.to_string(),
+        TokenType::LiteralValue,
+        span_to_location(&value.as_span()),
+        pax,
+    );
+
+    let easing = keyframe_pairs
+        .next()
+        .map(|easing| {
+            if EasingCurve::from_name(easing.as_str()).is_none() {
+                return Err(span_error(
+                    easing.as_span(),
+                    format!(
+                        "unknown easing curve {} in timeline {}",
+                        easing.as_str(),
+                        timeline_name
+                    ),
+                ));
+            }
+            Ok(Token::new(
+                easing.as_str().to_string(),
+                TokenType::Identifier,
+                span_to_location(&easing.as_span()),
+                pax,
+            ))
+        })
+        .transpose()?;
+
+    Ok(KeyframeDefinition {
+        time_ms: millis.round() as u64,
+        value: value_token,
+        easing,
+    })
+}
+
 fn parse_inline_attribute_from_final_pairs_of_tag(
     final_pairs_of_tag: Pairs<Rule>,
     pax: &str,
@@ -983,84 +1209,67 @@ fn derive_value_definition_from_literal_object_pair(
 pub fn parse_settings_from_component_definition_string(
     pax: &str,
     pax_component_definition: Pair<Rule>,
-) -> Vec<SettingsBlockElement> {
+) -> Result<Vec<SettingsBlockElement>, String> {
     let mut settings: Vec<SettingsBlockElement> = vec![];
 
-    pax_component_definition
+    let settings_blocks = pax_component_definition
         .into_inner()
-        .for_each(|top_level_pair| {
-            match top_level_pair.as_rule() {
-                Rule::settings_block_declaration => {
-                    top_level_pair
-                        .into_inner()
-                        .for_each(|top_level_settings_block_entity| {
-                            match top_level_settings_block_entity.as_rule() {
-                                Rule::settings_event_binding => {
-                                    //event handler binding in the form of `@pre_render: handle_pre_render`
-                                    let mut settings_event_binding_pairs =
-                                        top_level_settings_block_entity.into_inner();
-                                    let event_id_token = parse_event_id(
-                                        settings_event_binding_pairs.next().unwrap(),
-                                        pax,
-                                    );
-                                    let literal_function_token = parse_literal_function(
-                                        settings_event_binding_pairs.next().unwrap(),
-                                        pax,
-                                    );
-                                    let handler_element: SettingsBlockElement =
-                                        SettingsBlockElement::Handler(
-                                            event_id_token,
-                                            vec![literal_function_token],
-                                        );
-                                    settings.push(handler_element);
-                                }
-                                Rule::selector_block => {
-                                    //selector_block => settings_key_value_pair where v is a ValueDefinition
-                                    let mut selector_block_pairs =
-                                        top_level_settings_block_entity.into_inner();
-                                    //first pair is the selector itself
-                                    let raw_selector = selector_block_pairs.next().unwrap();
-                                    let raw_value_location =
-                                        span_to_location(&raw_selector.as_span());
-                                    let selector: String = raw_selector
-                                        .as_str()
-                                        .chars()
-                                        .filter(|c| !c.is_whitespace())
-                                        .collect();
-                                    let token = Token::new(
-                                        selector,
-                                        TokenType::Selector,
-                                        raw_value_location,
-                                        pax,
-                                    );
-                                    let literal_object = selector_block_pairs.next().unwrap();
-
-                                    settings.push(SettingsBlockElement::SelectorBlock(
-                                        token,
-                                        derive_value_definition_from_literal_object_pair(
-                                            literal_object,
-                                            pax,
-                                        ),
-                                    ));
-                                }
-                                Rule::comment => {
-                                    let comment =
-                                        top_level_settings_block_entity.as_str().to_string();
-                                    settings.push(SettingsBlockElement::Comment(comment));
-                                }
-                                _ => {
-                                    unreachable!(
-                                        "Parsing error: {:?}",
-                                        top_level_settings_block_entity.as_rule()
-                                    );
-                                }
-                            }
-                        });
+        .filter(|pair| pair.as_rule() == Rule::settings_block_declaration);
+    for settings_block in settings_blocks {
+        for top_level_settings_block_entity in settings_block.into_inner() {
+            match top_level_settings_block_entity.as_rule() {
+                Rule::settings_event_binding => {
+                    //event handler binding in the form of `@pre_render: handle_pre_render`
+                    let mut settings_event_binding_pairs =
+                        top_level_settings_block_entity.into_inner();
+                    let event_id_token =
+                        parse_event_id(settings_event_binding_pairs.next().unwrap(), pax);
+                    let literal_function_token =
+                        parse_literal_function(settings_event_binding_pairs.next().unwrap(), pax);
+                    let handler_element: SettingsBlockElement =
+                        SettingsBlockElement::Handler(event_id_token, vec![literal_function_token]);
+                    settings.push(handler_element);
+                }
+                Rule::settings_timeline => {
+                    //keyframe timeline in the form of `@timeline intro { opacity: [0ms: 0.0, 1s: 1.0] }`
+                    settings.push(SettingsBlockElement::Timeline(parse_timeline(
+                        top_level_settings_block_entity,
+                        pax,
+                    )?));
+                }
+                Rule::selector_block => {
+                    //selector_block => settings_key_value_pair where v is a ValueDefinition
+                    let mut selector_block_pairs = top_level_settings_block_entity.into_inner();
+                    //first pair is the selector itself
+                    let raw_selector = selector_block_pairs.next().unwrap();
+                    let raw_value_location = span_to_location(&raw_selector.as_span());
+                    let selector: String = raw_selector
+                        .as_str()
+                        .chars()
+                        .filter(|c| !c.is_whitespace())
+                        .collect();
+                    let token = Token::new(selector, TokenType::Selector, raw_value_location, pax);
+                    let literal_object = selector_block_pairs.next().unwrap();
+
+                    settings.push(SettingsBlockElement::SelectorBlock(
+                        token,
+                        derive_value_definition_from_literal_object_pair(literal_object, pax),
+                    ));
+                }
+                Rule::comment => {
+                    let comment = top_level_settings_block_entity.as_str().to_string();
+                    settings.push(SettingsBlockElement::Comment(comment));
+                }
+                _ => {
+                    unreachable!(
+                        "Parsing error: {:?}",
+                        top_level_settings_block_entity.as_rule()
+                    );
                 }
-                _ => {}
             }
-        });
-    settings
+        }
+    }
+    Ok(settings)
 }
 
 pub struct ParsingContext {
@@ -1138,7 +1347,8 @@ pub fn assemble_component_definition(
     //populate template_node_definitions vec, needed for traversing node tree at codegen-time
     ctx.template_node_definitions = tpc.template.clone();
 
-    let settings = parse_settings_from_component_definition_string(pax, ast);
+    let settings = parse_settings_from_component_definition_string(pax, ast)
+        .unwrap_or_else(|err| panic!("{}", err));
 
     let new_def = ComponentDefinition {
         is_primitive: false,
@@ -1149,6 +1359,7 @@ pub fn assemble_component_definition(
         template: Some(tpc.template),
         settings: Some(settings),
         module_path: modified_module_path,
+        custom_events: vec![],
     };
 
     (ctx, new_def)
@@ -1178,6 +1389,7 @@ pub fn assemble_struct_only_component_definition(
         primitive_instance_import_path: None,
         template: None,
         settings: None,
+        custom_events: vec![],
     };
     (ctx, new_def)
 }
@@ -1198,6 +1410,7 @@ pub fn assemble_primitive_definition(
         template: None,
         settings: None,
         module_path: modified_module_path,
+        custom_events: vec![],
     }
 }
 
@@ -1706,3 +1919,247 @@ impl<T: Reflectable> Reflectable for std::vec::Vec<T> {
         Some(T::get_type_id())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn else_if_and_else_branches_desugar_to_sibling_conditionals() {
+        let pax = "if a { <Rectangle/> } else if b > 2 { <Ellipse/> } else { <Text/> }";
+        let mut ctx = TemplateNodeParseContext {
+            pascal_identifier_to_type_id_map: HashMap::new(),
+            template: ComponentTemplate::new(TypeId::build_blank_component("Test"), None),
+        };
+        let pax_component_definition = parse_pax_str(Rule::pax_component_definition, pax).unwrap();
+        parse_template_from_component_definition_string(&mut ctx, pax, pax_component_definition);
+
+        let roots = ctx.template.get_root();
+        let conditions: Vec<String> = roots
+            .iter()
+            .map(|id| {
+                let node = ctx.template.get_node(id).unwrap();
+                assert_eq!(node.type_id, TypeId::build_if());
+                assert_eq!(ctx.template.get_children(id).unwrap().len(), 1);
+                let cfsd = node.control_flow_settings.as_ref().unwrap();
+                cfsd.condition_expression_paxel
+                    .as_ref()
+                    .unwrap()
+                    .token_value
+                    .clone()
+            })
+            .collect();
+        assert_eq!(
+            conditions,
+            vec![
+                "a".to_string(),
+                "!(a) && (b > 2)".to_string(),
+                "!(a) && !(b > 2)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn else_if_and_else_branches_are_recorded_as_written() {
+        let pax = "if a { <Rectangle/> } else if b > 2 { <Ellipse/> } else { <Text/> }";
+        let mut ctx = TemplateNodeParseContext {
+            pascal_identifier_to_type_id_map: HashMap::new(),
+            template: ComponentTemplate::new(TypeId::build_blank_component("Test"), None),
+        };
+        let pax_component_definition = parse_pax_str(Rule::pax_component_definition, pax).unwrap();
+        parse_template_from_component_definition_string(&mut ctx, pax, pax_component_definition);
+
+        let cfsds: Vec<ControlFlowSettingsDefinition> = ctx
+            .template
+            .get_root()
+            .iter()
+            .map(|id| {
+                let node = ctx.template.get_node(id).unwrap();
+                node.control_flow_settings.clone().unwrap()
+            })
+            .collect();
+        let branches: Vec<_> = cfsds
+            .iter()
+            .map(|cfsd| cfsd.conditional_branch.clone().unwrap())
+            .collect();
+        let ConditionalBranchDefinition::ElseIf(else_if_condition) = &branches[1] else {
+            panic!("expected an `else if` branch, got {:?}", branches[1]);
+        };
+        assert_eq!(branches[0], ConditionalBranchDefinition::If);
+        assert_eq!(else_if_condition.raw_value, "b > 2");
+        assert_eq!(branches[2], ConditionalBranchDefinition::Else);
+
+        // the `else` branch is located at its keyword, not the whole branch
+        let locations: Vec<_> = cfsds
+            .iter()
+            .map(|cfsd| {
+                let location = cfsd
+                    .condition_expression_paxel
+                    .as_ref()
+                    .unwrap()
+                    .token_location
+                    .clone()
+                    .unwrap();
+                (location.start_line_col, location.end_line_col)
+            })
+            .collect();
+        assert_eq!(
+            locations,
+            vec![((0, 3), (0, 5)), ((0, 30), (0, 36)), ((0, 51), (0, 55))]
+        );
+    }
+
+    #[test]
+    fn for_statement_parses_optional_key_expression() {
+        let pax =
+            "for (item, i) in self.items key=item.id { <Rectangle/> } for j in 0..3 { <Ellipse/> }";
+        let mut ctx = TemplateNodeParseContext {
+            pascal_identifier_to_type_id_map: HashMap::new(),
+            template: ComponentTemplate::new(TypeId::build_blank_component("Test"), None),
+        };
+        let pax_component_definition = parse_pax_str(Rule::pax_component_definition, pax).unwrap();
+        parse_template_from_component_definition_string(&mut ctx, pax, pax_component_definition);
+
+        let roots = ctx.template.get_root();
+        assert_eq!(roots.len(), 2);
+        let keys: Vec<Option<String>> = roots
+            .iter()
+            .map(|id| {
+                assert_eq!(ctx.template.get_children(id).unwrap().len(), 1);
+                let node = ctx.template.get_node(id).unwrap();
+                let cfsd = node.control_flow_settings.as_ref().unwrap();
+                cfsd.repeat_key_expression_paxel
+                    .as_ref()
+                    .map(|t| t.token_value.clone())
+            })
+            .collect();
+        assert_eq!(keys, vec![Some("item.id".to_string()), None]);
+    }
+
+    #[test]
+    fn settings_parse_timelines() {
+        let pax = "<Rectangle/>
+            @settings {
+                @timeline intro (autoplay, loop) {
+                    // fade in, then slide
+                    opacity: [0ms: 0.0, 300ms: 1.0 out_quad],
+                    x: [0.3s: 0px, 1s: 100px in_out_cubic]
+                }
+                @mount: handle_mount
+            }";
+        let pax_component_definition = parse_pax_str(Rule::pax_component_definition, pax).unwrap();
+        let settings =
+            parse_settings_from_component_definition_string(pax, pax_component_definition).unwrap();
+        let SettingsBlockElement::Timeline(timeline) = &settings[0] else {
+            panic!("expected a timeline, got {:?}", settings[0]);
+        };
+        assert_eq!(timeline.name.token_value, "intro");
+        assert!(timeline.autoplay && timeline.looping);
+        let tracks: Vec<(&str, Vec<(u64, &str, Option<&str>)>)> = timeline
+            .tracks
+            .iter()
+            .map(|track| {
+                let keyframes = track
+                    .keyframes
+                    .iter()
+                    .map(|k| {
+                        (
+                            k.time_ms,
+                            k.value.raw_value.as_str(),
+                            k.easing.as_ref().map(|e| e.token_value.as_str()),
+                        )
+                    })
+                    .collect();
+                (track.property.token_value.as_str(), keyframes)
+            })
+            .collect();
+        assert_eq!(
+            tracks,
+            vec![
+                (
+                    "opacity",
+                    vec![(0, "0.0", None), (300, "1.0", Some("out_quad"))]
+                ),
+                (
+                    "x",
+                    vec![(300, "0px", None), (1000, "100px", Some("in_out_cubic"))]
+                ),
+            ]
+        );
+        assert!(matches!(settings[1], SettingsBlockElement::Handler(..)));
+    }
+
+    #[test]
+    fn invalid_keyframes_are_located_parse_errors() {
+        let parse = |keyframes: &str| {
+            let pax = format!(
+                "<Rectangle/>\n@settings {{\n    @timeline intro {{ opacity: [{}] }}\n}}",
+                keyframes
+            );
+            let ast = parse_pax_str(Rule::pax_component_definition, &pax).unwrap();
+            parse_settings_from_component_definition_string(&pax, ast)
+        };
+        assert!(parse("0ms: 0.0, 1s: 1.0 out_quad").is_ok());
+
+        let err = parse("0ms: 0.0, 1s: 1.0 wobbly").unwrap_err();
+        assert!(err.contains("unknown easing curve wobbly in timeline intro"));
+        assert!(err.contains("3:51"), "{}", err);
+
+        let err = parse("-5ms: 0.0").unwrap_err();
+        assert!(err.contains("keyframes can't be placed before the start of timeline intro"));
+        assert!(err.contains("3:33"), "{}", err);
+    }
+
+    #[test]
+    fn event_bindings_parse_capture_suffix() {
+        let pax = "<Rectangle @click.capture=self.on_capture @click=self.on_click/>
+            @settings {
+                @mouse_down.capture: handle_mouse_down
+            }";
+        let mut ctx = TemplateNodeParseContext {
+            pascal_identifier_to_type_id_map: HashMap::new(),
+            template: ComponentTemplate::new(TypeId::build_blank_component("Test"), None),
+        };
+        let pax_component_definition = parse_pax_str(Rule::pax_component_definition, pax).unwrap();
+        parse_template_from_component_definition_string(
+            &mut ctx,
+            pax,
+            pax_component_definition.clone(),
+        );
+
+        let root = ctx.template.get_root()[0].clone();
+        let node = ctx.template.get_node(&root).unwrap();
+        let events: Vec<(&str, &str)> = node
+            .settings
+            .iter()
+            .flatten()
+            .filter_map(|setting| match setting {
+                SettingElement::Setting(key, ValueDefinition::EventBindingTarget(handler)) => {
+                    Some((key.token_value.as_str(), handler.raw_value.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("click.capture", "self.on_capture"),
+                ("click", "self.on_click")
+            ]
+        );
+
+        let settings =
+            parse_settings_from_component_definition_string(pax, pax_component_definition).unwrap();
+        let SettingsBlockElement::Handler(key, _) = &settings[0] else {
+            panic!("expected a handler, got {:?}", settings[0]);
+        };
+        assert_eq!(key.token_value, "mouse_down.capture");
+
+        for pax in [
+            "<Rectangle @click .capture=self.on_capture/>",
+            "<Rectangle @ click=self.on_click/>",
+        ] {
+            assert!(parse_pax_str(Rule::pax_component_definition, pax).is_err());
+        }
+    }
+}
@@ -0,0 +1,1849 @@
+//! # Static Analysis
+//!
+//! Builds the [`PaxManifest`] for a Pax project by reading its Rust sources with `syn`, rather
+//! than compiling and running the project's `parser` binary. Starting from the `#[main]`
+//! component, this walks the same steps as the `Reflectable::parse_to_manifest` impls generated
+//! by `#[pax]`: every `#[pax]` type, the types of its fields and the components used in its
+//! template are added to the manifest under the same `TypeId`s the parser binary would report.
+//!
+//! Identifiers are resolved the way `rustc` would for the common cases — through `mod`
+//! declarations and `use` items (including renames, globs and `pub use` re-exports), across the
+//! host crate and every dependency that can declare `#[pax]` types. Anything that doesn't resolve
+//! to a `#[pax]` item is looked up among the built-in `Reflectable` types in [`crate::parsing`].
+//!
+//! Items, fields and variants behind a `#[cfg(...)]` that doesn't hold for the parser binary
+//! (built for the host platform in release, with the host's `parser` feature) are skipped.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pax_lang::custom_events::parse_custom_event_declarations;
+use pax_lang::{parse_pax_str, Rule};
+use pax_manifest::deserializer::from_pax_try_coerce;
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, CustomEventDefinition, PaxManifest, PropertyDefinition,
+    PropertyDefinitionFlags, SettingElement, SettingsBlockElement, TemplateNodeId, Token,
+    TypeDefinition, TypeId, ValueDefinition,
+};
+use pax_runtime_api::{Color, ColorChannel, Fill, Numeric, Rotation, Size, Stroke, Transform2D};
+use syn::{Attribute, Fields, GenericArgument, Item, Lit, Meta, NestedMeta, PathArguments, Type};
+
+use crate::parsing::{
+    assemble_component_definition, assemble_primitive_definition,
+    assemble_struct_only_component_definition, assemble_type_definition, collect_tag_identifiers,
+    ParsingContext, Reflectable,
+};
+
+/// `#[pax]` expands to paths under `pax_engine`, so only crates that depend on it can declare
+/// `#[pax]` types. Other dependencies are never read.
+const PAX_ENGINE_PACKAGE: &str = "pax-engine";
+
+/// Bound on chains of module re-exports, which may otherwise be cyclic
+const MAX_REEXPORT_DEPTH: usize = 32;
+
+/// Indentation of the first line of templates in `embedded_pax`
+const EMBEDDED_PAX_INDENT: usize = 24;
+
+const IS_DESIGN_TIME_BUILD: bool = cfg!(feature = "designtime");
+
+/// Statically analyzes the Pax project at `project_path` (the directory containing its
+/// `Cargo.toml`) and returns the same manifest its `parser` binary would print
+pub fn analyze_project(project_path: &Path) -> Result<PaxManifest, String> {
+    let mut analyzer = Analyzer::new(project_path)?;
+    let main_component = analyzer.find_main_component()?;
+
+    let ctx = ParsingContext::default();
+    let (mut ctx, _) = analyzer.parse_to_manifest(ctx, &Reflected::Pax(main_component))?;
+
+    //Special-case pax_runtime::api built-ins, like the generated `main` of the parser binary
+    ctx.import_paths.insert(Size::get_import_path());
+    ctx.import_paths.insert(Color::get_import_path());
+    ctx.import_paths.insert(ColorChannel::get_import_path());
+    ctx.import_paths.insert(Numeric::get_import_path());
+    ctx.import_paths.insert(Rotation::get_import_path());
+    ctx.import_paths.insert(Transform2D::get_import_path());
+
+    let manifest = PaxManifest {
+        components: ctx.component_definitions,
+        main_component_type_id: ctx.main_component_type_id,
+        expression_specs: None,
+        type_table: ctx.type_table,
+        import_paths: ctx.import_paths,
+    };
+    check_capture_bindings(&manifest)?;
+    check_timelines(&manifest)?;
+    Ok(manifest)
+}
+
+fn check_capture_bindings(manifest: &PaxManifest) -> Result<(), String> {
+    for component in manifest.components.values() {
+        check_component_capture_bindings(manifest, component, component.template.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Only events dispatched through the node tree (pointer, keyboard and form events) have
+/// a capture phase to bind to, in `template` or the `@settings` of `component`. Custom
+/// events are only dispatched to the handlers bound on the dispatching component, and
+/// lifecycle events (`mount`, `tick`, `focus`, ...) only to the node itself.
+pub(crate) fn check_component_capture_bindings(
+    manifest: &PaxManifest,
+    component: &ComponentDefinition,
+    template: Option<&ComponentTemplate>,
+) -> Result<(), String> {
+    let builtin_events = manifest.event_to_args_map();
+    let template_keys = template
+        .into_iter()
+        .flat_map(|template| template.get_nodes())
+        .flat_map(|tnd| tnd.settings.iter().flatten())
+        .filter_map(|setting| match setting {
+            SettingElement::Setting(key, ValueDefinition::EventBindingTarget(_)) => Some(key),
+            _ => None,
+        });
+    let settings_keys = component
+        .settings
+        .iter()
+        .flatten()
+        .filter_map(|setting| match setting {
+            SettingsBlockElement::Handler(key, _) => Some(key),
+            _ => None,
+        });
+    for key in template_keys.chain(settings_keys) {
+        let Some(event) = key.token_value.strip_suffix(".capture") else {
+            continue;
+        };
+        let reason = match builtin_events.get(event) {
+            Some(Some(_)) => continue,
+            Some(None) => "it is only dispatched to the node itself, so it has no capture phase",
+            None => "custom events have no capture phase",
+        };
+        return Err(located(
+            component,
+            key,
+            format!(
+                "`@{}` in `{}`: {}, bind `@{}` instead",
+                key.token_value, component.type_id, reason, event
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// `@timeline`s can only animate properties of their component, and keyframe values need
+/// to read as the type of the property they animate
+fn check_timelines(manifest: &PaxManifest) -> Result<(), String> {
+    for (type_id, component) in &manifest.components {
+        let properties = manifest
+            .type_table
+            .get(type_id)
+            .map(|type_definition| type_definition.property_definitions.as_slice())
+            .unwrap_or_default();
+        for timeline in manifest.get_component_timelines(type_id) {
+            for track in &timeline.tracks {
+                let Some(property) = properties
+                    .iter()
+                    .find(|p| p.name == track.property.token_value)
+                else {
+                    return Err(located(
+                        component,
+                        &track.property,
+                        format!(
+                            "`@timeline {}` animates `{}`, which isn't a property of `{}`",
+                            timeline.name.token_value, track.property.token_value, type_id
+                        ),
+                    ));
+                };
+                for keyframe in &track.keyframes {
+                    if let Err(e) = coerce_keyframe_value(&property.type_id, &keyframe.value) {
+                        return Err(located(
+                            component,
+                            &keyframe.value,
+                            format!(
+                                "`@timeline {}`: `{}` isn't a valid value for `{}`: {}",
+                                timeline.name.token_value,
+                                keyframe.value.raw_value,
+                                property.name,
+                                e
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads `value` as the built-in type `type_id`, the way the cartridge will when building
+/// timelines. Values of other types can only be read by the cartridge itself.
+fn coerce_keyframe_value(type_id: &TypeId, value: &Token) -> Result<(), String> {
+    macro_rules! coerce {
+        ($($t:ty),*) => {
+            $(
+                if *type_id == <$t as Reflectable>::get_type_id() {
+                    return from_pax_try_coerce::<$t>(&value.raw_value).map(|_| ());
+                }
+            )*
+        };
+    }
+    coerce!(usize, isize, i64, u64, i32, u32, i8, u8, f64, bool, String);
+    coerce!(Size, Color, ColorChannel, Rotation, Numeric);
+    Ok(())
+}
+
+/// Prefixes `message` with where `token` is in the pax of `component`, if known
+fn located(component: &ComponentDefinition, token: &Token, message: String) -> String {
+    let Some(location) = &token.token_location else {
+        return message;
+    };
+    // locations are within the framing of `embedded_pax`, which starts with an empty line
+    let (line, col) = location.start_line_col;
+    let col = if line == 1 {
+        col.saturating_sub(EMBEDDED_PAX_INDENT)
+    } else {
+        col
+    };
+    let source = match component.template.as_ref().and_then(|t| t.get_file_path()) {
+        Some(path) if path.ends_with(".pax") => path,
+        _ => format!("inlined pax of `{}`", component.type_id),
+    };
+    format!("{}:{}:{}: {}", source, line, col + 1, message)
+}
+
+/// (crate index, module index)
+type ModuleRef = (usize, usize);
+
+struct Crate {
+    manifest_dir: PathBuf,
+    /// Identifier of each dependency as used in paths, mapped to its package name
+    dependencies: HashMap<String, Dependency>,
+    /// Features enabled by default, and for the host those the parser binary is built with.
+    /// Used if `cargo metadata` can't resolve the features of the build.
+    default_features: HashSet<String>,
+    modules: Vec<Module>,
+}
+
+struct Dependency {
+    package: String,
+    path: Option<PathBuf>,
+}
+
+#[derive(Default)]
+struct CargoMetadata {
+    /// Manifest dir of every package in the host's dependency graph, by package name
+    packages: HashMap<String, PathBuf>,
+    /// Features resolved for the build of the parser binary, by manifest dir
+    features: HashMap<PathBuf, HashSet<String>>,
+}
+
+impl CargoMetadata {
+    fn new(metadata: cargo_metadata::Metadata) -> Self {
+        let mut packages = HashMap::new();
+        let mut manifest_dirs = HashMap::new();
+        for package in metadata.packages {
+            let dir = package.manifest_path.parent().unwrap().as_std_path();
+            let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_owned());
+            manifest_dirs.insert(package.id, dir.clone());
+            packages.insert(package.name, dir);
+        }
+        let features = metadata
+            .resolve
+            .map(|resolve| resolve.nodes)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|node| {
+                let dir = manifest_dirs.get(&node.id)?.clone();
+                Some((dir, node.features.into_iter().collect()))
+            })
+            .collect();
+        CargoMetadata { packages, features }
+    }
+}
+
+struct Module {
+    /// As `clean_module_path(module_path!())` reports it, e.g. `crate::foo` or `pax_std::primitives`
+    path: String,
+    parent: Option<usize>,
+    children: HashMap<String, usize>,
+    pax_items: HashMap<String, usize>,
+    uses: Vec<UseEntry>,
+}
+
+/// A single name brought into scope by a `use` item
+#[derive(Clone)]
+struct UseEntry {
+    /// `None` for globs
+    name: Option<String>,
+    path: Vec<String>,
+}
+
+#[derive(Clone)]
+struct PaxItem {
+    pascal_identifier: String,
+    module: ModuleRef,
+    kind: PaxItemKind,
+    is_main_component: bool,
+    fields: Vec<PaxField>,
+}
+
+#[derive(Clone)]
+enum PaxItemKind {
+    Component {
+        pax: String,
+        source_file_path: String,
+        /// Declared with `#[events(...)]`: each event and the type of its payload, if any
+        custom_events: Vec<(String, Option<Type>)>,
+    },
+    Primitive {
+        instance_import_path: String,
+    },
+    StructOnly,
+}
+
+#[derive(Clone)]
+struct PaxField {
+    name: String,
+    ty: Type,
+    is_property_wrapped: bool,
+    is_enum: bool,
+}
+
+enum Resolution {
+    Pax(usize),
+    /// Resolved to something that isn't a `#[pax]` item, known only by its identifier
+    Foreign(String),
+}
+
+/// A type whose `Reflectable` impl is known: either generated by `#[pax]` or built in
+#[derive(Clone)]
+enum Reflected {
+    Pax(usize),
+    Builtin(Builtin),
+    Vec(Box<Reflected>),
+    Option(Box<Reflected>),
+}
+
+#[derive(Clone, Copy)]
+struct Builtin {
+    get_type_id: fn() -> TypeId,
+    get_import_path: fn() -> String,
+    parse_to_manifest: fn(ParsingContext) -> (ParsingContext, Vec<PropertyDefinition>),
+}
+
+macro_rules! builtin {
+    ($t:ty) => {
+        Some(Builtin {
+            get_type_id: <$t as Reflectable>::get_type_id,
+            get_import_path: <$t as Reflectable>::get_import_path,
+            parse_to_manifest: <$t as Reflectable>::parse_to_manifest,
+        })
+    };
+}
+
+/// Built-in `Reflectable` types without generics, by identifier. `Vec` and `Option` are
+/// handled separately because their type ids depend on their contents.
+fn builtin(identifier: &str) -> Option<Builtin> {
+    match identifier {
+        "usize" => builtin!(usize),
+        "isize" => builtin!(isize),
+        "i128" => builtin!(i128),
+        "u128" => builtin!(u128),
+        "i64" => builtin!(i64),
+        "u64" => builtin!(u64),
+        "i32" => builtin!(i32),
+        "u32" => builtin!(u32),
+        "i8" => builtin!(i8),
+        "u8" => builtin!(u8),
+        "f64" => builtin!(f64),
+        "f32" => builtin!(f32),
+        "bool" => builtin!(bool),
+        "String" => builtin!(String),
+        // `Rc<T>` is reflected without regard to `T`
+        "Rc" => builtin!(std::rc::Rc<()>),
+        "TypeId" => builtin!(TypeId),
+        "TemplateNodeId" => builtin!(TemplateNodeId),
+        "Fill" => builtin!(Fill),
+        "Stroke" => builtin!(Stroke),
+        "Size" => builtin!(Size),
+        "Color" => builtin!(Color),
+        "ColorChannel" => builtin!(ColorChannel),
+        "Rotation" => builtin!(Rotation),
+        "Numeric" => builtin!(Numeric),
+        "Point" => builtin!(kurbo::Point),
+        "Transform2D" => builtin!(Transform2D),
+        _ => None,
+    }
+}
+
+fn is_builtin(identifier: &str) -> bool {
+    matches!(identifier, "Vec" | "Option") || builtin(identifier).is_some()
+}
+
+struct Analyzer {
+    host_manifest_path: PathBuf,
+    crates: Vec<Crate>,
+    crates_by_manifest_dir: HashMap<PathBuf, Option<usize>>,
+    items: Vec<PaxItem>,
+    /// Only populated if a dependency without a `path` or a `#[cfg(feature = ...)]` is met
+    cargo_metadata: Option<CargoMetadata>,
+    /// Items whose fields are currently being parsed, to stop on recursive types
+    in_progress: HashSet<usize>,
+}
+
+impl Analyzer {
+    fn new(project_path: &Path) -> Result<Self, String> {
+        let manifest_dir = fs::canonicalize(project_path)
+            .map_err(|e| format!("{}: {}", project_path.display(), e))?;
+        let mut analyzer = Analyzer {
+            host_manifest_path: manifest_dir.join("Cargo.toml"),
+            crates: vec![],
+            crates_by_manifest_dir: HashMap::new(),
+            items: vec![],
+            cargo_metadata: None,
+            in_progress: HashSet::new(),
+        };
+        analyzer.load_crate(&manifest_dir, true)?;
+        Ok(analyzer)
+    }
+
+    /// The `#[main]` component of the host crate. The `parser` binary runs the `main` generated
+    /// for the one in the crate root, if there are several.
+    fn find_main_component(&self) -> Result<usize, String> {
+        let mains: Vec<usize> = (0..self.items.len())
+            .filter(|i| self.items[*i].module.0 == 0 && self.items[*i].is_main_component)
+            .collect();
+        let root_mains: Vec<usize> = mains
+            .iter()
+            .copied()
+            .filter(|i| self.items[*i].module.1 == 0)
+            .collect();
+        match (root_mains.as_slice(), mains.as_slice()) {
+            ([main], _) | ([], [main]) => Ok(*main),
+            (_, []) => Err(format!(
+                "No `#[main]` component found in {}",
+                self.host_manifest_path.display()
+            )),
+            _ => Err("Only one component may be marked `#[main]`".to_string()),
+        }
+    }
+
+    /// Loads and indexes the crate at `manifest_dir`, unless it can't declare `#[pax]` types.
+    /// The host crate is read from the source of its `parser` binary.
+    fn load_crate(&mut self, manifest_dir: &Path, is_host: bool) -> Result<Option<usize>, String> {
+        if let Some(loaded) = self.crates_by_manifest_dir.get(manifest_dir) {
+            return Ok(*loaded);
+        }
+        self.crates_by_manifest_dir
+            .insert(manifest_dir.to_path_buf(), None);
+
+        let cargo_toml_path = manifest_dir.join("Cargo.toml");
+        let cargo_toml = fs::read_to_string(&cargo_toml_path)
+            .map_err(|e| format!("{}: {}", cargo_toml_path.display(), e))?
+            .parse::<toml_edit::Document>()
+            .map_err(|e| format!("{}: {}", cargo_toml_path.display(), e))?;
+
+        let mut dependencies = HashMap::new();
+        if let Some(table) = cargo_toml
+            .get("dependencies")
+            .and_then(|d| d.as_table_like())
+        {
+            for (key, item) in table.iter() {
+                let package = item
+                    .get("package")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or(key)
+                    .to_string();
+                let path = item
+                    .get("path")
+                    .and_then(|p| p.as_str())
+                    .map(|p| manifest_dir.join(p));
+                dependencies.insert(key.replace('-', "_"), Dependency { package, path });
+            }
+        }
+        if !is_host
+            && !dependencies
+                .values()
+                .any(|d| d.package == PAX_ENGINE_PACKAGE)
+        {
+            return Ok(None);
+        }
+
+        let declared_features = cargo_toml.get("features").and_then(|f| f.as_table_like());
+        let mut requested_features = vec!["default".to_string()];
+        if is_host {
+            requested_features.push("parser".to_string());
+            if IS_DESIGN_TIME_BUILD {
+                requested_features.push("designtime".to_string());
+            }
+        }
+        let mut default_features = HashSet::new();
+        while let Some(feature) = requested_features.pop() {
+            let Some(enables) = declared_features.and_then(|f| f.get(&feature)) else {
+                continue;
+            };
+            if !default_features.insert(feature) {
+                continue;
+            }
+            // `dep:` and `crate/feature` entries only concern dependencies
+            requested_features.extend(
+                enables
+                    .as_array()
+                    .into_iter()
+                    .flat_map(|enables| enables.iter())
+                    .filter_map(|enabled| enabled.as_str())
+                    .filter(|enabled| !enabled.contains(':') && !enabled.contains('/'))
+                    .map(str::to_string),
+            );
+        }
+        default_features.remove("default");
+
+        let (module_prefix, root_file) = if is_host {
+            let bins = cargo_toml.get("bin").and_then(|b| b.as_array_of_tables());
+            let parser_bin = bins.and_then(|bins| {
+                bins.iter()
+                    .find(|bin| bin.get("name").and_then(|n| n.as_str()) == Some("parser"))
+                    .and_then(|bin| bin.get("path").and_then(|p| p.as_str()))
+                    .map(str::to_string)
+            });
+            (
+                "crate".to_string(),
+                parser_bin.unwrap_or_else(|| "src/lib.rs".to_string()),
+            )
+        } else {
+            let lib = cargo_toml.get("lib");
+            let name = lib
+                .and_then(|l| l.get("name"))
+                .or_else(|| cargo_toml.get("package").and_then(|p| p.get("name")))
+                .and_then(|n| n.as_str())
+                .ok_or_else(|| format!("{}: missing package name", cargo_toml_path.display()))?;
+            let path = lib
+                .and_then(|l| l.get("path"))
+                .and_then(|p| p.as_str())
+                .unwrap_or("src/lib.rs");
+            (name.replace('-', "_"), path.to_string())
+        };
+
+        let crate_index = self.crates.len();
+        self.crates.push(Crate {
+            manifest_dir: manifest_dir.to_path_buf(),
+            dependencies,
+            default_features,
+            modules: vec![],
+        });
+        self.crates_by_manifest_dir
+            .insert(manifest_dir.to_path_buf(), Some(crate_index));
+
+        let root_file = manifest_dir.join(root_file);
+        let child_dir = root_file.parent().unwrap().to_path_buf();
+        self.load_module_file(crate_index, &root_file, module_prefix, None, &child_dir)?;
+        Ok(Some(crate_index))
+    }
+
+    fn load_module_file(
+        &mut self,
+        crate_index: usize,
+        file: &Path,
+        path: String,
+        parent: Option<usize>,
+        child_dir: &Path,
+    ) -> Result<usize, String> {
+        let source = fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        let ast = syn::parse_file(&source).map_err(|e| {
+            let start = e.span().start();
+            format!(
+                "{}:{}:{}: {}",
+                file.display(),
+                start.line,
+                start.column + 1,
+                e
+            )
+        })?;
+        let module_index = self.add_module(crate_index, path, parent);
+        self.load_items(crate_index, module_index, file, &ast.items, child_dir)?;
+        Ok(module_index)
+    }
+
+    fn add_module(&mut self, crate_index: usize, path: String, parent: Option<usize>) -> usize {
+        let modules = &mut self.crates[crate_index].modules;
+        modules.push(Module {
+            path,
+            parent,
+            children: HashMap::new(),
+            pax_items: HashMap::new(),
+            uses: vec![],
+        });
+        modules.len() - 1
+    }
+
+    fn load_items(
+        &mut self,
+        crate_index: usize,
+        module_index: usize,
+        file: &Path,
+        items: &[Item],
+        child_dir: &Path,
+    ) -> Result<(), String> {
+        for item in items {
+            let attrs = match item {
+                Item::Mod(item) => &item.attrs,
+                Item::Use(item) => &item.attrs,
+                Item::Struct(item) => &item.attrs,
+                Item::Enum(item) => &item.attrs,
+                _ => continue,
+            };
+            if !self.is_cfg_enabled(crate_index, attrs) {
+                continue;
+            }
+            match item {
+                Item::Mod(item_mod) => {
+                    let name = item_mod.ident.to_string();
+                    let path = format!(
+                        "{}::{}",
+                        self.crates[crate_index].modules[module_index].path, name
+                    );
+                    let child = if let Some((_, inline_items)) = &item_mod.content {
+                        let child = self.add_module(crate_index, path, Some(module_index));
+                        self.load_items(
+                            crate_index,
+                            child,
+                            file,
+                            inline_items,
+                            &child_dir.join(&name),
+                        )?;
+                        child
+                    } else {
+                        let Some(child_file) = module_file(file, child_dir, &name, &item_mod.attrs)
+                        else {
+                            // e.g. a module generated by a build script
+                            continue;
+                        };
+                        let owns_dir = child_file.file_name().is_some_and(|f| f == "mod.rs");
+                        let grandchild_dir = if owns_dir {
+                            child_file.parent().unwrap().to_path_buf()
+                        } else {
+                            child_file.with_extension("")
+                        };
+                        self.load_module_file(
+                            crate_index,
+                            &child_file,
+                            path,
+                            Some(module_index),
+                            &grandchild_dir,
+                        )?
+                    };
+                    self.crates[crate_index].modules[module_index]
+                        .children
+                        .insert(name, child);
+                }
+                Item::Use(item_use) => {
+                    let mut uses = vec![];
+                    flatten_use_tree(&item_use.tree, vec![], &mut uses);
+                    self.crates[crate_index].modules[module_index]
+                        .uses
+                        .extend(uses);
+                }
+                Item::Struct(item_struct) if has_pax_attribute(&item_struct.attrs) => {
+                    let fields = match &item_struct.fields {
+                        Fields::Named(named) => named
+                            .named
+                            .iter()
+                            .filter(|f| self.is_cfg_enabled(crate_index, &f.attrs))
+                            .filter_map(|f| pax_field(f, f.ident.as_ref().unwrap(), false))
+                            .collect(),
+                        Fields::Unit => vec![],
+                        Fields::Unnamed(_) => {
+                            return Err(format!(
+                            "{}: Pax may only be attached to `struct`s with named fields (`{}`)",
+                            file.display(),
+                            item_struct.ident
+                        ))
+                        }
+                    };
+                    self.add_pax_item(
+                        crate_index,
+                        module_index,
+                        file,
+                        &item_struct.ident,
+                        &item_struct.attrs,
+                        fields,
+                    )?;
+                }
+                Item::Enum(item_enum) if has_pax_attribute(&item_enum.attrs) => {
+                    let variants: Vec<_> = item_enum
+                        .variants
+                        .iter()
+                        .filter(|variant| self.is_cfg_enabled(crate_index, &variant.attrs))
+                        .collect();
+                    let fields = variants
+                        .into_iter()
+                        .flat_map(|variant| {
+                            variant
+                                .fields
+                                .iter()
+                                .filter(|f| self.is_cfg_enabled(crate_index, &f.attrs))
+                                .filter_map(|f| pax_field(f, &variant.ident, true))
+                                .collect::<Vec<_>>()
+                        })
+                        .collect();
+                    self.add_pax_item(
+                        crate_index,
+                        module_index,
+                        file,
+                        &item_enum.ident,
+                        &item_enum.attrs,
+                        fields,
+                    )?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the `#[pax]` helper attributes of an item, like the `pax` macro does
+    fn add_pax_item(
+        &mut self,
+        crate_index: usize,
+        module_index: usize,
+        file: &Path,
+        ident: &syn::Ident,
+        attrs: &[Attribute],
+        fields: Vec<PaxField>,
+    ) -> Result<(), String> {
+        let mut is_main_component = false;
+        let mut file_path = None;
+        let mut inlined_contents = None;
+        let mut primitive_instance_import_path = None;
+        let mut custom_events = vec![];
+        for attr in attrs {
+            if attr.path.is_ident("events") {
+                custom_events = parse_custom_events(attr)
+                    .map_err(|e| format!("`#[events(...)]` on `{}`: {}", ident, e))?;
+                continue;
+            }
+            if attr.path.is_ident("inlined") {
+                // Printing the tokens back (`rgb (20 , 20 , 20)`) doesn't always give valid
+                // Pax, so the template is read from the source between the parentheses
+                for token in attr.tokens.clone() {
+                    if let proc_macro2::TokenTree::Group(group) = token {
+                        if group.delimiter() == proc_macro2::Delimiter::Parenthesis
+                            && !group.stream().is_empty()
+                        {
+                            let source = group.span().source_text().ok_or_else(|| {
+                                format!(
+                                    "{}: couldn't read `#[inlined(...)]` on `{}`",
+                                    file.display(),
+                                    ident
+                                )
+                            })?;
+                            inlined_contents = Some(source[1..source.len() - 1].trim().to_string());
+                        }
+                    }
+                }
+                continue;
+            }
+            match attr.parse_meta() {
+                Ok(Meta::Path(path)) if path.is_ident("main") => is_main_component = true,
+                Ok(Meta::List(list)) if list.path.is_ident("file") => {
+                    if let Some(NestedMeta::Lit(Lit::Str(s))) = list.nested.first() {
+                        file_path = Some(s.value());
+                    }
+                }
+                Ok(Meta::List(list)) if list.path.is_ident("primitive") => {
+                    if let Some(NestedMeta::Lit(Lit::Str(s))) = list.nested.first() {
+                        primitive_instance_import_path = Some(s.value());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let declares_custom_events = !custom_events.is_empty();
+        let manifest_dir = &self.crates[crate_index].manifest_dir;
+        let kind = if let Some(file_path) = file_path {
+            let path = manifest_dir.join("src").join(file_path);
+            let pax =
+                fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            PaxItemKind::Component {
+                pax: embedded_pax(&pax),
+                source_file_path: path.to_string_lossy().to_string(),
+                custom_events,
+            }
+        } else if let Some(pax) = inlined_contents {
+            // The parser binary reports `file!()`, which is relative for the host crate
+            let source_file_path = file
+                .strip_prefix(&self.crates[0].manifest_dir)
+                .unwrap_or(file);
+            PaxItemKind::Component {
+                pax: embedded_pax(&pax),
+                source_file_path: source_file_path.to_string_lossy().to_string(),
+                custom_events,
+            }
+        } else if let Some(instance_import_path) = primitive_instance_import_path {
+            PaxItemKind::Primitive {
+                instance_import_path,
+            }
+        } else {
+            PaxItemKind::StructOnly
+        };
+        if declares_custom_events && !matches!(kind, PaxItemKind::Component { .. }) {
+            return Err(format!(
+                "Only components with a template can dispatch custom events; `#[events(...)]` on `{}` requires #[file(...)] or #[inlined(...)]",
+                ident
+            ));
+        }
+        if is_main_component && !matches!(kind, PaxItemKind::Component { .. }) {
+            return Err(format!(
+                "Main (application-root) component `{}` must specify either a Pax file or inlined Pax content",
+                ident
+            ));
+        }
+
+        self.items.push(PaxItem {
+            pascal_identifier: ident.to_string(),
+            module: (crate_index, module_index),
+            kind,
+            is_main_component,
+            fields,
+        });
+        self.crates[crate_index].modules[module_index]
+            .pax_items
+            .insert(ident.to_string(), self.items.len() - 1);
+        Ok(())
+    }
+
+    fn module(&self, (crate_index, module_index): ModuleRef) -> &Module {
+        &self.crates[crate_index].modules[module_index]
+    }
+
+    /// Resolves the dependency `identifier` of `crate_index` to the root module of its crate
+    fn dependency_root(&mut self, crate_index: usize, identifier: &str) -> Option<ModuleRef> {
+        let dependency = self.crates[crate_index].dependencies.get(identifier)?;
+        let manifest_dir = match &dependency.path {
+            Some(path) => fs::canonicalize(path).ok()?,
+            None => {
+                let package = dependency.package.clone();
+                self.cargo_metadata().packages.get(&package)?.clone()
+            }
+        };
+        match self.load_crate(&manifest_dir, false) {
+            Ok(Some(loaded)) => Some((loaded, 0)),
+            _ => None,
+        }
+    }
+
+    fn cargo_metadata(&mut self) -> &CargoMetadata {
+        if self.cargo_metadata.is_none() {
+            let mut command = cargo_metadata::MetadataCommand::new();
+            command.manifest_path(&self.host_manifest_path);
+            let host_features = &self.crates[0].default_features;
+            let features: Vec<String> = ["parser", "designtime"]
+                .into_iter()
+                .filter(|feature| host_features.contains(*feature))
+                .map(str::to_string)
+                .collect();
+            if !features.is_empty() {
+                command.features(cargo_metadata::CargoOpt::SomeFeatures(features));
+            }
+            let metadata = command.exec().map(CargoMetadata::new).unwrap_or_default();
+            self.cargo_metadata = Some(metadata);
+        }
+        self.cargo_metadata.as_ref().unwrap()
+    }
+
+    /// Whether every `#[cfg(...)]` in `attrs` holds for the parser binary
+    fn is_cfg_enabled(&mut self, crate_index: usize, attrs: &[Attribute]) -> bool {
+        attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("cfg"))
+            .all(|attr| match attr.parse_meta() {
+                Ok(Meta::List(list)) if list.nested.len() == 1 => {
+                    self.evaluate_cfg(crate_index, &list.nested[0])
+                }
+                _ => true,
+            })
+    }
+
+    fn evaluate_cfg(&mut self, crate_index: usize, predicate: &NestedMeta) -> bool {
+        let NestedMeta::Meta(meta) = predicate else {
+            return false;
+        };
+        match meta {
+            Meta::List(list) if list.path.is_ident("all") => list
+                .nested
+                .iter()
+                .all(|predicate| self.evaluate_cfg(crate_index, predicate)),
+            Meta::List(list) if list.path.is_ident("any") => list
+                .nested
+                .iter()
+                .any(|predicate| self.evaluate_cfg(crate_index, predicate)),
+            Meta::List(list) if list.path.is_ident("not") && list.nested.len() == 1 => {
+                !self.evaluate_cfg(crate_index, &list.nested[0])
+            }
+            Meta::NameValue(name_value) => {
+                let Lit::Str(value) = &name_value.lit else {
+                    return false;
+                };
+                let value = value.value();
+                let Some(name) = name_value.path.get_ident() else {
+                    return false;
+                };
+                match name.to_string().as_str() {
+                    "feature" => self.is_feature_enabled(crate_index, &value),
+                    "target_os" => value == std::env::consts::OS,
+                    "target_family" => value == std::env::consts::FAMILY,
+                    "target_arch" => value == std::env::consts::ARCH,
+                    "target_pointer_width" => value == usize::BITS.to_string(),
+                    _ => false,
+                }
+            }
+            Meta::Path(path) => {
+                (path.is_ident("unix") && cfg!(unix)) || (path.is_ident("windows") && cfg!(windows))
+            }
+            _ => false,
+        }
+    }
+
+    fn is_feature_enabled(&mut self, crate_index: usize, feature: &str) -> bool {
+        let manifest_dir = self.crates[crate_index].manifest_dir.clone();
+        match self.cargo_metadata().features.get(&manifest_dir) {
+            Some(features) => features.contains(feature),
+            None => self.crates[crate_index].default_features.contains(feature),
+        }
+    }
+
+    /// Resolves a module path like `crate::foo` or `pax_std::primitives`, as written in `module`
+    fn resolve_module(
+        &mut self,
+        module: ModuleRef,
+        segments: &[String],
+        depth: usize,
+    ) -> Option<ModuleRef> {
+        if depth > MAX_REEXPORT_DEPTH {
+            return None;
+        }
+        let mut current = module;
+        for (i, segment) in segments.iter().enumerate() {
+            current = match segment.as_str() {
+                "crate" if i == 0 => (current.0, 0),
+                "self" => current,
+                "super" => (current.0, self.module(current).parent?),
+                _ => {
+                    if let Some(child) = self.module(current).children.get(segment) {
+                        (current.0, *child)
+                    } else if let Some(entry) = self.use_entry(current, segment) {
+                        self.resolve_module(current, &entry.path, depth + 1)?
+                    } else if i == 0 {
+                        self.dependency_root(current.0, segment)?
+                    } else {
+                        return None;
+                    }
+                }
+            };
+        }
+        Some(current)
+    }
+
+    fn use_entry(&self, module: ModuleRef, name: &str) -> Option<UseEntry> {
+        self.module(module)
+            .uses
+            .iter()
+            .find(|entry| entry.name.as_deref() == Some(name))
+            .cloned()
+    }
+
+    /// Resolves `name` among the items and explicit (non-glob) imports of `module`
+    fn lookup_named(
+        &mut self,
+        module: ModuleRef,
+        name: &str,
+        visited: &mut HashSet<(ModuleRef, String)>,
+    ) -> Option<Resolution> {
+        if !visited.insert((module, name.to_string())) {
+            return None;
+        }
+        if let Some(item) = self.module(module).pax_items.get(name) {
+            return Some(Resolution::Pax(*item));
+        }
+        let entry = self.use_entry(module, name)?;
+        match self.resolve_path(module, &entry.path, visited) {
+            Some(Resolution::Pax(item)) => Some(Resolution::Pax(item)),
+            _ => Some(Resolution::Foreign(entry.path.last().unwrap().clone())),
+        }
+    }
+
+    /// Resolves `name` through the glob imports of `module`
+    fn lookup_glob(
+        &mut self,
+        module: ModuleRef,
+        name: &str,
+        visited: &mut HashSet<(ModuleRef, String)>,
+    ) -> Option<usize> {
+        let globs: Vec<UseEntry> = self
+            .module(module)
+            .uses
+            .iter()
+            .filter(|entry| entry.name.is_none())
+            .cloned()
+            .collect();
+        for glob in globs {
+            let Some(target) = self.resolve_module(module, &glob.path, 0) else {
+                continue;
+            };
+            if let Some(Resolution::Pax(item)) = self.lookup(target, name, visited) {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn lookup(
+        &mut self,
+        module: ModuleRef,
+        name: &str,
+        visited: &mut HashSet<(ModuleRef, String)>,
+    ) -> Option<Resolution> {
+        if let Some(resolution) = self.lookup_named(module, name, visited) {
+            return Some(resolution);
+        }
+        self.lookup_glob(module, name, visited).map(Resolution::Pax)
+    }
+
+    fn resolve_path(
+        &mut self,
+        module: ModuleRef,
+        segments: &[String],
+        visited: &mut HashSet<(ModuleRef, String)>,
+    ) -> Option<Resolution> {
+        let (name, prefix) = segments.split_last()?;
+        let target = self.resolve_module(module, prefix, 0)?;
+        self.lookup(target, name, visited)
+    }
+
+    /// Resolves a path to a type as written in `module`. Built-in types take precedence over
+    /// glob imports, so that e.g. `use pax_engine::api::*` doesn't require reading `pax_engine`.
+    fn resolve_type_path(&mut self, module: ModuleRef, segments: &[String]) -> Option<Resolution> {
+        let mut visited = HashSet::new();
+        if let [name] = segments {
+            if let Some(resolution) = self.lookup_named(module, name, &mut visited) {
+                return Some(resolution);
+            }
+            if is_builtin(name) {
+                return Some(Resolution::Foreign(name.clone()));
+            }
+            return self
+                .lookup_glob(module, name, &mut visited)
+                .map(Resolution::Pax);
+        }
+        self.resolve_path(module, segments, &mut visited)
+            .or_else(|| Some(Resolution::Foreign(segments.last()?.clone())))
+    }
+
+    /// Breaks apart a field type into every type that must be reflected, children first, so
+    /// that the last element is the field type itself — like `get_scoped_resolvable_types` in
+    /// `pax-macro`
+    fn scoped_resolvable_types(
+        &mut self,
+        ty: &Type,
+        module: ModuleRef,
+    ) -> Result<Vec<Reflected>, String> {
+        let mut accum = vec![];
+        match ty {
+            Type::Path(type_path) if type_path.qself.is_none() => {
+                let mut generics = vec![];
+                for segment in &type_path.path.segments {
+                    match &segment.arguments {
+                        PathArguments::AngleBracketed(args) => {
+                            for arg in &args.args {
+                                if let GenericArgument::Type(arg) = arg {
+                                    let inner = self.scoped_resolvable_types(arg, module)?;
+                                    generics.extend(inner.last().cloned());
+                                    accum.extend(inner);
+                                }
+                            }
+                        }
+                        PathArguments::Parenthesized(_) => {
+                            return Err(format!(
+                                "Parenthesized path arguments (for example, Fn types) not yet supported in `{}`",
+                                self.module(module).path
+                            ))
+                        }
+                        PathArguments::None => {}
+                    }
+                }
+                let segments: Vec<String> = type_path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.to_string())
+                    .collect();
+                accum.push(self.reflect(module, &segments, generics)?);
+            }
+            Type::Tuple(tuple) => {
+                for elem in &tuple.elems {
+                    accum.extend(self.scoped_resolvable_types(elem, module)?);
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Unsupported property type in `{}`",
+                    self.module(module).path
+                ))
+            }
+        }
+        Ok(accum)
+    }
+
+    fn reflect(
+        &mut self,
+        module: ModuleRef,
+        segments: &[String],
+        generics: Vec<Reflected>,
+    ) -> Result<Reflected, String> {
+        let module_path = self.module(module).path.clone();
+        let unresolved = || {
+            format!(
+                "`{}` (used in `{}`) is neither a `#[pax]` type nor a built-in Reflectable type",
+                segments.join("::"),
+                module_path
+            )
+        };
+        let inner = || generics.first().cloned().map(Box::new);
+        match self.resolve_type_path(module, segments) {
+            Some(Resolution::Pax(item)) => Ok(Reflected::Pax(item)),
+            Some(Resolution::Foreign(identifier)) => match identifier.as_str() {
+                "Vec" => inner().map(Reflected::Vec).ok_or_else(unresolved),
+                "Option" => inner().map(Reflected::Option).ok_or_else(unresolved),
+                _ => builtin(&identifier)
+                    .map(Reflected::Builtin)
+                    .ok_or_else(unresolved),
+            },
+            None => Err(unresolved()),
+        }
+    }
+
+    fn get_import_path(&self, reflected: &Reflected) -> String {
+        match reflected {
+            Reflected::Pax(item) => {
+                let item = &self.items[*item];
+                format!(
+                    "{}::{}",
+                    self.module(item.module).path,
+                    item.pascal_identifier
+                )
+            }
+            Reflected::Builtin(builtin) => (builtin.get_import_path)(),
+            Reflected::Vec(_) => "std::vec::Vec".to_string(),
+            Reflected::Option(_) => "std::option::Option".to_string(),
+        }
+    }
+
+    fn get_type_id(&self, reflected: &Reflected) -> TypeId {
+        match reflected {
+            Reflected::Pax(item) => TypeId::build_singleton(
+                &self.get_import_path(reflected),
+                Some(&self.items[*item].pascal_identifier),
+            ),
+            Reflected::Builtin(builtin) => (builtin.get_type_id)(),
+            Reflected::Vec(inner) => {
+                TypeId::build_vector(&format!("{}{}", "{PREFIX}", self.get_type_id(inner)))
+            }
+            Reflected::Option(inner) => {
+                TypeId::build_option(&format!("{}{}", "{PREFIX}", self.get_type_id(inner)))
+            }
+        }
+    }
+
+    /// Mirrors `Reflectable::parse_to_manifest` for `reflected`
+    fn parse_to_manifest(
+        &mut self,
+        mut ctx: ParsingContext,
+        reflected: &Reflected,
+    ) -> Result<(ParsingContext, Vec<PropertyDefinition>), String> {
+        match reflected {
+            Reflected::Pax(item) => self.parse_pax_item(ctx, *item),
+            Reflected::Builtin(builtin) => Ok((builtin.parse_to_manifest)(ctx)),
+            Reflected::Vec(inner) | Reflected::Option(inner) => {
+                let type_id = self.get_type_id(reflected);
+                let inner_iterable_type_id = match reflected {
+                    Reflected::Vec(_) => Some(self.get_type_id(inner)),
+                    _ => None,
+                };
+                if !ctx.type_table.contains_key(&type_id) {
+                    ctx.type_table.insert(
+                        type_id.clone(),
+                        TypeDefinition {
+                            type_id,
+                            inner_iterable_type_id,
+                            property_definitions: vec![],
+                        },
+                    );
+                }
+                let (ctx, property_definitions) = self.parse_to_manifest(ctx, inner)?;
+                match reflected {
+                    Reflected::Vec(_) => Ok((ctx, property_definitions)),
+                    _ => Ok((ctx, vec![])),
+                }
+            }
+        }
+    }
+
+    /// Mirrors the `parse_to_manifest` generated by `#[pax]` (see `derive_pax.stpl`)
+    fn parse_pax_item(
+        &mut self,
+        mut ctx: ParsingContext,
+        item_index: usize,
+    ) -> Result<(ParsingContext, Vec<PropertyDefinition>), String> {
+        let item = self.items[item_index].clone();
+        let self_type_id = self.get_type_id(&Reflected::Pax(item_index));
+        let module_path = self.module(item.module).path.clone();
+        ctx.import_paths
+            .insert(self.get_import_path(&Reflected::Pax(item_index)));
+
+        let mut property_definitions = vec![];
+        if !self.in_progress.insert(item_index) {
+            return Ok((ctx, property_definitions));
+        }
+        for field in &item.fields {
+            let scoped_resolvable_types = self.scoped_resolvable_types(&field.ty, item.module)?;
+            let Some(root) = scoped_resolvable_types.last() else {
+                continue;
+            };
+            ctx = self.parse_to_manifest(ctx, root)?.0;
+            for scoped_resolvable_type in &scoped_resolvable_types {
+                ctx.import_paths
+                    .insert(self.get_import_path(scoped_resolvable_type));
+                ctx = self.parse_to_manifest(ctx, scoped_resolvable_type)?.0;
+            }
+
+            property_definitions.push(PropertyDefinition {
+                name: field.name.clone(),
+                type_id: self.get_type_id(root),
+                flags: PropertyDefinitionFlags {
+                    is_property_wrapped: field.is_property_wrapped,
+                    is_enum: field.is_enum,
+                    ..Default::default()
+                },
+            });
+        }
+        self.in_progress.remove(&item_index);
+
+        if ctx.visited_type_ids.contains(&self_type_id) {
+            //early return; this type has already been parsed
+            return Ok((ctx, property_definitions));
+        }
+
+        if let PaxItemKind::Component { pax, .. } = &item.kind {
+            let ast = parse_pax_str(Rule::pax_component_definition, pax)
+                .map_err(|e| format!("{}::{}: {}", module_path, item.pascal_identifier, e))?;
+            let mut dependencies = vec![];
+            collect_tag_identifiers(ast, &mut dependencies);
+            // Add BlankComponent so it's guaranteed to be included in the PaxManifest
+            if item.is_main_component {
+                dependencies.push("BlankComponent".to_string());
+            }
+            let mut seen = HashSet::new();
+            dependencies.retain(|dependency| seen.insert(dependency.clone()));
+
+            for dependency in dependencies {
+                let resolved =
+                    self.resolve_type_path(item.module, std::slice::from_ref(&dependency));
+                let Some(Resolution::Pax(dependency_index)) = resolved else {
+                    return Err(format!(
+                        "`<{}>` in the template of `{}::{}` is not a Pax component in scope",
+                        dependency, module_path, item.pascal_identifier
+                    ));
+                };
+                ctx = self.parse_pax_item(ctx, dependency_index)?.0;
+                let dependency_type_id = self.get_type_id(&Reflected::Pax(dependency_index));
+                ctx.template_map.insert(dependency, dependency_type_id);
+            }
+        }
+
+        ctx.visited_type_ids.insert(self_type_id.clone());
+        let (ctx, _) = assemble_type_definition(
+            ctx,
+            property_definitions.clone(),
+            None,
+            self_type_id.clone(),
+        );
+
+        let (mut ctx, component_definition) = match item.kind {
+            PaxItemKind::Component {
+                pax,
+                source_file_path,
+                custom_events,
+            } => {
+                let template_map = ctx.template_map.clone();
+                let (mut ctx, mut definition) = assemble_component_definition(
+                    ctx,
+                    &pax,
+                    item.is_main_component,
+                    template_map,
+                    &module_path,
+                    self_type_id.clone(),
+                    &source_file_path,
+                );
+                // Payload types are reflected like field types
+                for (name, payload_type) in custom_events {
+                    let payload_type = match payload_type {
+                        Some(ty) => {
+                            let scoped_resolvable_types =
+                                self.scoped_resolvable_types(&ty, item.module)?;
+                            let root = scoped_resolvable_types.last().unwrap().clone();
+                            ctx = self.parse_to_manifest(ctx, &root)?.0;
+                            for scoped_resolvable_type in &scoped_resolvable_types {
+                                ctx.import_paths
+                                    .insert(self.get_import_path(scoped_resolvable_type));
+                                ctx = self.parse_to_manifest(ctx, scoped_resolvable_type)?.0;
+                            }
+                            Some(self.get_type_id(&root))
+                        }
+                        None => None,
+                    };
+                    definition
+                        .custom_events
+                        .push(CustomEventDefinition { name, payload_type });
+                }
+                (ctx, definition)
+            }
+            PaxItemKind::Primitive {
+                instance_import_path,
+            } => {
+                let definition = assemble_primitive_definition(
+                    &module_path,
+                    instance_import_path,
+                    self_type_id.clone(),
+                );
+                (ctx, definition)
+            }
+            PaxItemKind::StructOnly => {
+                assemble_struct_only_component_definition(ctx, &module_path, self_type_id.clone())
+            }
+        };
+        ctx.component_definitions
+            .insert(self_type_id, component_definition);
+
+        Ok((ctx, property_definitions))
+    }
+}
+
+/// Reads `#[events(selection_changed: usize, closed)]`, with the grammar the `pax` macro uses
+fn parse_custom_events(attr: &Attribute) -> syn::Result<Vec<(String, Option<Type>)>> {
+    let declarations = attr.parse_args_with(parse_custom_event_declarations)?;
+    Ok(declarations
+        .into_iter()
+        .map(|declaration| (declaration.name.to_string(), declaration.payload_type))
+        .collect())
+}
+
+/// The parser binary embeds each template in an indented raw string literal (see
+/// `derive_pax.stpl`). Templates are framed the same way so that token locations match.
+pub(crate) fn embedded_pax(pax: &str) -> String {
+    format!(
+        "\n{:indent$}{}\n{:20}",
+        "",
+        pax,
+        "",
+        indent = EMBEDDED_PAX_INDENT
+    )
+}
+
+fn has_pax_attribute(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "pax")
+    })
+}
+
+/// The type reflected for a field, unwrapped from `Property<T>` if applicable. Like `pax-macro`,
+/// fields whose types aren't paths are skipped.
+fn pax_field(field: &syn::Field, name: &syn::Ident, is_enum: bool) -> Option<PaxField> {
+    let Type::Path(type_path) = &field.ty else {
+        return None;
+    };
+    if type_path.qself.is_some() {
+        return None;
+    }
+    let property_inner = type_path
+        .path
+        .segments
+        .iter()
+        .filter(|segment| segment.ident.to_string().ends_with("Property"))
+        .filter_map(|segment| match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            }),
+            _ => None,
+        })
+        .last();
+    Some(PaxField {
+        name: name.to_string(),
+        is_property_wrapped: property_inner.is_some(),
+        ty: property_inner.unwrap_or_else(|| field.ty.clone()),
+        is_enum,
+    })
+}
+
+/// The file for `mod name;` declared in `file`, whose child modules live in `child_dir`
+fn module_file(file: &Path, child_dir: &Path, name: &str, attrs: &[Attribute]) -> Option<PathBuf> {
+    let path_attribute = attrs.iter().find_map(|attr| match attr.parse_meta() {
+        Ok(Meta::NameValue(nv)) if nv.path.is_ident("path") => match nv.lit {
+            Lit::Str(s) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    });
+    let candidates = match path_attribute {
+        Some(path) => vec![file.parent()?.join(path)],
+        None => vec![
+            child_dir.join(format!("{}.rs", name)),
+            child_dir.join(name).join("mod.rs"),
+        ],
+    };
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+fn flatten_use_tree(tree: &syn::UseTree, mut prefix: Vec<String>, accum: &mut Vec<UseEntry>) {
+    match tree {
+        syn::UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            flatten_use_tree(&path.tree, prefix, accum);
+        }
+        syn::UseTree::Name(name) => {
+            let name = name.ident.to_string();
+            if name == "self" {
+                if let Some(last) = prefix.last().cloned() {
+                    accum.push(UseEntry {
+                        name: Some(last),
+                        path: prefix,
+                    });
+                }
+            } else {
+                prefix.push(name.clone());
+                accum.push(UseEntry {
+                    name: Some(name),
+                    path: prefix,
+                });
+            }
+        }
+        syn::UseTree::Rename(rename) => {
+            prefix.push(rename.ident.to_string());
+            accum.push(UseEntry {
+                name: Some(rename.rename.to_string()),
+                path: prefix,
+            });
+        }
+        syn::UseTree::Glob(_) => accum.push(UseEntry {
+            name: None,
+            path: prefix,
+        }),
+        syn::UseTree::Group(group) => {
+            for tree in &group.items {
+                flatten_use_tree(tree, prefix.clone(), accum);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Writes a host crate depending on this checkout's `pax-engine` and `pax-std`
+    pub(crate) fn host_crate(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{}\"\n\n[dependencies]\npax-engine = {{ path = \"{}\" }}\npax-std = {{ path = \"{}\" }}\n",
+                name,
+                root.join("pax-engine").display(),
+                root.join("pax-std").display(),
+            ),
+        )
+        .unwrap();
+        for (path, contents) in files {
+            let path = dir.join("src").join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn reflects_components_reachable_from_main() {
+        let dir = host_crate(
+            "static-analysis-fixture",
+            &[
+                (
+                    "lib.rs",
+                    r#"
+                    use pax_engine::api::*;
+                    use pax_engine::*;
+                    use pax_std::primitives::*;
+
+                    mod cards;
+                    use cards::Card as PlayingCard;
+
+                    #[pax]
+                    #[main]
+                    #[file("lib.pax")]
+                    pub struct Table {
+                        pub hands: Property<Vec<cards::Hand>>,
+                        pub ticks: Property<usize>,
+                    }
+                    "#,
+                ),
+                (
+                    "lib.pax",
+                    "<PlayingCard/>\nfor hand in hands { <Rectangle fill=RED/> }",
+                ),
+                (
+                    "cards/mod.rs",
+                    r#"
+                    use pax_engine::*;
+                    use pax_std::primitives::Text;
+
+                    #[pax]
+                    #[inlined(<Text text="card"/>)]
+                    pub struct Card {
+                        pub suit: Property<Option<Suit>>,
+                    }
+
+                    #[pax]
+                    pub struct Hand {
+                        pub size: Property<Size>,
+                        pub cards: Vec<Card>,
+                    }
+
+                    #[pax]
+                    pub enum Suit {
+                        #[default]
+                        Hearts,
+                        Numbered(u8),
+                    }
+                    "#,
+                ),
+            ],
+        );
+        let manifest = analyze_project(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let table = TypeId::build_singleton("crate::Table", Some("Table"));
+        let card = TypeId::build_singleton("crate::cards::Card", Some("Card"));
+        let hand = TypeId::build_singleton("crate::cards::Hand", Some("Hand"));
+        let suit = TypeId::build_singleton("crate::cards::Suit", Some("Suit"));
+        let rectangle =
+            TypeId::build_singleton("pax_std::primitives::Rectangle", Some("Rectangle"));
+        assert_eq!(manifest.main_component_type_id, table);
+        for type_id in [&table, &card, &hand, &suit, &rectangle] {
+            assert!(manifest.components.contains_key(type_id), "{}", type_id);
+        }
+        for pascal_identifier in ["Text", "BlankComponent", "Group"] {
+            let type_id = TypeId::build_singleton(
+                &format!("pax_std::primitives::{}", pascal_identifier),
+                Some(pascal_identifier),
+            );
+            assert!(manifest.components.contains_key(&type_id), "{}", type_id);
+        }
+        assert!(manifest.components[&rectangle].is_primitive);
+        assert!(manifest.components[&hand].is_struct_only_component);
+        assert_eq!(manifest.components[&card].module_path, "crate::cards");
+
+        let hands = &manifest.type_table[&table].property_definitions[0];
+        let hands_type_id = TypeId::build_vector(&format!("{{PREFIX}}{}", hand));
+        assert_eq!(hands.type_id, hands_type_id);
+        assert!(hands.flags.is_property_wrapped);
+        assert_eq!(
+            manifest.type_table[&hands_type_id].inner_iterable_type_id,
+            Some(hand.clone())
+        );
+
+        let hand_properties = &manifest.type_table[&hand].property_definitions;
+        assert_eq!(hand_properties[0].type_id, Size::get_type_id());
+        assert!(!hand_properties[1].flags.is_property_wrapped);
+
+        let suit_properties = &manifest.type_table[&suit].property_definitions;
+        assert_eq!(suit_properties.len(), 1);
+        assert_eq!(suit_properties[0].name, "Numbered");
+        assert!(suit_properties[0].flags.is_enum);
+
+        assert!(manifest.import_paths.contains("crate::cards::Card"));
+        assert!(manifest.import_paths.contains("std::option::Option"));
+        assert!(manifest
+            .import_paths
+            .contains("pax_engine::api::Transform2D"));
+    }
+
+    #[test]
+    fn reads_custom_event_payload_types() {
+        let dir = host_crate(
+            "static-analysis-events-fixture",
+            &[
+                (
+                    "lib.rs",
+                    r#"
+                    use pax_engine::api::*;
+                    use pax_engine::*;
+                    use pax_std::primitives::*;
+
+                    #[pax]
+                    #[main]
+                    #[file("lib.pax")]
+                    pub struct Example {}
+
+                    #[pax]
+                    #[events(picked: Option<Choice>, closed)]
+                    #[inlined(<Rectangle/>)]
+                    pub struct Picker {}
+
+                    #[pax]
+                    pub struct Choice {
+                        pub index: usize,
+                    }
+                    "#,
+                ),
+                (
+                    "lib.pax",
+                    "<Picker @picked=self.on_picked @closed=self.on_closed/>",
+                ),
+            ],
+        );
+        let manifest = analyze_project(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let example = TypeId::build_singleton("crate::Example", Some("Example"));
+        let picker = TypeId::build_singleton("crate::Picker", Some("Picker"));
+        let choice = TypeId::build_singleton("crate::Choice", Some("Choice"));
+        let option_of_choice = TypeId::build_option(&format!("{{PREFIX}}{}", choice));
+        assert_eq!(
+            manifest.components[&picker].custom_events,
+            vec![
+                CustomEventDefinition {
+                    name: "picked".to_string(),
+                    payload_type: Some(option_of_choice.clone()),
+                },
+                CustomEventDefinition {
+                    name: "closed".to_string(),
+                    payload_type: None,
+                },
+            ]
+        );
+        assert!(manifest.components.contains_key(&choice));
+        assert!(manifest.type_table.contains_key(&option_of_choice));
+
+        // handlers bound to the events in the parent's template take the payload type
+        let example_info = manifest
+            .generate_codegen_component_info()
+            .into_iter()
+            .find(|info| info.type_id == example)
+            .unwrap();
+        let args_type = |name: &str| {
+            example_info
+                .handlers
+                .iter()
+                .find(|handler| handler.name == name)
+                .unwrap()
+                .args_type
+                .clone()
+        };
+        assert_eq!(
+            args_type("on_picked"),
+            Some(option_of_choice.get_unique_identifier())
+        );
+        assert_eq!(args_type("on_closed"), None);
+    }
+
+    #[test]
+    fn rejects_capture_bindings_on_events_without_a_capture_phase() {
+        let dir = host_crate(
+            "static-analysis-events-capture-fixture",
+            &[(
+                "lib.rs",
+                r#"
+                use pax_engine::api::*;
+                use pax_engine::*;
+                use pax_std::primitives::*;
+
+                #[pax]
+                #[main]
+                #[file("lib.pax")]
+                pub struct Example {}
+
+                #[pax]
+                #[events(closed)]
+                #[inlined(<Rectangle/>)]
+                pub struct Picker {}
+                "#,
+            )],
+        );
+        let analyze = |pax: &str| {
+            fs::write(dir.join("src/lib.pax"), pax).unwrap();
+            analyze_project(&dir)
+        };
+        let pointer = analyze(
+            "<Picker @click.capture=self.on_click/>\n@settings { @key_down.capture: on_key }",
+        );
+        let custom =
+            analyze("<Picker @click.capture=self.on_click @closed.capture=self.on_closed/>");
+        let lifecycle = analyze("<Picker/>\n@settings {\n    @mount.capture: on_mount\n}");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(pointer.is_ok(), "{:?}", pointer.err());
+        let file = dir.join("src/lib.pax");
+        assert_eq!(
+            custom.err().unwrap(),
+            format!(
+                "{}:1:39: `@closed.capture` in `crate::Example`: custom events have no capture phase, bind `@closed` instead",
+                file.display()
+            )
+        );
+        assert_eq!(
+            lifecycle.err().unwrap(),
+            format!(
+                "{}:3:6: `@mount.capture` in `crate::Example`: it is only dispatched to the node itself, so it has no capture phase, bind `@mount` instead",
+                file.display()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_timelines_on_unknown_properties_or_with_invalid_values() {
+        let dir = host_crate(
+            "static-analysis-timelines-fixture",
+            &[(
+                "lib.rs",
+                r#"
+                use pax_engine::api::*;
+                use pax_engine::*;
+                use pax_std::primitives::*;
+
+                #[pax]
+                #[main]
+                #[file("lib.pax")]
+                pub struct Example {
+                    pub opacity: Property<f64>,
+                    pub width: Property<Size>,
+                }
+                "#,
+            )],
+        );
+        let analyze = |settings: &str| {
+            let pax = format!("<Rectangle/>\n@settings {{\n    {}\n}}", settings);
+            fs::write(dir.join("src/lib.pax"), pax).unwrap();
+            analyze_project(&dir)
+        };
+        let valid =
+            analyze("@timeline intro { opacity: [0ms: 0.0, 1s: 1], width: [0ms: 10px, 1s: 50%] }");
+        let unknown = analyze("@timeline intro { opacity: [0ms: 0.0], height: [0ms: 10px] }");
+        let invalid = analyze("@timeline intro { width: [0ms: 10px, 1s: \"wide\"] }");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(valid.is_ok(), "{:?}", valid.err());
+        let file = dir.join("src/lib.pax");
+        assert_eq!(
+            unknown.err().unwrap(),
+            format!(
+                "{}:3:44: `@timeline intro` animates `height`, which isn't a property of `crate::Example`",
+                file.display()
+            )
+        );
+        let invalid = invalid.err().unwrap();
+        assert!(
+            invalid.starts_with(&format!(
+                "{}:3:46: `@timeline intro`: `\"wide\"` isn't a valid value for `width`",
+                file.display()
+            )),
+            "{}",
+            invalid
+        );
+    }
+
+    #[test]
+    fn skips_cfg_gated_items_and_reads_inlined_templates_verbatim() {
+        let dir = host_crate(
+            "static-analysis-cfg-fixture",
+            &[(
+                "lib.rs",
+                r#"
+                use pax_engine::api::*;
+                use pax_engine::*;
+                use pax_std::primitives::*;
+
+                #[pax]
+                #[main]
+                #[inlined(<Rectangle fill=rgb(20, 20, 20) width=100% />)]
+                pub struct Example {
+                    pub shown: Property<bool>,
+                    #[cfg(feature = "fancy")]
+                    pub fancy: Property<bool>,
+                    #[cfg(all(feature = "debug", not(feature = "fancy")))]
+                    pub debug: Property<bool>,
+                }
+
+                #[cfg(not(feature = "parser"))]
+                #[pax]
+                #[main]
+                #[inlined(<Rectangle/>)]
+                pub struct Example {}
+                "#,
+            )],
+        );
+        let mut cargo_toml = fs::OpenOptions::new()
+            .append(true)
+            .open(dir.join("Cargo.toml"))
+            .unwrap();
+        std::io::Write::write_all(
+            &mut cargo_toml,
+            b"\n[features]\ndefault = [\"fancy\"]\nfancy = []\ndebug = []\nparser = []\n",
+        )
+        .unwrap();
+        let result = analyze_project(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let manifest = result.unwrap();
+        let example = TypeId::build_singleton("crate::Example", Some("Example"));
+        let properties: Vec<_> = manifest.type_table[&example]
+            .property_definitions
+            .iter()
+            .map(|property| property.name.as_str())
+            .collect();
+        assert_eq!(properties, vec!["shown", "fancy"]);
+        let template = manifest.components[&example].template.as_ref().unwrap();
+        assert!(template
+            .get_nodes()
+            .iter()
+            .any(|node| node.type_id.get_pascal_identifier() == Some("Rectangle".to_string())));
+    }
+
+    #[test]
+    fn reports_components_missing_from_scope() {
+        let dir = host_crate(
+            "static-analysis-missing-fixture",
+            &[(
+                "lib.rs",
+                r#"
+                use pax_engine::*;
+
+                #[pax]
+                #[main]
+                #[inlined(<Rectangle/>)]
+                pub struct Example {}
+                "#,
+            )],
+        );
+        let result = analyze_project(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let err = result.err().unwrap();
+        assert!(err.contains("`<Rectangle>`"), "{}", err);
+    }
+}
@@ -0,0 +1,34 @@
+//! The grammar of `#[events(...)]` on a component, shared by the `pax` macro and
+//! the static analysis of the compiler so that both accept the same declarations.
+
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Ident, Token, Type};
+
+/// A custom event in `#[events(...)]`: its name, optionally followed by the
+/// type of its payload, e.g. `selection_changed: usize` or `closed`
+pub struct CustomEventDeclaration {
+    pub name: Ident,
+    pub payload_type: Option<Type>,
+}
+
+impl Parse for CustomEventDeclaration {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let payload_type = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { name, payload_type })
+    }
+}
+
+/// Parses the arguments of `#[events(...)]`, e.g. with `Attribute::parse_args_with`
+pub fn parse_custom_event_declarations(
+    input: ParseStream,
+) -> syn::Result<Vec<CustomEventDeclaration>> {
+    let declarations = Punctuated::<CustomEventDeclaration, Token![,]>::parse_terminated(input)?;
+    Ok(declarations.into_iter().collect())
+}
@@ -1,10 +1,12 @@
-use pest::error::Error;
+use pest::error::{Error, ErrorVariant};
 pub use pest::iterators::{Pair, Pairs};
 
 pub use pest::pratt_parser::{Assoc, Op, PrattParser};
 pub use pest::{Parser, Span};
 pub use pest_derive::Parser;
 
+pub mod custom_events;
+
 #[derive(Parser)]
 #[grammar = "pax.pest"]
 pub struct PaxParser;
@@ -25,6 +27,7 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::identifier => "identifier".to_string(),
         Rule::pascal_identifier => "identifier".to_string(),
         Rule::event_id => "@HANDLER_NAME".to_string(),
+        Rule::event_capture => ".capture".to_string(),
         Rule::attribute_key_value_pair => "setting key-value pair".to_string(),
         Rule::attribute_event_binding => "handler binding".to_string(),
         Rule::double_binding => "two-way binding".to_string(),
@@ -43,6 +46,14 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::settings_event_binding => "handler binding".to_string(),
         Rule::settings_key => "setting key (e.g. PROPERTY_NAME: )".to_string(),
         Rule::settings_value => "literal value, literal object, {expression}".to_string(),
+        Rule::settings_timeline => "timeline (e.g. @timeline NAME { PROPERTY_NAME: [0ms: VALUE, 1s: VALUE] })".to_string(),
+        Rule::timeline_options => "timeline options (e.g. (autoplay, loop) )".to_string(),
+        Rule::timeline_option => "autoplay or loop".to_string(),
+        Rule::timeline_track => "timeline track (e.g. PROPERTY_NAME: [0ms: VALUE, 1s: VALUE] )".to_string(),
+        Rule::timeline_keyframe => "keyframe (e.g. 300ms: VALUE out_quad )".to_string(),
+        Rule::timeline_time => "keyframe time (e.g. 300ms, 1.5s )".to_string(),
+        Rule::timeline_time_unit => "unit (ms, s)".to_string(),
+        Rule::timeline_easing => "easing curve (e.g. linear, in_out_quad )".to_string(),
         Rule::literal_function => "function name".to_string(),
         Rule::silent_comma => ",".to_string(),
         Rule::function_list => "function list (e.g. [handle_click, handle_click_again] )".to_string(),
@@ -98,10 +109,13 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::xo_enum_or_function_args_list => "args list".to_string(),
         Rule::statement_control_flow => "if, for, slot".to_string(),
         Rule::statement_if => "if".to_string(),
+        Rule::statement_else_if => "else if".to_string(),
+        Rule::statement_else => "else".to_string(),
         Rule::statement_for => "for".to_string(),
         Rule::statement_slot => "slot".to_string(),
         Rule::statement_for_predicate_declaration => "for predicate (e.g. i, (elem,i) )".to_string(),
         Rule::statement_for_source => "for source (e.g. 0..5 )".to_string(),
+        Rule::statement_for_key => "for key (e.g. key=elem.id )".to_string(),
     }
 }
 
@@ -119,6 +133,13 @@ pub fn parse_pax_str(expected_rule: Rule, input: &str) -> Result<Pair<Rule>, Str
     }
 }
 
+/// Formats `message` as an error pointing at `span`, like the errors of [`parse_pax_str`],
+/// for input that parses but isn't valid
+pub fn span_error(span: Span, message: String) -> String {
+    let error: Error<Rule> = Error::new_from_span(ErrorVariant::CustomError { message }, span);
+    format!("{error}")
+}
+
 pub fn parse_pax_err(expected_rule: Rule, input: &str) -> Result<Pair<Rule>, Error<Rule>> {
     let pairs = PaxParser::parse(expected_rule, input);
     match pairs {
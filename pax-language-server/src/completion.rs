@@ -300,8 +300,22 @@ lazy_static! {
             ("tick", "Set Tick event handler"),
             ("pre_render", "Set Will Render event handler"),
             ("drop", "Set drop event handler"),
+            ("drag_start", "Set Drag Start event handler"),
+            ("drag", "Set Drag event handler"),
+            ("drag_end", "Set Drag End event handler"),
+            ("drag_over", "Set Drag Over event handler"),
+            ("drop_item", "Set Drop Item event handler"),
+            ("tap", "Set Tap event handler"),
+            ("double_tap", "Set Double Tap event handler"),
+            ("long_press", "Set Long Press event handler"),
+            ("pan", "Set Pan event handler"),
+            ("swipe", "Set Swipe event handler"),
+            ("pinch", "Set Pinch event handler"),
+            ("rotate", "Set Rotate event handler"),
             ("mount", "Set Did Mount event handler"),
             ("unmount", "Set Unmount event handler"),
+            ("focus", "Set Focus event handler"),
+            ("blur", "Set Blur event handler"),
         ];
 
         for (event, description) in &events {
@@ -363,6 +377,30 @@ pub fn get_block_declaration_completions() -> Vec<CompletionItem> {
     completions
 }
 
+pub fn get_else_completions() -> Vec<CompletionItem> {
+    let mut completions = Vec::new();
+
+    let mut completion = CompletionItem::new_simple(
+        String::from("else if"),
+        String::from("Add a conditional branch to this if"),
+    );
+    completion.kind = Some(CompletionItemKind::KEYWORD);
+    completion.insert_text = Some("else if $1 {\n\t $0 \n}".to_string());
+    completion.insert_text_format = Some(InsertTextFormat::SNIPPET);
+    completions.push(completion);
+
+    let mut completion = CompletionItem::new_simple(
+        String::from("else"),
+        String::from("Add a final branch to this if"),
+    );
+    completion.kind = Some(CompletionItemKind::KEYWORD);
+    completion.insert_text = Some("else {\n\t $0 \n}".to_string());
+    completion.insert_text_format = Some(InsertTextFormat::SNIPPET);
+    completions.push(completion);
+
+    completions
+}
+
 pub fn get_root_component_methods(component: &PaxComponent) -> Vec<CompletionItem> {
     let mut completions = Vec::new();
     if let Some(c) = component.identifier_map.get(&component.component_name) {
@@ -1,9 +1,9 @@
 use completion::{
     get_all_root_component_member_completions, get_block_declaration_completions,
     get_class_completions, get_common_properties_setting_completions,
-    get_common_property_type_completion, get_id_completions, get_root_component_methods,
-    get_struct_property_setting_completions, get_struct_property_type_completion,
-    get_struct_static_member_completions,
+    get_common_property_type_completion, get_else_completions, get_id_completions,
+    get_root_component_methods, get_struct_property_setting_completions,
+    get_struct_property_type_completion, get_struct_static_member_completions,
 };
 use completion::{get_event_completions, get_struct_completion};
 use core::panic;
@@ -16,7 +16,7 @@ use positional::is_inside_selector_block;
 use positional::is_inside_settings_block;
 use positional::{
     extract_positional_nodes, find_nodes_at_position, find_priority_node, find_relevant_ident,
-    find_relevant_tag, has_attribute_error, NodeType, PositionalNode,
+    find_relevant_tag, has_attribute_error, is_end_of_conditional, NodeType, PositionalNode,
 };
 use regex::Captures;
 use regex::Regex;
@@ -517,6 +517,14 @@ impl Backend {
                                         "transform",
                                         "width",
                                         "height",
+                                        "focusable",
+                                        "tab_index",
+                                        "hit_test",
+                                        "grid_row",
+                                        "grid_column",
+                                        "grid_row_span",
+                                        "grid_column_span",
+                                        "grid_area",
                                     ];
 
                                     if let Some(struct_ident) = struct_name {
@@ -597,6 +605,32 @@ impl Backend {
             .await;
     }
 
+    /// Whether the word being typed at `pos` directly follows an `if` chain, so it
+    /// can continue it with `else if` or `else`
+    fn follows_conditional(&self, uri: &str, pos: &Position, nodes: &[PositionalNode]) -> bool {
+        if let Some(rope) = self.document_content.get(uri) {
+            let char_pos = rope.line_to_char(pos.line as usize) + pos.character as usize;
+            let start = char_pos.saturating_sub(50);
+            let text_before_pos = rope.slice(start..char_pos).to_string();
+
+            // skip the partially typed `else` or `else if`
+            let before_word =
+                text_before_pos.trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace());
+            if !before_word.ends_with('}') {
+                return false;
+            }
+            let brace_end = start + before_word.chars().count();
+            let line = rope.char_to_line(brace_end);
+            let end = Position {
+                line: line as u32,
+                character: (brace_end - rope.line_to_char(line)) as u32,
+            };
+            return is_end_of_conditional(&end, nodes);
+        }
+
+        false
+    }
+
     fn get_valid_setter(&self, uri: &str, pos: &Position) -> Option<String> {
         if let Some(rope) = self.document_content.get(uri) {
             let char_pos = rope.line_to_char(pos.line as usize) + pos.character as usize;
@@ -936,6 +970,10 @@ impl LanguageServer for Backend {
                             return Ok(Some(CompletionResponse::Array(completions)));
                         }
                     }
+                    if self.follows_conditional(uri.path(), pos, &cached_nodes) {
+                        completions.extend(get_else_completions());
+                        return Ok(Some(CompletionResponse::Array(completions)));
+                    }
                 }
             }
         }
@@ -22,6 +22,8 @@ pub enum NodeType {
     AttributeKeyValuePair(AttributeData),
     AttributeKeyValuePairError(),
     XoFunctionCall(FunctionCallData),
+    /// An `if` chain that an `else` can still be added to
+    Conditional,
 }
 
 #[derive(Debug, Clone)]
@@ -70,6 +72,20 @@ fn pair_to_positions(pair: &Pair<Rule>) -> (Position, Position) {
     (start, end)
 }
 
+/// The position after the last character of `text`, which starts at `start`
+fn end_of_text(start: Position, text: &str) -> Position {
+    match text.rsplit_once('\n') {
+        Some((before, last_line)) => Position {
+            line: start.line + before.matches('\n').count() as u32 + 1,
+            character: last_line.chars().count() as u32,
+        },
+        None => Position {
+            line: start.line,
+            character: start.character + text.chars().count() as u32,
+        },
+    }
+}
+
 pub fn extract_positional_nodes(
     pair: Pair<'_, Rule>,
     nodes: &mut Vec<PositionalNode>,
@@ -82,6 +98,15 @@ pub fn extract_positional_nodes(
     let mut inner = pair.into_inner();
 
     match rule {
+        Rule::statement_if if !inner.clone().any(|p| p.as_rule() == Rule::statement_else) => {
+            // an `if` chain's span runs on over the whitespace after its last `}`,
+            // so end it at the brace that an `else` would follow
+            nodes.push(PositionalNode {
+                start,
+                end: end_of_text(start, as_str.trim_end()),
+                node_type: NodeType::Conditional,
+            });
+        }
         Rule::settings_block_declaration => {
             nodes.push(PositionalNode {
                 start,
@@ -385,3 +410,12 @@ pub fn is_inside_selector_block(nodes: &Vec<PositionalNode>) -> bool {
     }
     false
 }
+
+/// Whether an `if` chain without a final `else` ends at `pos`
+pub fn is_end_of_conditional(pos: &Position, nodes: &[PositionalNode]) -> bool {
+    nodes.iter().any(|node| {
+        matches!(node.node_type, NodeType::Conditional)
+            && node.end.line == pos.line
+            && node.end.character == pos.character
+    })
+}
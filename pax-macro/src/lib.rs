@@ -11,10 +11,11 @@ use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 
 use templating::{
-    ArgsFullComponent, ArgsPrimitive, ArgsStructOnlyComponent, StaticPropertyDefinition,
-    TemplateArgsDerivePax,
+    ArgsFullComponent, ArgsPrimitive, ArgsStructOnlyComponent, StaticCustomEventDefinition,
+    StaticPropertyDefinition, TemplateArgsDerivePax,
 };
 
+use pax_lang::custom_events::{parse_custom_event_declarations, CustomEventDeclaration};
 use sailfish::TemplateOnce;
 
 use syn::{
@@ -263,6 +264,29 @@ fn get_static_property_definitions_from_tokens(data: &Data) -> Vec<StaticPropert
     ret
 }
 
+fn get_static_custom_event_definitions(
+    declarations: &[CustomEventDeclaration],
+) -> Vec<StaticCustomEventDefinition> {
+    declarations
+        .iter()
+        .map(|declaration| {
+            let (scoped_resolvable_types, root_scoped_resolvable_type) =
+                match &declaration.payload_type {
+                    Some(payload_type) => {
+                        let (scoped, root) = get_scoped_resolvable_types(payload_type);
+                        (scoped, Some(root))
+                    }
+                    None => (vec![], None),
+                };
+            StaticCustomEventDefinition {
+                name: declaration.name.to_string(),
+                scoped_resolvable_types,
+                root_scoped_resolvable_type,
+            }
+        })
+        .collect()
+}
+
 fn pax_full_component(
     raw_pax: String,
     input_parsed: &DeriveInput,
@@ -270,6 +294,7 @@ fn pax_full_component(
     include_fix: Option<TokenStream>,
     is_custom_interpolatable: bool,
     associated_pax_file_path: Option<PathBuf>,
+    custom_events: &[CustomEventDeclaration],
 ) -> proc_macro2::TokenStream {
     let pascal_identifier = input_parsed.ident.to_string();
 
@@ -311,6 +336,7 @@ fn pax_full_component(
             reexports_snippet,
             associated_pax_file_path,
             error_message,
+            custom_events: get_static_custom_event_definitions(custom_events),
         }),
         pascal_identifier,
         static_property_definitions,
@@ -339,6 +365,7 @@ struct Config {
     custom_values: Option<Vec<String>>,
     primitive_instance_import_path: Option<String>,
     is_primitive: bool,
+    custom_events: syn::Result<Vec<CustomEventDeclaration>>,
 }
 
 fn parse_config(attrs: &mut Vec<syn::Attribute>) -> Config {
@@ -349,6 +376,7 @@ fn parse_config(attrs: &mut Vec<syn::Attribute>) -> Config {
         custom_values: None,
         primitive_instance_import_path: None,
         is_primitive: false,
+        custom_events: Ok(vec![]),
     };
 
     // iterate through `derive macro helper attributes` to gather config & args
@@ -376,6 +404,10 @@ fn parse_config(attrs: &mut Vec<syn::Attribute>) -> Config {
                     }
                 }
             }
+            Some(s) if s == "events" => {
+                config.custom_events = attr.parse_args_with(parse_custom_event_declarations);
+                return false;
+            }
             Some(s) if s == "inlined" => {
                 let tokens = attr.tokens.clone();
                 let mut content = proc_macro2::TokenStream::new();
@@ -444,6 +476,22 @@ fn validate_config(
         .to_compile_error()
         .into());
     }
+    match &config.custom_events {
+        Err(err) => return Err(err.to_compile_error().into()),
+        Ok(events)
+            if !events.is_empty()
+                && config.file_path.is_none()
+                && config.inlined_contents.is_none() =>
+        {
+            return Err(syn::Error::new_spanned(
+                input.ident.clone(),
+                "Only components with a template can dispatch custom events; `#[events(...)]` requires #[file(...)] or #[inlined(...)]",
+            )
+            .to_compile_error()
+            .into());
+        }
+        _ => {}
+    }
     if config.is_primitive && (config.file_path.is_some() || config.inlined_contents.is_some()) {
         const ERR: &str = "Primitives cannot have attached templates. Instead, specify a fully qualified Rust import path pointing to the `impl RenderNode` struct for this primitive.";
         return Err(syn::Error::new_spanned(input.ident.clone(), ERR)
@@ -498,6 +546,7 @@ pub fn pax(
             Some(include_fix),
             is_custom_interpolatable,
             associated_pax_file,
+            config.custom_events.as_deref().unwrap_or_default(),
         )
     } else if is_pax_inlined {
         let contents = config.inlined_contents.unwrap();
@@ -509,6 +558,7 @@ pub fn pax(
             None,
             is_custom_interpolatable,
             None,
+            config.custom_events.as_deref().unwrap_or_default(),
         )
     } else if config.is_primitive {
         pax_primitive(
@@ -85,6 +85,24 @@ fn recurse_visit_tag_pairs_for_pascal_identifiers(
         Rule::statement_control_flow => {
             let matched_tag = any_tag_pair.into_inner().next().unwrap();
 
+            if matched_tag.as_rule() == Rule::statement_if {
+                //`else if` and `else` branches each carry their own inner_nodes
+                for branch in matched_tag.clone().into_inner() {
+                    if let Rule::statement_else_if | Rule::statement_else = branch.as_rule() {
+                        let inner_nodes = branch
+                            .into_inner()
+                            .find(|p| p.as_rule() == Rule::inner_nodes)
+                            .unwrap();
+                        inner_nodes.into_inner().for_each(|sub_tag_pair| {
+                            recurse_visit_tag_pairs_for_pascal_identifiers(
+                                sub_tag_pair,
+                                Rc::clone(&pascal_identifiers),
+                            );
+                        })
+                    }
+                }
+            }
+
             let n = match matched_tag.as_rule() {
                 Rule::statement_if => 1,
                 Rule::statement_for => 2,
@@ -94,6 +112,15 @@ fn recurse_visit_tag_pairs_for_pascal_identifiers(
                 }
             };
 
+            //`for` carries an optional `key=...` before its inner_nodes
+            let n = if matched_tag.as_rule() == Rule::statement_for
+                && matched_tag.clone().into_inner().nth(n).unwrap().as_rule()
+                    == Rule::statement_for_key
+            {
+                n + 1
+            } else {
+                n
+            };
             let prospective_inner_nodes = matched_tag.into_inner().nth(n).expect("WRONG nth");
             match prospective_inner_nodes.as_rule() {
                 Rule::inner_nodes => {
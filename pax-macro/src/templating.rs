@@ -15,6 +15,15 @@ pub struct StaticPropertyDefinition {
     pub is_enum: bool,
 }
 
+/// A custom event declared with `#[events(...)]`, e.g. `selection_changed: usize`
+#[derive(Serialize, Debug)]
+pub struct StaticCustomEventDefinition {
+    pub name: String,
+    /// Same as for [`StaticPropertyDefinition`], for the payload type if there is one
+    pub scoped_resolvable_types: Vec<String>,
+    pub root_scoped_resolvable_type: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct ArgsPrimitive {
     /// For example: "pax_std_primitives::RectangleInstance" for Rectangle (pax_std::primitives::Rectangle)
@@ -32,6 +41,7 @@ pub struct ArgsFullComponent {
     pub reexports_snippet: String,
     pub associated_pax_file_path: Option<PathBuf>,
     pub error_message: Option<String>,
+    pub custom_events: Vec<StaticCustomEventDefinition>,
 }
 
 #[derive(TemplateOnce)]
@@ -4,7 +4,7 @@ use std::collections::{BTreeMap, HashMap};
 use crate::{
     constants::{COMMON_PROPERTIES, COMMON_PROPERTIES_TYPE},
     PaxManifest, PropertyDefinition, SettingElement, SettingsBlockElement, TemplateNodeDefinition,
-    Token, TypeId, ValueDefinition,
+    TimelineDefinition, Token, TypeId, ValueDefinition,
 };
 
 #[derive(Serialize, Debug)]
@@ -14,6 +14,10 @@ pub struct ComponentInfo {
     pub primitive_instance_import_path: Option<String>,
     pub properties: Vec<PropertyInfo>,
     pub handlers: Vec<HandlerInfo>,
+    /// Properties animated by the timelines declared in the component's `@settings`
+    pub timeline_properties: Vec<PropertyInfo>,
+    /// Custom events declared with `#[events(...)]`
+    pub custom_events: Vec<CustomEventInfo>,
 }
 
 #[derive(Serialize, Debug)]
@@ -28,6 +32,18 @@ pub struct HandlerInfo {
     pub args_type: Option<String>,
 }
 
+#[derive(Serialize, Debug)]
+pub struct CustomEventInfo {
+    pub name: String,
+    pub payload_type: Option<String>,
+}
+
+/// Capture phase handlers, e.g. `@click.capture`, take the same arguments as
+/// their event
+fn without_capture_suffix(event: &str) -> &str {
+    event.strip_suffix(".capture").unwrap_or(event)
+}
+
 impl PaxManifest {
     pub fn get_component_handlers(&self, type_id: &TypeId) -> Vec<(String, Vec<String>)> {
         let mut handlers = Vec::new();
@@ -49,6 +65,20 @@ impl PaxManifest {
         handlers
     }
 
+    pub fn get_component_timelines(&self, type_id: &TypeId) -> Vec<TimelineDefinition> {
+        let mut timelines = Vec::new();
+        if let Some(component) = self.components.get(type_id) {
+            if let Some(settings) = &component.settings {
+                for setting in settings {
+                    if let SettingsBlockElement::Timeline(timeline) = setting {
+                        timelines.push(timeline.clone());
+                    }
+                }
+            }
+        }
+        timelines
+    }
+
     pub fn event_to_args_map(&self) -> HashMap<String, Option<String>> {
         let mut map = HashMap::new();
         let mut add = |from: &str, to: &str| {
@@ -77,13 +107,41 @@ impl PaxManifest {
         add("context_menu", "ContextMenu");
         add("wheel", "Wheel");
         add("drop", "Drop");
+        add("drag_start", "DragStart");
+        add("drag", "Drag");
+        add("drag_end", "DragEnd");
+        add("drag_over", "DragOver");
+        add("drop_item", "DropItem");
+        add("tap", "Tap");
+        add("double_tap", "DoubleTap");
+        add("long_press", "LongPress");
+        add("pan", "Pan");
+        add("swipe", "Swipe");
+        add("pinch", "Pinch");
+        add("rotate", "Rotate");
         map.insert("pre_render".to_string(), None);
         map.insert("mount".to_string(), None);
         map.insert("unmount".to_string(), None);
         map.insert("tick".to_string(), None);
+        map.insert("focus".to_string(), None);
+        map.insert("blur".to_string(), None);
         map
     }
 
+    /// The payload type of the custom event `event` dispatched by components of type
+    /// `type_id`, as declared with `#[events(...)]`. Events that aren't declared carry
+    /// no payload.
+    pub fn get_custom_event_payload_type(&self, type_id: &TypeId, event: &str) -> Option<String> {
+        self.components
+            .get(type_id)?
+            .custom_events
+            .iter()
+            .find(|custom_event| custom_event.name == event)?
+            .payload_type
+            .as_ref()
+            .map(|payload_type| payload_type.get_unique_identifier())
+    }
+
     fn clean_handler(&self, handler: String) -> String {
         handler.replace("self.", "").replace("this.", "")
     }
@@ -107,6 +165,24 @@ impl PaxManifest {
                 });
             }
 
+            let mut timeline_properties: Vec<PropertyInfo> = Vec::new();
+            for timeline in self.get_component_timelines(type_id) {
+                for track in &timeline.tracks {
+                    let name = &track.property.token_value;
+                    if timeline_properties.iter().any(|p| &p.name == name) {
+                        continue;
+                    }
+                    // unknown properties are reported when analyzing the project
+                    let Some(property) = properties.iter().find(|p| &p.name == name) else {
+                        continue;
+                    };
+                    timeline_properties.push(PropertyInfo {
+                        name: property.name.clone(),
+                        property_type: property.property_type.clone(),
+                    });
+                }
+            }
+
             let mut handler_data = Vec::new();
 
             // pull all handlers from the component settings
@@ -115,7 +191,7 @@ impl PaxManifest {
                     if let SettingsBlockElement::Handler(key, values) = setting {
                         for value in values {
                             let event_args = event_map
-                                .get(key.token_value.as_str())
+                                .get(without_capture_suffix(&key.token_value))
                                 .expect("custom handlers not supported in settings block")
                                 .as_ref();
                             handler_data.push(HandlerInfo {
@@ -134,12 +210,18 @@ impl PaxManifest {
                         for setting in settings {
                             if let SettingElement::Setting(key, value) = setting {
                                 if let ValueDefinition::EventBindingTarget(e) = value {
-                                    let event_args = event_map
-                                        .get(key.token_value.as_str())
-                                        .and_then(|v| v.as_ref());
+                                    let event = key.token_value.as_str();
+                                    let args_type =
+                                        match event_map.get(without_capture_suffix(event)) {
+                                            Some(event_args) => event_args
+                                                .as_ref()
+                                                .map(|t| format!("Event<{}>", &t)),
+                                            None => self
+                                                .get_custom_event_payload_type(&tnd.type_id, event),
+                                        };
                                     handler_data.push(HandlerInfo {
                                         name: self.clean_handler(e.raw_value.clone()),
-                                        args_type: event_args.map(|t| format!("Event<{}>", &t)),
+                                        args_type,
                                     });
                                 }
                             }
@@ -154,6 +236,18 @@ impl PaxManifest {
                 primitive_instance_import_path: component.primitive_instance_import_path.clone(),
                 properties,
                 handlers: handler_data,
+                timeline_properties,
+                custom_events: component
+                    .custom_events
+                    .iter()
+                    .map(|custom_event| CustomEventInfo {
+                        name: custom_event.name.clone(),
+                        payload_type: custom_event
+                            .payload_type
+                            .as_ref()
+                            .map(|payload_type| payload_type.get_unique_identifier()),
+                    })
+                    .collect(),
             });
         }
         component_infos
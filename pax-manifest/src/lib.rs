@@ -20,7 +20,7 @@ pub mod deserializer;
 
 /// Definition container for an entire Pax cartridge
 #[serde_with::serde_as]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "pax_message::serde")]
 pub struct PaxManifest {
     #[serde_as(as = "BTreeMap<serde_with::json::JsonString, _>")]
@@ -32,7 +32,31 @@ pub struct PaxManifest {
     pub import_paths: std::collections::HashSet<String>,
 }
 
+/// Replacement templates for a running cartridge, sent by `pax-cli run --watch` when
+/// `.pax` files change without requiring a recompile. Every expression in these templates
+/// either has a vtable id in the running cartridge or is listed in `interpreted_expressions`.
+#[serde_with::serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(crate = "pax_message::serde")]
+pub struct TemplatePatch {
+    #[serde_as(as = "BTreeMap<serde_with::json::JsonString, _>")]
+    pub templates: BTreeMap<TypeId, ComponentTemplate>,
+    /// Source of the expressions the cartridge has no compiled code for, by the vtable
+    /// id assigned to them in `templates`. The chassis registers interpreted entries for these.
+    #[serde(default)]
+    pub interpreted_expressions: BTreeMap<usize, String>,
+}
+
 impl PaxManifest {
+    /// Replaces the templates of the components in `patch`
+    pub fn apply_template_patch(&mut self, patch: TemplatePatch) {
+        for (type_id, template) in patch.templates {
+            if let Some(component) = self.components.get_mut(&type_id) {
+                component.template = Some(template);
+            }
+        }
+    }
+
     pub fn get_template_node(
         &self,
         uni: &UniqueTemplateNodeIdentifier,
@@ -260,6 +284,18 @@ pub struct ComponentDefinition {
     pub primitive_instance_import_path: Option<String>,
     pub template: Option<ComponentTemplate>,
     pub settings: Option<Vec<SettingsBlockElement>>,
+    /// Custom events this component dispatches, declared with `#[events(...)]`
+    pub custom_events: Vec<CustomEventDefinition>,
+}
+
+/// A custom event declared on a component, e.g. `selection_changed: usize` in
+/// `#[events(selection_changed: usize)]`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "pax_message::serde")]
+pub struct CustomEventDefinition {
+    pub name: String,
+    /// The type passed to handlers bound to this event, if any
+    pub payload_type: Option<TypeId>,
 }
 
 impl ComponentDefinition {
@@ -273,9 +309,40 @@ impl ComponentDefinition {
 pub enum SettingsBlockElement {
     SelectorBlock(Token, LiteralBlockDefinition),
     Handler(Token, Vec<Token>),
+    Timeline(TimelineDefinition),
     Comment(String),
 }
 
+/// A keyframe timeline declared in a `@settings` block, e.g.
+/// `@timeline intro (autoplay) { opacity: [0ms: 0.0, 300ms: 1.0 out_quad] }`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "pax_message::serde")]
+pub struct TimelineDefinition {
+    pub name: Token,
+    pub autoplay: bool,
+    pub looping: bool,
+    pub tracks: Vec<TimelineTrackDefinition>,
+}
+
+/// The keyframes of a single property of the component
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "pax_message::serde")]
+pub struct TimelineTrackDefinition {
+    pub property: Token,
+    pub keyframes: Vec<KeyframeDefinition>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "pax_message::serde")]
+pub struct KeyframeDefinition {
+    /// Rounded to whole milliseconds
+    pub time_ms: u64,
+    pub value: Token,
+    /// Name of the curve easing into this keyframe (see `EasingCurve::from_name`),
+    /// linear when not specified
+    pub easing: Option<Token>,
+}
+
 #[derive(Serialize, Default, Deserialize, Debug, Clone, Hash, PartialEq, Eq)]
 #[serde(crate = "pax_message::serde")]
 pub struct TemplateNodeId(usize);
@@ -1210,8 +1277,10 @@ impl ComponentTemplate {
             if let Some(settings) = &tnd.settings {
                 for setting in settings {
                     if let SettingElement::Setting(_, v) = setting {
-                        if let ValueDefinition::Expression(t, id) = v {
-                            ret.insert(t.raw_value.clone(), id.clone().unwrap());
+                        if let ValueDefinition::Expression(t, Some(id))
+                        | ValueDefinition::Identifier(t, Some(id)) = v
+                        {
+                            ret.insert(t.raw_value.clone(), id.clone());
                         }
                         if let ValueDefinition::Block(b) = v {
                             Self::recurse_get_known_expressions(b, &mut ret);
@@ -1229,8 +1298,10 @@ impl ComponentTemplate {
     ) {
         for s in block.elements.iter() {
             if let SettingElement::Setting(_, v) = s {
-                if let ValueDefinition::Expression(t, e) = v {
-                    known_expressions.insert(t.raw_value.clone(), e.clone().unwrap());
+                if let ValueDefinition::Expression(t, Some(e))
+                | ValueDefinition::Identifier(t, Some(e)) = v
+                {
+                    known_expressions.insert(t.raw_value.clone(), e.clone());
                 }
                 if let ValueDefinition::Block(b) = v {
                     Self::recurse_get_known_expressions(b, known_expressions);
@@ -1247,12 +1318,23 @@ impl ComponentTemplate {
         for (_, tnd) in self.nodes.iter_mut() {
             if let Some(settings) = &mut tnd.settings {
                 for setting in settings {
-                    if let SettingElement::Setting(_k, v) = setting {
+                    if let SettingElement::Setting(k, v) = setting {
+                        // `id=some_identifier` and `class=some_identifier` are never compiled
+                        let is_compiled_identifier =
+                            k.token_value != "id" && k.token_value != "class";
                         if let ValueDefinition::Expression(t, ec) = v {
                             if let Some(new_ec) = known_expressions.get(t.raw_value.trim()) {
                                 *ec = Some(new_ec.clone());
                             }
                         }
+                        if let ValueDefinition::Identifier(t, ec) = v {
+                            if let Some(new_ec) = known_expressions
+                                .get(t.raw_value.trim())
+                                .filter(|_| is_compiled_identifier)
+                            {
+                                *ec = Some(new_ec.clone());
+                            }
+                        }
                         if let ValueDefinition::Block(b) = v {
                             Self::recurse_update_block(b, known_expressions);
                         }
@@ -1268,7 +1350,7 @@ impl ComponentTemplate {
     ) {
         for s in block.elements.iter_mut() {
             if let SettingElement::Setting(_k, v) = s {
-                if let ValueDefinition::Expression(t, ec) = v {
+                if let ValueDefinition::Expression(t, ec) | ValueDefinition::Identifier(t, ec) = v {
                     if let Some(new_ec) = known_expressions.get(t.raw_value.trim()) {
                         *ec = Some(new_ec.clone());
                     }
@@ -1307,6 +1389,71 @@ impl ComponentTemplate {
         }
     }
 
+    /// Returns the source of every expression in this template that has not been
+    /// assigned a vtable id, e.g. after [`Self::populate_template_with_known_entities`]
+    /// with an expression that was not part of the compiled template
+    pub fn get_uncompiled_expressions(&self) -> Vec<String> {
+        let mut ret = Vec::new();
+        for (_, tnd) in self.nodes.iter() {
+            if let Some(settings) = &tnd.settings {
+                for setting in settings {
+                    if let SettingElement::Setting(k, v) = setting {
+                        if k.token_value == "id" || k.token_value == "class" {
+                            continue;
+                        }
+                        Self::recurse_get_uncompiled_expressions(v, &mut ret);
+                    }
+                }
+            }
+            if let Some(cfsd) = &tnd.control_flow_settings {
+                if let (Some(t), None) = (
+                    &cfsd.condition_expression_paxel,
+                    &cfsd.condition_expression_info,
+                ) {
+                    ret.push(t.raw_value.clone());
+                }
+                if let (Some(t), None) = (
+                    &cfsd.slot_index_expression_paxel,
+                    &cfsd.slot_index_expression_info,
+                ) {
+                    ret.push(t.raw_value.clone());
+                }
+                if let Some(source) = &cfsd.repeat_source_definition {
+                    if source.expression_info.is_none() {
+                        let t = source
+                            .range_expression_paxel
+                            .as_ref()
+                            .or(source.symbolic_binding.as_ref());
+                        ret.extend(t.map(|t| t.raw_value.clone()));
+                    }
+                }
+                if let (Some(t), None) = (
+                    &cfsd.repeat_key_expression_paxel,
+                    &cfsd.repeat_key_expression_info,
+                ) {
+                    ret.push(t.raw_value.clone());
+                }
+            }
+        }
+        ret
+    }
+
+    fn recurse_get_uncompiled_expressions(value: &ValueDefinition, uncompiled: &mut Vec<String>) {
+        match value {
+            ValueDefinition::Expression(t, None) | ValueDefinition::Identifier(t, None) => {
+                uncompiled.push(t.raw_value.clone())
+            }
+            ValueDefinition::Block(b) => {
+                for s in b.elements.iter() {
+                    if let SettingElement::Setting(_, v) = s {
+                        Self::recurse_get_uncompiled_expressions(v, uncompiled);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
     pub fn get_all_children_relationships(
         &self,
     ) -> HashMap<TemplateNodeId, VecDeque<TemplateNodeId>> {
@@ -1657,11 +1804,28 @@ pub struct ControlFlowSettingsDefinition {
     pub slot_index_expression_info: Option<ExpressionCompilationInfo>,
     pub repeat_predicate_definition: Option<ControlFlowRepeatPredicateDefinition>,
     pub repeat_source_definition: Option<ControlFlowRepeatSourceDefinition>,
+    pub repeat_key_expression_paxel: Option<Token>,
+    pub repeat_key_expression_info: Option<ExpressionCompilationInfo>,
+    /// For conditionals, the branch of its `if` chain this node was written as
+    pub conditional_branch: Option<ConditionalBranchDefinition>,
+}
+
+/// A branch of an `if` / `else if` / `else` chain. `else if` and `else` branches
+/// compile to sibling conditionals whose condition negates every preceding branch,
+/// so this keeps each branch as written for printing the template back
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(crate = "pax_message::serde")]
+pub enum ConditionalBranchDefinition {
+    If,
+    /// `else if`, with its own condition
+    ElseIf(Token),
+    Else,
 }
 
 impl PartialEq for ControlFlowRepeatSourceDefinition {
     fn eq(&self, other: &Self) -> bool {
         self.range_expression_paxel == other.range_expression_paxel
+            && self.symbolic_binding == other.symbolic_binding
     }
 }
 
@@ -1670,6 +1834,7 @@ impl Eq for ControlFlowRepeatSourceDefinition {}
 impl Hash for ControlFlowRepeatSourceDefinition {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.range_expression_paxel.hash(state);
+        self.symbolic_binding.hash(state);
     }
 }
 
@@ -1679,6 +1844,8 @@ impl PartialEq for ControlFlowSettingsDefinition {
             && self.slot_index_expression_paxel == other.slot_index_expression_paxel
             && self.repeat_predicate_definition == other.repeat_predicate_definition
             && self.repeat_source_definition == other.repeat_source_definition
+            && self.repeat_key_expression_paxel == other.repeat_key_expression_paxel
+            && self.conditional_branch == other.conditional_branch
     }
 }
 
@@ -1690,6 +1857,8 @@ impl Hash for ControlFlowSettingsDefinition {
         self.slot_index_expression_paxel.hash(state);
         self.repeat_predicate_definition.hash(state);
         self.repeat_source_definition.hash(state);
+        self.repeat_key_expression_paxel.hash(state);
+        self.conditional_branch.hash(state);
     }
 }
 
@@ -1796,6 +1965,7 @@ pub enum TokenType {
     IfExpression,
     ForPredicate,
     ForSource,
+    ForKey,
     SlotExpression,
     EventId,
     Handler,
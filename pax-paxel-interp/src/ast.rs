@@ -0,0 +1,465 @@
+use pax_lang::{Assoc, Op, Pair, Pairs, Parser, PaxParser, PrattParser, Rule};
+use pax_runtime_api::{Color, Numeric};
+
+/// Parsed representation of a PAXEL `expression_body`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaxelExpr {
+    Bool(bool),
+    Number(Numeric),
+    String(String),
+    Color(Color),
+    /// A number literal or grouped expression followed by a unit, e.g. `10px` or `(self.x + 5)%`
+    WithUnit(Box<PaxelExpr>, Unit),
+    /// A symbol such as `self.num_clicks` or `elem`, with any trailing member or index accesses
+    Symbol {
+        root: String,
+        accessors: Vec<Accessor>,
+    },
+    Unary(UnaryOp, Box<PaxelExpr>),
+    Binary(BinaryOp, Box<PaxelExpr>, Box<PaxelExpr>),
+    Ternary(Box<PaxelExpr>, Box<PaxelExpr>, Box<PaxelExpr>),
+    /// Exclusive range, e.g. `0..self.count`
+    Range(Box<PaxelExpr>, Box<PaxelExpr>),
+    List(Vec<PaxelExpr>),
+    /// `rgb(..)`, `rgba(..)`, `hsl(..)` or `hsla(..)`
+    ColorFunc(ColorFunc, Vec<PaxelExpr>),
+    /// Syntax that parses but relies on Rust types only known to the compiler (object literals,
+    /// enum values, function calls, tuples). Kept so that errors can point at the offending source.
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Pixels,
+    Percent,
+    Degrees,
+    Radians,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Accessor {
+    Member(String),
+    Index(PaxelExpr),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Exp,
+    And,
+    Or,
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFunc {
+    Rgb,
+    Rgba,
+    Hsl,
+    Hsla,
+}
+
+/// Intermediate result of the pratt parser: the `?` half of a ternary is only
+/// meaningful once its matching `:` has been seen.
+enum Parsed {
+    Expr(PaxelExpr),
+    TernaryThen(PaxelExpr, PaxelExpr),
+}
+
+impl Parsed {
+    fn into_expr(self) -> Result<PaxelExpr, String> {
+        match self {
+            Parsed::Expr(expr) => Ok(expr),
+            Parsed::TernaryThen(..) => Err("ternary `?` without matching `:`".to_string()),
+        }
+    }
+}
+
+/// Parses a PAXEL expression, e.g. `self.num_clicks % 2 == 0 ? 10px : 20px`
+pub fn parse(paxel: &str) -> Result<PaxelExpr, String> {
+    let mut pairs = PaxParser::parse(Rule::expression_body, paxel)
+        .map_err(|e| format!("failed to parse `{}`: {}", paxel, e))?;
+    let expression_body = pairs.next().unwrap();
+    let unparsed = paxel[expression_body.as_span().end()..].trim();
+    if !unparsed.is_empty() {
+        return Err(format!(
+            "failed to parse `{}`: unexpected `{}`",
+            paxel, unparsed
+        ));
+    }
+    parse_expression_body(expression_body.into_inner())
+}
+
+fn pratt_parser() -> PrattParser<Rule> {
+    // Same precedence as the compiler's transpiler in `pax_compiler::parsing::run_pratt_parser`
+    PrattParser::new()
+        .op(Op::infix(Rule::xo_tern_then, Assoc::Left)
+            | Op::infix(Rule::xo_tern_else, Assoc::Right))
+        .op(Op::infix(Rule::xo_bool_and, Assoc::Left) | Op::infix(Rule::xo_bool_or, Assoc::Left))
+        .op(Op::infix(Rule::xo_add, Assoc::Left) | Op::infix(Rule::xo_sub, Assoc::Left))
+        .op(Op::infix(Rule::xo_mul, Assoc::Left) | Op::infix(Rule::xo_div, Assoc::Left))
+        .op(Op::infix(Rule::xo_mod, Assoc::Left))
+        .op(Op::infix(Rule::xo_exp, Assoc::Right))
+        .op(Op::prefix(Rule::xo_neg))
+        .op(Op::infix(Rule::xo_rel_eq, Assoc::Left)
+            | Op::infix(Rule::xo_rel_neq, Assoc::Left)
+            | Op::infix(Rule::xo_rel_lt, Assoc::Left)
+            | Op::infix(Rule::xo_rel_lte, Assoc::Left)
+            | Op::infix(Rule::xo_rel_gt, Assoc::Left)
+            | Op::infix(Rule::xo_rel_gte, Assoc::Left))
+        .op(Op::prefix(Rule::xo_bool_not))
+}
+
+fn parse_expression_body(pairs: Pairs<Rule>) -> Result<PaxelExpr, String> {
+    pratt_parser()
+        .map_primary(|primary| parse_primary(primary).map(Parsed::Expr))
+        .map_prefix(|op, rhs| {
+            let op = match op.as_rule() {
+                Rule::xo_neg => UnaryOp::Neg,
+                Rule::xo_bool_not => UnaryOp::Not,
+                rule => unreachable!("unexpected prefix operator {:?}", rule),
+            };
+            Ok(Parsed::Expr(PaxelExpr::Unary(
+                op,
+                Box::new(rhs?.into_expr()?),
+            )))
+        })
+        .map_infix(|lhs, op, rhs| {
+            let lhs = lhs?;
+            let rhs = rhs?.into_expr()?;
+            let op = match op.as_rule() {
+                Rule::xo_tern_then => return Ok(Parsed::TernaryThen(lhs.into_expr()?, rhs)),
+                Rule::xo_tern_else => {
+                    return match lhs {
+                        Parsed::TernaryThen(condition, then) => Ok(Parsed::Expr(
+                            PaxelExpr::Ternary(Box::new(condition), Box::new(then), Box::new(rhs)),
+                        )),
+                        Parsed::Expr(_) => Err("ternary `:` without matching `?`".to_string()),
+                    }
+                }
+                Rule::xo_add => BinaryOp::Add,
+                Rule::xo_sub => BinaryOp::Sub,
+                Rule::xo_mul => BinaryOp::Mul,
+                Rule::xo_div => BinaryOp::Div,
+                Rule::xo_mod => BinaryOp::Mod,
+                Rule::xo_exp => BinaryOp::Exp,
+                Rule::xo_bool_and => BinaryOp::And,
+                Rule::xo_bool_or => BinaryOp::Or,
+                Rule::xo_rel_eq => BinaryOp::Eq,
+                Rule::xo_rel_neq => BinaryOp::Neq,
+                Rule::xo_rel_lt => BinaryOp::Lt,
+                Rule::xo_rel_lte => BinaryOp::Lte,
+                Rule::xo_rel_gt => BinaryOp::Gt,
+                Rule::xo_rel_gte => BinaryOp::Gte,
+                rule => unreachable!("unexpected infix operator {:?}", rule),
+            };
+            Ok(Parsed::Expr(PaxelExpr::Binary(
+                op,
+                Box::new(lhs.into_expr()?),
+                Box::new(rhs),
+            )))
+        })
+        .parse(pairs)?
+        .into_expr()
+}
+
+fn parse_primary(primary: Pair<Rule>) -> Result<PaxelExpr, String> {
+    match primary.as_rule() {
+        Rule::expression_body => parse_expression_body(primary.into_inner()),
+        Rule::expression_grouped => {
+            /* expression_grouped = { "(" ~ expression_body ~ ")" ~ literal_number_unit? } */
+            let mut inner = primary.into_inner();
+            let expr = parse_expression_body(inner.next().unwrap().into_inner())?;
+            Ok(match inner.next() {
+                Some(unit) => PaxelExpr::WithUnit(Box::new(expr), parse_unit(unit.as_str())),
+                None => expr,
+            })
+        }
+        Rule::xo_symbol => Ok(parse_symbol(primary)?),
+        Rule::xo_literal => parse_literal(primary.into_inner().next().unwrap()),
+        Rule::xo_range => {
+            /* xo_range = { (xo_literal | xo_symbol) ~ xo_range_exclusive ~ (xo_literal | xo_symbol) } */
+            let mut inner = primary.into_inner();
+            let start = parse_primary(inner.next().unwrap())?;
+            let _exclusive = inner.next();
+            let end = parse_primary(inner.next().unwrap())?;
+            Ok(PaxelExpr::Range(Box::new(start), Box::new(end)))
+        }
+        Rule::xo_list => Ok(PaxelExpr::List(
+            primary
+                .into_inner()
+                .map(|item| parse_expression_body(item.into_inner()))
+                .collect::<Result<_, _>>()?,
+        )),
+        Rule::xo_color_space_func => {
+            let func = parse_color_func(primary.as_str());
+            let args = primary
+                .into_inner()
+                .map(|arg| parse_expression_body(arg.into_inner()))
+                .collect::<Result<_, _>>()?;
+            Ok(PaxelExpr::ColorFunc(func, args))
+        }
+        Rule::xo_object | Rule::xo_enum_or_function_call | Rule::xo_tuple => {
+            Ok(PaxelExpr::Unsupported(primary.as_str().trim().to_string()))
+        }
+        rule => unreachable!("unexpected primary {:?}", rule),
+    }
+}
+
+fn parse_symbol(xo_symbol: Pair<Rule>) -> Result<PaxelExpr, String> {
+    /* xo_symbol = { "$"? ~ identifier ~ (("." ~ identifier) | ("[" ~ expression_body ~ "]") )* } */
+    let source = xo_symbol.as_str().trim();
+    let mut accessors = vec![];
+    for pair in xo_symbol.into_inner() {
+        accessors.push(match pair.as_rule() {
+            Rule::identifier => Accessor::Member(pair.as_str().to_string()),
+            Rule::expression_body => Accessor::Index(parse_expression_body(pair.into_inner())?),
+            rule => unreachable!("unexpected symbol part {:?}", rule),
+        });
+    }
+    let mut accessors = accessors.into_iter();
+    let Some(Accessor::Member(mut root)) = accessors.next() else {
+        return Err(format!("invalid symbol `{}`", source));
+    };
+    let mut accessors: Vec<_> = accessors.collect();
+    if (root == "self" || root == "this") && !accessors.is_empty() {
+        let Accessor::Member(member) = accessors.remove(0) else {
+            return Err(format!("invalid symbol `{}`", source));
+        };
+        root = member;
+    }
+    // `true` and `false` aren't literals in expressions; they parse as symbols
+    match (root.as_str(), accessors.is_empty()) {
+        ("true", true) => Ok(PaxelExpr::Bool(true)),
+        ("false", true) => Ok(PaxelExpr::Bool(false)),
+        _ => Ok(PaxelExpr::Symbol { root, accessors }),
+    }
+}
+
+fn parse_literal(literal: Pair<Rule>) -> Result<PaxelExpr, String> {
+    match literal.as_rule() {
+        Rule::literal_number => parse_number(literal),
+        Rule::literal_number_with_unit => {
+            let mut inner = literal.into_inner();
+            let number = parse_number(inner.next().unwrap())?;
+            let unit = parse_unit(inner.next().unwrap().as_str());
+            Ok(PaxelExpr::WithUnit(Box::new(number), unit))
+        }
+        Rule::string => Ok(PaxelExpr::String(unescape(
+            literal.into_inner().next().unwrap().as_str(),
+        )?)),
+        Rule::literal_color => {
+            let color = literal.into_inner().next().unwrap();
+            match color.as_rule() {
+                Rule::literal_color_const => Ok(PaxelExpr::Color(color_const(color.as_str()))),
+                _ => {
+                    let func = parse_color_func(color.as_str());
+                    let args = color
+                        .into_inner()
+                        .map(|channel| parse_literal(channel.into_inner().next().unwrap()))
+                        .collect::<Result<_, _>>()?;
+                    Ok(PaxelExpr::ColorFunc(func, args))
+                }
+            }
+        }
+        Rule::literal_number_integer | Rule::literal_number_float => {
+            parse_number_str(literal.as_str())
+        }
+        /* literal_enum_value | literal_tuple_access | literal_tuple */
+        _ => Ok(PaxelExpr::Unsupported(literal.as_str().trim().to_string())),
+    }
+}
+
+fn parse_number(literal_number: Pair<Rule>) -> Result<PaxelExpr, String> {
+    parse_number_str(literal_number.as_str())
+}
+
+fn parse_number_str(number: &str) -> Result<PaxelExpr, String> {
+    let number = number.trim();
+    let numeric = if number.contains('.') {
+        Numeric::F64(
+            number
+                .parse()
+                .map_err(|e| format!("invalid number `{}`: {}", number, e))?,
+        )
+    } else {
+        Numeric::I64(
+            number
+                .parse()
+                .map_err(|e| format!("invalid number `{}`: {}", number, e))?,
+        )
+    };
+    Ok(PaxelExpr::Number(numeric))
+}
+
+fn parse_unit(unit: &str) -> Unit {
+    match unit.trim() {
+        "px" => Unit::Pixels,
+        "%" => Unit::Percent,
+        "deg" => Unit::Degrees,
+        "rad" => Unit::Radians,
+        unit => unreachable!("unexpected unit {}", unit),
+    }
+}
+
+fn parse_color_func(source: &str) -> ColorFunc {
+    match source.split('(').next().unwrap().trim() {
+        "rgb" => ColorFunc::Rgb,
+        "rgba" => ColorFunc::Rgba,
+        "hsl" => ColorFunc::Hsl,
+        "hsla" => ColorFunc::Hsla,
+        func => unreachable!("unexpected color function {}", func),
+    }
+}
+
+fn color_const(name: &str) -> Color {
+    match name {
+        "SLATE" => Color::SLATE,
+        "GRAY" => Color::GRAY,
+        "ZINC" => Color::ZINC,
+        "NEUTRAL" => Color::NEUTRAL,
+        "STONE" => Color::STONE,
+        "RED" => Color::RED,
+        "ORANGE" => Color::ORANGE,
+        "AMBER" => Color::AMBER,
+        "YELLOW" => Color::YELLOW,
+        "LIME" => Color::LIME,
+        "GREEN" => Color::GREEN,
+        "EMERALD" => Color::EMERALD,
+        "TEAL" => Color::TEAL,
+        "CYAN" => Color::CYAN,
+        "SKY" => Color::SKY,
+        "BLUE" => Color::BLUE,
+        "INDIGO" => Color::INDIGO,
+        "VIOLET" => Color::VIOLET,
+        "PURPLE" => Color::PURPLE,
+        "FUCHSIA" => Color::FUCHSIA,
+        "PINK" => Color::PINK,
+        "ROSE" => Color::ROSE,
+        "BLACK" => Color::BLACK,
+        "WHITE" => Color::WHITE,
+        "TRANSPARENT" => Color::TRANSPARENT,
+        "NONE" => Color::NONE,
+        name => unreachable!("unexpected color constant {}", name),
+    }
+}
+
+/// Resolves the escape sequences allowed by the grammar's `char` rule
+fn unescape(inner: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('b') => out.push('\u{8}'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("invalid unicode escape `\\u{}`", hex))?;
+                out.push(c);
+            }
+            Some(c) => out.push(c),
+            None => return Err("unterminated escape sequence".to_string()),
+        }
+    }
+    Ok(out)
+}
+
+impl PaxelExpr {
+    /// The root symbols this expression reads, e.g. `["a", "b"]` for `self.a + b[0]`.
+    /// These are the properties an interpreted expression needs to be recomputed on.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols = vec![];
+        self.collect_symbols(&mut symbols);
+        symbols
+    }
+
+    /// The first syntax in this expression that the interpreter can't evaluate, if any,
+    /// e.g. `Foo::bar(1)` for `1 + Foo::bar(1)`
+    pub fn unsupported(&self) -> Option<&str> {
+        match self {
+            PaxelExpr::Unsupported(source) => Some(source),
+            PaxelExpr::Symbol { accessors, .. } => {
+                accessors.iter().find_map(|accessor| match accessor {
+                    Accessor::Index(index) => index.unsupported(),
+                    Accessor::Member(_) => None,
+                })
+            }
+            PaxelExpr::WithUnit(expr, _) | PaxelExpr::Unary(_, expr) => expr.unsupported(),
+            PaxelExpr::Binary(_, lhs, rhs) | PaxelExpr::Range(lhs, rhs) => {
+                lhs.unsupported().or_else(|| rhs.unsupported())
+            }
+            PaxelExpr::Ternary(condition, then, otherwise) => [condition, then, otherwise]
+                .into_iter()
+                .find_map(|expr| expr.unsupported()),
+            PaxelExpr::List(items) | PaxelExpr::ColorFunc(_, items) => {
+                items.iter().find_map(PaxelExpr::unsupported)
+            }
+            PaxelExpr::Bool(_)
+            | PaxelExpr::Number(_)
+            | PaxelExpr::String(_)
+            | PaxelExpr::Color(_) => None,
+        }
+    }
+
+    fn collect_symbols(&self, symbols: &mut Vec<String>) {
+        match self {
+            PaxelExpr::Symbol { root, accessors } => {
+                if !symbols.contains(root) {
+                    symbols.push(root.clone());
+                }
+                for accessor in accessors {
+                    if let Accessor::Index(index) = accessor {
+                        index.collect_symbols(symbols);
+                    }
+                }
+            }
+            PaxelExpr::WithUnit(expr, _) | PaxelExpr::Unary(_, expr) => {
+                expr.collect_symbols(symbols)
+            }
+            PaxelExpr::Binary(_, lhs, rhs) | PaxelExpr::Range(lhs, rhs) => {
+                lhs.collect_symbols(symbols);
+                rhs.collect_symbols(symbols);
+            }
+            PaxelExpr::Ternary(condition, then, otherwise) => {
+                condition.collect_symbols(symbols);
+                then.collect_symbols(symbols);
+                otherwise.collect_symbols(symbols);
+            }
+            PaxelExpr::List(items) | PaxelExpr::ColorFunc(_, items) => {
+                for item in items {
+                    item.collect_symbols(symbols);
+                }
+            }
+            PaxelExpr::Bool(_)
+            | PaxelExpr::Number(_)
+            | PaxelExpr::String(_)
+            | PaxelExpr::Color(_)
+            | PaxelExpr::Unsupported(_) => {}
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{borrow, use_RefCell};
+use pax_runtime::RuntimePropertiesStackFrame;
+use pax_runtime_api::pax_value::{CoercionRules, PaxAny, PaxValue, ToFromPaxAny, ToFromPaxValue};
+use pax_runtime_api::properties::UntypedProperty;
+use pax_runtime_api::{Color, Fill, Numeric, Percent, Rotation, Size, Stroke, Transform2D};
+use_RefCell!();
+
+use crate::ast::{Accessor, BinaryOp, ColorFunc, PaxelExpr, UnaryOp, Unit};
+
+/// Evaluates `expr` against `stack_frame`. Produces the same value the compiled
+/// vtable entry for the expression would: a builtin `PaxValue` for everything except
+/// top-level ranges, which evaluate to a `Range<isize>` (the type `for` sources coerce into).
+pub fn evaluate(
+    expr: &PaxelExpr,
+    stack_frame: &Rc<RuntimePropertiesStackFrame>,
+) -> Result<PaxAny, String> {
+    match expr {
+        PaxelExpr::Range(start, end) => {
+            let start = eval_isize(start, stack_frame)?;
+            let end = eval_isize(end, stack_frame)?;
+            Ok((start..end).to_pax_any())
+        }
+        expr => Ok(PaxAny::Builtin(eval(expr, stack_frame)?)),
+    }
+}
+
+fn eval(
+    expr: &PaxelExpr,
+    stack_frame: &Rc<RuntimePropertiesStackFrame>,
+) -> Result<PaxValue, String> {
+    Ok(match expr {
+        PaxelExpr::Bool(b) => PaxValue::Bool(*b),
+        PaxelExpr::Number(n) => PaxValue::Numeric(*n),
+        PaxelExpr::String(s) => PaxValue::String(s.clone()),
+        PaxelExpr::Color(c) => PaxValue::Color(c.clone()),
+        PaxelExpr::WithUnit(expr, unit) => {
+            let value = eval(expr, stack_frame)?;
+            let numeric = coerce::<Numeric>(value)?;
+            match unit {
+                Unit::Pixels => Size::Pixels(numeric).to_pax_value(),
+                Unit::Percent => Percent(numeric).to_pax_value(),
+                Unit::Degrees => Rotation::Degrees(numeric).to_pax_value(),
+                Unit::Radians => Rotation::Radians(numeric).to_pax_value(),
+            }
+        }
+        PaxelExpr::Symbol { root, accessors } => {
+            let property = stack_frame
+                .resolve_symbol_as_erased_property(root)
+                .ok_or_else(|| format!("unresolved symbol `{}`", root))?;
+            let mut value = read_property(&property)
+                .ok_or_else(|| format!("symbol `{}` has a type expressions can't read", root))?;
+            for accessor in accessors {
+                value = access(value, accessor, stack_frame)?;
+            }
+            value
+        }
+        PaxelExpr::Unary(op, expr) => {
+            let value = eval(expr, stack_frame)?;
+            match (op, value) {
+                (UnaryOp::Neg, v @ (PaxValue::Numeric(_) | PaxValue::Size(_))) => -v,
+                (UnaryOp::Not, v @ PaxValue::Bool(_)) => !v,
+                (UnaryOp::Neg, v) => return Err(format!("can't negate {:?}", v)),
+                (UnaryOp::Not, v) => return Err(format!("! operator not valid for {:?}", v)),
+            }
+        }
+        PaxelExpr::Binary(BinaryOp::And, lhs, rhs) => match eval(lhs, stack_frame)? {
+            PaxValue::Bool(false) => PaxValue::Bool(false),
+            PaxValue::Bool(true) => PaxValue::Bool(expect_bool(eval(rhs, stack_frame)?, "&&")?),
+            v => return Err(format!("&& operator not valid for {:?}", v)),
+        },
+        PaxelExpr::Binary(BinaryOp::Or, lhs, rhs) => match eval(lhs, stack_frame)? {
+            PaxValue::Bool(true) => PaxValue::Bool(true),
+            PaxValue::Bool(false) => PaxValue::Bool(expect_bool(eval(rhs, stack_frame)?, "||")?),
+            v => return Err(format!("|| operator not valid for {:?}", v)),
+        },
+        PaxelExpr::Binary(op, lhs, rhs) => {
+            binary(*op, eval(lhs, stack_frame)?, eval(rhs, stack_frame)?)?
+        }
+        PaxelExpr::Ternary(condition, then, otherwise) => match eval(condition, stack_frame)? {
+            PaxValue::Bool(true) => eval(then, stack_frame)?,
+            PaxValue::Bool(false) => eval(otherwise, stack_frame)?,
+            v => return Err(format!("ternary condition must be a bool, got {:?}", v)),
+        },
+        PaxelExpr::Range(..) => {
+            return Err("ranges are only supported as a whole expression".to_string())
+        }
+        PaxelExpr::List(items) => PaxValue::Vec(
+            items
+                .iter()
+                .map(|item| eval(item, stack_frame).map(PaxAny::Builtin))
+                .collect::<Result<_, _>>()?,
+        ),
+        PaxelExpr::ColorFunc(func, args) => {
+            let mut args = args
+                .iter()
+                .map(|arg| eval(arg, stack_frame))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter();
+            let mut next = || args.next().ok_or("missing color channel".to_string());
+            let color = match func {
+                ColorFunc::Rgb => Color::rgb(coerce(next()?)?, coerce(next()?)?, coerce(next()?)?),
+                ColorFunc::Rgba => Color::rgba(
+                    coerce(next()?)?,
+                    coerce(next()?)?,
+                    coerce(next()?)?,
+                    coerce(next()?)?,
+                ),
+                ColorFunc::Hsl => Color::hsl(coerce(next()?)?, coerce(next()?)?, coerce(next()?)?),
+                ColorFunc::Hsla => Color::hsla(
+                    coerce(next()?)?,
+                    coerce(next()?)?,
+                    coerce(next()?)?,
+                    coerce(next()?)?,
+                ),
+            };
+            PaxValue::Color(color)
+        }
+        PaxelExpr::Unsupported(source) => {
+            return Err(format!(
+                "`{}` is not supported by the interpreter; it requires compiling the template",
+                source
+            ))
+        }
+    })
+}
+
+fn eval_isize(
+    expr: &PaxelExpr,
+    stack_frame: &Rc<RuntimePropertiesStackFrame>,
+) -> Result<isize, String> {
+    match eval(expr, stack_frame)? {
+        PaxValue::Numeric(n) if !n.is_float() => Ok(n.to_int() as isize),
+        v => Err(format!("range bounds must be integers, got {:?}", v)),
+    }
+}
+
+fn expect_bool(value: PaxValue, op: &str) -> Result<bool, String> {
+    match value {
+        PaxValue::Bool(b) => Ok(b),
+        v => Err(format!("{} operator not valid for {:?}", op, v)),
+    }
+}
+
+/// Applies `op`, delegating to the `PaxValue` operator impls used by compiled expressions
+/// once the operands are known to be supported (those impls panic otherwise).
+fn binary(op: BinaryOp, lhs: PaxValue, rhs: PaxValue) -> Result<PaxValue, String> {
+    use PaxValue::{Bool, Numeric as Num, Percent as Pct, Size as Sz, String as Str};
+    let supported = match op {
+        BinaryOp::Add => matches!(
+            (&lhs, &rhs),
+            (Num(_), Num(_))
+                | (Str(_), Str(_))
+                | (Str(_), Num(_))
+                | (Num(_), Str(_))
+                | (Sz(_), Sz(_))
+                | (Pct(_), Pct(_))
+                | (Pct(_), Sz(_))
+                | (Sz(_), Pct(_))
+                | (Bool(_), Num(_))
+                | (Num(_), Bool(_))
+        ),
+        BinaryOp::Sub => matches!(
+            (&lhs, &rhs),
+            (Num(_), Num(_))
+                | (Sz(_), Sz(_))
+                | (Pct(_), Pct(_))
+                | (Pct(_), Sz(_))
+                | (Sz(_), Pct(_))
+        ),
+        BinaryOp::Mul => matches!(
+            (&lhs, &rhs),
+            (Num(_), Num(_)) | (Bool(_), Num(_)) | (Num(_), Bool(_))
+        ),
+        BinaryOp::Div | BinaryOp::Mod => match (&lhs, &rhs) {
+            (Num(_), Num(b)) if !b.is_float() && b.to_int() == 0 => {
+                return Err(format!("integer division by zero: {:?} / {:?}", lhs, rhs))
+            }
+            (Num(_), Num(_)) => true,
+            _ => false,
+        },
+        BinaryOp::Exp => matches!((&lhs, &rhs), (Num(_), Num(_))),
+        BinaryOp::Eq
+        | BinaryOp::Neq
+        | BinaryOp::Lt
+        | BinaryOp::Lte
+        | BinaryOp::Gt
+        | BinaryOp::Gte => matches!(
+            (&lhs, &rhs),
+            (Bool(_), Bool(_)) | (Num(_), Num(_)) | (Str(_), Str(_))
+        ),
+        BinaryOp::And | BinaryOp::Or => matches!((&lhs, &rhs), (Bool(_), Bool(_))),
+    };
+    if !supported {
+        return Err(format!(
+            "operator {:?} not valid for {:?} and {:?}",
+            op, lhs, rhs
+        ));
+    }
+    Ok(match op {
+        BinaryOp::Add => lhs + rhs,
+        BinaryOp::Sub => lhs - rhs,
+        BinaryOp::Mul => lhs * rhs,
+        BinaryOp::Div => lhs / rhs,
+        BinaryOp::Mod => lhs % rhs,
+        BinaryOp::Exp => lhs.pow(rhs),
+        BinaryOp::And => lhs.op_and(rhs),
+        BinaryOp::Or => lhs.op_or(rhs),
+        BinaryOp::Eq => Bool(lhs == rhs),
+        BinaryOp::Neq => Bool(lhs != rhs),
+        BinaryOp::Lt => Bool(lhs < rhs),
+        BinaryOp::Lte => Bool(lhs <= rhs),
+        BinaryOp::Gt => Bool(lhs > rhs),
+        BinaryOp::Gte => Bool(lhs >= rhs),
+    })
+}
+
+fn access(
+    value: PaxValue,
+    accessor: &Accessor,
+    stack_frame: &Rc<RuntimePropertiesStackFrame>,
+) -> Result<PaxValue, String> {
+    match (value, accessor) {
+        (PaxValue::Vec(items), Accessor::Index(index)) => {
+            let index = eval_isize(index, stack_frame)?;
+            let len = items.len();
+            let item = usize::try_from(index)
+                .ok()
+                .and_then(|i| items.into_iter().nth(i))
+                .ok_or_else(|| {
+                    format!("index {} out of bounds for list of length {}", index, len)
+                })?;
+            match item {
+                PaxAny::Builtin(value) => Ok(value),
+                PaxAny::Any(_) => Err("list item has a type expressions can't read".to_string()),
+            }
+        }
+        (value, Accessor::Index(_)) => Err(format!("can't index into {:?}", value)),
+        (value, Accessor::Member(member)) => Err(format!(
+            "can't access `{}` on {:?}; field access requires compiling the template",
+            member, value
+        )),
+    }
+}
+
+/// Reads the current value of a type-erased property as a `PaxValue`, for the
+/// property types expressions can operate on
+fn read_property(property: &UntypedProperty) -> Option<PaxValue> {
+    macro_rules! try_read {
+        ($($t:ty),*) => {
+            $(
+                if let Some(v) = property.try_get::<$t>() {
+                    return Some(v.to_pax_value());
+                }
+                if let Some(v) = property.try_get::<Vec<$t>>() {
+                    return Some(v.to_pax_value());
+                }
+            )*
+        };
+    }
+    try_read!(
+        bool,
+        String,
+        u8,
+        u16,
+        u32,
+        u64,
+        usize,
+        i8,
+        i16,
+        i32,
+        i64,
+        isize,
+        f64,
+        Numeric,
+        Size,
+        Color,
+        Rotation,
+        Fill,
+        Stroke,
+        Transform2D
+    );
+    // `elem` within a `for` body
+    if let Some(elem) = property.try_get::<Option<Rc<RefCell<PaxAny>>>>() {
+        return elem.and_then(|elem| clone_builtin(&*borrow!(elem)));
+    }
+    None
+}
+
+/// `PaxValue` isn't `Clone`; round-trip the value through its concrete type instead
+fn clone_builtin(any: &PaxAny) -> Option<PaxValue> {
+    let PaxAny::Builtin(value) = any else {
+        return None;
+    };
+    Some(match value {
+        PaxValue::Bool(v) => PaxValue::Bool(*v),
+        PaxValue::Numeric(v) => PaxValue::Numeric(*v),
+        PaxValue::String(v) => PaxValue::String(v.clone()),
+        PaxValue::Transform2D(v) => PaxValue::Transform2D(v.clone()),
+        PaxValue::Size(v) => PaxValue::Size(*v),
+        PaxValue::Percent(v) => PaxValue::Percent(v.clone()),
+        PaxValue::Color(v) => PaxValue::Color(v.clone()),
+        PaxValue::ColorChannel(v) => PaxValue::ColorChannel(v.clone()),
+        PaxValue::Rotation(v) => PaxValue::Rotation(v.clone()),
+        PaxValue::Fill(v) => PaxValue::Fill(v.clone()),
+        PaxValue::Stroke(v) => PaxValue::Stroke(v.clone()),
+        PaxValue::Vec(items) => PaxValue::Vec(
+            items
+                .iter()
+                .map(|item| clone_builtin(item).map(PaxAny::Builtin))
+                .collect::<Option<_>>()?,
+        ),
+        PaxValue::Component {} => PaxValue::Component {},
+    })
+}
+
+/// Coerces an evaluated value into the types units and color functions take, following
+/// the same rules as the `try_coerce` calls in compiled expressions
+fn coerce<T: ToFromPaxAny + CoercionRules + 'static>(value: PaxValue) -> Result<T, String> {
+    PaxAny::Builtin(value).try_coerce::<T>()
+}
@@ -0,0 +1,116 @@
+//! Tree-walking interpreter for PAXEL, the expression language used in Pax templates.
+//!
+//! Compiled templates evaluate expressions through generated Rust closures stored in the
+//! [`ExpressionTable`]. This crate instead parses an `expression_body` into a [`PaxelExpr`]
+//! and evaluates it directly against a [`RuntimePropertiesStackFrame`], which makes it
+//! possible to change expressions without recompiling (live editing) and to evaluate
+//! ad-hoc expressions while debugging a running template (REPL).
+//!
+//! ```ignore
+//! let expr = pax_paxel_interp::parse("self.num_clicks %% 2 == 0 ? 10px : 20px")?;
+//! let value = pax_paxel_interp::evaluate(&expr, &stack_frame)?;
+//! ```
+//!
+//! Values follow the same operator and coercion rules as compiled expressions. Syntax that
+//! depends on Rust types only the compiler knows about (object literals, enum variants,
+//! function calls, tuples and field access on user structs) is parsed but evaluates to an error.
+
+use std::ops::Range;
+use std::rc::Rc;
+
+use pax_runtime::{ExpressionContext, ExpressionTable, RuntimePropertiesStackFrame};
+use pax_runtime_api::pax_value::{PaxAny, PaxValue, ToFromPaxAny};
+use pax_runtime_api::{borrow, borrow_mut, use_RefCell, Numeric};
+use_RefCell!();
+
+mod ast;
+mod eval;
+
+pub use ast::{parse, Accessor, BinaryOp, ColorFunc, PaxelExpr, UnaryOp, Unit};
+pub use eval::evaluate;
+
+#[cfg(test)]
+mod tests;
+
+/// Parses and evaluates `paxel` in one step, e.g. for a debugging REPL
+pub fn evaluate_str(
+    paxel: &str,
+    stack_frame: &Rc<RuntimePropertiesStackFrame>,
+) -> Result<PaxAny, String> {
+    evaluate(&parse(paxel)?, stack_frame)
+}
+
+/// Builds an [`ExpressionTable`] entry that interprets `expr`. Unlike compiled entries,
+/// expressions can fail to evaluate (e.g. a symbol of the wrong type after an edit): the
+/// error is logged and the entry falls back to the last value it produced, or to `0`
+/// (which coerces into numbers, sizes, rotations and strings) if it never succeeded.
+pub fn vtable_entry(expr: PaxelExpr) -> Box<dyn Fn(ExpressionContext) -> PaxAny> {
+    let last_value: RefCell<Option<PaxAny>> = RefCell::new(None);
+    Box::new(
+        move |ec: ExpressionContext| match evaluate(&expr, &ec.stack_frame) {
+            Ok(value) => {
+                *borrow_mut!(last_value) = try_clone(&value);
+                value
+            }
+            Err(e) => {
+                log::error!("failed to evaluate expression: {}", e);
+                borrow!(last_value)
+                    .as_ref()
+                    .and_then(try_clone)
+                    .unwrap_or(PaxAny::Builtin(PaxValue::Numeric(Numeric::I64(0))))
+            }
+        },
+    )
+}
+
+/// Copies the values [`evaluate`] produces, `None` for opaque ones
+fn try_clone(value: &PaxAny) -> Option<PaxAny> {
+    match value {
+        PaxAny::Builtin(value) => Some(PaxAny::Builtin(match value {
+            PaxValue::Bool(b) => PaxValue::Bool(*b),
+            PaxValue::Numeric(n) => PaxValue::Numeric(*n),
+            PaxValue::String(s) => PaxValue::String(s.clone()),
+            PaxValue::Transform2D(t) => PaxValue::Transform2D(t.clone()),
+            PaxValue::Size(s) => PaxValue::Size(*s),
+            PaxValue::Percent(p) => PaxValue::Percent(p.clone()),
+            PaxValue::Color(c) => PaxValue::Color(c.clone()),
+            PaxValue::ColorChannel(c) => PaxValue::ColorChannel(c.clone()),
+            PaxValue::Rotation(r) => PaxValue::Rotation(r.clone()),
+            PaxValue::Fill(f) => PaxValue::Fill(f.clone()),
+            PaxValue::Stroke(s) => PaxValue::Stroke(s.clone()),
+            PaxValue::Vec(items) => {
+                PaxValue::Vec(items.iter().map(try_clone).collect::<Option<_>>()?)
+            }
+            PaxValue::Component {} => PaxValue::Component {},
+        })),
+        PaxAny::Any(any) => any
+            .downcast_ref::<Range<isize>>()
+            .map(|range| range.clone().to_pax_any()),
+    }
+}
+
+/// Registers an interpreted entry for `vtable_id`, unless the table already has one
+/// (e.g. a compiled one). Returns whether the entry was inserted.
+pub fn insert_fallback(
+    table: &mut ExpressionTable,
+    vtable_id: usize,
+    paxel: &str,
+) -> Result<bool, String> {
+    if table.table.contains_key(&vtable_id) {
+        return Ok(false);
+    }
+    table.table.insert(vtable_id, vtable_entry(parse(paxel)?));
+    Ok(true)
+}
+
+/// Registers an interpreted entry for `vtable_id`, replacing any existing entry, in a
+/// table that may already be in use. Used to apply an edited expression to a running
+/// template, e.g. the `interpreted_expressions` of a hot reload `TemplatePatch`.
+pub fn override_entry(
+    table: &ExpressionTable,
+    vtable_id: usize,
+    paxel: &str,
+) -> Result<(), String> {
+    table.set_runtime_entry(vtable_id, vtable_entry(parse(paxel)?));
+    Ok(())
+}
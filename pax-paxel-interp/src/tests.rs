@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use pax_runtime::{ExpressionContext, ExpressionTable, RuntimePropertiesStackFrame};
+use pax_runtime_api::pax_value::{PaxAny, PaxValue, ToFromPaxAny};
+use pax_runtime_api::{use_RefCell, Color, Numeric, Property, Rotation, Size};
+use_RefCell!();
+
+use super::*;
+
+/// A stack frame exposing `count: usize`, `name: String`, `width: Size` and `items: Vec<i64>`
+fn stack_frame() -> (Rc<RuntimePropertiesStackFrame>, Property<usize>) {
+    let count = Property::new(3usize);
+    let mut symbols = HashMap::new();
+    symbols.insert("count".to_string(), count.untyped());
+    symbols.insert(
+        "name".to_string(),
+        Property::new("pax".to_string()).untyped(),
+    );
+    symbols.insert(
+        "width".to_string(),
+        Property::new(Size::Pixels(100.into())).untyped(),
+    );
+    symbols.insert(
+        "items".to_string(),
+        Property::new(vec![10i64, 20, 30]).untyped(),
+    );
+    let frame = RuntimePropertiesStackFrame::new(symbols, Rc::new(RefCell::new(().to_pax_any())));
+    (frame, count)
+}
+
+fn eval_value(paxel: &str) -> Result<PaxValue, String> {
+    let (frame, _count) = stack_frame();
+    match evaluate_str(paxel, &frame)? {
+        PaxAny::Builtin(value) => Ok(value),
+        PaxAny::Any(_) => Err("not a builtin".to_string()),
+    }
+}
+
+fn eval_as<T: ToFromPaxAny + pax_runtime_api::pax_value::CoercionRules + 'static>(
+    paxel: &str,
+) -> T {
+    let (frame, _count) = stack_frame();
+    evaluate_str(paxel, &frame).unwrap().try_coerce().unwrap()
+}
+
+#[test]
+fn arithmetic_follows_operator_precedence() {
+    assert_eq!(eval_as::<i64>("1 + 2 * 3"), 7);
+    assert_eq!(eval_as::<i64>("(1 + 2) * 3"), 9);
+    assert_eq!(eval_as::<i64>("2 ^ 3 ^ 2"), 512);
+    assert_eq!(eval_as::<i64>("-2 + 5"), 3);
+    assert_eq!(eval_as::<i64>("count %% 2"), 1);
+    assert_eq!(eval_as::<f64>("1.5 * 2"), 3.0);
+}
+
+#[test]
+fn logic_and_ternaries() {
+    assert!(eval_as::<bool>("1 < 2 && !false"));
+    assert!(eval_as::<bool>("false || self.count == 3"));
+    assert_eq!(
+        eval_as::<String>("self.count > 2 ? \"big\" : \"small\""),
+        "big"
+    );
+    assert_eq!(eval_as::<i64>("count == 1 ? 1 : count == 3 ? 3 : 0"), 3);
+    // short circuits before reaching the unresolved symbol
+    assert!(!eval_as::<bool>("false && missing"));
+}
+
+#[test]
+fn symbols_strings_and_indexing() {
+    assert_eq!(eval_as::<String>("\"hello \" + this.name"), "hello pax");
+    assert_eq!(eval_as::<String>("\"a\\tb\""), "a\tb");
+    assert_eq!(eval_as::<i64>("items[count - 2]"), 20);
+    assert_eq!(eval_as::<usize>("$count"), 3);
+}
+
+#[test]
+fn units_and_colors() {
+    assert_eq!(eval_as::<Size>("10px"), Size::Pixels(10.into()));
+    assert_eq!(
+        eval_as::<Size>("width + 50%"),
+        Size::Combined(100.into(), 50.into())
+    );
+    assert_eq!(eval_as::<Size>("(count * 10)px"), Size::Pixels(30.into()));
+    assert_eq!(eval_as::<Rotation>("45deg"), Rotation::Degrees(45.into()));
+    assert_eq!(eval_as::<Color>("RED"), Color::RED);
+    assert!(matches!(
+        eval_as::<Color>("rgb(count * 10, 0, 255)"),
+        Color::rgb(..)
+    ));
+    assert!(matches!(
+        eval_as::<Color>("hsl(120deg, 50%, 50%)"),
+        Color::hsl(..)
+    ));
+}
+
+#[test]
+fn ranges_and_lists() {
+    let (frame, _count) = stack_frame();
+    let range: Range<isize> = evaluate_str("0..self.count", &frame)
+        .unwrap()
+        .try_coerce()
+        .unwrap();
+    assert_eq!(range, 0..3);
+    assert_eq!(eval_as::<Vec<i64>>("[1, count, 5]"), vec![1, 3, 5]);
+}
+
+#[test]
+fn errors_instead_of_panicking() {
+    assert!(parse("1 +").is_err());
+    assert!(parse("1 2").is_err());
+    assert!(eval_value("missing + 1").unwrap_err().contains("missing"));
+    assert!(eval_value("\"a\" * 2").is_err());
+    assert!(eval_value("1 / 0").is_err());
+    assert!(eval_value("items[10]").is_err());
+    assert!(eval_value("1 ? 2 : 3").is_err());
+    assert!(eval_value("{a: 1}").is_err());
+    assert!(eval_value("Foo::bar(1)").is_err());
+    let expr = parse("count > 1 ? items[Foo::bar(1)] : 0").unwrap();
+    assert_eq!(expr.unsupported(), Some("Foo::bar(1)"));
+    assert_eq!(parse("items[count] + 1").unwrap().unsupported(), None);
+}
+
+#[test]
+fn reports_symbol_dependencies() {
+    let expr = parse("self.count + items[count] > this.width ? name : \"x\"").unwrap();
+    assert_eq!(expr.symbols(), vec!["count", "items", "width", "name"]);
+    assert_eq!(parse("2").unwrap(), PaxelExpr::Number(Numeric::I64(2)));
+}
+
+#[test]
+fn vtable_entries_reflect_property_changes() {
+    let (frame, count) = stack_frame();
+    let mut table = ExpressionTable::new();
+    table
+        .table
+        .insert(1, Box::new(|_: ExpressionContext| 0usize.to_pax_any()));
+    assert!(!insert_fallback(&mut table, 1, "count * 2").unwrap());
+    assert!(insert_fallback(&mut table, 2, "count * 2").unwrap());
+    assert!(insert_fallback(&mut table, 3, "count *").is_err());
+
+    let value = |table: &ExpressionTable, id| {
+        table
+            .compute_vtable_value(&frame, id)
+            .try_coerce::<usize>()
+            .unwrap()
+    };
+    assert_eq!(value(&table, 1), 0);
+    assert_eq!(value(&table, 2), 6);
+    count.set(5);
+    assert_eq!(value(&table, 2), 10);
+
+    override_entry(&table, 1, "count + 1").unwrap();
+    assert_eq!(value(&table, 1), 6);
+}
+
+#[test]
+fn vtable_entries_fall_back_to_their_last_value() {
+    let (frame, count) = stack_frame();
+    let entry = vtable_entry(parse("12 / count").unwrap());
+    let value = || {
+        entry(ExpressionContext {
+            stack_frame: frame.clone(),
+        })
+        .try_coerce::<i64>()
+        .unwrap()
+    };
+    count.set(0);
+    assert_eq!(value(), 0);
+    count.set(4);
+    assert_eq!(value(), 3);
+    count.set(0);
+    assert_eq!(value(), 3);
+}
@@ -18,7 +18,7 @@ pub const TYPE_ID_REPEAT: &str = "REPEAT";
 pub const TYPE_ID_SLOT: &str = "SLOT";
 pub const TYPE_ID_COMMENT: &str = "COMMENT";
 
-pub const COMMON_PROPERTIES: [&str; 13] = [
+pub const COMMON_PROPERTIES: [&str; 21] = [
     "id",
     "x",
     "y",
@@ -32,6 +32,14 @@ pub const COMMON_PROPERTIES: [&str; 13] = [
     "transform",
     "width",
     "height",
+    "focusable",
+    "tab_index",
+    "hit_test",
+    "grid_row",
+    "grid_column",
+    "grid_row_span",
+    "grid_column_span",
+    "grid_area",
 ];
 
 pub fn is_intoable_downstream_type(type_to_check: &str) -> bool {
@@ -50,7 +58,7 @@ const BLESSED_INTOABLE_DOWNSTREAM_TYPES: [&'static str; 5] = [
     "pax_std::types::Fill",
 ];
 
-pub const COMMON_PROPERTIES_TYPE: [(&str, &str); 13] = [
+pub const COMMON_PROPERTIES_TYPE: [(&str, &str); 21] = [
     ("id", "String"),
     ("x", "pax_engine::api::Size"),
     ("y", "pax_engine::api::Size"),
@@ -64,4 +72,12 @@ pub const COMMON_PROPERTIES_TYPE: [(&str, &str); 13] = [
     ("transform", "pax_engine::api::Transform2D"),
     ("width", "pax_engine::api::Size"),
     ("height", "pax_engine::api::Size"),
+    ("focusable", "bool"),
+    ("tab_index", "i64"),
+    ("hit_test", "pax_engine::api::HitTest"),
+    ("grid_row", "usize"),
+    ("grid_column", "usize"),
+    ("grid_row_span", "usize"),
+    ("grid_column_span", "usize"),
+    ("grid_area", "String"),
 ];
@@ -1,3 +1,4 @@
+use std::any::Any;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Display;
 use std::ops::{Add, Deref, Mul, Neg, Sub};
@@ -6,32 +7,32 @@ use crate::math::Space;
 use kurbo::BezPath;
 pub use pax_value::numeric::Numeric;
 pub use pax_value::{ImplToFromPaxAny, PaxValue, ToFromPaxValue};
+use pax_value::{PaxAny, ToFromPaxAny};
 use piet::{PaintBrush, UnitPoint};
 use properties::UntypedProperty;
 pub mod refcell_debug;
 pub use refcell_debug::*;
 
-/// Marker trait that needs to be implemented for a struct for insertion and
-/// deletion in a store
-/// NOTE: Stored objects need to be UNIQUE for any given stack. Do not insert
-/// values with types that could potentially be used in another use case,
-/// instead create a local type only used for a single purpose
-pub trait Store: 'static {}
-
 #[cfg(feature = "designtime")]
 use {
     crate::math::Point2, crate::node_interface::NodeInterface, pax_designtime::DesigntimeManager,
 };
 
 use std::cell::Cell;
+use std::f64::consts::PI;
 use std::rc::{Rc, Weak};
+use std::time::Duration;
 
 pub mod constants;
 pub mod math;
 pub mod pax_value;
 pub mod properties;
+pub mod stores;
+pub mod timeline;
 
-pub use properties::Property;
+pub use properties::{batch, effect, on_dirty, EffectHandle, Property};
+pub use stores::{Store, StoreError};
+pub use timeline::{Keyframe, Timeline};
 
 use crate::constants::COMMON_PROPERTIES_TYPE;
 pub use pax_message::serde;
@@ -39,11 +40,90 @@ use pax_message::{ColorMessage, ModifierKeyMessage, MouseButtonMessage, TouchMes
 use serde::{Deserialize, Serialize};
 
 pub struct TransitionQueueEntry<T> {
-    pub duration_frames: u64,
+    pub duration: TransitionDuration,
     pub curve: EasingCurve,
     pub ending_value: T,
 }
 
+/// How long a transition runs: either a number of engine ticks, or a wall
+/// clock duration measured with the millisecond clock supplied by the chassis
+/// (which keeps animations on schedule when frames are dropped or throttled).
+/// Integers convert to `Frames` and `std::time::Duration`s to `Time`, so both
+/// can be passed to `Property::ease_to`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionDuration {
+    Frames(u64),
+    Time(Duration),
+}
+
+impl From<u64> for TransitionDuration {
+    fn from(frames: u64) -> Self {
+        TransitionDuration::Frames(frames)
+    }
+}
+
+impl From<Duration> for TransitionDuration {
+    fn from(duration: Duration) -> Self {
+        TransitionDuration::Time(duration)
+    }
+}
+
+/// Nominal frame rate, used to give frame based transitions a duration in
+/// seconds (needed by `EasingCurve::Spring`)
+const NOMINAL_FRAMES_PER_SECOND: f64 = 60.0;
+
+/// A point in time as seen by transitions: engine ticks and milliseconds since
+/// the engine started
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AnimationTime {
+    pub frames: u64,
+    pub ms: f64,
+}
+
+impl TransitionDuration {
+    fn as_secs_f64(&self) -> f64 {
+        match self {
+            TransitionDuration::Frames(frames) => *frames as f64 / NOMINAL_FRAMES_PER_SECOND,
+            TransitionDuration::Time(duration) => duration.as_secs_f64(),
+        }
+    }
+
+    /// Time elapsed since `origin`, as a fraction of this duration (not clamped)
+    fn progress(&self, origin: AnimationTime, now: AnimationTime) -> f64 {
+        let (elapsed, total) = match self {
+            TransitionDuration::Frames(frames) => (
+                now.frames.saturating_sub(origin.frames) as f64,
+                *frames as f64,
+            ),
+            TransitionDuration::Time(duration) => (
+                (now.ms - origin.ms).max(0.0),
+                duration.as_secs_f64() * 1000.0,
+            ),
+        };
+        if total == 0.0 {
+            1.0
+        } else {
+            elapsed / total
+        }
+    }
+
+    /// The time at which a transition started at `origin` with this duration ends.
+    /// Only the unit of this duration is known exactly: the other one is
+    /// taken from `now`, the first tick at which the transition was over.
+    fn end(&self, origin: AnimationTime, now: AnimationTime) -> AnimationTime {
+        match self {
+            TransitionDuration::Frames(frames) => AnimationTime {
+                frames: origin.frames + frames,
+                ms: now.ms,
+            },
+            TransitionDuration::Time(duration) => AnimationTime {
+                frames: now.frames,
+                ms: origin.ms + duration.as_secs_f64() * 1000.0,
+            },
+        }
+    }
+}
+
 pub trait RenderContext {
     fn fill(&mut self, layer: &str, path: BezPath, brush: &PaintBrush);
     fn stroke(&mut self, layer: &str, path: BezPath, brush: &PaintBrush, width: f64);
@@ -61,7 +141,7 @@ pub trait RenderContext {
 impl<T> std::fmt::Debug for TransitionQueueEntry<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TransitionQueueEntry")
-            .field("duration_frames", &self.duration_frames)
+            .field("duration", &self.duration)
             // .field("ending_value", &self.ending_value)
             .finish()
     }
@@ -92,10 +172,28 @@ impl Space for Window {}
 
 // Unified events
 
+/// The leg of its propagation an [`Event`] is on when a handler receives it.
+/// Capture handlers (`@click.capture`) run first, from the root down to the
+/// target's parent; then the target's own handlers; then bubble handlers
+/// (`@click`) from the target's parent back up to the root.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPhase {
+    Capturing,
+    #[default]
+    AtTarget,
+    Bubbling,
+}
+
 #[derive(Clone)]
 pub struct Event<T> {
     pub args: T,
     cancelled: Rc<Cell<bool>>,
+    propagation_stopped: Rc<Cell<bool>>,
+    phase: EventPhase,
+    // type-erased engine nodes, read back as `NodeInterface`s by the
+    // engine's `EventTarget` trait
+    target: Option<Rc<dyn Any>>,
+    current_target: Option<Rc<dyn Any>>,
 }
 
 impl<T: Clone + 'static> ImplToFromPaxAny for Event<T> {}
@@ -105,6 +203,30 @@ impl<T> Event<T> {
         Self {
             args,
             cancelled: Default::default(),
+            propagation_stopped: Default::default(),
+            phase: EventPhase::default(),
+            target: None,
+            current_target: None,
+        }
+    }
+
+    /// Sets the node this event was dispatched to
+    pub fn with_target(mut self, target: Rc<dyn Any>) -> Self {
+        self.current_target = Some(Rc::clone(&target));
+        self.target = Some(target);
+        self
+    }
+
+    /// This event as seen by the handlers of `current_target` during `phase`.
+    /// Cancellation and stopped propagation are shared with `self`.
+    pub fn at(&self, current_target: Rc<dyn Any>, phase: EventPhase) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            phase,
+            current_target: Some(current_target),
+            ..self.clone()
         }
     }
 
@@ -115,6 +237,29 @@ impl<T> Event<T> {
     pub fn cancelled(&self) -> bool {
         self.cancelled.get()
     }
+
+    /// Keeps this event from reaching nodes further along its propagation path.
+    /// Remaining handlers on the current node still run. Unlike
+    /// [`Event::prevent_default`], this doesn't affect the default action.
+    pub fn stop_propagation(&self) {
+        self.propagation_stopped.set(true);
+    }
+
+    pub fn propagation_stopped(&self) -> bool {
+        self.propagation_stopped.get()
+    }
+
+    pub fn phase(&self) -> EventPhase {
+        self.phase
+    }
+
+    pub fn target_any(&self) -> Option<&Rc<dyn Any>> {
+        self.target.as_ref()
+    }
+
+    pub fn current_target_any(&self) -> Option<&Rc<dyn Any>> {
+        self.current_target.as_ref()
+    }
 }
 
 impl<T> Deref for Event<T> {
@@ -190,6 +335,89 @@ pub struct TouchEnd {
     pub touches: Vec<Touch>,
 }
 
+// Gestures, recognized from touch and mouse events
+
+/// Whether a continuous gesture (pan, pinch, rotate) just started, is
+/// ongoing or just ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GesturePhase {
+    Began,
+    Changed,
+    Ended,
+}
+
+/// User briefly presses and releases an element without moving.
+#[derive(Clone)]
+pub struct Tap {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// User taps an element twice in quick succession. Dispatched after the
+/// second `Tap`.
+#[derive(Clone)]
+pub struct DoubleTap {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// User presses an element and holds without moving.
+#[derive(Clone)]
+pub struct LongPress {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// User presses an element and moves one finger (or the mouse).
+/// `translation_x`/`translation_y` are relative to where the pan began,
+/// velocities are in px per second.
+#[derive(Clone)]
+pub struct Pan {
+    pub phase: GesturePhase,
+    pub x: f64,
+    pub y: f64,
+    pub translation_x: f64,
+    pub translation_y: f64,
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// User ends a pan with a quick flick. Dispatched after the pan ends.
+#[derive(Clone)]
+pub struct Swipe {
+    pub direction: SwipeDirection,
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+}
+
+/// User moves two fingers apart or together. `scale` is relative to the
+/// distance between them when the pinch began.
+#[derive(Clone)]
+pub struct Pinch {
+    pub phase: GesturePhase,
+    pub scale: f64,
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
+/// User turns two fingers around each other. `rotation` is in degrees,
+/// clockwise, relative to their angle when the rotation began.
+#[derive(Clone)]
+pub struct Rotate {
+    pub phase: GesturePhase,
+    pub rotation: f64,
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
 // Keyboard Events
 
 /// Common properties in keyboard events.
@@ -358,6 +586,75 @@ pub struct ContextMenu {
     pub mouse: MouseEventArgs,
 }
 
+// In-app drag and drop
+
+/// Rebuilds the value of a drag payload for each reader, since a `PaxAny`
+/// can't be cloned
+type DragValue = Rc<dyn Fn() -> PaxAny>;
+
+/// The value carried by an in-app drag. A `@drag_start` handler sets it to
+/// start dragging; `@drag_over` and `@drop_item` handlers read it back.
+#[derive(Clone, Default)]
+pub struct DragPayload {
+    value: Rc<std::cell::RefCell<Option<DragValue>>>,
+}
+
+impl DragPayload {
+    pub fn set<T: ToFromPaxAny + Clone>(&self, value: T) {
+        *self.value.borrow_mut() = Some(Rc::new(move || value.clone().to_pax_any()));
+    }
+
+    /// The payload, if one was set and it is a `T`
+    pub fn get<T: ToFromPaxAny>(&self) -> Option<T> {
+        let value = self.value.borrow().as_ref().map(|value| value())?;
+        T::from_pax_any(value).ok()
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.value.borrow().is_some()
+    }
+}
+
+#[derive(Clone)]
+pub struct DragEventArgs {
+    pub x: f64,
+    pub y: f64,
+    pub payload: DragPayload,
+}
+
+/// User presses on an element and moves the pointer. Dragging starts if a
+/// handler sets the payload.
+#[derive(Clone)]
+pub struct DragStart {
+    pub drag: DragEventArgs,
+}
+
+/// User moves the pointer while dragging an element. Dispatched to the
+/// element being dragged.
+#[derive(Clone)]
+pub struct Drag {
+    pub drag: DragEventArgs,
+}
+
+/// User releases the pointer while dragging an element. Dispatched to the
+/// element being dragged, after `DropItem`.
+#[derive(Clone)]
+pub struct DragEnd {
+    pub drag: DragEventArgs,
+}
+
+/// User drags something over an element.
+#[derive(Clone)]
+pub struct DragOver {
+    pub drag: DragEventArgs,
+}
+
+/// User releases something they dragged over an element.
+#[derive(Clone)]
+pub struct DropItem {
+    pub drag: DragEventArgs,
+}
+
 /// A Size value that can be either a concrete pixel value
 /// or a percent of parent bounds.
 
@@ -535,8 +832,46 @@ pub struct CommonProperties {
     pub skew_y: Property<Option<Rotation>>,
     pub rotate: Property<Option<Rotation>>,
     pub transform: Property<Option<Transform2D>>,
+    /// Whether this node can receive keyboard focus. Defaults to whether
+    /// `tab_index` is set.
+    pub focusable: Property<Option<bool>>,
+    /// Position of this node in the Tab order, like HTML's `tabindex`: positive
+    /// values come first in ascending order, then zero in template order.
+    /// Negative values can be focused, but aren't reachable with Tab.
+    pub tab_index: Property<Option<i64>>,
+    /// Where this node is hit by clicks and other pointer events. Defaults
+    /// to `HitTest::Shape`.
+    pub hit_test: Property<Option<HitTest>>,
+    /// The 1-based row this node is placed at when it is a slot child of a
+    /// `Grid`. Auto-placed if not set.
+    pub grid_row: Property<Option<usize>>,
+    /// The 1-based column this node is placed at in a `Grid`, see `grid_row`
+    pub grid_column: Property<Option<usize>>,
+    /// The number of rows this node covers in a `Grid`, 1 if not set
+    pub grid_row_span: Property<Option<usize>>,
+    /// The number of columns this node covers in a `Grid`, 1 if not set
+    pub grid_column_span: Property<Option<usize>>,
+    /// One of the named `areas` of the `Grid` this node is a slot child of,
+    /// overriding `grid_row` / `grid_column` and their spans
+    pub grid_area: Property<Option<String>>,
+}
+
+/// How raycasting decides whether a node is hit, see `CommonProperties::hit_test`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum HitTest {
+    /// Anywhere within the node's bounding box
+    Bounds,
+    /// Only on what the node draws, e.g. inside an `Ellipse` or on the stroke
+    /// of a `Path`. Nodes that don't draw a shape use their bounding box.
+    #[default]
+    Shape,
+    /// Never: events go to whatever is beneath the node
+    None,
 }
 
+impl ImplToFromPaxAny for HitTest {}
+impl Interpolatable for HitTest {}
+
 impl CommonProperties {
     pub fn get_default_properties_literal() -> Vec<(String, String)> {
         Self::get_property_identifiers()
@@ -588,6 +923,17 @@ impl CommonProperties {
         scope.insert("transform".to_string(), self.transform.untyped());
         scope.insert("width".to_string(), self.width.untyped());
         scope.insert("height".to_string(), self.height.untyped());
+        scope.insert("focusable".to_string(), self.focusable.untyped());
+        scope.insert("tab_index".to_string(), self.tab_index.untyped());
+        scope.insert("hit_test".to_string(), self.hit_test.untyped());
+        scope.insert("grid_row".to_string(), self.grid_row.untyped());
+        scope.insert("grid_column".to_string(), self.grid_column.untyped());
+        scope.insert("grid_row_span".to_string(), self.grid_row_span.untyped());
+        scope.insert(
+            "grid_column_span".to_string(),
+            self.grid_column_span.untyped(),
+        );
+        scope.insert("grid_area".to_string(), self.grid_area.untyped());
 
         scope
     }
@@ -609,8 +955,32 @@ pub struct TransitionManager<T> {
     queue: VecDeque<TransitionQueueEntry<T>>,
     /// The value we are currently transitioning from
     transition_checkpoint_value: T,
+    /// A spring transition that was interrupted by a new `ease_to`. Until the
+    /// first queued transition is done, it eases from this still moving value
+    /// instead of from `transition_checkpoint_value`, which keeps the velocity
+    /// the property had when it was retargeted.
+    interrupted: Option<InterruptedTransition<T>>,
     /// The time the current transition started
-    origin_frames_elapsed: u64,
+    origin: AnimationTime,
+}
+
+struct InterruptedTransition<T> {
+    from: T,
+    transition: TransitionQueueEntry<T>,
+    origin: AnimationTime,
+}
+
+impl<T: Interpolatable> InterruptedTransition<T> {
+    fn value_at(&self, now: AnimationTime) -> T {
+        let duration = self.transition.duration;
+        let progress = duration.progress(self.origin, now).min(1.0);
+        self.transition.curve.interpolate_over(
+            &self.from,
+            &self.transition.ending_value,
+            progress,
+            duration.as_secs_f64(),
+        )
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -624,11 +994,12 @@ impl<T> std::fmt::Debug for TransitionManager<T> {
 }
 
 impl<T: Interpolatable> TransitionManager<T> {
-    pub fn new(value: T, current_time: u64) -> Self {
+    pub fn new(value: T, current_time: AnimationTime) -> Self {
         Self {
             queue: VecDeque::new(),
             transition_checkpoint_value: value,
-            origin_frames_elapsed: current_time,
+            interrupted: None,
+            origin: current_time,
         }
     }
 
@@ -636,42 +1007,116 @@ impl<T: Interpolatable> TransitionManager<T> {
         self.queue.push_back(transition);
     }
 
-    pub fn reset_transitions(&mut self, current_time: u64) {
-        // update current value as to ease from this position
-        self.compute_eased_value(current_time);
+    /// Drops all queued transitions, so that the next one pushed starts at
+    /// `current_time` from the current (eased) value
+    pub fn reset_transitions(&mut self, current_time: AnimationTime) {
+        let Some(current_value) = self.compute_eased_value(current_time) else {
+            self.interrupted = None;
+            self.origin = current_time;
+            return;
+        };
+        let in_flight = self.queue.pop_front();
         self.queue.clear();
-        self.origin_frames_elapsed = current_time;
+        self.interrupted = match in_flight {
+            Some(transition) if matches!(transition.curve, EasingCurve::Spring { .. }) => {
+                // a transition that itself started from a moving value is
+                // approximated as starting from where that value is now
+                let from = match &self.interrupted {
+                    Some(interrupted) => interrupted.value_at(current_time),
+                    None => self.transition_checkpoint_value.clone(),
+                };
+                Some(InterruptedTransition {
+                    from,
+                    transition,
+                    origin: self.origin,
+                })
+            }
+            _ => None,
+        };
+        self.transition_checkpoint_value = current_value;
+        self.origin = current_time;
     }
 
-    pub fn compute_eased_value(&mut self, frames_elapsed: u64) -> Option<T> {
-        let global_fe = frames_elapsed;
-        let origin_fe = &mut self.origin_frames_elapsed;
-
+    pub fn compute_eased_value(&mut self, now: AnimationTime) -> Option<T> {
         // Fast-forward transitions that have already passed
-        while global_fe - *origin_fe > self.queue.front()?.duration_frames {
+        while self.queue.front()?.duration.progress(self.origin, now) > 1.0 {
             let curr = self.queue.pop_front()?;
-            *origin_fe += curr.duration_frames;
+            self.origin = curr.duration.end(self.origin, now);
             self.transition_checkpoint_value = curr.ending_value;
+            self.interrupted = None;
+            if self.queue.is_empty() {
+                // land exactly on the end value, even if the tick at which
+                // the last transition ended was skipped
+                return Some(self.transition_checkpoint_value.clone());
+            }
         }
         let current_transition = self.queue.front()?;
-        let local_fe = global_fe - *origin_fe;
-        let progress = local_fe as f64 / current_transition.duration_frames as f64;
-        let interpolated_val = current_transition.curve.interpolate(
-            &self.transition_checkpoint_value,
+        let duration = current_transition.duration;
+        let progress = duration.progress(self.origin, now);
+        let from = match &self.interrupted {
+            Some(interrupted) => interrupted.value_at(now),
+            None => self.transition_checkpoint_value.clone(),
+        };
+        let interpolated_val = current_transition.curve.interpolate_over(
+            &from,
             &current_transition.ending_value,
             progress,
+            duration.as_secs_f64(),
         );
         Some(interpolated_val)
     }
 }
 
+/// Maps the progress of a transition (`t ∈ [0,1]`) to how far along the
+/// value is from its start to its end value. The named curves follow
+/// <https://easings.net>.
 pub enum EasingCurve {
     Linear,
     InQuad,
     OutQuad,
+    InOutQuad,
+    InSine,
+    OutSine,
+    InOutSine,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InQuart,
+    OutQuart,
+    InOutQuart,
+    InQuint,
+    OutQuint,
+    InOutQuint,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    InCirc,
+    OutCirc,
+    InOutCirc,
     InBack,
     OutBack,
     InOutBack,
+    InElastic,
+    OutElastic,
+    InOutElastic,
+    InBounce,
+    OutBounce,
+    InOutBounce,
+    /// CSS style `cubic-bezier(x1, y1, x2, y2)`, with control points
+    /// (x1, y1) and (x2, y2) and `x1, x2 ∈ [0,1]`
+    CubicBezier(f64, f64, f64, f64),
+    /// Jumps in `n` equal steps, at the end of each interval (CSS `steps(n)`)
+    Steps(u32),
+    /// Damped spring starting at rest, simulated in seconds (frame based
+    /// transitions assume 60 frames per second). Jumps to the end value once
+    /// the transition is over, so pick a duration of at least
+    /// [`EasingCurve::settling_time`]. Retargeting a property mid-flight with
+    /// `ease_to` keeps its current velocity.
+    Spring {
+        stiffness: f64,
+        damping: f64,
+        mass: f64,
+    },
     Custom(Box<dyn Fn(f64) -> f64>),
 }
 
@@ -694,6 +1139,67 @@ impl EasingEvaluators {
     fn out_quad(t: f64) -> f64 {
         1.0 - (1.0 - t) * (1.0 - t)
     }
+    fn in_out_quad(t: f64) -> f64 {
+        Self::in_out_pow(t, 2)
+    }
+    fn in_sine(t: f64) -> f64 {
+        1.0 - (t * PI / 2.0).cos()
+    }
+    fn out_sine(t: f64) -> f64 {
+        (t * PI / 2.0).sin()
+    }
+    fn in_out_sine(t: f64) -> f64 {
+        -((PI * t).cos() - 1.0) / 2.0
+    }
+    fn in_pow(t: f64, n: i32) -> f64 {
+        t.powi(n)
+    }
+    fn out_pow(t: f64, n: i32) -> f64 {
+        1.0 - (1.0 - t).powi(n)
+    }
+    fn in_out_pow(t: f64, n: i32) -> f64 {
+        if t < 0.5 {
+            2f64.powi(n - 1) * t.powi(n)
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(n) / 2.0
+        }
+    }
+    fn in_expo(t: f64) -> f64 {
+        if t == 0.0 {
+            0.0
+        } else {
+            2f64.powf(10.0 * t - 10.0)
+        }
+    }
+    fn out_expo(t: f64) -> f64 {
+        if t == 1.0 {
+            1.0
+        } else {
+            1.0 - 2f64.powf(-10.0 * t)
+        }
+    }
+    fn in_out_expo(t: f64) -> f64 {
+        if t == 0.0 || t == 1.0 {
+            t
+        } else if t < 0.5 {
+            2f64.powf(20.0 * t - 10.0) / 2.0
+        } else {
+            (2.0 - 2f64.powf(-20.0 * t + 10.0)) / 2.0
+        }
+    }
+    fn in_circ(t: f64) -> f64 {
+        1.0 - (1.0 - t.powi(2)).max(0.0).sqrt()
+    }
+    fn out_circ(t: f64) -> f64 {
+        (1.0 - (t - 1.0).powi(2)).max(0.0).sqrt()
+    }
+    fn in_out_circ(t: f64) -> f64 {
+        if t < 0.5 {
+            (1.0 - (1.0 - (2.0 * t).powi(2)).max(0.0).sqrt()) / 2.0
+        } else {
+            ((1.0 - (-2.0 * t + 2.0).powi(2)).max(0.0).sqrt() + 1.0) / 2.0
+        }
+    }
     fn in_back(t: f64) -> f64 {
         const C1: f64 = 1.70158;
         const C3: f64 = C1 + 1.00;
@@ -714,24 +1220,274 @@ impl EasingEvaluators {
             ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
         }
     }
+    fn in_elastic(t: f64) -> f64 {
+        const C4: f64 = 2.0 * PI / 3.0;
+        if t == 0.0 || t == 1.0 {
+            t
+        } else {
+            -(2f64.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * C4).sin()
+        }
+    }
+    fn out_elastic(t: f64) -> f64 {
+        const C4: f64 = 2.0 * PI / 3.0;
+        if t == 0.0 || t == 1.0 {
+            t
+        } else {
+            2f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * C4).sin() + 1.0
+        }
+    }
+    fn in_out_elastic(t: f64) -> f64 {
+        const C5: f64 = 2.0 * PI / 4.5;
+        if t == 0.0 || t == 1.0 {
+            t
+        } else if t < 0.5 {
+            -(2f64.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0
+        } else {
+            (2f64.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0 + 1.0
+        }
+    }
+    fn in_bounce(t: f64) -> f64 {
+        1.0 - Self::out_bounce(1.0 - t)
+    }
+    fn out_bounce(t: f64) -> f64 {
+        const N1: f64 = 7.5625;
+        const D1: f64 = 2.75;
+        if t < 1.0 / D1 {
+            N1 * t * t
+        } else if t < 2.0 / D1 {
+            let t = t - 1.5 / D1;
+            N1 * t * t + 0.75
+        } else if t < 2.5 / D1 {
+            let t = t - 2.25 / D1;
+            N1 * t * t + 0.9375
+        } else {
+            let t = t - 2.625 / D1;
+            N1 * t * t + 0.984375
+        }
+    }
+    fn in_out_bounce(t: f64) -> f64 {
+        if t < 0.5 {
+            (1.0 - Self::out_bounce(1.0 - 2.0 * t)) / 2.0
+        } else {
+            (1.0 + Self::out_bounce(2.0 * t - 1.0)) / 2.0
+        }
+    }
+    fn steps(t: f64, n: u32) -> f64 {
+        let n = n.max(1) as f64;
+        if t >= 1.0 {
+            1.0
+        } else {
+            (t * n).floor() / n
+        }
+    }
+    fn cubic_bezier(t: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+        // coordinate of the curve at parameter s, for control points p1 and p2
+        // (the end points being 0 and 1)
+        let bezier = |s: f64, p1: f64, p2: f64| {
+            3.0 * (1.0 - s).powi(2) * s * p1 + 3.0 * (1.0 - s) * s.powi(2) * p2 + s.powi(3)
+        };
+        let bezier_slope = |s: f64, p1: f64, p2: f64| {
+            3.0 * (1.0 - s).powi(2) * p1
+                + 6.0 * (1.0 - s) * s * (p2 - p1)
+                + 3.0 * s.powi(2) * (1.0 - p2)
+        };
+        if t <= 0.0 || t >= 1.0 {
+            return t.clamp(0.0, 1.0);
+        }
+        // find s such that x(s) = t: newton's method, falling back to
+        // bisection (x is monotonic for x1, x2 in [0, 1])
+        let mut s = t;
+        for _ in 0..8 {
+            let error = bezier(s, x1, x2) - t;
+            let slope = bezier_slope(s, x1, x2);
+            if error.abs() < 1e-7 {
+                return bezier(s, y1, y2);
+            }
+            if slope.abs() < 1e-6 {
+                break;
+            }
+            s -= error / slope;
+        }
+        let (mut low, mut high) = (0.0, 1.0);
+        s = t;
+        for _ in 0..64 {
+            let x = bezier(s, x1, x2);
+            if (x - t).abs() < 1e-7 {
+                break;
+            }
+            if x < t {
+                low = s;
+            } else {
+                high = s;
+            }
+            s = (low + high) / 2.0;
+        }
+        bezier(s, y1, y2)
+    }
+    /// Position of a spring released at rest from 0 towards 1, after `time` seconds
+    fn spring(time: f64, stiffness: f64, damping: f64, mass: f64) -> f64 {
+        let omega = (stiffness / mass).sqrt();
+        let zeta = damping / (2.0 * (stiffness * mass).sqrt());
+        // displacement from the target, starting at -1 with no velocity
+        let displacement = if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            -(-zeta * omega * time).exp()
+                * ((omega_d * time).cos() + zeta * omega / omega_d * (omega_d * time).sin())
+        } else if zeta == 1.0 {
+            -(1.0 + omega * time) * (-omega * time).exp()
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let (r1, r2) = (-omega * (zeta - root), -omega * (zeta + root));
+            let c2 = r1 / (r2 - r1);
+            (-1.0 - c2) * (r1 * time).exp() + c2 * (r2 * time).exp()
+        };
+        if displacement.is_finite() {
+            1.0 + displacement
+        } else {
+            1.0
+        }
+    }
 }
 
 impl EasingCurve {
-    //for a time on the unit interval `t ∈ [0,1]`, given a value `t`,
-    // find the interpolated value `vt` between `v0` and `v1` given the self-contained easing curve
-    pub fn interpolate<T: Interpolatable>(&self, v0: &T, v1: &T, t: f64) -> T /*vt*/ {
-        let multiplier = match self {
+    /// The eased progress for a time on the unit interval `t ∈ [0,1]`, for a
+    /// transition taking `duration_secs` (only used by `Spring`)
+    fn ease(&self, t: f64, duration_secs: f64) -> f64 {
+        match self {
             EasingCurve::Linear => EasingEvaluators::linear(t),
             EasingCurve::InQuad => EasingEvaluators::in_quad(t),
             EasingCurve::OutQuad => EasingEvaluators::out_quad(t),
+            EasingCurve::InOutQuad => EasingEvaluators::in_out_quad(t),
+            EasingCurve::InSine => EasingEvaluators::in_sine(t),
+            EasingCurve::OutSine => EasingEvaluators::out_sine(t),
+            EasingCurve::InOutSine => EasingEvaluators::in_out_sine(t),
+            EasingCurve::InCubic => EasingEvaluators::in_pow(t, 3),
+            EasingCurve::OutCubic => EasingEvaluators::out_pow(t, 3),
+            EasingCurve::InOutCubic => EasingEvaluators::in_out_pow(t, 3),
+            EasingCurve::InQuart => EasingEvaluators::in_pow(t, 4),
+            EasingCurve::OutQuart => EasingEvaluators::out_pow(t, 4),
+            EasingCurve::InOutQuart => EasingEvaluators::in_out_pow(t, 4),
+            EasingCurve::InQuint => EasingEvaluators::in_pow(t, 5),
+            EasingCurve::OutQuint => EasingEvaluators::out_pow(t, 5),
+            EasingCurve::InOutQuint => EasingEvaluators::in_out_pow(t, 5),
+            EasingCurve::InExpo => EasingEvaluators::in_expo(t),
+            EasingCurve::OutExpo => EasingEvaluators::out_expo(t),
+            EasingCurve::InOutExpo => EasingEvaluators::in_out_expo(t),
+            EasingCurve::InCirc => EasingEvaluators::in_circ(t),
+            EasingCurve::OutCirc => EasingEvaluators::out_circ(t),
+            EasingCurve::InOutCirc => EasingEvaluators::in_out_circ(t),
             EasingCurve::InBack => EasingEvaluators::in_back(t),
             EasingCurve::OutBack => EasingEvaluators::out_back(t),
             EasingCurve::InOutBack => EasingEvaluators::in_out_back(t),
+            EasingCurve::InElastic => EasingEvaluators::in_elastic(t),
+            EasingCurve::OutElastic => EasingEvaluators::out_elastic(t),
+            EasingCurve::InOutElastic => EasingEvaluators::in_out_elastic(t),
+            EasingCurve::InBounce => EasingEvaluators::in_bounce(t),
+            EasingCurve::OutBounce => EasingEvaluators::out_bounce(t),
+            EasingCurve::InOutBounce => EasingEvaluators::in_out_bounce(t),
+            EasingCurve::CubicBezier(x1, y1, x2, y2) => {
+                EasingEvaluators::cubic_bezier(t, *x1, *y1, *x2, *y2)
+            }
+            EasingCurve::Steps(n) => EasingEvaluators::steps(t, *n),
+            EasingCurve::Spring {
+                stiffness,
+                damping,
+                mass,
+            } => EasingEvaluators::spring(t * duration_secs, *stiffness, *damping, *mass),
             EasingCurve::Custom(evaluator) => (*evaluator)(t),
-        };
+        }
+    }
 
+    //for a time on the unit interval `t ∈ [0,1]`, given a value `t`,
+    // find the interpolated value `vt` between `v0` and `v1` given the self-contained easing curve
+    // (a `Spring` is assumed to run for one second)
+    pub fn interpolate<T: Interpolatable>(&self, v0: &T, v1: &T, t: f64) -> T /*vt*/ {
+        self.interpolate_over(v0, v1, t, 1.0)
+    }
+
+    pub(crate) fn interpolate_over<T: Interpolatable>(
+        &self,
+        v0: &T,
+        v1: &T,
+        t: f64,
+        duration_secs: f64,
+    ) -> T {
+        let multiplier = self.ease(t, duration_secs);
         v0.interpolate(v1, multiplier)
     }
+
+    /// Looks up a curve without parameters by its snake_case name, like
+    /// `linear` or `in_out_quad` (used by timelines declared in `@settings`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let curve = match name {
+            "linear" => EasingCurve::Linear,
+            "in_quad" => EasingCurve::InQuad,
+            "out_quad" => EasingCurve::OutQuad,
+            "in_out_quad" => EasingCurve::InOutQuad,
+            "in_sine" => EasingCurve::InSine,
+            "out_sine" => EasingCurve::OutSine,
+            "in_out_sine" => EasingCurve::InOutSine,
+            "in_cubic" => EasingCurve::InCubic,
+            "out_cubic" => EasingCurve::OutCubic,
+            "in_out_cubic" => EasingCurve::InOutCubic,
+            "in_quart" => EasingCurve::InQuart,
+            "out_quart" => EasingCurve::OutQuart,
+            "in_out_quart" => EasingCurve::InOutQuart,
+            "in_quint" => EasingCurve::InQuint,
+            "out_quint" => EasingCurve::OutQuint,
+            "in_out_quint" => EasingCurve::InOutQuint,
+            "in_expo" => EasingCurve::InExpo,
+            "out_expo" => EasingCurve::OutExpo,
+            "in_out_expo" => EasingCurve::InOutExpo,
+            "in_circ" => EasingCurve::InCirc,
+            "out_circ" => EasingCurve::OutCirc,
+            "in_out_circ" => EasingCurve::InOutCirc,
+            "in_back" => EasingCurve::InBack,
+            "out_back" => EasingCurve::OutBack,
+            "in_out_back" => EasingCurve::InOutBack,
+            "in_elastic" => EasingCurve::InElastic,
+            "out_elastic" => EasingCurve::OutElastic,
+            "in_out_elastic" => EasingCurve::InOutElastic,
+            "in_bounce" => EasingCurve::InBounce,
+            "out_bounce" => EasingCurve::OutBounce,
+            "in_out_bounce" => EasingCurve::InOutBounce,
+            _ => return None,
+        };
+        Some(curve)
+    }
+
+    /// For a `Spring`, the time it takes to come within 0.1% of its end value
+    /// and stay there. None for other curves.
+    pub fn settling_time(&self) -> Option<Duration> {
+        let EasingCurve::Spring {
+            stiffness,
+            damping,
+            mass,
+        } = self
+        else {
+            return None;
+        };
+        let omega = (stiffness / mass).sqrt();
+        let zeta = damping / (2.0 * (stiffness * mass).sqrt());
+        const TOLERANCE: f64 = 1e-3;
+        let secs = if zeta < 1.0 {
+            // bounded by the envelope of the oscillation
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            let amplitude = (1.0 + (zeta * omega / omega_d).powi(2)).sqrt();
+            (amplitude / TOLERANCE).ln() / (zeta * omega)
+        } else {
+            // no overshoot: walk forward from the decay of the slowest term
+            let decay_rate = omega * (zeta - (zeta * zeta - 1.0).sqrt());
+            let mut secs = (1.0 / TOLERANCE).ln() / decay_rate;
+            while secs.is_finite()
+                && 1.0 - EasingEvaluators::spring(secs, *stiffness, *damping, *mass) > TOLERANCE
+            {
+                secs *= 1.1;
+            }
+            secs
+        };
+        secs.is_finite().then(|| Duration::from_secs_f64(secs))
+    }
 }
 
 impl<I: Clone + 'static> ImplToFromPaxAny for std::ops::Range<I> {}
@@ -762,7 +1518,23 @@ impl Interpolatable for () {}
 impl<T: ?Sized + Clone> Interpolatable for HashSet<T> {}
 impl<T: ?Sized> Interpolatable for Rc<T> {}
 impl<T: Interpolatable> Interpolatable for Weak<T> {}
-impl<T1: Interpolatable, T2: Interpolatable> Interpolatable for (T1, T2) {}
+impl<T1: Interpolatable, T2: Interpolatable> Interpolatable for (T1, T2) {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        (
+            self.0.interpolate(&other.0, t),
+            self.1.interpolate(&other.1, t),
+        )
+    }
+}
+
+impl<T: Interpolatable> Interpolatable for [T; 2] {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        [
+            self[0].interpolate(&other[0], t),
+            self[1].interpolate(&other[1], t),
+        ]
+    }
+}
 
 impl<I: Interpolatable> Interpolatable for Vec<I> {
     fn interpolate(&self, other: &Self, t: f64) -> Self {
@@ -856,12 +1628,6 @@ impl Interpolatable for i32 {
 
 impl Interpolatable for String {}
 
-pub struct Timeline {
-    pub playhead_position: usize,
-    pub frame_count: usize,
-    pub is_playing: bool,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Layer {
     Native,
@@ -1332,17 +2098,77 @@ impl PartialEq<ColorMessage> for Color {
         }
     }
 }
+/// Colors are blended in OKLab with premultiplied alpha, so that midpoints keep a
+/// perceptually even lightness and fading from/to a transparent color doesn't
+/// pick up the hue of its (invisible) channels.
 impl Interpolatable for Color {
     fn interpolate(&self, other: &Self, t: f64) -> Self {
         let rgba_s = self.to_rgba_0_1();
         let rgba_o = other.to_rgba_0_1();
-        let rgba_i = [
-            rgba_s[0].interpolate(&rgba_o[0], t),
-            rgba_s[1].interpolate(&rgba_o[1], t),
-            rgba_s[2].interpolate(&rgba_o[2], t),
-            rgba_s[3].interpolate(&rgba_o[3], t),
-        ];
-        Color::from_rgba_0_1(rgba_i)
+        let alpha = rgba_s[3].interpolate(&rgba_o[3], t);
+        if alpha <= 0.0 {
+            return Color::from_rgba_0_1([0.0, 0.0, 0.0, 0.0]);
+        }
+        let lab_s = srgb_to_oklab([rgba_s[0], rgba_s[1], rgba_s[2]]);
+        let lab_o = srgb_to_oklab([rgba_o[0], rgba_o[1], rgba_o[2]]);
+        let mut lab_i = [0.0; 3];
+        for i in 0..3 {
+            lab_i[i] = (lab_s[i] * rgba_s[3]).interpolate(&(lab_o[i] * rgba_o[3]), t) / alpha;
+        }
+        let rgb = oklab_to_srgb(lab_i);
+        Color::from_rgba_0_1([rgb[0], rgb[1], rgb[2], alpha])
+    }
+}
+
+//OKLab conversions from https://bottosson.github.io/posts/oklab/ (public domain)
+fn srgb_to_oklab(rgb: [f64; 3]) -> [f64; 3] {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+fn oklab_to_srgb(lab: [f64; 3]) -> [f64; 3] {
+    let [l, a, b] = lab;
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    [
+        4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+        -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+        -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+    ]
+    .map(|c| linear_to_srgb(c).clamp(0.0, 1.0))
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Values that can't be blended (e.g. different kinds of gradients) switch over
+/// halfway through, the way CSS handles discrete animations.
+fn interpolate_discrete<T: Clone>(from: &T, to: &T, t: f64) -> T {
+    if t < 0.5 {
+        from.clone()
+    } else {
+        to.clone()
     }
 }
 
@@ -1361,8 +2187,9 @@ impl Default for Rotation {
 
 impl Interpolatable for Rotation {
     fn interpolate(&self, other: &Self, t: f64) -> Self {
-        Self::Percent(Numeric::F64(
-            other.to_float_0_1() - self.to_float_0_1() * t / 100.0,
+        Self::Radians(Numeric::F64(
+            self.get_as_radians()
+                .interpolate(&other.get_as_radians(), t),
         ))
     }
 }
@@ -1445,9 +2272,11 @@ impl Default for Stroke {
 }
 
 impl Interpolatable for Stroke {
-    fn interpolate(&self, _other: &Self, _t: f64) -> Self {
-        // TODO interpolation
-        self.clone()
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            color: Property::new(self.color.get().interpolate(&other.color.get(), t)),
+            width: Property::new(self.width.get().interpolate(&other.width.get(), t)),
+        }
     }
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1458,10 +2287,38 @@ pub enum Fill {
     RadialGradient(RadialGradient),
 }
 
+/// Gradients of the same kind blend stop by stop when they have the same number of
+/// stops, and a solid fill blends into (or out of) a gradient by acting as that
+/// gradient painted in a single color. Anything else switches over halfway.
 impl Interpolatable for Fill {
-    fn interpolate(&self, _other: &Self, _t: f64) -> Self {
-        // TODO interpolation
-        self.clone()
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Fill::Solid(s), Fill::Solid(o)) => Fill::Solid(s.interpolate(o, t)),
+            (Fill::LinearGradient(s), Fill::LinearGradient(o))
+                if s.stops.len() == o.stops.len() =>
+            {
+                Fill::LinearGradient(LinearGradient {
+                    start: s.start.interpolate(&o.start, t),
+                    end: s.end.interpolate(&o.end, t),
+                    stops: s.stops.interpolate(&o.stops, t),
+                })
+            }
+            (Fill::RadialGradient(s), Fill::RadialGradient(o))
+                if s.stops.len() == o.stops.len() =>
+            {
+                Fill::RadialGradient(RadialGradient {
+                    end: s.end.interpolate(&o.end, t),
+                    start: s.start.interpolate(&o.start, t),
+                    radius: s.radius.interpolate(&o.radius, t),
+                    stops: s.stops.interpolate(&o.stops, t),
+                })
+            }
+            (Fill::Solid(color), gradient) => gradient.painted_with(color).interpolate(gradient, t),
+            (gradient, Fill::Solid(color)) => {
+                gradient.interpolate(&gradient.painted_with(color), t)
+            }
+            _ => interpolate_discrete(self, other, t),
+        }
     }
 }
 
@@ -1495,6 +2352,15 @@ impl GradientStop {
     }
 }
 
+impl Interpolatable for GradientStop {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        GradientStop {
+            position: self.position.interpolate(&other.position, t),
+            color: self.color.interpolate(&other.color, t),
+        }
+    }
+}
+
 impl Default for Fill {
     fn default() -> Self {
         Self::Solid(Color::default())
@@ -1502,6 +2368,27 @@ impl Default for Fill {
 }
 
 impl Fill {
+    /// The same fill with every gradient stop set to `color`
+    fn painted_with(&self, color: &Color) -> Fill {
+        let repaint = |stops: &Vec<GradientStop>| {
+            stops
+                .iter()
+                .map(|stop| GradientStop::get(color.clone(), stop.position))
+                .collect()
+        };
+        match self {
+            Fill::Solid(_) => Fill::Solid(color.clone()),
+            Fill::LinearGradient(gradient) => Fill::LinearGradient(LinearGradient {
+                stops: repaint(&gradient.stops),
+                ..gradient.clone()
+            }),
+            Fill::RadialGradient(gradient) => Fill::RadialGradient(RadialGradient {
+                stops: repaint(&gradient.stops),
+                ..gradient.clone()
+            }),
+        }
+    }
+
     pub fn to_unit_point((x, y): (Size, Size), (width, height): (f64, f64)) -> UnitPoint {
         let normalized_x = match x {
             Size::Pixels(val) => val.to_float() / width,
@@ -1666,7 +2553,48 @@ pub struct Transform2D {
     pub skew: Option<[Rotation; 2]>,
 }
 
-impl Interpolatable for Transform2D {}
+/// Interpolates each component (translate, rotate, scale, skew) separately instead
+/// of blending matrices, so that e.g. a rotation keeps its size while turning. A
+/// component set on only one side eases from/to its identity value.
+impl Interpolatable for Transform2D {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let zero = || Size::Pixels(Numeric::F64(0.0));
+        let full = || Size::Percent(Numeric::F64(100.0));
+        let no_skew = || [Rotation::ZERO(), Rotation::ZERO()];
+        Transform2D {
+            previous: match (&self.previous, &other.previous) {
+                (Some(s), Some(o)) => Some(Box::new(s.interpolate(o, t))),
+                _ => interpolate_discrete(&self.previous, &other.previous, t),
+            },
+            rotate: interpolate_component(&self.rotate, &other.rotate, t, Rotation::ZERO),
+            translate: interpolate_component(&self.translate, &other.translate, t, || {
+                [zero(), zero()]
+            }),
+            anchor: match (&self.anchor, &other.anchor) {
+                (Some(s), Some(o)) => Some(s.interpolate(o, t)),
+                _ => interpolate_discrete(&self.anchor, &other.anchor, t),
+            },
+            scale: interpolate_component(&self.scale, &other.scale, t, || [full(), full()]),
+            skew: interpolate_component(&self.skew, &other.skew, t, no_skew),
+        }
+    }
+}
+
+fn interpolate_component<T: Interpolatable>(
+    from: &Option<T>,
+    to: &Option<T>,
+    t: f64,
+    identity: impl Fn() -> T,
+) -> Option<T> {
+    match (from, to) {
+        (None, None) => None,
+        (from, to) => {
+            let from = from.clone().unwrap_or_else(&identity);
+            let to = to.clone().unwrap_or_else(&identity);
+            Some(from.interpolate(&to, t))
+        }
+    }
+}
 
 impl Mul for Transform2D {
     type Output = Transform2D;
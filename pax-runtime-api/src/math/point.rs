@@ -7,7 +7,14 @@ use crate::Interpolatable;
 
 use super::{vector::Vector2, Generic, Space};
 
-impl<W: Space> Interpolatable for Point2<W> {}
+impl<W: Space> Interpolatable for Point2<W> {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Point2::new(
+            self.x.interpolate(&other.x, t),
+            self.y.interpolate(&other.y, t),
+        )
+    }
+}
 
 pub struct Point2<W = Generic> {
     pub x: f64,
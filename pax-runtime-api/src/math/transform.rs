@@ -1,7 +1,7 @@
 use crate::Interpolatable;
 
 use super::{Generic, Point2, Space, Vector2};
-use std::{marker::PhantomData, ops::Mul};
+use std::{f64::consts::PI, marker::PhantomData, ops::Mul};
 
 //-----------------------------------------------------------
 // Pax matrix/transform class heavily borrows from kurbos
@@ -11,7 +11,22 @@ use std::{marker::PhantomData, ops::Mul};
 // Kurbo is distributed under an MIT license.
 //-----------------------------------------------------------
 
-impl<W: Space, T: Space> Interpolatable for Transform2<W, T> {}
+/// Interpolates the decomposed [`Parts`] (translation, rotation, scale and skew)
+/// rather than the matrix coefficients, turning along the shortest arc.
+impl<W: Space, T: Space> Interpolatable for Transform2<W, T> {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let from: Parts = (*self).into();
+        let to: Parts = (*other).into();
+        let turn = (to.rotation - from.rotation + PI).rem_euclid(2.0 * PI) - PI;
+        Parts {
+            origin: from.origin.interpolate(&to.origin, t),
+            scale: from.scale.interpolate(&to.scale, t),
+            skew: from.skew.interpolate(&to.skew, t),
+            rotation: from.rotation + turn * t,
+        }
+        .into()
+    }
+}
 
 pub struct Transform2<WFrom = Generic, WTo = WFrom> {
     m: [f64; 6],
@@ -234,6 +249,7 @@ mod tests {
     use crate::math::{Generic, Vector2};
 
     use super::{Parts, Transform2};
+    use crate::Interpolatable;
 
     #[test]
     fn from_to_parts() {
@@ -301,4 +317,17 @@ mod tests {
                 < 1e-3)
         );
     }
+
+    #[test]
+    fn interpolates_parts_along_the_shortest_turn() {
+        let from = Transform2::<Generic>::translate(Vector2::new(10.0, 0.0))
+            * Transform2::<Generic>::rotate(170.0_f64.to_radians());
+        let to = Transform2::<Generic>::translate(Vector2::new(20.0, 10.0))
+            * Transform2::<Generic>::rotate(-170.0_f64.to_radians())
+            * Transform2::<Generic>::scale(3.0);
+        let mid: Parts = from.interpolate(&to, 0.5).into();
+        assert!((mid.origin - Vector2::new(15.0, 5.0)).length() < 1e-9);
+        assert!((mid.scale - Vector2::new(2.0, 2.0)).length() < 1e-9);
+        assert!((mid.rotation.abs() - PI).abs() < 1e-9);
+    }
 }
@@ -16,7 +16,14 @@ pub struct Vector2<W = Generic> {
 
 // Implement Clone, Copy, PartialEq, etc manually, as
 // to not require the Space to implement these.
-impl<W: Space> Interpolatable for Vector2<W> {}
+impl<W: Space> Interpolatable for Vector2<W> {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Vector2::new(
+            self.x.interpolate(&other.x, t),
+            self.y.interpolate(&other.y, t),
+        )
+    }
+}
 
 impl<W: Space> std::fmt::Debug for Vector2<W> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -1,7 +1,8 @@
 use super::{private::PropertyId, properties_table::PropertyTable};
 
 impl PropertyTable {
-    /// marks dependencies of self dirty recursively
+    /// marks dependencies of self dirty recursively, queueing any effects
+    /// reached to be run
     pub fn dirtify_outbound(&self, id: PropertyId) {
         let mut to_dirtify =
             self.with_property_data_mut(id, |property_data| property_data.outbound.clone());
@@ -13,6 +14,9 @@ impl PropertyTable {
                 }
                 if !dep_data.dirty {
                     dep_data.dirty = true;
+                    if dep_data.is_effect {
+                        self.queue_effect(dep_id);
+                    }
                     to_dirtify.extend_from_slice(&dep_data.outbound);
                 }
             });
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use slotmap::Key;
+
+use super::{private::PropertyId, properties_table::PROPERTY_TABLE, UntypedProperty};
+
+/// What backs a property in the property table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PropertyKind {
+    /// Created with a value (Property::new)
+    Literal,
+    /// Recomputed from its inbound properties when read after they changed.
+    /// Stays `Computed` after being `set`: the next change of one of its
+    /// inbound properties recomputes it.
+    Computed,
+    /// Re-run as soon as one of its inbound properties changed (see `effect`)
+    Effect,
+}
+
+/// A property as seen in a [`PropertyGraph`] snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyNode {
+    pub id: u64,
+    /// Debug name given on creation (e.g. `Property::computed_with_name`)
+    pub name: Option<String>,
+    pub type_name: String,
+    pub kind: PropertyKind,
+    /// Number of live `Property` handles pointing to this property
+    pub ref_count: usize,
+    pub dirty: bool,
+    /// Properties this one depends on
+    pub inbound: Vec<u64>,
+    /// Properties depending on this one
+    pub outbound: Vec<u64>,
+}
+
+/// Snapshot of all properties in the (thread local) property table and the
+/// dependencies between them. Meant for debugging reactive leaks and
+/// over-broad dependencies, e.g. by rendering [`PropertyGraph::to_dot`] with
+/// Graphviz: `dot -Tsvg graph.dot > graph.svg`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PropertyGraph {
+    pub nodes: Vec<PropertyNode>,
+}
+
+impl PropertyGraph {
+    /// Takes a snapshot of the current property table
+    pub fn snapshot() -> Self {
+        PROPERTY_TABLE.with(|t| t.snapshot())
+    }
+
+    pub(crate) fn id_of(id: PropertyId) -> u64 {
+        id.data().as_ffi()
+    }
+
+    /// The snapshot of `property`, if it existed when the snapshot was taken
+    pub fn node(&self, property: &UntypedProperty) -> Option<&PropertyNode> {
+        self.get(Self::id_of(property.get_id()))
+    }
+
+    pub fn get(&self, id: u64) -> Option<&PropertyNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Properties in this snapshot that weren't in `earlier`
+    pub fn added_since(&self, earlier: &PropertyGraph) -> Vec<&PropertyNode> {
+        let before: HashSet<u64> = earlier.nodes.iter().map(|node| node.id).collect();
+        self.nodes
+            .iter()
+            .filter(|node| !before.contains(&node.id))
+            .collect()
+    }
+
+    /// Graphviz DOT representation, with edges pointing from a property to
+    /// the properties depending on it. Literals are drawn as boxes, computed
+    /// properties as ellipses and effects as hexagons, dashed when dirty.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph properties {\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                PropertyKind::Literal => "box",
+                PropertyKind::Computed => "ellipse",
+                PropertyKind::Effect => "hexagon",
+            };
+            let style = if node.dirty { "dashed" } else { "solid" };
+            let label = format!(
+                "{}\\n{:?}<{}> refs: {}",
+                escape(node.name.as_deref().unwrap_or("<no name>")),
+                node.kind,
+                escape(&node.type_name),
+                node.ref_count
+            );
+            let _ = writeln!(
+                dot,
+                "    p{} [label=\"{}\", shape={}, style={}];",
+                node.id, label, shape, style
+            );
+        }
+        for node in &self.nodes {
+            for dependent in &node.outbound {
+                let _ = writeln!(dot, "    p{} -> p{};", node.id, dependent);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("property graph to be serializable")
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Runs `f` (e.g. mounting and then unmounting a subtree) and returns the
+/// properties it created that are still alive afterwards. Anything returned
+/// here is either kept on purpose by code outside of the subtree, or leaked
+/// (typically a property captured by a closure stored in another property).
+pub fn properties_leaked_by(f: impl FnOnce()) -> PropertyGraph {
+    let before = PropertyGraph::snapshot();
+    f();
+    let after = PropertyGraph::snapshot();
+    PropertyGraph {
+        nodes: after.added_since(&before).into_iter().cloned().collect(),
+    }
+}
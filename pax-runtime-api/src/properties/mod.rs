@@ -1,15 +1,17 @@
 use serde::{Deserialize, Serialize};
-use std::{marker::PhantomData, rc::Rc};
+use std::{cell::Cell, marker::PhantomData, rc::Rc};
 
 mod graph_operations;
+mod inspect;
 mod properties_table;
 #[cfg(test)]
 mod tests;
 mod untyped_property;
 
-use crate::{EasingCurve, Interpolatable, TransitionQueueEntry};
+use crate::{EasingCurve, Interpolatable, TransitionDuration, TransitionQueueEntry};
 
-use self::properties_table::{PropertyType, PROPERTY_TIME};
+use self::properties_table::{PropertyType, PROPERTY_TIME, PROPERTY_TIME_MS};
+pub use inspect::{properties_leaked_by, PropertyGraph, PropertyKind, PropertyNode};
 use properties_table::PROPERTY_TABLE;
 pub use untyped_property::UntypedProperty;
 
@@ -104,20 +106,34 @@ impl<T: PropertyValue> Property<T> {
         }
     }
 
-    pub fn ease_to(&self, end_val: T, time: u64, curve: EasingCurve) {
-        self.ease_to_value(end_val, time, curve, true);
+    /// Transitions to `end_val`, replacing any queued transitions. `time` is
+    /// either a number of frames or a `std::time::Duration`.
+    pub fn ease_to(&self, end_val: T, time: impl Into<TransitionDuration>, curve: EasingCurve) {
+        self.ease_to_value(end_val, time.into(), curve, true);
     }
 
-    pub fn ease_to_later(&self, end_val: T, time: u64, curve: EasingCurve) {
-        self.ease_to_value(end_val, time, curve, false);
+    /// Transitions to `end_val` once all queued transitions are done
+    pub fn ease_to_later(
+        &self,
+        end_val: T,
+        time: impl Into<TransitionDuration>,
+        curve: EasingCurve,
+    ) {
+        self.ease_to_value(end_val, time.into(), curve, false);
     }
 
-    fn ease_to_value(&self, end_val: T, time: u64, curve: EasingCurve, overwrite: bool) {
+    fn ease_to_value(
+        &self,
+        end_val: T,
+        duration: TransitionDuration,
+        curve: EasingCurve,
+        overwrite: bool,
+    ) {
         PROPERTY_TABLE.with(|t| {
             t.transition(
                 self.untyped.id,
                 TransitionQueueEntry {
-                    duration_frames: time,
+                    duration,
                     curve,
                     ending_value: end_val,
                 },
@@ -139,15 +155,20 @@ impl<T: PropertyValue> Property<T> {
         PROPERTY_TABLE.with(|t| t.set_value(self.untyped.id, val));
     }
 
-    // Get access to a mutable reference to the inner value T.
-    // Always updates dependents of this property, no matter
-    // if the value changed or not
+    /// Get access to a mutable reference to the inner value T, mutating it
+    /// in place. Always updates dependents of this property, no matter
+    /// if the value changed or not.
+    ///
+    /// Setting this same property from within `f` is deferred: the value set
+    /// replaces the one `f` leaves once it returns.
+    ///
+    /// # Panics
+    ///
+    /// If `f` gets or updates this same property (directly, or through a
+    /// computed property depending on it): its value is taken out of the
+    /// property table while `f` runs. `f` can access any other property.
     pub fn update(&self, f: impl FnOnce(&mut T)) {
-        // This is a temporary impl of the update method.
-        // (very bad perf comparatively, but very safe).
-        let mut val = self.get();
-        f(&mut val);
-        self.set(val);
+        PROPERTY_TABLE.with(|t| t.update_value_in_place(self.untyped.id, f));
     }
 
     // Get access to a reference to the inner value T.
@@ -173,6 +194,87 @@ impl<T: PropertyValue> Property<T> {
     pub fn untyped(&self) -> UntypedProperty {
         self.untyped.clone()
     }
+
+    /// Calls `f` with the new value every time this property is set or one
+    /// of its dependencies changes, until the returned handle is dropped
+    pub fn subscribe(&self, f: impl Fn(T) + 'static) -> EffectHandle {
+        let cp_self = self.clone();
+        let initialized = Cell::new(false);
+        effect(&[self.untyped()], move || {
+            // read even on the first run, to stay subscribed to the chain
+            // of computed properties this one might depend on
+            let value = cp_self.get();
+            if initialized.replace(true) {
+                f(value);
+            }
+        })
+    }
+}
+
+/// Runs `f` as a single transaction: properties set within it only let their
+/// dependents know (and effects only run) once `f` returns, instead of after
+/// every set. Computed properties read within `f` still see the values from
+/// before the batch. Batches can be nested, in which case everything is
+/// deferred until the outermost one ends.
+pub fn batch<V>(f: impl FnOnce() -> V) -> V {
+    PROPERTY_TABLE.with(|t| t.start_batch());
+    // ends the batch even if f panics, so later sets aren't deferred forever
+    let _batch = BatchGuard;
+    f()
+}
+
+struct BatchGuard;
+
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        // effects running during unwinding could panic again and abort
+        PROPERTY_TABLE.with(|t| t.end_batch(!std::thread::panicking()));
+    }
+}
+
+/// Runs `f` once now, and again every time one of `dependencies` changes,
+/// until the returned handle is dropped. Effects run as soon as the change
+/// that dirtied them has propagated, instead of waiting for the next tick.
+pub fn effect(dependencies: &[UntypedProperty], f: impl Fn() + 'static) -> EffectHandle {
+    let inbound: Vec<_> = dependencies.iter().map(|v| v.get_id()).collect();
+    let cp_inbound = inbound.clone();
+    let f = move || {
+        // dependencies that are dirty but were never read would otherwise
+        // stop dirtying from reaching this effect the next time they change
+        PROPERTY_TABLE.with(|t| {
+            for id in &cp_inbound {
+                t.refresh(*id);
+            }
+        });
+        f()
+    };
+    EffectHandle {
+        _effect: UntypedProperty {
+            id: PROPERTY_TABLE.with(|t| t.add_effect(f, inbound, None)),
+        },
+    }
+}
+
+/// Runs `f` once now, and again every time one of `dependencies` goes from
+/// up to date to dirty, until the returned handle is dropped. Unlike
+/// [`effect`], nothing is evaluated: a dependency that stays dirty won't
+/// notify about further changes until it is read, so `f` is expected to
+/// schedule that read. Used by the engine to queue node updates for the next
+/// tick, without re-entering userland code in the middle of a `set`.
+pub fn on_dirty(dependencies: &[UntypedProperty], f: impl Fn() + 'static) -> EffectHandle {
+    let inbound: Vec<_> = dependencies.iter().map(|v| v.get_id()).collect();
+    EffectHandle {
+        _effect: UntypedProperty {
+            id: PROPERTY_TABLE.with(|t| t.add_effect(f, inbound, None)),
+        },
+    }
+}
+
+/// Keeps an effect (see [`effect`], [`on_dirty`] and [`Property::subscribe`]) alive:
+/// dropping the handle removes the effect.
+#[must_use = "the effect is removed as soon as its handle is dropped"]
+pub struct EffectHandle {
+    _effect: UntypedProperty,
 }
 
 impl<T: PropertyValue> Default for Property<T> {
@@ -211,3 +313,15 @@ pub fn property_table_total_properties_count() -> usize {
 pub fn register_time(prop: &Property<u64>) {
     PROPERTY_TIME.with_borrow_mut(|time| *time = prop.clone());
 }
+
+/// Registers the millisecond clock used by transitions with a
+/// `std::time::Duration`. Needs to be updated whenever the property passed to
+/// [`register_time`] is.
+pub fn register_time_ms(prop: &Property<f64>) {
+    PROPERTY_TIME_MS.with_borrow_mut(|time| *time = prop.clone());
+}
+
+/// The millisecond clock registered with [`register_time_ms`]
+pub(crate) fn time_ms() -> Property<f64> {
+    PROPERTY_TIME_MS.with_borrow(|time| time.clone())
+}
@@ -1,16 +1,35 @@
-use std::{any::Any, cell::RefCell, rc::Rc};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+};
 
 use slotmap::{SlotMap, SparseSecondaryMap};
 
-use crate::{Property, TransitionManager, TransitionQueueEntry};
+use crate::{AnimationTime, Property, TransitionManager, TransitionQueueEntry};
 
-use super::{private::PropertyId, PropertyValue};
+use super::{
+    inspect::{PropertyGraph, PropertyKind, PropertyNode},
+    private::PropertyId,
+    PropertyValue,
+};
 
 thread_local! {
     /// Global property table used to store data backing dirty-dag
     pub(crate) static PROPERTY_TABLE: PropertyTable = PropertyTable::default();
     /// Property time variable, to be used by
     pub(crate) static PROPERTY_TIME: RefCell<Property<u64>> = RefCell::new(Property::new(0));
+    /// Milliseconds elapsed, updated together with PROPERTY_TIME (which is
+    /// what transitioning properties depend on)
+    pub(crate) static PROPERTY_TIME_MS: RefCell<Property<f64>> = RefCell::new(Property::new(0.0));
+}
+
+fn current_animation_time() -> AnimationTime {
+    AnimationTime {
+        frames: PROPERTY_TIME.with_borrow(|time| time.get()),
+        ms: PROPERTY_TIME_MS.with_borrow(|time| time.get()),
+    }
 }
 
 /// The main collection of data associated with a specific property id
@@ -27,6 +46,15 @@ pub struct PropertyData {
     // has been changed. For computed this can be any other props,
     // for literals, only time variable
     pub dirty: bool,
+    // Set for effects: instead of waiting to be read, these are queued to be
+    // re-run as soon as they are dirtied
+    pub is_effect: bool,
+    // Brings this property up to date without knowing its type, used to
+    // refresh the dependencies of an effect when running it
+    refresh: fn(&PropertyTable, PropertyId),
+    // Type information kept around for inspection (see PropertyGraph)
+    type_name: &'static str,
+    is_computed: fn(&PropertyData) -> bool,
 }
 
 impl PropertyData {
@@ -35,6 +63,12 @@ impl PropertyData {
             .downcast_mut::<TypedPropertyData<T>>()
             .unwrap_or_else(|| panic!("Failed to downcast to TypedPropertyData<{}>. The actual type does not match the expected type.", std::any::type_name::<T>()))
     }
+
+    fn is_computed<T: 'static>(&self) -> bool {
+        self.typed_data
+            .downcast_ref::<TypedPropertyData<T>>()
+            .is_some_and(|typed| matches!(typed.property_type, PropertyType::Computed { .. }))
+    }
 }
 
 pub struct TypedPropertyData<T> {
@@ -61,6 +95,27 @@ pub(crate) struct PropertyTable {
     // Box<dyn Any> is of type Box<Entry<T>> where T is the proptype
     pub(crate) property_map: RefCell<SlotMap<PropertyId, Entry>>,
     debug_names: RefCell<SparseSecondaryMap<PropertyId, String>>,
+    // Effects dirtied since they last ran
+    pending_effects: RefCell<VecDeque<PropertyId>>,
+    running_effects: Cell<bool>,
+    // Nesting depth of batches currently running, and the properties set
+    // within them whose dependents are yet to be dirtied
+    batch_depth: Cell<usize>,
+    batched: RefCell<Vec<PropertyId>>,
+    // Properties currently being updated in place, with the last value set
+    // on them while doing so (applied once the update is done)
+    deferred_sets: RefCell<SparseSecondaryMap<PropertyId, Option<Box<dyn Any>>>>,
+}
+
+struct DeferredSetsGuard<'a> {
+    table: &'a PropertyTable,
+    id: PropertyId,
+}
+
+impl Drop for DeferredSetsGuard<'_> {
+    fn drop(&mut self) {
+        self.table.deferred_sets.borrow_mut().remove(self.id);
+    }
 }
 
 pub struct Entry {
@@ -79,15 +134,92 @@ impl PropertyTable {
         })
     }
 
+    /// Whether the property with this id stores a value of type T
+    pub fn is_of_type<T: 'static>(&self, id: PropertyId) -> bool {
+        self.with_property_data(id, |property_data| {
+            property_data.typed_data.is::<TypedPropertyData<T>>()
+        })
+    }
+
     // Main function to set a value of a property.
     // NOTE: This always assumes the underlying data was changed, and marks
     // it and it's dependents as dirty irrespective of actual modification
     pub fn set_value<T: PropertyValue>(&self, id: PropertyId, new_val: T) {
+        if let Some(deferred) = self.deferred_sets.borrow_mut().get_mut(id) {
+            *deferred = Some(Box::new(new_val));
+            return;
+        }
         self.with_property_data_mut(id, |property_data: &mut PropertyData| {
             let typed_data = property_data.typed_data();
             typed_data.value = new_val;
         });
-        self.dirtify_outbound(id);
+        self.value_changed(id);
+    }
+
+    /// Mutates the value of a property where it is stored, without cloning it.
+    /// Like set_value, this always marks dependents as dirty.
+    /// WARNING: f is run while the entry is taken out of the table (see
+    /// with_property_data_mut). Sets of the property it is updating are
+    /// deferred until f returns, but it can't get or update it.
+    pub fn update_value_in_place<T: PropertyValue>(&self, id: PropertyId, f: impl FnOnce(&mut T)) {
+        self.update_value::<T>(id);
+        if self.deferred_sets.borrow_mut().insert(id, None).is_some() {
+            panic!("can't update a property from within its own update");
+        }
+        // stop deferring sets even if f panics
+        let guard = DeferredSetsGuard { table: self, id };
+        self.with_property_data_mut(id, |property_data: &mut PropertyData| {
+            let typed_data = property_data.typed_data::<T>();
+            f(&mut typed_data.value);
+            let deferred = self
+                .deferred_sets
+                .borrow_mut()
+                .get_mut(id)
+                .and_then(Option::take);
+            if let Some(new_val) = deferred {
+                typed_data.value = *new_val
+                    .downcast::<T>()
+                    .expect("deferred value should have the property's type");
+            }
+        });
+        drop(guard);
+        self.value_changed(id);
+    }
+
+    /// Lets the dependents of id know it changed, now or when the current
+    /// batch ends
+    fn value_changed(&self, id: PropertyId) {
+        if self.batch_depth.get() > 0 {
+            self.batched.borrow_mut().push(id);
+        } else {
+            self.dirtify_outbound(id);
+            self.run_pending_effects();
+        }
+    }
+
+    pub fn start_batch(&self) {
+        self.batch_depth.set(self.batch_depth.get() + 1);
+    }
+
+    /// Ends the innermost batch. Once the outermost one ends, dirties the
+    /// dependents of everything set while batching and, if run_effects is
+    /// set, runs effects (otherwise they stay queued until the next change).
+    pub fn end_batch(&self, run_effects: bool) {
+        let depth = self.batch_depth.get() - 1;
+        self.batch_depth.set(depth);
+        if depth > 0 {
+            return;
+        }
+        let batched = std::mem::take(&mut *self.batched.borrow_mut());
+        for id in batched {
+            // the property might have been dropped within the batch
+            if self.property_map.borrow().contains_key(id) {
+                self.dirtify_outbound(id);
+            }
+        }
+        if run_effects {
+            self.run_pending_effects();
+        }
     }
 
     /// Adds a new untyped property entry
@@ -110,6 +242,10 @@ impl PropertyTable {
                 data: Some(PropertyData {
                     inbound,
                     dirty: true,
+                    is_effect: false,
+                    refresh: Self::update_value::<T>,
+                    type_name: std::any::type_name::<T>(),
+                    is_computed: PropertyData::is_computed::<T>,
                     typed_data: Box::new(TypedPropertyData {
                         value: start_val,
                         property_type: data,
@@ -137,7 +273,8 @@ impl PropertyTable {
         overwrite: bool,
     ) {
         let mut should_connect_to_time = false;
-        let (time_id, curr_time) = PROPERTY_TIME.with_borrow(|time| (time.untyped.id, time.get()));
+        let time_id = PROPERTY_TIME.with_borrow(|time| time.untyped.id);
+        let curr_time = current_animation_time();
         self.with_property_data_mut(id, |property_data: &mut PropertyData| {
             let typed_data = property_data.typed_data::<T>();
             let transition_manager = typed_data
@@ -259,8 +396,11 @@ impl PropertyTable {
 
         // overwrite with more descriptive name
         let target_name = self.debug_name(target_id);
-        let mut names = self.debug_names.borrow_mut();
-        names.insert(source_id, format!("{}", target_name));
+        self.debug_names
+            .borrow_mut()
+            .insert(source_id, format!("{}", target_name));
+
+        self.run_pending_effects();
     }
 
     // re-computes the value if dirty
@@ -277,8 +417,7 @@ impl PropertyTable {
                 PropertyType::Computed { evaluator, .. } => Some(Rc::clone(&evaluator)),
                 PropertyType::Literal => {
                     let tm = typed_data.transition_manager.as_mut()?;
-                    let curr_time = PROPERTY_TIME.with_borrow(|time| time.get());
-                    let value = tm.compute_eased_value(curr_time);
+                    let value = tm.compute_eased_value(current_animation_time());
                     if let Some(interp_value) = value {
                         typed_data.value = interp_value;
                     } else {
@@ -312,6 +451,65 @@ impl PropertyTable {
         }
     }
 
+    /// Adds an effect: an entry without a value that runs `f` once now, and
+    /// again whenever one of its dependencies goes from up to date to dirty.
+    /// NOTE: a dependency that stays dirty (is never read) won't let the
+    /// effect know about further changes, see refresh
+    pub fn add_effect(
+        &self,
+        f: impl Fn() + 'static,
+        inbound: Vec<PropertyId>,
+        debug_name: Option<&str>,
+    ) -> PropertyId {
+        let evaluator = Rc::new(f);
+        let id = self.add_entry(
+            (),
+            inbound,
+            PropertyType::Computed { evaluator },
+            debug_name,
+        );
+        self.with_property_data_mut(id, |property_data| property_data.is_effect = true);
+        self.run_effect(id);
+        id
+    }
+
+    pub(crate) fn queue_effect(&self, id: PropertyId) {
+        self.pending_effects.borrow_mut().push_back(id);
+    }
+
+    /// Runs effects dirtied since they last ran, including the ones dirtied by
+    /// running them. Effects dirtied while another effect is running are left
+    /// for the outer call to pick up.
+    pub fn run_pending_effects(&self) {
+        if self.running_effects.replace(true) {
+            return;
+        }
+        // lets later calls run effects again even if one of them panics
+        let _running = ResetOnDrop(&self.running_effects);
+        loop {
+            let Some(id) = self.pending_effects.borrow_mut().pop_front() else {
+                break;
+            };
+            // the effect might have been dropped since it was queued
+            if self.property_map.borrow().contains_key(id) {
+                self.run_effect(id);
+            }
+        }
+    }
+
+    fn run_effect(&self, id: PropertyId) {
+        self.update_value::<()>(id);
+    }
+
+    /// Brings a property up to date (if it still exists) without knowing its type
+    pub fn refresh(&self, id: PropertyId) {
+        if !self.property_map.borrow().contains_key(id) {
+            return;
+        }
+        let refresh = self.with_property_data(id, |property_data| property_data.refresh);
+        refresh(self, id);
+    }
+
     /// drop a properties underlying data, making any subsequent calls invalid by panic
     pub fn remove_entry(&self, id: PropertyId) {
         let res = {
@@ -341,4 +539,53 @@ impl PropertyTable {
     pub(crate) fn total_properties_count(&self) -> usize {
         self.property_map.borrow().len()
     }
+
+    /// Copies out the current state of all properties and their connections.
+    /// Properties that are being evaluated right now (when called from inside
+    /// a computed property or effect) are left out.
+    pub(crate) fn snapshot(&self) -> PropertyGraph {
+        let sm = self.property_map.borrow();
+        let debug_names = self.debug_names.borrow();
+        let nodes = sm
+            .iter()
+            .filter_map(|(id, entry)| {
+                let data = entry.data.as_ref()?;
+                let kind = if data.is_effect {
+                    PropertyKind::Effect
+                } else if (data.is_computed)(data) {
+                    PropertyKind::Computed
+                } else {
+                    PropertyKind::Literal
+                };
+                Some(PropertyNode {
+                    id: PropertyGraph::id_of(id),
+                    name: debug_names.get(id).cloned(),
+                    type_name: data.type_name.to_owned(),
+                    kind,
+                    ref_count: entry.ref_count,
+                    dirty: data.dirty,
+                    inbound: data
+                        .inbound
+                        .iter()
+                        .map(|&id| PropertyGraph::id_of(id))
+                        .collect(),
+                    outbound: data
+                        .outbound
+                        .iter()
+                        .map(|&id| PropertyGraph::id_of(id))
+                        .collect(),
+                })
+            })
+            .collect();
+        PropertyGraph { nodes }
+    }
+}
+
+/// Sets a flag back to false when dropped, including while unwinding
+struct ResetOnDrop<'a>(&'a Cell<bool>);
+
+impl Drop for ResetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use crate::EasingCurve;
 
 #[test]
 fn test_literal_set_get() {
@@ -85,3 +86,417 @@ fn test_recursive_props() {
     }
     assert!(PROPERTY_TABLE.with(|t| t.property_map.borrow().is_empty()));
 }
+
+#[test]
+fn test_untyped_try_get() {
+    let prop = Property::new(5.0f64);
+    let untyped = prop.untyped();
+    assert_eq!(untyped.try_get::<f64>(), Some(5.0));
+    assert_eq!(untyped.try_get::<i32>(), None);
+    prop.set(2.0);
+    assert_eq!(untyped.try_get::<f64>(), Some(2.0));
+}
+
+#[test]
+fn test_effect_runs_on_dependency_change() {
+    let prop_1 = Property::new(1);
+    let p1 = prop_1.clone();
+    let prop_2 = Property::computed(move || p1.get() * 2, &[prop_1.untyped()]);
+    let seen = Rc::new(std::cell::RefCell::new(vec![]));
+    let cp_seen = seen.clone();
+    let p2 = prop_2.clone();
+    let handle = effect(&[prop_2.untyped()], move || {
+        cp_seen.borrow_mut().push(p2.get())
+    });
+    prop_1.set(2);
+    prop_1.set(3);
+    assert_eq!(*seen.borrow(), vec![2, 4, 6]);
+    drop(handle);
+    prop_1.set(4);
+    assert_eq!(*seen.borrow(), vec![2, 4, 6]);
+}
+
+#[test]
+fn test_effect_without_reading_dependencies() {
+    let prop_1 = Property::new(1);
+    let p1 = prop_1.clone();
+    let prop_2 = Property::computed(move || p1.get() + 1, &[prop_1.untyped()]);
+    let runs = Rc::new(Cell::new(0));
+    let cp_runs = runs.clone();
+    let _handle = effect(&[prop_2.untyped()], move || cp_runs.set(cp_runs.get() + 1));
+    prop_1.set(2);
+    prop_1.set(3);
+    assert_eq!(runs.get(), 3);
+}
+
+#[test]
+fn test_on_dirty_waits_for_read() {
+    let prop_1 = Property::new(1);
+    let p1 = prop_1.clone();
+    let prop_2 = Property::computed(move || p1.get() + 1, &[prop_1.untyped()]);
+    let runs = Rc::new(Cell::new(0));
+    let cp_runs = runs.clone();
+    let _handle = on_dirty(&[prop_2.untyped()], move || cp_runs.set(cp_runs.get() + 1));
+    prop_2.get();
+    prop_1.set(2);
+    prop_1.set(3);
+    assert_eq!(runs.get(), 2);
+    assert_eq!(prop_2.get(), 4);
+    prop_1.set(4);
+    assert_eq!(runs.get(), 3);
+}
+
+#[test]
+fn test_subscribe_skips_current_value() {
+    let prop = Property::new(1);
+    let seen = Rc::new(std::cell::RefCell::new(vec![]));
+    let cp_seen = seen.clone();
+    let handle = prop.subscribe(move |v| cp_seen.borrow_mut().push(v));
+    prop.set(2);
+    prop.set(3);
+    assert_eq!(*seen.borrow(), vec![2, 3]);
+    drop(handle);
+    drop(prop);
+    assert!(PROPERTY_TABLE.with(|t| t.property_map.borrow().is_empty()));
+}
+
+#[test]
+fn test_effect_setting_other_properties() {
+    let source = Property::new(1);
+    let mirror = Property::new(0);
+    let doubled = Property::new(0);
+    let (cp_source, cp_mirror) = (source.clone(), mirror.clone());
+    let _mirror_handle = effect(&[source.untyped()], move || cp_mirror.set(cp_source.get()));
+    let (cp_mirror, cp_doubled) = (mirror.clone(), doubled.clone());
+    let _doubled_handle = effect(&[mirror.untyped()], move || {
+        cp_doubled.set(cp_mirror.get() * 2)
+    });
+    source.set(5);
+    assert_eq!(mirror.get(), 5);
+    assert_eq!(doubled.get(), 10);
+}
+
+#[test]
+fn test_effects_run_after_one_panicked() {
+    let prop = Property::new(0);
+    let cp_prop = prop.clone();
+    let _panicking = effect(&[prop.untyped()], move || {
+        if cp_prop.get() == 1 {
+            panic!("effect failed");
+        }
+    });
+    let other = Property::new(0);
+    let runs = Rc::new(Cell::new(0));
+    let cp_runs = runs.clone();
+    let _counting = effect(&[other.untyped()], move || cp_runs.set(cp_runs.get() + 1));
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| prop.set(1)));
+    assert!(res.is_err());
+    other.set(1);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn test_batch_defers_dependents_until_end() {
+    let prop_1 = Property::new(1);
+    let prop_2 = Property::new(2);
+    let (p1, p2) = (prop_1.clone(), prop_2.clone());
+    let sum = Property::computed(
+        move || p1.get() + p2.get(),
+        &[prop_1.untyped(), prop_2.untyped()],
+    );
+    let runs = Rc::new(Cell::new(0));
+    let cp_runs = runs.clone();
+    let _handle = sum.subscribe(move |_| cp_runs.set(cp_runs.get() + 1));
+
+    batch(|| {
+        prop_1.set(10);
+        batch(|| prop_2.set(20));
+        assert_eq!(sum.get(), 3);
+        prop_1.set(100);
+    });
+    assert_eq!(runs.get(), 1);
+    assert_eq!(sum.get(), 120);
+}
+
+#[test]
+fn test_batch_ends_when_it_panics() {
+    let prop = Property::new(1);
+    let cp_prop = prop.clone();
+    let doubled = Property::computed(move || cp_prop.get() * 2, &[prop.untyped()]);
+    assert_eq!(doubled.get(), 2);
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        batch(|| {
+            prop.set(2);
+            panic!("batch failed");
+        })
+    }));
+    assert!(res.is_err());
+    assert_eq!(doubled.get(), 4);
+    prop.set(3);
+    assert_eq!(doubled.get(), 6);
+}
+
+#[test]
+fn test_batch_does_not_run_effects_when_it_panics() {
+    let prop = Property::new(1);
+    let runs = Rc::new(Cell::new(0));
+    let cp_runs = runs.clone();
+    let _handle = prop.subscribe(move |_| cp_runs.set(cp_runs.get() + 1));
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        batch(|| {
+            prop.set(2);
+            panic!("batch failed");
+        })
+    }));
+    assert!(res.is_err());
+    assert_eq!(runs.get(), 0);
+    // the effect was kept queued, and runs with the next change
+    prop.set(3);
+    assert_eq!(runs.get(), 1);
+}
+
+#[test]
+fn test_update_in_place() {
+    let prop = Property::new(vec![1, 2]);
+    let cp_prop = prop.clone();
+    let len = Property::computed(move || cp_prop.get().len(), &[prop.untyped()]);
+    assert_eq!(len.get(), 2);
+    prop.update(|v| v.push(3));
+    assert_eq!(prop.get(), vec![1, 2, 3]);
+    assert_eq!(len.get(), 3);
+}
+
+#[test]
+fn test_set_within_update_is_deferred() {
+    let prop = Property::new(vec![1, 2]);
+    let cp_prop = prop.clone();
+    let len = Property::computed(move || cp_prop.get().len(), &[prop.untyped()]);
+    assert_eq!(len.get(), 2);
+    let cp_prop = prop.clone();
+    prop.update(|v| {
+        v.push(3);
+        cp_prop.set(vec![4]);
+        assert_eq!(v, &vec![1, 2, 3]);
+    });
+    assert_eq!(prop.get(), vec![4]);
+    assert_eq!(len.get(), 1);
+    // sets are no longer deferred once the update is done
+    prop.set(vec![5, 6]);
+    assert_eq!(len.get(), 2);
+}
+
+#[test]
+fn test_graph_snapshot() {
+    let prop_1 = Property::new_with_name(1, "source");
+    let p1 = prop_1.clone();
+    let doubled =
+        Property::computed_with_name(move || p1.get() * 2, &[prop_1.untyped()], "doubled");
+    let graph = PropertyGraph::snapshot();
+    let source = graph.node(&prop_1.untyped()).unwrap();
+    let computed = graph.node(&doubled.untyped()).unwrap();
+    assert_eq!(source.kind, PropertyKind::Literal);
+    assert_eq!(computed.kind, PropertyKind::Computed);
+    assert_eq!(computed.name.as_deref(), Some("doubled"));
+    assert_eq!(computed.inbound, vec![source.id]);
+    assert_eq!(source.outbound, vec![computed.id]);
+    // the clone moved into the closure counts as well
+    assert_eq!(source.ref_count, 2);
+    assert!(graph
+        .to_dot()
+        .contains(&format!("p{} -> p{};", source.id, computed.id)));
+    let json: PropertyGraph = serde_json::from_str(&graph.to_json()).unwrap();
+    assert_eq!(json, graph);
+}
+
+#[test]
+fn test_properties_leaked_by() {
+    let keep = Property::new(0);
+    let leaked = properties_leaked_by(|| {
+        let temporary = Property::new_with_name(1, "temporary");
+        let cp_temporary = temporary.clone();
+        keep.replace_with(Property::computed(
+            move || cp_temporary.get(),
+            &[temporary.untyped()],
+        ));
+    });
+    let names: Vec<_> = leaked.nodes.iter().map(|n| n.name.as_deref()).collect();
+    assert!(names.contains(&Some("temporary")));
+}
+
+/// Registers a fresh frame and millisecond clock for transitions
+fn clock() -> (Property<u64>, Property<f64>) {
+    let (frames, ms) = (Property::new(0), Property::new(0.0));
+    register_time(&frames);
+    register_time_ms(&ms);
+    (frames, ms)
+}
+
+#[test]
+fn test_ease_to_frames_and_duration() {
+    let (frames, ms) = clock();
+    let by_frames = Property::new(0.0);
+    let by_time = Property::new(0.0);
+    by_frames.ease_to(10.0, 10, EasingCurve::Linear);
+    by_time.ease_to(
+        100.0,
+        std::time::Duration::from_millis(100),
+        EasingCurve::Linear,
+    );
+    // a throttled tick: few frames, but a lot of time passed
+    ms.set(50.0);
+    frames.set(2);
+    assert_eq!(by_frames.get(), 2.0);
+    assert_eq!(by_time.get(), 50.0);
+    ms.set(200.0);
+    frames.set(20);
+    assert_eq!(by_frames.get(), 10.0);
+    assert_eq!(by_time.get(), 100.0);
+}
+
+#[test]
+fn test_ease_to_retargets_from_current_value() {
+    let (frames, _ms) = clock();
+    let prop = Property::new(0.0);
+    prop.ease_to(10.0, 10, EasingCurve::Linear);
+    frames.set(5);
+    assert_eq!(prop.get(), 5.0);
+    prop.ease_to(0.0, 5, EasingCurve::Linear);
+    frames.set(6);
+    assert_eq!(prop.get(), 4.0);
+}
+
+#[test]
+fn test_spring_retarget_keeps_velocity() {
+    let spring = || EasingCurve::Spring {
+        stiffness: 100.0,
+        damping: 10.0,
+        mass: 1.0,
+    };
+    let (frames, _ms) = clock();
+    let prop = Property::new(0.0);
+    prop.ease_to(100.0, 120, spring());
+    frames.set(6);
+    let before = prop.get();
+    prop.ease_to(-100.0, 120, spring());
+    frames.set(7);
+    let after = prop.get();
+    // still moving towards the old target right after retargeting
+    assert!(after > before, "{} > {}", after, before);
+    frames.set(200);
+    assert_eq!(prop.get(), -100.0);
+}
+
+#[test]
+fn test_easing_curves() {
+    let curves = [
+        EasingCurve::Linear,
+        EasingCurve::InOutQuad,
+        EasingCurve::InOutSine,
+        EasingCurve::InOutCubic,
+        EasingCurve::InOutQuart,
+        EasingCurve::InOutQuint,
+        EasingCurve::InOutExpo,
+        EasingCurve::InOutCirc,
+        EasingCurve::InOutElastic,
+        EasingCurve::InOutBounce,
+        EasingCurve::CubicBezier(0.25, 0.1, 0.25, 1.0),
+        EasingCurve::Steps(4),
+    ];
+    for curve in &curves {
+        assert!(curve.interpolate(&0.0, &1.0, 0.0).abs() < 1e-9);
+        assert!((curve.interpolate(&0.0, &1.0, 1.0) - 1.0).abs() < 1e-9);
+    }
+    let ease = EasingCurve::CubicBezier(0.25, 0.1, 0.25, 1.0);
+    assert!((ease.interpolate(&0.0, &1.0, 0.5) - 0.8024).abs() < 1e-3);
+    assert_eq!(EasingCurve::Steps(4).interpolate(&0.0, &1.0, 0.3), 0.25);
+    let spring = EasingCurve::Spring {
+        stiffness: 100.0,
+        damping: 20.0,
+        mass: 1.0,
+    };
+    let settled = spring.settling_time().unwrap().as_secs_f64();
+    assert!((spring.interpolate(&0.0, &1.0, settled) - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_color_and_fill_interpolation() {
+    use crate::{Color, Fill, GradientStop, LinearGradient, Size, Stroke};
+
+    let close = |a: [f64; 4], b: [f64; 4]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3);
+    let red = Color::RED;
+    let blue = Color::BLUE;
+    assert!(close(
+        red.interpolate(&blue, 0.0).to_rgba_0_1(),
+        red.to_rgba_0_1()
+    ));
+    assert!(close(
+        red.interpolate(&blue, 1.0).to_rgba_0_1(),
+        blue.to_rgba_0_1()
+    ));
+    // fading in from transparent keeps the hue of the visible color
+    let half = Color::TRANSPARENT.interpolate(&red, 0.5).to_rgba_0_1();
+    assert!(close(
+        half,
+        [
+            red.to_rgba_0_1()[0],
+            red.to_rgba_0_1()[1],
+            red.to_rgba_0_1()[2],
+            0.5
+        ]
+    ));
+
+    let stops = |a: Color, b: Color| {
+        vec![
+            GradientStop::get(a, Size::Percent(0.into())),
+            GradientStop::get(b, Size::Percent(100.into())),
+        ]
+    };
+    let start = (Size::Percent(0.into()), Size::Percent(0.into()));
+    let gradient = Fill::linearGradient(
+        start,
+        (Size::Percent(100.into()), Size::Percent(0.into())),
+        stops(Color::WHITE, Color::BLACK),
+    );
+    let Fill::LinearGradient(mid) = Fill::Solid(Color::WHITE).interpolate(&gradient, 0.5) else {
+        panic!("a solid fill should blend into a gradient");
+    };
+    assert!(close(
+        mid.stops[0].color.to_rgba_0_1(),
+        [1.0, 1.0, 1.0, 1.0]
+    ));
+    assert!(mid.stops[1].color.to_rgba_0_1()[0] < 1.0);
+    assert!(mid.stops[1].color.to_rgba_0_1()[0] > 0.0);
+    let mismatched = Fill::linearGradient(
+        start,
+        start,
+        vec![GradientStop::get(Color::RED, Size::Percent(0.into()))],
+    );
+    assert!(matches!(
+        gradient.interpolate(&mismatched, 0.4),
+        Fill::LinearGradient(LinearGradient { ref stops, .. }) if stops.len() == 2
+    ));
+
+    let thin = Stroke::default();
+    let thick = Stroke {
+        color: Property::new(Color::BLACK),
+        width: Property::new(Size::Pixels(10.into())),
+    };
+    let stroke = thin.interpolate(&thick, 0.5);
+    assert_eq!(stroke.width.get().get_pixels(0.0), 5.0);
+}
+
+#[test]
+fn test_transform_interpolation() {
+    use crate::{Rotation, Size, Transform2D};
+
+    let from = Transform2D::translate(Size::Pixels(20.into()), Size::Pixels(0.into()));
+    let to = Transform2D::rotate(Rotation::Degrees(90.into()));
+    let mid = from.interpolate(&to, 0.5);
+    assert_eq!(mid.rotate.unwrap().get_as_degrees(), 45.0);
+    // translate only exists on the first transform, so it eases back to zero
+    assert_eq!(mid.translate.unwrap()[0].get_pixels(0.0), 10.0);
+    assert!(mid.scale.is_none());
+}
@@ -23,7 +23,9 @@ impl Clone for UntypedProperty {
 
 impl Drop for UntypedProperty {
     fn drop(&mut self) {
-        PROPERTY_TABLE.with(|t| {
+        // The table may already be tearing down (thread exit), in which case
+        // it drops its remaining entries itself: nothing left to do
+        let _ = PROPERTY_TABLE.try_with(|t| {
             let ref_count = t.decrease_ref_count(self.id);
             if ref_count == 0 {
                 t.remove_entry(self.id);
@@ -47,4 +49,11 @@ impl UntypedProperty {
     pub fn get_id(&self) -> PropertyId {
         self.id
     }
+
+    /// Gets the current value of this property if it stores a T, and None
+    /// otherwise. Useful for reading properties whose type isn't statically
+    /// known, e.g. symbols resolved at runtime.
+    pub fn try_get<T: PropertyValue>(&self) -> Option<T> {
+        PROPERTY_TABLE.with(|t| t.is_of_type::<T>(self.id).then(|| t.get_value(self.id)))
+    }
 }
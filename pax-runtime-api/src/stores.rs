@@ -0,0 +1,97 @@
+//! Stores: values shared with the nodes of an app by type instead of through
+//! properties, e.g. a theme, the logged in user or the context of a `<Grid>`.
+//!
+//! A store can be pushed on the stack of a component (visible to the nodes it
+//! expands into), provided for a subtree, or set app-wide, see `NodeContext`.
+//! App-wide stores belong to the engine running the app. Make the fields of a
+//! store `Property`s for the nodes reading them to update when they change.
+
+use std::any::{type_name, Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// Marker trait that needs to be implemented for a struct for insertion and
+/// deletion in a store
+/// NOTE: Stored objects need to be UNIQUE for any given stack. Do not insert
+/// values with types that could potentially be used in another use case,
+/// instead create a local type only used for a single purpose
+pub trait Store: 'static {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreError {
+    /// No store of this type is visible from where it was looked up
+    Missing { store: &'static str },
+    /// The store is already being accessed further up the call stack
+    InUse { store: &'static str },
+}
+
+impl StoreError {
+    fn missing<T: Store>() -> Self {
+        StoreError::Missing {
+            store: type_name::<T>(),
+        }
+    }
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::Missing { store } => write!(f, "couldn't find store {}", store),
+            StoreError::InUse { store } => write!(f, "store {} is already in use", store),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<StoreError> for String {
+    fn from(error: StoreError) -> Self {
+        error.to_string()
+    }
+}
+
+/// At most one store of each type
+#[derive(Default)]
+pub struct StoreMap {
+    stores: RefCell<HashMap<TypeId, Rc<RefCell<dyn Any>>>>,
+}
+
+impl StoreMap {
+    /// Inserts `store`, replacing any store of the same type
+    pub fn insert<T: Store>(&self, store: T) {
+        self.stores
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), Rc::new(RefCell::new(store)));
+    }
+
+    pub fn remove<T: Store>(&self) -> bool {
+        self.stores
+            .borrow_mut()
+            .remove(&TypeId::of::<T>())
+            .is_some()
+    }
+
+    pub fn contains<T: Store>(&self) -> bool {
+        self.stores.borrow().contains_key(&TypeId::of::<T>())
+    }
+
+    /// Calls `f` with the store of type `T`. Other stores, of this map or
+    /// others, can be accessed from within `f`.
+    pub fn with<T: Store, V>(&self, f: impl FnOnce(&mut T) -> V) -> Result<V, StoreError> {
+        let store = self
+            .stores
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .ok_or_else(StoreError::missing::<T>)?;
+        let mut store = store.try_borrow_mut().map_err(|_| StoreError::InUse {
+            store: type_name::<T>(),
+        })?;
+        let store = store
+            .downcast_mut()
+            .expect("stores are keyed by their type id");
+        Ok(f(store))
+    }
+}
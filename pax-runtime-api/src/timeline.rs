@@ -0,0 +1,349 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::properties::{effect, time_ms, EffectHandle, Property, PropertyValue};
+use crate::{EasingCurve, Interpolatable};
+
+/// A value a track passes through at `time` into its timeline. `curve` eases
+/// the segment arriving at this keyframe (the one from the previous keyframe).
+pub struct Keyframe<T> {
+    pub time: Duration,
+    pub value: T,
+    pub curve: EasingCurve,
+}
+
+impl<T> Keyframe<T> {
+    pub fn new(time: Duration, value: T) -> Self {
+        Self {
+            time,
+            value,
+            curve: EasingCurve::Linear,
+        }
+    }
+
+    pub fn with_curve(mut self, curve: EasingCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+}
+
+/// Sequences keyframed animations of several properties, driven by the
+/// millisecond clock supplied by the chassis. `Timeline` is a handle: clones
+/// control the same playback.
+///
+/// ```ignore
+/// let timeline = Timeline::new();
+/// timeline.track(&self.opacity, vec![
+///     Keyframe::new(Duration::ZERO, 0.0),
+///     Keyframe::new(Duration::from_millis(300), 1.0).with_curve(EasingCurve::OutQuad),
+/// ]);
+/// timeline.on_complete(|| log::info!("faded in"));
+/// timeline.play();
+/// ```
+#[derive(Clone)]
+pub struct Timeline {
+    state: Rc<TimelineState>,
+}
+
+struct TimelineState {
+    clock: Property<f64>,
+    /// Milliseconds into the timeline
+    playhead: Property<f64>,
+    /// End of the last keyframe of all tracks, in milliseconds
+    duration: Cell<f64>,
+    rate: Cell<f64>,
+    looping: Cell<bool>,
+    playing: Cell<bool>,
+    /// Clock time and playhead position when playback last (re)started
+    anchor: Cell<(f64, f64)>,
+    on_complete: RefCell<Vec<Rc<dyn Fn()>>>,
+    /// Moves the playhead whenever the clock changes
+    _driver: RefCell<Option<EffectHandle>>,
+}
+
+impl TimelineState {
+    fn reanchor(&self) {
+        self.anchor.set((self.clock.get(), self.playhead.get()));
+    }
+
+    fn advance(&self) {
+        if !self.playing.get() {
+            return;
+        }
+        let (anchor_time, anchor_position) = self.anchor.get();
+        let rate = self.rate.get();
+        let position = anchor_position + (self.clock.get() - anchor_time) * rate;
+        let duration = self.duration.get();
+        if self.looping.get() && duration > 0.0 {
+            self.playhead.set(position.rem_euclid(duration));
+            return;
+        }
+        let (finished, end) = if rate < 0.0 {
+            (position <= 0.0, 0.0)
+        } else {
+            (position >= duration, duration)
+        };
+        if !finished {
+            self.playhead.set(position);
+            return;
+        }
+        self.playhead.set(end);
+        self.playing.set(false);
+        // callbacks are free to restart or add callbacks to this timeline
+        let callbacks = self.on_complete.borrow().clone();
+        for callback in callbacks {
+            callback();
+        }
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timeline {
+    /// A paused, empty timeline. Needs the clock to be registered (see
+    /// `register_time_ms`), which the engine does on startup.
+    pub fn new() -> Self {
+        let state = Rc::new(TimelineState {
+            clock: time_ms(),
+            playhead: Property::new(0.0),
+            duration: Cell::new(0.0),
+            rate: Cell::new(1.0),
+            looping: Cell::new(false),
+            playing: Cell::new(false),
+            anchor: Cell::new((0.0, 0.0)),
+            on_complete: RefCell::new(Vec::new()),
+            _driver: RefCell::new(None),
+        });
+        let weak = Rc::downgrade(&state);
+        let driver = effect(&[state.clock.untyped()], move || {
+            if let Some(state) = weak.upgrade() {
+                state.advance();
+            }
+        });
+        *state._driver.borrow_mut() = Some(driver);
+        Self { state }
+    }
+
+    /// Binds `property` to the keyframes: from now on it follows the
+    /// playhead, until it is set or bound to something else. Before the first
+    /// and after the last keyframe, the property holds their value. The
+    /// timeline lasts until the last keyframe of its longest track.
+    pub fn track<T: PropertyValue>(&self, property: &Property<T>, mut keyframes: Vec<Keyframe<T>>) {
+        keyframes.sort_by_key(|keyframe| keyframe.time);
+        let Some(last) = keyframes.last() else {
+            return;
+        };
+        let end = last.time.as_secs_f64() * 1000.0;
+        if end > self.state.duration.get() {
+            self.state.duration.set(end);
+        }
+        let playhead = self.state.playhead.clone();
+        let deps = [playhead.untyped()];
+        property.replace_with(Property::computed(
+            move || value_at(&keyframes, playhead.get()),
+            &deps,
+        ));
+    }
+
+    /// Starts (or resumes) playback from the playhead. A timeline that isn't
+    /// looping and already reached its end starts over.
+    pub fn play(&self) {
+        let state = &self.state;
+        if state.playing.get() {
+            return;
+        }
+        if !state.looping.get() {
+            let position = state.playhead.get();
+            let duration = state.duration.get();
+            if state.rate.get() < 0.0 && position <= 0.0 {
+                state.playhead.set(duration);
+            } else if state.rate.get() >= 0.0 && position >= duration {
+                state.playhead.set(0.0);
+            }
+        }
+        state.reanchor();
+        state.playing.set(true);
+    }
+
+    pub fn pause(&self) {
+        self.state.playing.set(false);
+    }
+
+    /// Moves the playhead, clamped to the timeline, keeping the timeline
+    /// playing or paused
+    pub fn seek(&self, position: Duration) {
+        let position = (position.as_secs_f64() * 1000.0).min(self.state.duration.get());
+        self.state.playhead.set(position);
+        self.state.reanchor();
+    }
+
+    /// Plays in the other direction from where the playhead is
+    pub fn reverse(&self) {
+        self.set_playback_rate(-self.state.rate.get());
+    }
+
+    /// How fast the playhead moves compared to the clock. Negative rates play
+    /// backwards, finishing at the start of the timeline.
+    pub fn set_playback_rate(&self, rate: f64) {
+        self.state.reanchor();
+        self.state.rate.set(rate);
+    }
+
+    pub fn playback_rate(&self) -> f64 {
+        self.state.rate.get()
+    }
+
+    /// A looping timeline wraps around instead of finishing, and never calls
+    /// its completion callbacks
+    pub fn set_looping(&self, looping: bool) {
+        self.state.reanchor();
+        self.state.looping.set(looping);
+    }
+
+    /// Calls `f` every time playback reaches the end of the timeline (or the
+    /// start, when playing backwards). Capturing a clone of this timeline in
+    /// `f` keeps it alive for good.
+    pub fn on_complete(&self, f: impl Fn() + 'static) {
+        self.state.on_complete.borrow_mut().push(Rc::new(f));
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state.playing.get()
+    }
+
+    pub fn position(&self) -> Duration {
+        Duration::from_secs_f64(self.state.playhead.get() / 1000.0)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.state.duration.get() / 1000.0)
+    }
+
+    /// The position of the playhead in milliseconds, e.g. to drive properties
+    /// that aren't keyframed from an expression
+    pub fn playhead(&self) -> Property<f64> {
+        self.state.playhead.clone()
+    }
+}
+
+fn value_at<T: Interpolatable>(keyframes: &[Keyframe<T>], position_ms: f64) -> T {
+    let ms = |keyframe: &Keyframe<T>| keyframe.time.as_secs_f64() * 1000.0;
+    let next = keyframes.partition_point(|keyframe| ms(keyframe) <= position_ms);
+    if next == 0 {
+        return keyframes[0].value.clone();
+    }
+    let Some(to) = keyframes.get(next) else {
+        return keyframes[next - 1].value.clone();
+    };
+    let from = &keyframes[next - 1];
+    let span = ms(to) - ms(from);
+    let t = (position_ms - ms(from)) / span;
+    to.curve
+        .interpolate_over(&from.value, &to.value, t, span / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::properties::register_time_ms;
+
+    fn clock() -> Property<f64> {
+        let ms = Property::new(0.0);
+        register_time_ms(&ms);
+        ms
+    }
+
+    fn fade(timeline: &Timeline) -> Property<f64> {
+        let opacity = Property::new(0.5);
+        timeline.track(
+            &opacity,
+            vec![
+                Keyframe::new(Duration::ZERO, 0.0),
+                Keyframe::new(Duration::from_millis(100), 1.0),
+                Keyframe::new(Duration::from_millis(200), 0.0).with_curve(EasingCurve::Steps(2)),
+            ],
+        );
+        opacity
+    }
+
+    #[test]
+    fn test_timeline_plays_tracks() {
+        let ms = clock();
+        let timeline = Timeline::new();
+        let opacity = fade(&timeline);
+        assert_eq!(timeline.duration(), Duration::from_millis(200));
+        assert_eq!(opacity.get(), 0.0);
+
+        // paused timelines don't move
+        ms.set(50.0);
+        assert_eq!(opacity.get(), 0.0);
+
+        timeline.play();
+        ms.set(100.0);
+        assert_eq!(opacity.get(), 0.5);
+        ms.set(150.0);
+        assert_eq!(opacity.get(), 1.0);
+        // the last segment eases with the steps of its end keyframe
+        ms.set(210.0);
+        assert_eq!(opacity.get(), 0.5);
+
+        timeline.pause();
+        ms.set(1000.0);
+        assert_eq!(opacity.get(), 0.5);
+        assert_eq!(timeline.playhead().get(), 160.0);
+    }
+
+    #[test]
+    fn test_timeline_seek_reverse_and_rate() {
+        let ms = clock();
+        let timeline = Timeline::new();
+        let opacity = fade(&timeline);
+        timeline.seek(Duration::from_millis(50));
+        assert_eq!(opacity.get(), 0.5);
+
+        timeline.set_playback_rate(2.0);
+        timeline.play();
+        ms.set(25.0);
+        assert_eq!(opacity.get(), 1.0);
+
+        timeline.reverse();
+        ms.set(50.0);
+        assert_eq!(timeline.playhead().get(), 50.0);
+        ms.set(100.0);
+        assert_eq!(timeline.playhead().get(), 0.0);
+        assert!(!timeline.is_playing());
+    }
+
+    #[test]
+    fn test_timeline_completion_and_looping() {
+        let ms = clock();
+        let timeline = Timeline::new();
+        let _opacity = fade(&timeline);
+        let completed = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&completed);
+        timeline.on_complete(move || counter.set(counter.get() + 1));
+
+        timeline.play();
+        ms.set(150.0);
+        assert_eq!(completed.get(), 0);
+        ms.set(250.0);
+        assert_eq!(completed.get(), 1);
+        assert_eq!(timeline.playhead().get(), 200.0);
+
+        // playing again starts over
+        timeline.play();
+        ms.set(300.0);
+        assert_eq!(timeline.playhead().get(), 50.0);
+
+        timeline.set_looping(true);
+        ms.set(500.0);
+        assert_eq!(timeline.playhead().get(), 50.0);
+        assert!(timeline.is_playing());
+        assert_eq!(completed.get(), 1);
+    }
+}
@@ -1,16 +1,20 @@
+use std::any::TypeId;
+use std::collections::HashMap;
 use std::rc::{Rc, Weak};
 
 use_RefCell!();
+use crate::api::math::Point2;
 use crate::{
-    node_interface::NodeInterface, ExpandedNode, RuntimeContext, RuntimePropertiesStackFrame,
+    node_interface::NodeInterface, ExpandedNode, ExpandedNodeIdentifier, GridPlacement,
+    IntrinsicSize, RuntimeContext, RuntimePropertiesStackFrame,
 };
+use pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_runtime_api::pax_value::ToFromPaxAny;
+use pax_runtime_api::properties::{PropertyValue, UntypedProperty};
 pub use pax_runtime_api::*;
 
 #[cfg(feature = "designtime")]
-use {
-    crate::api::math::Point2, crate::HandlerLocation, pax_designtime::DesigntimeManager,
-    pax_manifest::UniqueTemplateNodeIdentifier,
-};
+use {crate::HandlerLocation, pax_designtime::DesigntimeManager};
 
 #[derive(Clone)]
 pub struct NodeContext {
@@ -20,8 +24,12 @@ pub struct NodeContext {
     pub(crate) local_stack_frame: Rc<RuntimePropertiesStackFrame>,
     /// Registered handlers on the instance node
     pub(crate) component_origin: Weak<ExpandedNode>,
+    /// The node this context was created for
+    pub(crate) node_id: ExpandedNodeIdentifier,
     /// The current global engine tick count
     pub frames_elapsed: Property<u64>,
+    /// Milliseconds elapsed since the first tick, per the chassis clock
+    pub time_elapsed_ms: Property<f64>,
     /// The bounds of this element's immediate container (parent) in px
     pub bounds_parent: Property<(f64, f64)>,
     /// The bounds of this element in px
@@ -32,51 +40,264 @@ pub struct NodeContext {
     pub os: OS,
     /// The number of slot children provided to this component template
     pub slot_children_count: Property<usize>,
+    /// The sizes requested by each slot child provided to this component template, in slot order
+    pub slot_children_sizes: Property<Vec<IntrinsicSize>>,
+    /// The grid placements of each slot child provided to this component template, in slot order
+    pub slot_children_grid_placements: Property<Vec<GridPlacement>>,
     /// Borrow of the RuntimeContext, used at least for exposing raycasting to userland
     pub(crate) runtime_context: Rc<RuntimeContext>,
+    /// Effects owned by this node, removed when it unmounts
+    pub(crate) effects: Rc<RefCell<Vec<EffectHandle>>>,
+    /// Timelines declared in this node's `@settings`, if it is a component
+    pub(crate) timelines: Rc<RefCell<HashMap<String, Timeline>>>,
 
     #[cfg(feature = "designtime")]
     pub designtime: Rc<RefCell<DesigntimeManager>>,
 }
 
 impl NodeContext {
+    /// Pushes `store` on the stack of the component this node is part of,
+    /// making it visible to the other nodes of its template and to their slot
+    /// children
     pub fn push_local_store<T: Store>(&self, store: T) {
         self.local_stack_frame.insert_stack_local_store(store);
     }
 
-    pub fn peek_local_store<T: Store, V>(&self, f: impl FnOnce(&mut T) -> V) -> Result<V, String> {
-        self.local_stack_frame.peek_stack_local_store(f)
+    /// Makes `store` visible to this node and the nodes below it in the
+    /// template (including its slot children), overriding stores of the same
+    /// type from further up, e.g. a different theme for one part of the app.
+    /// Fails if this node isn't mounted.
+    pub fn provide_store<T: Store>(&self, store: T) -> Result<(), String> {
+        let node = self
+            .runtime_context
+            .get_expanded_node_by_eid(self.node_id)
+            .ok_or_else(|| "can't provide a store from a node that isn't mounted".to_owned())?;
+        node.provided_stores.insert(store);
+        Ok(())
+    }
+
+    /// Calls `f` with the closest store of type `T`, going up the template
+    /// from this node: stores provided by a node (see
+    /// [`NodeContext::provide_store`]) and stores pushed on the stack of a
+    /// component (see [`NodeContext::push_local_store`]) are found in the
+    /// order they are passed, and the app-wide store (see
+    /// [`NodeContext::set_app_store`]) comes last.
+    pub fn peek_local_store<T: Store, V>(
+        &self,
+        f: impl FnOnce(&mut T) -> V,
+    ) -> Result<V, StoreError> {
+        let mut node = self.runtime_context.get_expanded_node_by_eid(self.node_id);
+        let mut frame = Some(Rc::clone(&self.local_stack_frame));
+        while node.is_some() || frame.is_some() {
+            if let Some(provider) = node.as_ref().filter(|n| n.provided_stores.contains::<T>()) {
+                return provider.provided_stores.with(f);
+            }
+            let parent = node.and_then(|node| node.template_parent.upgrade());
+            // the frames pushed between this node and its parent, e.g. by a
+            // component for its template, or by a repeat for each item
+            while let Some(current) = frame.take() {
+                if parent
+                    .as_ref()
+                    .is_some_and(|parent| Rc::ptr_eq(&parent.stack, &current))
+                {
+                    frame = Some(current);
+                    break;
+                }
+                if current.own_local_stores().contains::<T>() {
+                    return current.own_local_stores().with(f);
+                }
+                frame = current.pop();
+            }
+            node = parent;
+        }
+        self.peek_app_store(f)
+    }
+
+    /// A clone of the closest store of type `T`, see
+    /// [`NodeContext::peek_local_store`]. `Property` fields of the clone are
+    /// shared with the store, so computed properties can depend on them.
+    pub fn get_store<T: Store + Clone>(&self) -> Result<T, StoreError> {
+        self.peek_local_store(|store: &mut T| store.clone())
+    }
+
+    /// Sets the app-wide store of type `T`, visible to every node that doesn't
+    /// have a closer store of the same type, e.g. from the `@mount` of the
+    /// root component. See `RuntimeContext::app_stores` to set it before the
+    /// first tick.
+    pub fn set_app_store<T: Store>(&self, store: T) {
+        self.runtime_context.app_stores().insert(store);
+    }
+
+    /// Removes the app-wide store of type `T`, returning whether there was one
+    pub fn remove_app_store<T: Store>(&self) -> bool {
+        self.runtime_context.app_stores().remove::<T>()
+    }
+
+    /// Calls `f` with the app-wide store of type `T`
+    pub fn peek_app_store<T: Store, V>(
+        &self,
+        f: impl FnOnce(&mut T) -> V,
+    ) -> Result<V, StoreError> {
+        self.runtime_context.app_stores().with(f)
+    }
+
+    /// Runs `f` once now, and again every time one of `dependencies` changes,
+    /// until this node is unmounted. See [`effect`].
+    pub fn effect(&self, dependencies: &[UntypedProperty], f: impl Fn() + 'static) {
+        let handle = effect(dependencies, f);
+        borrow_mut!(self.effects).push(handle);
     }
 
+    /// Calls `f` with the new value every time `property` changes, until
+    /// this node is unmounted. See [`Property::subscribe`].
+    pub fn subscribe<T: PropertyValue>(&self, property: &Property<T>, f: impl Fn(T) + 'static) {
+        let handle = property.subscribe(f);
+        borrow_mut!(self.effects).push(handle);
+    }
+
+    /// The timeline declared as `@timeline name` in the `@settings` block of
+    /// this component, or of the component whose template this node is part of
+    pub fn timeline(&self, name: &str) -> Option<Timeline> {
+        if let Some(timeline) = borrow!(self.timelines).get(name) {
+            return Some(timeline.clone());
+        }
+        let component_origin = self.component_origin.upgrade()?;
+        let timelines = borrow!(component_origin.timelines);
+        timelines.get(name).cloned()
+    }
+
+    /// Moves keyboard focus to this node, dispatching `@blur` on the node that
+    /// had it and `@focus` on this one. Fails if this node isn't focusable,
+    /// see `CommonProperties::focusable`.
+    pub fn request_focus(&self) -> Result<(), String> {
+        let node = self
+            .runtime_context
+            .get_expanded_node_by_eid(self.node_id)
+            .ok_or_else(|| "can't focus a node that isn't mounted".to_owned())?;
+        if !node.is_focusable() {
+            return Err("can't focus a node that isn't focusable".to_owned());
+        }
+        self.runtime_context.focus(Some(node));
+        Ok(())
+    }
+
+    /// Whether this node has keyboard focus
+    pub fn is_focused(&self) -> bool {
+        self.runtime_context
+            .focused_node()
+            .is_some_and(|node| node.id == self.node_id)
+    }
+
+    /// Removes keyboard focus from this node if it has it, dispatching `@blur`
+    /// on it. Focus then stays nowhere until a node is pressed, tabbed to or
+    /// requests it.
+    pub fn blur(&self) {
+        if self.is_focused() {
+            self.runtime_context.focus(None);
+        }
+    }
+
+    /// Routes mouse move and up events to this node until the mouse button is
+    /// released, even when the cursor leaves it. Fails if this node isn't mounted.
+    pub fn capture_pointer(&self) -> Result<(), String> {
+        let node = self
+            .runtime_context
+            .get_expanded_node_by_eid(self.node_id)
+            .ok_or_else(|| "can't capture the pointer for a node that isn't mounted".to_owned())?;
+        self.runtime_context.set_pointer_capture(Some(&node));
+        Ok(())
+    }
+
+    /// Releases the pointer if this node captured it
+    pub fn release_pointer(&self) {
+        if self.has_pointer_capture() {
+            self.runtime_context.set_pointer_capture(None);
+        }
+    }
+
+    /// Whether this node captured the pointer
+    pub fn has_pointer_capture(&self) -> bool {
+        self.runtime_context
+            .pointer_capture()
+            .is_some_and(|node| node.id == self.node_id)
+    }
+
+    /// Queues the custom event `identifier` for the handlers bound to it, once the
+    /// current tick is done. Events without bound handlers are dropped. Fails for
+    /// events the component doesn't declare, and for events declared with a
+    /// payload, which need [`NodeContext::dispatch_event_with`].
     pub fn dispatch_event(&self, identifier: &'static str) -> Result<(), String> {
+        let (component_origin, payload_type) = self.custom_event_origin(identifier)?;
+        if payload_type.is_some() {
+            return Err(format!(
+                "custom event \"{}\" carries a payload, dispatch it with dispatch_event_with",
+                identifier
+            ));
+        }
+        self.runtime_context
+            .queue_custom_event(component_origin, identifier, None);
+        Ok(())
+    }
+
+    /// Like [`NodeContext::dispatch_event`], but passes `payload` to the handlers
+    /// bound to the event. Fails unless `T` is the type declared for the event
+    /// with `#[events(identifier: T)]` on the dispatching component.
+    pub fn dispatch_event_with<T: ToFromPaxAny + Clone>(
+        &self,
+        identifier: &'static str,
+        payload: T,
+    ) -> Result<(), String> {
+        let (component_origin, payload_type) = self.custom_event_origin(identifier)?;
+        if payload_type != Some(TypeId::of::<T>()) {
+            return Err(format!(
+                "custom event \"{}\" doesn't carry a {} payload",
+                identifier,
+                std::any::type_name::<T>()
+            ));
+        }
+        self.runtime_context.queue_custom_event(
+            component_origin,
+            identifier,
+            Some(Rc::new(move || payload.clone().to_pax_any())),
+        );
+        Ok(())
+    }
+
+    /// The component dispatching the custom event `identifier` from this node,
+    /// and the payload type it declares for the event
+    fn custom_event_origin(
+        &self,
+        identifier: &str,
+    ) -> Result<(Rc<ExpandedNode>, Option<TypeId>), String> {
         let component_origin = self
             .component_origin
             .upgrade()
             .ok_or_else(|| "can't dispatch from root component".to_owned())?;
 
         // Check that this is a valid custom event to trigger
-        {
+        let payload_type = {
             let component_origin_instance = borrow!(component_origin.instance_node);
             let registry = component_origin_instance
                 .base()
                 .handler_registry
                 .as_ref()
                 .ok_or_else(|| "no registry present".to_owned())?;
-            borrow!(registry).handlers.get(identifier).ok_or_else(|| {
-                format!("no registered handler with name \"{}\" exists", identifier)
-            })?;
-        }
+            let registry = borrow!(registry);
+            *registry.custom_events.get(identifier).ok_or_else(|| {
+                format!("no custom event with name \"{}\" is declared", identifier)
+            })?
+        };
+        Ok((component_origin, payload_type))
+    }
 
-        // ok now we know it's a valid thing to dispatch, queue it for end of tick
+    /// The node this context was created for, if it's still mounted
+    pub fn node(&self) -> Option<NodeInterface> {
         self.runtime_context
-            .queue_custom_event(Rc::clone(&component_origin), identifier);
-
-        Ok(())
+            .get_expanded_node_by_eid(self.node_id)
+            .map(Into::into)
     }
-}
 
-#[cfg(feature = "designtime")]
-impl NodeContext {
+    /// All nodes under `point`, topmost first
     pub fn raycast(&self, point: Point2<Window>) -> Vec<NodeInterface> {
         let expanded_nodes = self
             .runtime_context
@@ -87,6 +308,7 @@ impl NodeContext {
             .collect()
     }
 
+    /// All mounted instances of the template node `uni`
     pub fn get_nodes_by_global_id(&self, uni: UniqueTemplateNodeIdentifier) -> Vec<NodeInterface> {
         let expanded_nodes = self.runtime_context.get_expanded_nodes_by_global_ids(&uni);
         expanded_nodes
@@ -95,6 +317,7 @@ impl NodeContext {
             .collect()
     }
 
+    /// All mounted nodes whose `id` common property equals `id`
     pub fn get_nodes_by_id(&self, id: &str) -> Vec<NodeInterface> {
         let expanded_nodes = self.runtime_context.get_expanded_nodes_by_id(id);
         expanded_nodes
@@ -102,4 +325,17 @@ impl NodeContext {
             .map(Into::<NodeInterface>::into)
             .collect()
     }
+
+    /// All mounted nodes whose properties are of type `T` (e.g. every
+    /// `Rectangle`, or every instance of a component), in tree order
+    pub fn get_nodes_by_type<T: ToFromPaxAny>(&self) -> Vec<NodeInterface> {
+        let Some(root) = self.runtime_context.root_node() else {
+            return vec![];
+        };
+        let root = NodeInterface::from(root);
+        std::iter::once(root.clone())
+            .chain(root.descendants())
+            .filter(NodeInterface::is_of_type::<T>)
+            .collect()
+    }
 }
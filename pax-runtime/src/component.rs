@@ -4,7 +4,7 @@ use std::rc::Rc;
 use pax_runtime_api::{borrow, borrow_mut, use_RefCell, Property};
 
 use_RefCell!();
-use crate::api::{Layer, Timeline};
+use crate::api::Layer;
 use crate::{
     BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstanceNodePtrList,
     InstantiationArgs, RuntimeContext,
@@ -18,7 +18,6 @@ use crate::{
 /// properties attached to each of Repeat's virtual nodes.
 pub struct ComponentInstance {
     pub template: InstanceNodePtrList,
-    pub timeline: Option<Rc<RefCell<Timeline>>>,
     base: BaseInstance,
 }
 
@@ -40,11 +39,7 @@ impl InstanceNode for ComponentInstance {
                 is_component: true,
             },
         );
-        Rc::new(ComponentInstance {
-            base,
-            template,
-            timeline: None,
-        })
+        Rc::new(ComponentInstance { base, template })
     }
 
     fn handle_mount(
@@ -84,15 +79,6 @@ impl InstanceNode for ComponentInstance {
         }
     }
 
-    fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, context: &Rc<RuntimeContext>) {
-        if let Some(slot_children) = borrow_mut!(expanded_node.expanded_slot_children).as_ref() {
-            for slot_child in slot_children {
-                slot_child.recurse_update(context);
-            }
-        }
-        expanded_node.compute_flattened_slot_children();
-    }
-
     fn resolve_debug(
         &self,
         f: &mut std::fmt::Formatter,
@@ -13,6 +13,18 @@ pub const TEXT_INPUT_HANDLERS: &str = "text_input";
 pub const TEXTBOX_INPUT_HANDLERS: &str = "textbox_input";
 pub const CLICK_HANDLERS: &str = "click";
 pub const DROP_HANDLERS: &str = "drop";
+pub const DRAG_START_HANDLERS: &str = "drag_start";
+pub const DRAG_HANDLERS: &str = "drag";
+pub const DRAG_END_HANDLERS: &str = "drag_end";
+pub const DRAG_OVER_HANDLERS: &str = "drag_over";
+pub const DROP_ITEM_HANDLERS: &str = "drop_item";
+pub const TAP_HANDLERS: &str = "tap";
+pub const DOUBLE_TAP_HANDLERS: &str = "double_tap";
+pub const LONG_PRESS_HANDLERS: &str = "long_press";
+pub const PAN_HANDLERS: &str = "pan";
+pub const SWIPE_HANDLERS: &str = "swipe";
+pub const PINCH_HANDLERS: &str = "pinch";
+pub const ROTATE_HANDLERS: &str = "rotate";
 pub const MOUSE_DOWN_HANDLERS: &str = "mouse_down";
 pub const MOUSE_UP_HANDLERS: &str = "mouse_up";
 pub const MOUSE_MOVE_HANDLERS: &str = "mouse_move";
@@ -21,5 +33,11 @@ pub const MOUSE_OUT_HANDLERS: &str = "mouse_out";
 pub const DOUBLE_CLICK_HANDLERS: &str = "double_click";
 pub const CONTEXT_MENU_HANDLERS: &str = "context_menu";
 pub const WHEEL_HANDLERS: &str = "wheel";
+pub const TICK_HANDLERS: &str = "tick";
 pub const PRE_RENDER_HANDLERS: &str = "pre_render";
 pub const MOUNT_HANDLERS: &str = "mount";
+pub const UNMOUNT_HANDLERS: &str = "unmount";
+pub const FOCUS_HANDLERS: &str = "focus";
+pub const BLUR_HANDLERS: &str = "blur";
+/// Appended to an event's handler key for handlers bound in the capture phase, e.g. `@click.capture`
+pub const CAPTURE_HANDLERS_SUFFIX: &str = ".capture";
@@ -2,34 +2,46 @@ use crate::api::TextInput;
 use crate::node_interface::NodeLocal;
 use pax_runtime_api::pax_value::{ImplToFromPaxAny, PaxAny, ToFromPaxAny};
 use pax_runtime_api::properties::UntypedProperty;
-use pax_runtime_api::{borrow, borrow_mut, use_RefCell, Interpolatable, Percent, Property};
+use pax_runtime_api::stores::StoreMap;
+use pax_runtime_api::{
+    borrow, borrow_mut, on_dirty, use_RefCell, EffectHandle, Interpolatable, Percent, Property,
+    Timeline,
+};
 
 use crate::api::math::Point2;
 use crate::constants::{
-    BUTTON_CLICK_HANDLERS, CHECKBOX_CHANGE_HANDLERS, CLAP_HANDLERS, CLICK_HANDLERS,
-    CONTEXT_MENU_HANDLERS, DOUBLE_CLICK_HANDLERS, DROP_HANDLERS, KEY_DOWN_HANDLERS,
-    KEY_PRESS_HANDLERS, KEY_UP_HANDLERS, MOUSE_DOWN_HANDLERS, MOUSE_MOVE_HANDLERS,
-    MOUSE_OUT_HANDLERS, MOUSE_OVER_HANDLERS, MOUSE_UP_HANDLERS, SCROLL_HANDLERS,
-    TEXTBOX_CHANGE_HANDLERS, TEXTBOX_INPUT_HANDLERS, TEXT_INPUT_HANDLERS, TOUCH_END_HANDLERS,
-    TOUCH_MOVE_HANDLERS, TOUCH_START_HANDLERS, WHEEL_HANDLERS,
+    BUTTON_CLICK_HANDLERS, CAPTURE_HANDLERS_SUFFIX, CHECKBOX_CHANGE_HANDLERS, CLAP_HANDLERS,
+    CLICK_HANDLERS, CONTEXT_MENU_HANDLERS, DOUBLE_CLICK_HANDLERS, DOUBLE_TAP_HANDLERS,
+    DRAG_END_HANDLERS, DRAG_HANDLERS, DRAG_OVER_HANDLERS, DRAG_START_HANDLERS, DROP_HANDLERS,
+    DROP_ITEM_HANDLERS, KEY_DOWN_HANDLERS, KEY_PRESS_HANDLERS, KEY_UP_HANDLERS,
+    LONG_PRESS_HANDLERS, MOUNT_HANDLERS, MOUSE_DOWN_HANDLERS, MOUSE_MOVE_HANDLERS,
+    MOUSE_OUT_HANDLERS, MOUSE_OVER_HANDLERS, MOUSE_UP_HANDLERS, PAN_HANDLERS, PINCH_HANDLERS,
+    ROTATE_HANDLERS, SCROLL_HANDLERS, SWIPE_HANDLERS, TAP_HANDLERS, TEXTBOX_CHANGE_HANDLERS,
+    TEXTBOX_INPUT_HANDLERS, TEXT_INPUT_HANDLERS, TOUCH_END_HANDLERS, TOUCH_MOVE_HANDLERS,
+    TOUCH_START_HANDLERS, UNMOUNT_HANDLERS, WHEEL_HANDLERS,
 };
 use_RefCell!();
-use crate::{ExpandedNodeIdentifier, Globals, LayoutProperties, TransformAndBounds};
+use crate::{
+    ExpandedNodeIdentifier, Globals, GridPlacement, IntrinsicSize, LayoutProperties,
+    TransformAndBounds,
+};
 use core::fmt;
+use std::any::Any;
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::{Rc, Weak};
 
 use crate::api::{
     Axis, ButtonClick, CheckboxChange, Clap, Click, CommonProperties, ContextMenu, DoubleClick,
-    Drop, Event, KeyDown, KeyPress, KeyUp, MouseDown, MouseMove, MouseOut, MouseOver, MouseUp,
-    NodeContext, RenderContext, Scroll, Size, TextboxChange, TextboxInput, TouchEnd, TouchMove,
-    TouchStart, Wheel, Window,
+    DoubleTap, Drag, DragEnd, DragOver, DragStart, Drop, DropItem, Event, EventPhase, HitTest,
+    KeyDown, KeyPress, KeyUp, LongPress, MouseDown, MouseMove, MouseOut, MouseOver, MouseUp,
+    NodeContext, Pan, Pinch, RenderContext, Rotate, Scroll, Size, Swipe, Tap, TextboxChange,
+    TextboxInput, TouchEnd, TouchMove, TouchStart, Wheel, Window,
 };
 
 use crate::{
-    compute_tab, ComponentInstance, HandlerLocation, InstanceNode, InstanceNodePtr, RuntimeContext,
-    RuntimePropertiesStackFrame,
+    compute_tab, ComponentInstance, CustomEventPayload, HandlerLocation, InstanceNode,
+    InstanceNodePtr, RuntimeContext, RuntimePropertiesStackFrame,
 };
 
 #[derive(Clone)]
@@ -98,6 +110,12 @@ pub struct ExpandedNode {
     pub expanded_and_flattened_slot_children: Property<Vec<Rc<ExpandedNode>>>,
     // Number of expanded and flattened slot children
     pub flattened_slot_children_count: Property<usize>,
+    /// Sizes requested by the expanded and flattened slot children, in slot order.
+    /// Re-pointed at the new children whenever the flattened list changes.
+    pub flattened_slot_children_sizes: Property<Vec<IntrinsicSize>>,
+    /// Grid placements of the expanded and flattened slot children, in slot
+    /// order. Re-pointed like `flattened_slot_children_sizes`.
+    pub flattened_slot_children_grid_placements: Property<Vec<GridPlacement>>,
 
     /// Flag that is > 0 if this node is part of the root tree. If it is,
     /// updates to this nodes children also marks them as attached (+1), triggering
@@ -118,6 +136,18 @@ pub struct ExpandedNode {
     /// The flattened index of this node in it's container (if this container
     /// cares about slot children, ex: component, path).
     pub slot_index: Property<Option<usize>>,
+
+    /// Effects registered through this node's NodeContext, dropped (and with
+    /// that removed) when this node is unmounted.
+    pub effects: Rc<RefCell<Vec<EffectHandle>>>,
+
+    /// Timelines declared in the `@settings` of this component, by name.
+    /// Built on mount and dropped on unmount.
+    pub timelines: Rc<RefCell<HashMap<String, Timeline>>>,
+
+    /// Stores provided through this node's NodeContext, visible to this node
+    /// and its template descendants. See `NodeContext::provide_store`.
+    pub provided_stores: Rc<StoreMap>,
 }
 
 impl ImplToFromPaxAny for ExpandedNode {}
@@ -126,47 +156,12 @@ impl Interpolatable for ExpandedNode {}
 macro_rules! dispatch_event_handler {
     ($fn_name:ident, $arg_type:ty, $handler_key:ident, $recurse:expr) => {
         pub fn $fn_name(
-            &self,
+            self: &Rc<Self>,
             args: $arg_type,
-            globals: &Globals,
+            _globals: &Globals,
             ctx: &Rc<RuntimeContext>,
         ) -> bool {
-            let event = Event::new(args.clone());
-            if let Some(registry) = borrow!(self.instance_node).base().get_handler_registry() {
-                let borrowed_registry = &borrow!(*registry);
-                if let Some(handlers) = borrowed_registry.handlers.get($handler_key) {
-                    if handlers.len() > 0 {
-                        let component_properties =
-                            if let Some(cc) = self.containing_component.upgrade() {
-                                Rc::clone(&*borrow!(cc.properties))
-                            } else {
-                                Rc::clone(&*borrow!(self.properties))
-                            };
-
-                        let context = self.get_node_context(ctx);
-                        handlers.iter().for_each(|handler| {
-                            let properties = if let HandlerLocation::Component = &handler.location {
-                                Rc::clone(&*borrow!(self.properties))
-                            } else {
-                                Rc::clone(&component_properties)
-                            };
-                            (handler.function)(
-                                Rc::clone(&properties),
-                                &context,
-                                Some(event.clone().to_pax_any()),
-                            );
-                        });
-                    }
-                };
-            }
-
-            if $recurse {
-                if let Some(parent) = self.template_parent.upgrade() {
-                    let parent_prevent_default = parent.$fn_name(args, globals, ctx);
-                    return event.cancelled() || parent_prevent_default;
-                }
-            }
-            event.cancelled()
+            self.dispatch_propagating_event(args, $handler_key, $recurse, ctx)
         }
     };
 }
@@ -243,9 +238,14 @@ impl ExpandedNode {
             expanded_slot_children: Default::default(),
             expanded_and_flattened_slot_children: Default::default(),
             flattened_slot_children_count: Property::new(0),
+            flattened_slot_children_sizes: Property::new(Vec::new()),
+            flattened_slot_children_grid_placements: Property::new(Vec::new()),
             occlusion_id: RefCell::new(0),
             properties_scope: RefCell::new(property_scope),
             slot_index: Property::default(),
+            effects: Default::default(),
+            timelines: Default::default(),
+            provided_stores: Default::default(),
         });
         res
     }
@@ -321,26 +321,60 @@ impl ExpandedNode {
         context: &Rc<RuntimeContext>,
     ) -> Vec<Rc<ExpandedNode>> {
         let mut curr_children = borrow_mut!(self.mounted_children);
-        //TODO here we could probably check intersection between old and new children (to avoid unmount + mount)
         for child in new_children.iter() {
             // set parent and connect up viewport bounds to new parent
             *borrow_mut!(child.render_parent) = Rc::downgrade(self);
         }
         if *borrow!(self.attached) > 0 {
+            // children present in both lists (e.g. reused by a keyed Repeat) stay mounted
+            let curr: HashSet<*const ExpandedNode> = curr_children.iter().map(Rc::as_ptr).collect();
+            let kept: HashSet<*const ExpandedNode> = new_children
+                .iter()
+                .map(Rc::as_ptr)
+                .filter(|child| curr.contains(child))
+                .collect();
             for child in curr_children.iter() {
-                Rc::clone(child).recurse_unmount(context);
+                if !kept.contains(&Rc::as_ptr(child)) {
+                    Rc::clone(child).recurse_unmount(context);
+                }
             }
             for child in new_children.iter() {
+                if kept.contains(&Rc::as_ptr(child)) {
+                    continue;
+                }
                 Rc::clone(child).recurse_mount(context);
                 child.bind_to_parent_bounds();
                 // set frame clipping reference
                 child.parent_frame.set(self.parent_frame.get());
             }
+            // also covers re-ordering, which changes flattened slot children and z-order
+            context.mark_tree_changed();
+            if let Some(owner) = self.slot_owner() {
+                context.mark_slot_children_changed(&owner);
+            }
         }
         *curr_children = new_children.clone();
         new_children
     }
 
+    /// The component whose flattened slot children include the children of this
+    /// node, i.e. that has it among its slot children, directly or nested in other
+    /// `if`s and `for`s. `None` for nodes that aren't an `if` or `for`.
+    fn slot_owner(self: &Rc<Self>) -> Option<Rc<ExpandedNode>> {
+        let mut node = Rc::clone(self);
+        while borrow!(node.instance_node).base().flags().invisible_to_slot {
+            let parent = node.template_parent.upgrade()?;
+            // nodes in the template of a component have it as their template parent too
+            if borrow!(parent.expanded_slot_children).is_some()
+                && !Weak::ptr_eq(&node.containing_component, &Rc::downgrade(&parent))
+            {
+                return Some(parent);
+            }
+            node = parent;
+        }
+        None
+    }
+
     fn bind_to_parent_bounds(self: &Rc<Self>) {
         let parent = borrow!(self.render_parent).upgrade().unwrap();
         let parent_transform_and_bounds = parent.transform_and_bounds.clone();
@@ -391,15 +425,20 @@ impl ExpandedNode {
         res
     }
 
-    /// This method recursively updates all node properties. When dirty-dag exists, this won't
-    /// need to be here since all property dependencies can be set up and removed during mount/unmount
-    pub fn recurse_update(self: &Rc<Self>, context: &Rc<RuntimeContext>) {
+    /// Brings this node up to date after one of its update dependencies
+    /// changed: lets the instance node react (e.g. send native patches), and
+    /// re-evaluates `children`, which (re)expands and mounts new children.
+    /// Called by RuntimeContext::run_pending_updates, see recurse_mount.
+    pub fn update(self: &Rc<Self>, context: &Rc<RuntimeContext>) {
+        Rc::clone(&*borrow!(self.instance_node)).update(&self, context);
+        self.children.get();
+    }
+
+    /// Calls the handlers registered under `name` that take no arguments
+    /// (mount, unmount, tick, pre_render)
+    pub fn dispatch_lifecycle_handlers(self: &Rc<Self>, name: &str, context: &Rc<RuntimeContext>) {
         if let Some(ref registry) = borrow!(self.instance_node).base().handler_registry {
-            for handler in borrow!(registry)
-                .handlers
-                .get("tick")
-                .unwrap_or(&Vec::new())
-            {
+            for handler in borrow!(registry).handlers.get(name).unwrap_or(&Vec::new()) {
                 (handler.function)(
                     Rc::clone(&*borrow!(self.properties)),
                     &self.get_node_context(context),
@@ -407,22 +446,105 @@ impl ExpandedNode {
                 )
             }
         }
-        Rc::clone(&*borrow!(self.instance_node)).update(&self, context);
-        if let Some(ref registry) = borrow!(self.instance_node).base().handler_registry {
-            for handler in borrow!(registry)
-                .handlers
-                .get("pre_render")
-                .unwrap_or(&Vec::new())
-            {
-                (handler.function)(
-                    Rc::clone(&*borrow!(self.properties)),
-                    &self.get_node_context(context),
-                    None,
-                )
+    }
+
+    /// Dispatches an event with `args` to this node, and evaluates to whether
+    /// default behavior should be prevented. See `propagate_event`.
+    pub(crate) fn dispatch_propagating_event<T: Clone + 'static>(
+        self: &Rc<Self>,
+        args: T,
+        handler_key: &str,
+        recurse: bool,
+        ctx: &Rc<RuntimeContext>,
+    ) -> bool {
+        let event = Event::new(args).with_target(Rc::clone(self) as Rc<dyn Any>);
+        self.propagate_event(&event, handler_key, recurse, ctx);
+        event.cancelled()
+    }
+
+    /// Runs the handlers for an event dispatched to this node, DOM style: the
+    /// capture handlers (`{handler_key}.capture`) of its ancestors from the root
+    /// down, then this node's handlers, then the bubble handlers of its ancestors
+    /// back up to the root. Ancestors are only visited if `recurse` is set, and
+    /// propagation ends at the node that calls `Event::stop_propagation`.
+    fn propagate_event<T: Clone + 'static>(
+        self: &Rc<Self>,
+        event: &Event<T>,
+        handler_key: &str,
+        recurse: bool,
+        ctx: &Rc<RuntimeContext>,
+    ) {
+        let ancestors: Vec<Rc<ExpandedNode>> = if recurse {
+            std::iter::successors(self.template_parent.upgrade(), |node| {
+                node.template_parent.upgrade()
+            })
+            .collect()
+        } else {
+            Vec::new()
+        };
+        let capture_key = format!("{}{}", handler_key, CAPTURE_HANDLERS_SUFFIX);
+
+        for node in ancestors.iter().rev() {
+            node.run_event_handlers(&capture_key, event, EventPhase::Capturing, ctx);
+            if event.propagation_stopped() {
+                return;
             }
         }
-        for child in self.children.get().iter() {
-            child.recurse_update(context);
+        self.run_event_handlers(&capture_key, event, EventPhase::AtTarget, ctx);
+        self.run_event_handlers(handler_key, event, EventPhase::AtTarget, ctx);
+        if event.propagation_stopped() {
+            return;
+        }
+        for node in ancestors.iter() {
+            node.run_event_handlers(handler_key, event, EventPhase::Bubbling, ctx);
+            if event.propagation_stopped() {
+                return;
+            }
+        }
+    }
+
+    fn run_event_handlers<T: Clone + 'static>(
+        self: &Rc<Self>,
+        handler_key: &str,
+        event: &Event<T>,
+        phase: EventPhase,
+        ctx: &Rc<RuntimeContext>,
+    ) {
+        let event = event.at(Rc::clone(self) as Rc<dyn Any>, phase);
+        self.run_handlers(handler_key, || Some(event.clone().to_pax_any()), ctx);
+    }
+
+    /// Calls the handlers registered under `handler_key` with the arguments
+    /// built by `args`, and the properties they were bound with
+    pub(crate) fn run_handlers(
+        self: &Rc<Self>,
+        handler_key: &str,
+        args: impl Fn() -> Option<PaxAny>,
+        ctx: &Rc<RuntimeContext>,
+    ) {
+        let Some(registry) = borrow!(self.instance_node).base().get_handler_registry() else {
+            return;
+        };
+        let borrowed_registry = &borrow!(*registry);
+        let Some(handlers) = borrowed_registry.handlers.get(handler_key) else {
+            return;
+        };
+        if handlers.is_empty() {
+            return;
+        }
+        let component_properties = if let Some(cc) = self.containing_component.upgrade() {
+            Rc::clone(&*borrow!(cc.properties))
+        } else {
+            Rc::clone(&*borrow!(self.properties))
+        };
+        let context = self.get_node_context(ctx);
+        for handler in handlers {
+            let properties = if let HandlerLocation::Component = &handler.location {
+                Rc::clone(&*borrow!(self.properties))
+            } else {
+                Rc::clone(&component_properties)
+            };
+            (handler.function)(properties, &context, args());
         }
     }
 
@@ -430,22 +552,27 @@ impl ExpandedNode {
         if *borrow!(self.attached) == 0 {
             *borrow_mut!(self.attached) += 1;
             context.add_to_cache(&self);
+            // before handle_mount, which mounts the template of components
+            if let Some(timelines_factory) = &borrow!(self.instance_node).base().timelines_factory {
+                *borrow_mut!(self.timelines) = timelines_factory(borrow!(self.properties).clone());
+            }
             borrow!(self.instance_node)
                 .clone()
                 .handle_mount(&self, context);
-            if let Some(ref registry) = borrow!(self.instance_node).base().handler_registry {
-                for handler in borrow!(registry)
-                    .handlers
-                    .get("mount")
-                    .unwrap_or(&Vec::new())
-                {
-                    (handler.function)(
-                        Rc::clone(&*borrow!(self.properties)),
-                        &self.get_node_context(context),
-                        None,
-                    )
-                }
+            if borrow!(self.expanded_slot_children).is_some() {
+                context.mark_slot_children_changed(self);
             }
+            self.dispatch_lifecycle_handlers(MOUNT_HANDLERS, context);
+            // Instead of visiting every node each tick, queue this node for an
+            // update whenever something it depends on changes (and once now).
+            // Dropped together with the other node effects on unmount.
+            let mut dependencies = borrow!(self.instance_node).update_dependencies(self);
+            dependencies.push(self.children.untyped());
+            let queue = context.pending_updates();
+            let weak_self = Rc::downgrade(self);
+            borrow_mut!(self.effects).push(on_dirty(&dependencies, move || {
+                borrow_mut!(queue).push(Weak::clone(&weak_self))
+            }));
         }
         // Mount slot children and children AFTER mounting self
         if let Some(slot_children) = borrow!(self.expanded_slot_children).as_ref() {
@@ -469,20 +596,14 @@ impl ExpandedNode {
         if *borrow!(self.attached) == 1 {
             *borrow_mut!(self.attached) -= 1;
             context.remove_from_cache(&self);
-            if let Some(ref registry) = borrow!(self.instance_node).base().handler_registry {
-                for handler in borrow!(registry)
-                    .handlers
-                    .get("unmount")
-                    .unwrap_or(&Vec::new())
-                {
-                    (handler.function)(
-                        Rc::clone(&*borrow!(self.properties)),
-                        &self.get_node_context(context),
-                        None,
-                    )
-                }
-            }
+            self.dispatch_lifecycle_handlers(UNMOUNT_HANDLERS, context);
             borrow!(self.instance_node).handle_unmount(&self, context);
+            let effects = std::mem::take(&mut *borrow_mut!(self.effects));
+            drop(effects);
+            let timelines = std::mem::take(&mut *borrow_mut!(self.timelines));
+            for timeline in timelines.values() {
+                timeline.pause();
+            }
         }
     }
 
@@ -551,26 +672,43 @@ impl ExpandedNode {
         let deps = [t_and_b_parent.untyped()];
         let bounds_parent = Property::computed(move || t_and_b_parent.get().bounds, &deps);
 
-        let slot_children_count = if borrow!(self.instance_node).base().flags().is_component {
-            self.flattened_slot_children_count.clone()
-        } else {
-            self.containing_component
-                .upgrade()
-                .map(|v| v.flattened_slot_children_count.clone())
-                .unwrap_or_default()
-        };
+        let (slot_children_count, slot_children_sizes, slot_children_grid_placements) =
+            if borrow!(self.instance_node).base().flags().is_component {
+                (
+                    self.flattened_slot_children_count.clone(),
+                    self.flattened_slot_children_sizes.clone(),
+                    self.flattened_slot_children_grid_placements.clone(),
+                )
+            } else {
+                self.containing_component
+                    .upgrade()
+                    .map(|v| {
+                        (
+                            v.flattened_slot_children_count.clone(),
+                            v.flattened_slot_children_sizes.clone(),
+                            v.flattened_slot_children_grid_placements.clone(),
+                        )
+                    })
+                    .unwrap_or_default()
+            };
 
         NodeContext {
             slot_index: self.slot_index.clone(),
             local_stack_frame: Rc::clone(&self.stack),
             component_origin: Weak::clone(&self.containing_component),
+            node_id: self.id,
             frames_elapsed: globals.frames_elapsed.clone(),
+            time_elapsed_ms: globals.time_elapsed_ms.clone(),
             bounds_self,
             bounds_parent,
             runtime_context: ctx.clone(),
             platform: globals.platform.clone(),
             os: globals.os.clone(),
             slot_children_count,
+            slot_children_sizes,
+            slot_children_grid_placements,
+            effects: Rc::clone(&self.effects),
+            timelines: Rc::clone(&self.timelines),
             #[cfg(feature = "designtime")]
             designtime: globals.designtime.clone(),
         }
@@ -580,6 +718,27 @@ impl ExpandedNode {
         Rc::clone(&*borrow!(self.common_properties))
     }
 
+    /// Whether this node can receive keyboard focus, see `CommonProperties::focusable`
+    pub fn is_focusable(&self) -> bool {
+        let common_properties = self.get_common_properties();
+        let common_properties = borrow!(common_properties);
+        common_properties
+            .focusable
+            .get()
+            .unwrap_or(common_properties.tab_index.get().is_some())
+    }
+
+    /// This node's position in the Tab order if it's reachable with Tab,
+    /// see `CommonProperties::tab_index`
+    pub fn tab_index(&self) -> Option<i64> {
+        if !self.is_focusable() {
+            return None;
+        }
+        let common_properties = self.get_common_properties();
+        let tab_index = borrow!(common_properties).tab_index.get().unwrap_or(0);
+        (tab_index >= 0).then_some(tab_index)
+    }
+
     /// Determines whether the provided ray, orthogonal to the view plane,
     /// intersects this `ExpandedNode`.
     pub fn ray_cast_test(&self, ray: Point2<Window>) -> bool {
@@ -591,17 +750,35 @@ impl ExpandedNode {
         {
             return false;
         }
-        let t_and_b = self.transform_and_bounds.get();
+        let common_properties = self.get_common_properties();
+        let hit_test = borrow!(common_properties)
+            .hit_test
+            .get()
+            .unwrap_or_default();
+        if hit_test == HitTest::None {
+            return false;
+        }
+        //Default implementation: rectilinear bounding hull
+        let in_bounds = self.bounds_contain(ray);
+        if hit_test == HitTest::Bounds {
+            return in_bounds;
+        }
+        let transformed_ray = self.transform_and_bounds.get().transform.inverse() * ray;
+        borrow!(self.instance_node)
+            .hit_test(self, transformed_ray)
+            .unwrap_or(in_bounds)
+    }
 
-        let inverted_transform = t_and_b.transform.inverse();
-        let transformed_ray = inverted_transform * ray;
+    /// Whether the provided ray falls within the bounds of this `ExpandedNode`,
+    /// whatever its shape and whether it can be hit
+    pub fn bounds_contain(&self, ray: Point2<Window>) -> bool {
+        let t_and_b = self.transform_and_bounds.get();
+        let transformed_ray = t_and_b.transform.inverse() * ray;
         let (width, height) = t_and_b.bounds;
-        //Default implementation: rectilinear bounding hull
-        let res = transformed_ray.x > 0.0
+        transformed_ray.x > 0.0
             && transformed_ray.y > 0.0
             && transformed_ray.x < width
-            && transformed_ray.y < height;
-        res
+            && transformed_ray.y < height
     }
 
     /// Used at least by ray-casting; only nodes that clip content (and thus should
@@ -630,10 +807,10 @@ impl ExpandedNode {
     }
 
     pub fn compute_flattened_slot_children(&self) {
-        // All of this should ideally be reactively updated,
-        // but currently doesn't exist a way to "listen to"
-        // an entire node tree, and generate the flattened list
-        // only when changed.
+        // There currently isn't a way to "listen to" an entire node tree, so
+        // this is called when this node is mounted and whenever an `if` or `for`
+        // among its slot children changed its children (see
+        // RuntimeContext::recompute_flattened_slot_children)
         if let Some(slot_children) = borrow!(self.expanded_slot_children).as_ref() {
             let new_flattened = flatten_expanded_nodes_for_slot(&slot_children);
             let old_and_new_filtered_same =
@@ -646,6 +823,11 @@ impl ExpandedNode {
 
             if !old_and_new_filtered_same {
                 self.flattened_slot_children_count.set(new_flattened.len());
+                //NOTE: replace with is needed to keep layouts depending on the sizes connected
+                self.flattened_slot_children_sizes
+                    .replace_with(intrinsic_sizes(&new_flattened));
+                self.flattened_slot_children_grid_placements
+                    .replace_with(grid_placements(&new_flattened));
                 self.expanded_and_flattened_slot_children.set(new_flattened);
                 for (i, slot_child) in self
                     .expanded_and_flattened_slot_children
@@ -715,10 +897,23 @@ impl ExpandedNode {
     dispatch_event_handler!(dispatch_click, Click, CLICK_HANDLERS, true);
     dispatch_event_handler!(dispatch_wheel, Wheel, WHEEL_HANDLERS, true);
     dispatch_event_handler!(dispatch_drop, Drop, DROP_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drag_start, DragStart, DRAG_START_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drag, Drag, DRAG_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drag_end, DragEnd, DRAG_END_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drag_over, DragOver, DRAG_OVER_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drop_item, DropItem, DROP_ITEM_HANDLERS, true);
+    dispatch_event_handler!(dispatch_tap, Tap, TAP_HANDLERS, true);
+    dispatch_event_handler!(dispatch_double_tap, DoubleTap, DOUBLE_TAP_HANDLERS, true);
+    dispatch_event_handler!(dispatch_long_press, LongPress, LONG_PRESS_HANDLERS, true);
+    dispatch_event_handler!(dispatch_pan, Pan, PAN_HANDLERS, true);
+    dispatch_event_handler!(dispatch_swipe, Swipe, SWIPE_HANDLERS, true);
+    dispatch_event_handler!(dispatch_pinch, Pinch, PINCH_HANDLERS, true);
+    dispatch_event_handler!(dispatch_rotate, Rotate, ROTATE_HANDLERS, true);
 
     pub fn dispatch_custom_event(
         &self,
         identifier: &str,
+        payload: Option<&CustomEventPayload>,
         ctx: &Rc<RuntimeContext>,
     ) -> Result<(), String> {
         let component_origin_instance = borrow!(self.instance_node);
@@ -734,12 +929,17 @@ impl ExpandedNode {
             .ok_or_else(|| "can't dispatch from root (has no parent)".to_owned())?;
         let properties = borrow!(parent_component.properties);
 
-        for handler in borrow!(registry)
-            .handlers
-            .get(identifier)
-            .expect("presence should have been checked when added to custom_event_queue")
-        {
-            (handler.function)(Rc::clone(&*properties), &self.get_node_context(ctx), None)
+        // declared events can be dispatched without any handlers bound to them
+        let registry = borrow!(registry);
+        let Some(handlers) = registry.handlers.get(identifier) else {
+            return Ok(());
+        };
+        for handler in handlers {
+            (handler.function)(
+                Rc::clone(&*properties),
+                &self.get_node_context(ctx),
+                payload.map(|payload| payload()),
+            )
         }
         Ok(())
     }
@@ -828,6 +1028,85 @@ fn flatten_expanded_nodes_for_slot(nodes: &[Rc<ExpandedNode>]) -> Vec<Rc<Expande
     result
 }
 
+/// Reactively collects the [`IntrinsicSize`] of each of `nodes`, from their layout
+/// properties and chassis-reported sizes
+fn intrinsic_sizes(nodes: &[Rc<ExpandedNode>]) -> Property<Vec<IntrinsicSize>> {
+    let sizes: Vec<_> = nodes
+        .iter()
+        .map(|node| (node.layout_properties(), node.rendered_size.clone()))
+        .collect();
+    let deps: Vec<_> = sizes
+        .iter()
+        .flat_map(|(layout_properties, rendered_size)| {
+            [layout_properties.untyped(), rendered_size.untyped()]
+        })
+        .collect();
+    Property::computed_with_name(
+        move || {
+            sizes
+                .iter()
+                .map(|(layout_properties, rendered_size)| {
+                    let layout_properties = layout_properties.get();
+                    IntrinsicSize {
+                        width: layout_properties.width,
+                        height: layout_properties.height,
+                        rendered_size: rendered_size.get(),
+                    }
+                })
+                .collect()
+        },
+        &deps,
+        "slot children sizes",
+    )
+}
+
+/// Reactively collects the [`GridPlacement`] of each of `nodes`, from their
+/// common properties
+fn grid_placements(nodes: &[Rc<ExpandedNode>]) -> Property<Vec<GridPlacement>> {
+    let placements: Vec<_> = nodes
+        .iter()
+        .map(|node| {
+            let common_properties = node.get_common_properties();
+            let common_properties = borrow!(common_properties);
+            (
+                common_properties.grid_row.clone(),
+                common_properties.grid_column.clone(),
+                common_properties.grid_row_span.clone(),
+                common_properties.grid_column_span.clone(),
+                common_properties.grid_area.clone(),
+            )
+        })
+        .collect();
+    let deps: Vec<_> = placements
+        .iter()
+        .flat_map(|(row, column, row_span, column_span, area)| {
+            [
+                row.untyped(),
+                column.untyped(),
+                row_span.untyped(),
+                column_span.untyped(),
+                area.untyped(),
+            ]
+        })
+        .collect();
+    Property::computed_with_name(
+        move || {
+            placements
+                .iter()
+                .map(|(row, column, row_span, column_span, area)| GridPlacement {
+                    row: row.get(),
+                    column: column.get(),
+                    row_span: row_span.get(),
+                    column_span: column_span.get(),
+                    area: area.get(),
+                })
+                .collect()
+        },
+        &deps,
+        "slot children grid placements",
+    )
+}
+
 impl std::fmt::Debug for ExpandedNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         //see: https://users.rust-lang.org/t/reusing-an-fmt-formatter/8531/4
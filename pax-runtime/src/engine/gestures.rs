@@ -0,0 +1,366 @@
+//! Recognizes gestures (tap, double tap, long press, pan, swipe, pinch and rotate)
+//! from the touch and mouse interrupts the engine receives, so that apps can bind
+//! `@pan`, `@pinch` etc. instead of tracking raw touches themselves.
+
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+
+use pax_runtime_api::{
+    DoubleTap, GesturePhase, LongPress, Pan, Pinch, Rotate, Swipe, SwipeDirection, Tap,
+};
+
+use crate::ExpandedNode;
+
+/// Max distance in px a pointer can move and still tap or long press
+const TAP_SLOP: f64 = 10.0;
+/// Max time in ms between pressing and releasing for a tap
+const TAP_MAX_MS: f64 = 300.0;
+/// Max time in ms and distance in px between the two taps of a double tap
+const DOUBLE_TAP_MS: f64 = 300.0;
+const DOUBLE_TAP_SLOP: f64 = 30.0;
+/// Time in ms a pointer has to be held still for a long press
+const LONG_PRESS_MS: f64 = 500.0;
+/// Min speed in px per second at the end of a pan for a swipe
+const SWIPE_MIN_VELOCITY: f64 = 300.0;
+/// Time in ms over which pan velocity is measured
+const VELOCITY_WINDOW_MS: f64 = 100.0;
+/// Time in ms after a touch during which mouse events are ignored, since
+/// browsers follow touches with emulated mouse events
+const MOUSE_AFTER_TOUCH_MS: f64 = 800.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PointerId {
+    Mouse,
+    Touch(i64),
+}
+
+pub(crate) enum Gesture {
+    Tap(Tap),
+    DoubleTap(DoubleTap),
+    LongPress(LongPress),
+    Pan(Pan),
+    Swipe(Swipe),
+    Pinch(Pinch),
+    Rotate(Rotate),
+}
+
+struct Pointer {
+    id: PointerId,
+    x: f64,
+    y: f64,
+    start_x: f64,
+    start_y: f64,
+    start_ms: f64,
+    /// Recent (x, y, time) samples, for velocity
+    samples: VecDeque<(f64, f64, f64)>,
+}
+
+impl Pointer {
+    fn new(id: PointerId, x: f64, y: f64, now_ms: f64) -> Self {
+        Self {
+            id,
+            x,
+            y,
+            start_x: x,
+            start_y: y,
+            start_ms: now_ms,
+            samples: VecDeque::from([(x, y, now_ms)]),
+        }
+    }
+
+    fn move_to(&mut self, x: f64, y: f64, now_ms: f64) {
+        self.x = x;
+        self.y = y;
+        self.samples.push_back((x, y, now_ms));
+        // keep the newest sample that's at least a window old
+        while self.samples.len() > 2 && now_ms - self.samples[1].2 >= VELOCITY_WINDOW_MS {
+            self.samples.pop_front();
+        }
+    }
+
+    fn distance_moved(&self) -> f64 {
+        (self.x - self.start_x).hypot(self.y - self.start_y)
+    }
+
+    /// In px per second
+    fn velocity(&self) -> (f64, f64) {
+        let (Some(&(x0, y0, t0)), Some(&(x, y, t))) = (self.samples.front(), self.samples.back())
+        else {
+            return (0.0, 0.0);
+        };
+        let seconds = (t - t0) / 1000.0;
+        if seconds <= 0.0 {
+            return (0.0, 0.0);
+        }
+        ((x - x0) / seconds, (y - y0) / seconds)
+    }
+}
+
+/// What the pointers that are down have done so far
+#[derive(Default)]
+enum Mode {
+    /// One pointer down that hasn't moved past the tap slop: may still turn
+    /// into a tap, long press or pan
+    #[default]
+    Pending,
+    LongPressed,
+    Panning,
+    /// Two pointers down, with their distance and angle when they both landed
+    Transforming {
+        distance: f64,
+        angle: f64,
+        began: bool,
+    },
+    /// Nothing more is recognized until all pointers are up
+    Done,
+}
+
+/// Tracks the pointers that are down and turns their movement into [`Gesture`]s,
+/// all targeting the node under the first pointer to go down.
+#[derive(Default)]
+pub(crate) struct GestureRecognizer {
+    pointers: Vec<Pointer>,
+    target: Weak<ExpandedNode>,
+    mode: Mode,
+    /// Position and time of the last tap, for double taps
+    last_tap: Option<(f64, f64, f64)>,
+    last_touch_ms: Option<f64>,
+}
+
+impl GestureRecognizer {
+    /// The node the current (or last) gesture started on
+    pub fn target(&self) -> Option<Rc<ExpandedNode>> {
+        self.target.upgrade()
+    }
+
+    pub fn pointer_down(
+        &mut self,
+        id: PointerId,
+        x: f64,
+        y: f64,
+        now_ms: f64,
+        hit: Option<&Rc<ExpandedNode>>,
+    ) -> Vec<Gesture> {
+        if self.ignores(id, now_ms) || self.pointers.iter().any(|p| p.id == id) {
+            return vec![];
+        }
+        let mut gestures = vec![];
+        if self.pointers.is_empty() {
+            self.target = hit.map(Rc::downgrade).unwrap_or_default();
+            self.mode = Mode::Pending;
+        }
+        self.pointers.push(Pointer::new(id, x, y, now_ms));
+        if self.pointers.len() == 2 {
+            match self.mode {
+                Mode::Pending => (),
+                Mode::Panning => gestures.push(self.pan(GesturePhase::Ended)),
+                _ => return gestures,
+            }
+            let (distance, angle) = self.span();
+            self.mode = Mode::Transforming {
+                distance,
+                angle,
+                began: false,
+            };
+        }
+        gestures
+    }
+
+    /// Moves any number of pointers at once, as a single touch event does
+    pub fn pointers_move(
+        &mut self,
+        moves: impl IntoIterator<Item = (PointerId, f64, f64)>,
+        now_ms: f64,
+    ) -> Vec<Gesture> {
+        let mut moved = false;
+        for (id, x, y) in moves {
+            if self.ignores(id, now_ms) {
+                continue;
+            }
+            if let Some(pointer) = self.pointers.iter_mut().find(|p| p.id == id) {
+                pointer.move_to(x, y, now_ms);
+                moved = true;
+            }
+        }
+        if !moved {
+            return vec![];
+        }
+        match self.mode {
+            Mode::Pending if self.pointers[0].distance_moved() > TAP_SLOP => {
+                self.mode = Mode::Panning;
+                vec![self.pan(GesturePhase::Began)]
+            }
+            Mode::Panning => vec![self.pan(GesturePhase::Changed)],
+            Mode::Transforming {
+                distance,
+                angle,
+                began,
+            } => {
+                self.mode = Mode::Transforming {
+                    distance,
+                    angle,
+                    began: true,
+                };
+                let phase = if began {
+                    GesturePhase::Changed
+                } else {
+                    GesturePhase::Began
+                };
+                self.transform(phase, distance, angle)
+            }
+            _ => vec![],
+        }
+    }
+
+    pub fn pointer_up(&mut self, id: PointerId, x: f64, y: f64, now_ms: f64) -> Vec<Gesture> {
+        if self.ignores(id, now_ms) {
+            return vec![];
+        }
+        let Some(index) = self.pointers.iter().position(|p| p.id == id) else {
+            return vec![];
+        };
+        self.pointers[index].move_to(x, y, now_ms);
+        let mut gestures = vec![];
+        match self.mode {
+            Mode::Pending if self.pointers[0].distance_moved() <= TAP_SLOP => {
+                let held_ms = now_ms - self.pointers[0].start_ms;
+                if held_ms >= LONG_PRESS_MS {
+                    gestures.push(Gesture::LongPress(LongPress { x, y }));
+                } else if held_ms <= TAP_MAX_MS {
+                    gestures.extend(self.tap(x, y, now_ms));
+                }
+            }
+            Mode::Panning => {
+                gestures.push(self.pan(GesturePhase::Ended));
+                gestures.extend(self.swipe());
+            }
+            Mode::Transforming {
+                distance,
+                angle,
+                began,
+            } => {
+                if began {
+                    gestures.extend(self.transform(GesturePhase::Ended, distance, angle));
+                }
+                self.mode = Mode::Done;
+            }
+            _ => (),
+        }
+        self.pointers.remove(index);
+        if self.pointers.is_empty() {
+            self.mode = Mode::Pending;
+        }
+        gestures
+    }
+
+    /// Recognizes long presses, which happen without any pointer event
+    pub fn tick(&mut self, now_ms: f64) -> Vec<Gesture> {
+        match (&self.mode, self.pointers.as_slice()) {
+            (Mode::Pending, [pointer]) if now_ms - pointer.start_ms >= LONG_PRESS_MS => {
+                let long_press = LongPress {
+                    x: pointer.x,
+                    y: pointer.y,
+                };
+                self.mode = Mode::LongPressed;
+                vec![Gesture::LongPress(long_press)]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn ignores(&mut self, id: PointerId, now_ms: f64) -> bool {
+        match id {
+            PointerId::Touch(_) => {
+                self.last_touch_ms = Some(now_ms);
+                false
+            }
+            PointerId::Mouse => self
+                .last_touch_ms
+                .is_some_and(|touch_ms| now_ms - touch_ms < MOUSE_AFTER_TOUCH_MS),
+        }
+    }
+
+    fn tap(&mut self, x: f64, y: f64, now_ms: f64) -> Vec<Gesture> {
+        let mut gestures = vec![Gesture::Tap(Tap { x, y })];
+        match self.last_tap.take() {
+            Some((last_x, last_y, last_ms))
+                if now_ms - last_ms <= DOUBLE_TAP_MS
+                    && (x - last_x).hypot(y - last_y) <= DOUBLE_TAP_SLOP =>
+            {
+                gestures.push(Gesture::DoubleTap(DoubleTap { x, y }));
+            }
+            _ => self.last_tap = Some((x, y, now_ms)),
+        }
+        gestures
+    }
+
+    fn pan(&self, phase: GesturePhase) -> Gesture {
+        let pointer = &self.pointers[0];
+        let (velocity_x, velocity_y) = pointer.velocity();
+        Gesture::Pan(Pan {
+            phase,
+            x: pointer.x,
+            y: pointer.y,
+            translation_x: pointer.x - pointer.start_x,
+            translation_y: pointer.y - pointer.start_y,
+            velocity_x,
+            velocity_y,
+        })
+    }
+
+    fn swipe(&self) -> Option<Gesture> {
+        let (velocity_x, velocity_y) = self.pointers[0].velocity();
+        if velocity_x.hypot(velocity_y) < SWIPE_MIN_VELOCITY {
+            return None;
+        }
+        let direction = match (velocity_x.abs() >= velocity_y.abs(), velocity_x, velocity_y) {
+            (true, vx, _) if vx > 0.0 => SwipeDirection::Right,
+            (true, _, _) => SwipeDirection::Left,
+            (false, _, vy) if vy > 0.0 => SwipeDirection::Down,
+            (false, _, _) => SwipeDirection::Up,
+        };
+        Some(Gesture::Swipe(Swipe {
+            direction,
+            velocity_x,
+            velocity_y,
+        }))
+    }
+
+    /// Distance and angle between the first two pointers
+    fn span(&self) -> (f64, f64) {
+        let (a, b) = (&self.pointers[0], &self.pointers[1]);
+        ((b.x - a.x).hypot(b.y - a.y), (b.y - a.y).atan2(b.x - a.x))
+    }
+
+    fn transform(&self, phase: GesturePhase, distance: f64, angle: f64) -> Vec<Gesture> {
+        let (a, b) = (&self.pointers[0], &self.pointers[1]);
+        let (center_x, center_y) = ((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+        let (new_distance, new_angle) = self.span();
+        let scale = if distance > 0.0 {
+            new_distance / distance
+        } else {
+            1.0
+        };
+        // in (-180, 180], so turning past the starting angle doesn't jump
+        let mut rotation = (new_angle - angle).to_degrees() % 360.0;
+        if rotation > 180.0 {
+            rotation -= 360.0;
+        } else if rotation <= -180.0 {
+            rotation += 360.0;
+        }
+        vec![
+            Gesture::Pinch(Pinch {
+                phase,
+                scale,
+                center_x,
+                center_y,
+            }),
+            Gesture::Rotate(Rotate {
+                phase,
+                rotation,
+                center_x,
+                center_y,
+            }),
+        ]
+    }
+}
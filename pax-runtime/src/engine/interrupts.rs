@@ -0,0 +1,445 @@
+use pax_message::{ModifierKeyMessage, NativeInterrupt};
+use std::rc::Rc;
+
+use pax_runtime_api::{
+    borrow, borrow_mut, math::Point2, use_RefCell, ButtonClick, CheckboxChange, Clap, Click,
+    ContextMenu, DoubleClick, Drag, DragEnd, DragEventArgs, DragOver, DragPayload, DragStart,
+    DropItem, KeyDown, KeyPress, KeyUp, KeyboardEventArgs, ModifierKey, MouseButton, MouseDown,
+    MouseEventArgs, MouseMove, MouseOut, MouseOver, MouseUp, TextInput, TextboxChange,
+    TextboxInput, Touch, TouchEnd, TouchMove, TouchStart, Wheel,
+};
+use_RefCell!();
+
+use crate::properties::{DragState, DRAG_THRESHOLD};
+use crate::{ExpandedNode, ExpandedNodeIdentifier};
+
+use super::gestures::{Gesture, GestureRecognizer, PointerId};
+use super::PaxEngine;
+
+fn modifiers(modifiers: &[ModifierKeyMessage]) -> Vec<ModifierKey> {
+    modifiers.iter().map(ModifierKey::from).collect()
+}
+
+/// Builds the [`MouseEventArgs`] of a mouse interrupt
+macro_rules! mouse_event_args {
+    ($args:expr) => {
+        MouseEventArgs {
+            x: $args.x,
+            y: $args.y,
+            button: MouseButton::from($args.button.clone()),
+            modifiers: modifiers(&$args.modifiers),
+        }
+    };
+}
+
+/// Raycasts at the interrupt's coordinates and dispatches a mouse event of the given type
+/// to the topmost hit node. Evaluates to whether default behavior should be prevented.
+macro_rules! dispatch_mouse_interrupt {
+    ($engine:expr, $args:expr, $event:ident, $dispatch_fn:ident) => {{
+        let ctx = &$engine.runtime_context;
+        let prospective_hit = ctx.get_topmost_element_beneath_ray(Point2::new($args.x, $args.y));
+        if let Some(topmost_node) = prospective_hit {
+            let event = $event {
+                mouse: mouse_event_args!($args),
+            };
+            topmost_node.$dispatch_fn(event, &ctx.globals(), ctx)
+        } else {
+            false
+        }
+    }};
+}
+
+/// Raycasts at the first touch point and dispatches a touch event of the given type
+/// to the topmost hit node.
+macro_rules! dispatch_touch_interrupt {
+    ($engine:expr, $args:expr, $event:ident, $dispatch_fn:ident) => {{
+        let ctx = &$engine.runtime_context;
+        let prospective_hit = $args
+            .touches
+            .first()
+            .and_then(|t| ctx.get_topmost_element_beneath_ray(Point2::new(t.x, t.y)));
+        if let Some(topmost_node) = prospective_hit {
+            let event = $event {
+                touches: $args.touches.iter().map(Touch::from).collect(),
+            };
+            topmost_node.$dispatch_fn(event, &ctx.globals(), ctx)
+        } else {
+            false
+        }
+    }};
+}
+
+impl PaxEngine {
+    /// Routes a [`NativeInterrupt`] to the expanded node(s) it targets, either by raycasting
+    /// its coordinates or by looking up the node id it carries. Returns whether the default
+    /// behavior of the originating platform event should be prevented.
+    ///
+    /// Interrupts that require chassis-owned resources (dropped file contents, decoded
+    /// image data, added drawing layers) are left to the chassis and are no-ops here.
+    pub fn handle_native_interrupt(&self, interrupt: &NativeInterrupt) -> bool {
+        let ctx = &self.runtime_context;
+        let globals = ctx.globals();
+        match interrupt {
+            NativeInterrupt::FormRadioSetChange(args) => {
+                if let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) {
+                    borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+                }
+                false
+            }
+            NativeInterrupt::FormSliderChange(args) => {
+                if let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) {
+                    borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+                }
+                false
+            }
+            NativeInterrupt::FormDropdownChange(args) => {
+                if let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) {
+                    borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+                }
+                false
+            }
+            NativeInterrupt::ChassisResizeRequestCollection(collection) => {
+                for args in collection {
+                    if let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) {
+                        node.chassis_resize_request(args.width, args.height);
+                    }
+                }
+                false
+            }
+            NativeInterrupt::FormButtonClick(args) => {
+                let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) else {
+                    return false;
+                };
+                node.dispatch_button_click(ButtonClick {}, &globals, ctx)
+            }
+            NativeInterrupt::FormTextboxInput(args) => {
+                let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) else {
+                    return false;
+                };
+                borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+                node.dispatch_textbox_input(
+                    TextboxInput {
+                        text: args.text.clone(),
+                    },
+                    &globals,
+                    ctx,
+                )
+            }
+            NativeInterrupt::TextInput(args) => {
+                let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) else {
+                    return false;
+                };
+                borrow!(node.instance_node).handle_text_change(&node, args.text.clone());
+                node.dispatch_text_input(
+                    TextInput {
+                        text: args.text.clone(),
+                    },
+                    &globals,
+                    ctx,
+                )
+            }
+            NativeInterrupt::FormTextboxChange(args) => {
+                let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) else {
+                    return false;
+                };
+                node.dispatch_textbox_change(
+                    TextboxChange {
+                        text: args.text.clone(),
+                    },
+                    &globals,
+                    ctx,
+                )
+            }
+            NativeInterrupt::FormCheckboxToggle(args) => {
+                let Some(node) = self.get_expanded_node(ExpandedNodeIdentifier(args.id)) else {
+                    return false;
+                };
+                borrow!(node.instance_node).handle_native_interrupt(&node, interrupt);
+                node.dispatch_checkbox_change(
+                    CheckboxChange {
+                        checked: args.state,
+                    },
+                    &globals,
+                    ctx,
+                )
+            }
+            NativeInterrupt::Clap(args) => {
+                let prospective_hit =
+                    ctx.get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+                if let Some(topmost_node) = prospective_hit {
+                    let args_clap = Clap {
+                        x: args.x,
+                        y: args.y,
+                    };
+                    topmost_node.dispatch_clap(args_clap, &globals, ctx)
+                } else {
+                    false
+                }
+            }
+            NativeInterrupt::Wheel(args) => {
+                let prospective_hit =
+                    ctx.get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+                if let Some(topmost_node) = prospective_hit {
+                    let args_wheel = Wheel {
+                        x: args.x,
+                        y: args.y,
+                        delta_x: args.delta_x,
+                        delta_y: args.delta_y,
+                        modifiers: modifiers(&args.modifiers),
+                    };
+                    topmost_node.dispatch_wheel(args_wheel, &globals, ctx)
+                } else {
+                    false
+                }
+            }
+            NativeInterrupt::TouchStart(args) => {
+                let prevent_default =
+                    dispatch_touch_interrupt!(self, args, TouchStart, dispatch_touch_start);
+                let hit = args
+                    .touches
+                    .first()
+                    .and_then(|t| ctx.get_topmost_element_beneath_ray(Point2::new(t.x, t.y)));
+                if !prevent_default {
+                    ctx.focus_on_press(hit.as_ref());
+                }
+                self.recognize_gestures(|gestures, now_ms| {
+                    let touches = args.touches.iter();
+                    touches
+                        .flat_map(|t| {
+                            let id = PointerId::Touch(t.identifier);
+                            gestures.pointer_down(id, t.x, t.y, now_ms, hit.as_ref())
+                        })
+                        .collect()
+                });
+                prevent_default
+            }
+            NativeInterrupt::TouchMove(args) => {
+                let prevent_default =
+                    dispatch_touch_interrupt!(self, args, TouchMove, dispatch_touch_move);
+                self.recognize_gestures(|gestures, now_ms| {
+                    let touches = args.touches.iter();
+                    let moves = touches.map(|t| (PointerId::Touch(t.identifier), t.x, t.y));
+                    gestures.pointers_move(moves, now_ms)
+                });
+                prevent_default
+            }
+            NativeInterrupt::TouchEnd(args) => {
+                let prevent_default =
+                    dispatch_touch_interrupt!(self, args, TouchEnd, dispatch_touch_end);
+                self.recognize_gestures(|gestures, now_ms| {
+                    let touches = args.touches.iter();
+                    touches
+                        .flat_map(|t| {
+                            gestures.pointer_up(PointerId::Touch(t.identifier), t.x, t.y, now_ms)
+                        })
+                        .collect()
+                });
+                prevent_default
+            }
+            NativeInterrupt::Click(args) => {
+                dispatch_mouse_interrupt!(self, args, Click, dispatch_click)
+            }
+            NativeInterrupt::DoubleClick(args) => {
+                dispatch_mouse_interrupt!(self, args, DoubleClick, dispatch_double_click)
+            }
+            NativeInterrupt::MouseMove(args) => {
+                let hit = ctx.get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+                let prevent_default =
+                    ctx.pointer_capture()
+                        .or_else(|| hit.clone())
+                        .is_some_and(|node| {
+                            node.dispatch_mouse_move(
+                                MouseMove {
+                                    mouse: mouse_event_args!(args),
+                                },
+                                &globals,
+                                ctx,
+                            )
+                        });
+                self.recognize_gestures(|gestures, now_ms| {
+                    gestures.pointers_move([(PointerId::Mouse, args.x, args.y)], now_ms)
+                });
+                self.continue_drag(args.x, args.y, hit);
+                prevent_default
+            }
+            NativeInterrupt::MouseDown(args) => {
+                let Some(node) = ctx.get_topmost_element_beneath_ray(Point2::new(args.x, args.y))
+                else {
+                    ctx.focus_on_press(None);
+                    return false;
+                };
+                let mouse = mouse_event_args!(args);
+                let is_left = matches!(mouse.button, MouseButton::Left);
+                let prevent_default = node.dispatch_mouse_down(MouseDown { mouse }, &globals, ctx);
+                if !prevent_default {
+                    ctx.focus_on_press(Some(&node));
+                }
+                if is_left {
+                    self.recognize_gestures(|gestures, now_ms| {
+                        gestures.pointer_down(PointerId::Mouse, args.x, args.y, now_ms, Some(&node))
+                    });
+                }
+                if is_left && !prevent_default {
+                    ctx.set_drag_state(DragState::Pressed {
+                        origin: Rc::downgrade(&node),
+                        x: args.x,
+                        y: args.y,
+                    });
+                }
+                prevent_default
+            }
+            NativeInterrupt::MouseUp(args) => {
+                let hit = ctx.get_topmost_element_beneath_ray(Point2::new(args.x, args.y));
+                let prevent_default =
+                    ctx.pointer_capture()
+                        .or_else(|| hit.clone())
+                        .is_some_and(|node| {
+                            node.dispatch_mouse_up(
+                                MouseUp {
+                                    mouse: mouse_event_args!(args),
+                                },
+                                &globals,
+                                ctx,
+                            )
+                        });
+                ctx.set_pointer_capture(None);
+                self.recognize_gestures(|gestures, now_ms| {
+                    gestures.pointer_up(PointerId::Mouse, args.x, args.y, now_ms)
+                });
+                self.end_drag(args.x, args.y, hit);
+                prevent_default
+            }
+            NativeInterrupt::MouseOver(args) => {
+                dispatch_mouse_interrupt!(self, args, MouseOver, dispatch_mouse_over)
+            }
+            NativeInterrupt::MouseOut(args) => {
+                dispatch_mouse_interrupt!(self, args, MouseOut, dispatch_mouse_out)
+            }
+            NativeInterrupt::ContextMenu(args) => {
+                dispatch_mouse_interrupt!(self, args, ContextMenu, dispatch_context_menu)
+            }
+            NativeInterrupt::KeyDown(args) => self.global_dispatch_key_down(KeyDown {
+                keyboard: KeyboardEventArgs {
+                    key: args.key.clone(),
+                    modifiers: modifiers(&args.modifiers),
+                    is_repeat: args.is_repeat,
+                },
+            }),
+            NativeInterrupt::KeyUp(args) => self.global_dispatch_key_up(KeyUp {
+                keyboard: KeyboardEventArgs {
+                    key: args.key.clone(),
+                    modifiers: modifiers(&args.modifiers),
+                    is_repeat: args.is_repeat,
+                },
+            }),
+            NativeInterrupt::KeyPress(args) => self.global_dispatch_key_press(KeyPress {
+                keyboard: KeyboardEventArgs {
+                    key: args.key.clone(),
+                    modifiers: modifiers(&args.modifiers),
+                    is_repeat: args.is_repeat,
+                },
+            }),
+            NativeInterrupt::Scroll(_)
+            | NativeInterrupt::Image(_)
+            | NativeInterrupt::AddedLayer(_)
+            | NativeInterrupt::DropFile(_) => false,
+        }
+    }
+}
+
+impl PaxEngine {
+    /// Feeds pointer events (or the passage of time) to the gesture recognizer with `f`,
+    /// then dispatches the gestures it recognized to the node the gesture started on
+    pub(super) fn recognize_gestures(
+        &self,
+        f: impl FnOnce(&mut GestureRecognizer, f64) -> Vec<Gesture>,
+    ) {
+        let ctx = &self.runtime_context;
+        let globals = ctx.globals();
+        let mut recognizer = borrow_mut!(self.gestures);
+        let gestures = f(&mut recognizer, globals.time_elapsed_ms.get());
+        let target = recognizer.target();
+        drop(recognizer);
+        let Some(target) = target else {
+            return;
+        };
+        for gesture in gestures {
+            match gesture {
+                Gesture::Tap(args) => target.dispatch_tap(args, &globals, ctx),
+                Gesture::DoubleTap(args) => target.dispatch_double_tap(args, &globals, ctx),
+                Gesture::LongPress(args) => target.dispatch_long_press(args, &globals, ctx),
+                Gesture::Pan(args) => target.dispatch_pan(args, &globals, ctx),
+                Gesture::Swipe(args) => target.dispatch_swipe(args, &globals, ctx),
+                Gesture::Pinch(args) => target.dispatch_pinch(args, &globals, ctx),
+                Gesture::Rotate(args) => target.dispatch_rotate(args, &globals, ctx),
+            };
+        }
+    }
+
+    /// Starts a drag once the cursor moved far enough from where the left button
+    /// went down, if `@drag_start` sets a payload, and otherwise dispatches `@drag`
+    /// on the drag source and `@drag_over` on the node under the cursor
+    fn continue_drag(&self, x: f64, y: f64, hit: Option<Rc<ExpandedNode>>) {
+        let ctx = &self.runtime_context;
+        let globals = ctx.globals();
+        match ctx.drag_state() {
+            DragState::Idle => (),
+            DragState::Pressed {
+                origin,
+                x: origin_x,
+                y: origin_y,
+            } => {
+                if (x - origin_x).hypot(y - origin_y) < DRAG_THRESHOLD {
+                    return;
+                }
+                let Some(origin) = origin.upgrade() else {
+                    ctx.set_drag_state(DragState::Idle);
+                    return;
+                };
+                let payload = DragPayload::default();
+                let drag = DragEventArgs {
+                    x,
+                    y,
+                    payload: payload.clone(),
+                };
+                origin.dispatch_drag_start(DragStart { drag }, &globals, ctx);
+                ctx.set_drag_state(if payload.is_set() {
+                    DragState::Dragging {
+                        source: Rc::downgrade(&origin),
+                        payload,
+                    }
+                } else {
+                    DragState::Idle
+                });
+            }
+            DragState::Dragging { source, payload } => {
+                let drag = DragEventArgs { x, y, payload };
+                if let Some(source) = source.upgrade() {
+                    source.dispatch_drag(Drag { drag: drag.clone() }, &globals, ctx);
+                }
+                if let Some(hit) = hit {
+                    hit.dispatch_drag_over(DragOver { drag }, &globals, ctx);
+                }
+            }
+        }
+    }
+
+    /// Dispatches `@drop_item` on the node under the cursor and then `@drag_end`
+    /// on the drag source, if a drag is in progress
+    fn end_drag(&self, x: f64, y: f64, hit: Option<Rc<ExpandedNode>>) {
+        let ctx = &self.runtime_context;
+        let globals = ctx.globals();
+        let DragState::Dragging { source, payload } = ctx.drag_state() else {
+            ctx.set_drag_state(DragState::Idle);
+            return;
+        };
+        ctx.set_drag_state(DragState::Idle);
+        let drag = DragEventArgs { x, y, payload };
+        if let Some(hit) = hit {
+            hit.dispatch_drop_item(DropItem { drag: drag.clone() }, &globals, ctx);
+        }
+        if let Some(source) = source.upgrade() {
+            source.dispatch_drag_end(DragEnd { drag }, &globals, ctx);
+        }
+    }
+}
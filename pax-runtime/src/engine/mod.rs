@@ -1,5 +1,6 @@
 use crate::{api::Property, ExpandedNodeIdentifier, TransformAndBounds};
 use_RefCell!();
+use std::any::TypeId;
 use std::collections::HashMap;
 use std::iter;
 use std::rc::Rc;
@@ -11,7 +12,12 @@ use pax_runtime_api::{
     borrow, borrow_mut, math::Transform2, pax_value::PaxAny, use_RefCell, Window, OS,
 };
 
-use crate::api::{KeyDown, KeyPress, KeyUp, Layer, NodeContext, OcclusionLayerGen, RenderContext};
+use crate::api::{
+    KeyDown, KeyPress, KeyUp, Layer, ModifierKey, NodeContext, OcclusionLayerGen, RenderContext,
+};
+use crate::constants::{
+    KEY_DOWN_HANDLERS, KEY_PRESS_HANDLERS, KEY_UP_HANDLERS, PRE_RENDER_HANDLERS, TICK_HANDLERS,
+};
 use piet::InterpolationMode;
 
 use crate::{
@@ -19,6 +25,8 @@ use crate::{
 };
 use pax_runtime_api::Platform;
 
+mod gestures;
+mod interrupts;
 pub mod node_interface;
 
 /// The atomic unit of rendering; also the container for each unique tuple of computed properties.
@@ -30,6 +38,7 @@ pub mod node_interface;
 mod expanded_node;
 pub use expanded_node::ExpandedNode;
 
+use self::gestures::GestureRecognizer;
 use self::node_interface::NodeLocal;
 
 #[cfg(feature = "designtime")]
@@ -38,6 +47,9 @@ use {pax_designtime::DesigntimeManager, pax_runtime_api::properties};
 #[derive(Clone)]
 pub struct Globals {
     pub frames_elapsed: Property<u64>,
+    /// Milliseconds elapsed since the first tick, according to the clock
+    /// supplied by the chassis (see PaxEngine::tick_at)
+    pub time_elapsed_ms: Property<f64>,
     pub viewport: Property<TransformAndBounds<NodeLocal, Window>>,
     pub platform: Platform,
     pub os: OS,
@@ -49,6 +61,7 @@ impl std::fmt::Debug for Globals {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Globals")
             .field("frames_elapsed", &self.frames_elapsed)
+            .field("time_elapsed_ms", &self.time_elapsed_ms)
             .field("viewport", &self.viewport)
             .finish_non_exhaustive()
     }
@@ -59,8 +72,16 @@ pub struct PaxEngine {
     pub runtime_context: Rc<RuntimeContext>,
     pub root_node: Rc<ExpandedNode>,
     main_component_instance: Rc<ComponentInstance>,
+    /// Chassis clock reading at the first tick_at, and at the latest one
+    clock_origin_ms: Option<f64>,
+    clock_now_ms: Option<f64>,
+    gestures: RefCell<GestureRecognizer>,
 }
 
+/// How far the millisecond clock advances per tick when the chassis doesn't
+/// supply a clock (headless, tests)
+const NOMINAL_FRAME_MS: f64 = 1000.0 / 60.0;
+
 pub enum HandlerLocation {
     Inline,
     Component,
@@ -69,6 +90,9 @@ pub enum HandlerLocation {
 pub struct Handler {
     pub function: fn(Rc<RefCell<PaxAny>>, &NodeContext, Option<PaxAny>),
     pub location: HandlerLocation,
+    /// The type `function` downcasts its arguments to, `None` if it takes none.
+    /// For custom events this is the payload type declared by the dispatching component.
+    pub args_type: Option<TypeId>,
 }
 
 impl Handler {
@@ -78,6 +102,7 @@ impl Handler {
         Handler {
             function,
             location: HandlerLocation::Inline,
+            args_type: None,
         }
     }
 
@@ -87,18 +112,28 @@ impl Handler {
         Handler {
             function,
             location: HandlerLocation::Component,
+            args_type: None,
         }
     }
+
+    pub fn with_args_type(mut self, args_type: Option<TypeId>) -> Self {
+        self.args_type = args_type;
+        self
+    }
 }
 
 pub struct HandlerRegistry {
     pub handlers: HashMap<String, Vec<Handler>>,
+    /// The custom events the component declares with `#[events(...)]`, and the
+    /// payload type each carries
+    pub custom_events: HashMap<String, Option<TypeId>>,
 }
 
 impl Default for HandlerRegistry {
     fn default() -> Self {
         HandlerRegistry {
             handlers: HashMap::new(),
+            custom_events: HashMap::new(),
         }
     }
 }
@@ -132,6 +167,15 @@ impl<R: piet::RenderContext> Renderer<R> {
     pub fn image_loaded(&self, path: &str) -> bool {
         self.image_map.contains_key(path)
     }
+
+    /// Finishes drawing on every context. Backends that buffer draw calls (e.g. bitmap
+    /// targets) need this before their contents are read back.
+    pub fn finish(&mut self) -> Result<(), piet::Error> {
+        for backend in self.backends.values_mut() {
+            backend.finish()?;
+        }
+        Ok(())
+    }
 }
 
 impl<R: piet::RenderContext> crate::api::RenderContext for Renderer<R> {
@@ -213,8 +257,13 @@ impl<R: piet::RenderContext> crate::api::RenderContext for Renderer<R> {
 
 pub struct ExpressionTable {
     pub table: HashMap<usize, Box<dyn Fn(ExpressionContext) -> PaxAny>>,
+    /// Entries registered once the table is shared with running nodes, e.g. interpreted
+    /// expressions applied during hot reload. Take precedence over `table`.
+    runtime_entries: RefCell<HashMap<usize, Rc<RuntimeEntry>>>,
 }
 
+type RuntimeEntry = dyn Fn(ExpressionContext) -> PaxAny;
+
 #[cfg(debug_assertions)]
 impl std::fmt::Debug for ExpressionTable {
     fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -224,19 +273,41 @@ impl std::fmt::Debug for ExpressionTable {
 
 impl ExpressionTable {
     pub fn new() -> Self {
+        Self::from_table(HashMap::new())
+    }
+
+    /// A table with the entries of a compiled cartridge, see `instantiate_expression_table`
+    pub fn from_table(table: HashMap<usize, Box<dyn Fn(ExpressionContext) -> PaxAny>>) -> Self {
         Self {
-            table: HashMap::new(),
+            table,
+            runtime_entries: Default::default(),
         }
     }
 
+    /// Registers `entry` for `vtable_id`, replacing any existing one, while the table is
+    /// already in use. Properties computed from a previous entry aren't invalidated, so
+    /// callers are expected to re-instantiate the nodes using it (see
+    /// PaxEngine::reload_main_template).
+    pub fn set_runtime_entry(
+        &self,
+        vtable_id: usize,
+        entry: Box<dyn Fn(ExpressionContext) -> PaxAny>,
+    ) {
+        borrow_mut!(self.runtime_entries).insert(vtable_id, Rc::from(entry));
+    }
+
     pub fn compute_vtable_value(
         &self,
         stack: &Rc<RuntimePropertiesStackFrame>,
         vtable_id: usize,
     ) -> PaxAny {
-        if let Some(evaluator) = self.table.get(&vtable_id) {
-            let stack_frame = Rc::clone(stack);
-            let ec = ExpressionContext { stack_frame };
+        let stack_frame = Rc::clone(stack);
+        let ec = ExpressionContext { stack_frame };
+        // not borrowed while evaluating, since expressions can read other expressions
+        let runtime_entry = borrow!(self.runtime_entries).get(&vtable_id).cloned();
+        if let Some(evaluator) = runtime_entry {
+            evaluator(ec)
+        } else if let Some(evaluator) = self.table.get(&vtable_id) {
             (**evaluator)(ec)
         } else {
             panic!() //unhandled error if an invalid id is passed or if vtable is incorrectly initialized
@@ -260,8 +331,11 @@ impl PaxEngine {
 
         let frames_elapsed = Property::new(0);
         properties::register_time(&frames_elapsed);
+        let time_elapsed_ms = Property::new(0.0);
+        properties::register_time_ms(&time_elapsed_ms);
         let globals = Globals {
             frames_elapsed,
+            time_elapsed_ms,
             viewport: Property::new(TransformAndBounds {
                 transform: Transform2::identity(),
                 bounds: viewport_size,
@@ -277,6 +351,9 @@ impl PaxEngine {
             runtime_context,
             root_node,
             main_component_instance,
+            clock_origin_ms: None,
+            clock_now_ms: None,
+            gestures: Default::default(),
         }
     }
 
@@ -292,8 +369,11 @@ impl PaxEngine {
         use pax_runtime_api::math::Transform2;
         let frames_elapsed = Property::new(0);
         properties::register_time(&frames_elapsed);
+        let time_elapsed_ms = Property::new(0.0);
+        properties::register_time_ms(&time_elapsed_ms);
         let globals = Globals {
             frames_elapsed,
+            time_elapsed_ms,
             viewport: Property::new(TransformAndBounds {
                 transform: Transform2::identity(),
                 bounds: viewport_size,
@@ -312,6 +392,9 @@ impl PaxEngine {
             runtime_context,
             root_node,
             main_component_instance,
+            clock_origin_ms: None,
+            clock_now_ms: None,
+            gestures: Default::default(),
         }
     }
 
@@ -396,25 +479,93 @@ impl PaxEngine {
         }
     }
 
+    /// Swaps in the template of `new_main_component_instance` and regenerates the main
+    /// component's children from it, e.g. after a `.pax` file was edited during hot reload.
+    /// The main component's own properties are kept, while everything below it is recreated.
+    pub fn reload_main_template(&mut self, new_main_component_instance: Rc<ComponentInstance>) {
+        let new_template = borrow!(new_main_component_instance.template).clone();
+        *borrow_mut!(self.main_component_instance.template) = new_template;
+
+        let root = &self.root_node;
+        let env = root.stack.push(
+            borrow!(root.properties_scope).clone(),
+            &*borrow!(root.properties),
+        );
+        let templates = borrow!(self.main_component_instance.template).clone();
+        let children = root.generate_children(
+            templates.into_iter().zip(iter::repeat(env)),
+            &self.runtime_context,
+        );
+        root.children.set(children);
+    }
+
     // NOTES: this is the order of different things being computed in recurse-expand-nodes
     // - expanded_node instantiated from instance_node.
 
+    /// Same as `tick`, for chassis that supply a millisecond clock reading
+    /// (e.g. `performance.now()`), used to drive transitions with a
+    /// `std::time::Duration`. Without one, the clock advances by 1/60th of a
+    /// second per tick.
+    pub fn tick_at(&mut self, now_ms: f64) -> Vec<NativeMessage> {
+        self.clock_origin_ms.get_or_insert(now_ms);
+        self.clock_now_ms = Some(now_ms);
+        self.tick()
+    }
+
     /// Workhorse methods of every tick.  Will be executed up to 240 Hz.
-    /// Three phases:
-    /// 1. Expand nodes & compute properties; recurse entire instance tree and evaluate ExpandedNodes, stitching
-    ///    together parent/child relationships between ExpandedNodes along the way.
-    /// 2. Compute layout (z-index & TransformAndBounds) by visiting ExpandedNode tree
-    ///    in rendering order, writing computed rendering-specific values to ExpandedNodes
-    /// 3. Render:
-    ///     a. find lowest node (last child of last node)
-    ///     b. start rendering, from lowest node on-up, throughout tree
+    /// Only does work proportional to what changed since the last tick:
+    /// 1. Dispatch long presses, run the tick handlers of all nodes (see
+    ///    RuntimeContext::dispatch_frame_handlers), then update the nodes whose dependencies
+    ///    changed (see ExpandedNode::recurse_mount), which expands/mounts/unmounts children
+    ///    and sends native patches. If that changed the tree, recompute the flattened slot
+    ///    children of the affected components, which may in turn queue more updates.
+    /// 2. Run the pre_render handlers of all nodes and update again.
+    /// 3. If the tree changed, recompute native occlusion layers.
+    ///
+    /// Rendering itself happens separately in `render`.
     pub fn tick(&mut self) -> Vec<NativeMessage> {
-        //
-        // 1. UPDATE NODES (properties, etc.). This part we should be able to
-        // completely remove once reactive properties dirty-dag is a thing.
-        //
-        self.root_node.recurse_update(&mut self.runtime_context);
+        let ctx = &self.runtime_context;
+        let mut tree_changed = false;
+        self.recognize_gestures(|gestures, now_ms| gestures.tick(now_ms));
+        ctx.dispatch_frame_handlers(TICK_HANDLERS);
+        tree_changed |= Self::settle(ctx);
+        ctx.dispatch_frame_handlers(PRE_RENDER_HANDLERS);
+        tree_changed |= Self::settle(ctx);
+
+        if tree_changed {
+            self.update_occlusion();
+        }
+
+        // the millisecond clock is updated first: transitions read it once
+        // frames_elapsed dirties them
+        let time_ms = &ctx.globals().time_elapsed_ms;
+        match self.clock_now_ms.take() {
+            Some(now_ms) => time_ms.set(now_ms - self.clock_origin_ms.unwrap_or(now_ms)),
+            None => time_ms.set(time_ms.get() + NOMINAL_FRAME_MS),
+        }
+        let time = &ctx.globals().frames_elapsed;
+
+        time.set(time.get() + 1);
+
+        ctx.flush_custom_events().unwrap();
+        ctx.take_native_messages()
+    }
 
+    /// Runs pending node updates until there are none left, returns whether the tree changed
+    fn settle(ctx: &Rc<RuntimeContext>) -> bool {
+        let mut tree_changed = false;
+        loop {
+            ctx.run_pending_updates();
+            if !ctx.take_tree_changed() {
+                break;
+            }
+            tree_changed = true;
+            ctx.recompute_flattened_slot_children();
+        }
+        tree_changed
+    }
+
+    fn update_occlusion(&self) {
         let ctx = &self.runtime_context;
         // Occlusion
         let mut occlusion_ind = OcclusionLayerGen::new(None);
@@ -433,13 +584,6 @@ impl PaxEngine {
             }
             *curr_occlusion_ind = new_occlusion_ind;
         });
-
-        let time = &ctx.globals().frames_elapsed;
-
-        time.set(time.get() + 1);
-
-        ctx.flush_custom_events().unwrap();
-        ctx.take_native_messages()
     }
 
     pub fn render(&mut self, rcs: &mut dyn RenderContext) {
@@ -464,36 +608,44 @@ impl PaxEngine {
         });
     }
 
-    pub fn global_dispatch_key_down(&self, args: KeyDown) {
+    /// Dispatches a key event to the focused node, bubbling up from there, or
+    /// to every node if nothing has focus. Evaluates to whether default
+    /// behavior should be prevented.
+    fn dispatch_key_event<T: Clone + 'static>(&self, args: T, handler_key: &str) -> bool {
+        let ctx = &self.runtime_context;
+        if let Some(focused) = ctx.focused_node() {
+            return focused.dispatch_propagating_event(args, handler_key, true, ctx);
+        }
+        let mut prevented = false;
         self.root_node
             .recurse_visit_postorder(&mut |expanded_node| {
-                expanded_node.dispatch_key_down(
-                    args.clone(),
-                    &self.runtime_context.globals(),
-                    &self.runtime_context,
-                );
+                prevented |=
+                    expanded_node.dispatch_propagating_event(args.clone(), handler_key, false, ctx);
             });
+        prevented
+    }
+
+    /// Tab and Shift-Tab move focus through the Tab order, unless a handler
+    /// prevented it. Returns whether default behavior should be prevented,
+    /// which is also the case when Tab moved focus.
+    pub fn global_dispatch_key_down(&self, args: KeyDown) -> bool {
+        let prevented = self.dispatch_key_event(args.clone(), KEY_DOWN_HANDLERS);
+        if !prevented && args.keyboard.key == "Tab" {
+            let backwards = args
+                .keyboard
+                .modifiers
+                .iter()
+                .any(|modifier| matches!(modifier, ModifierKey::Shift));
+            return self.runtime_context.focus_next(backwards);
+        }
+        prevented
     }
 
-    pub fn global_dispatch_key_up(&self, args: KeyUp) {
-        self.root_node
-            .recurse_visit_postorder(&mut |expanded_node| {
-                expanded_node.dispatch_key_up(
-                    args.clone(),
-                    &self.runtime_context.globals(),
-                    &self.runtime_context,
-                );
-            });
+    pub fn global_dispatch_key_up(&self, args: KeyUp) -> bool {
+        self.dispatch_key_event(args, KEY_UP_HANDLERS)
     }
 
-    pub fn global_dispatch_key_press(&self, args: KeyPress) {
-        self.root_node
-            .recurse_visit_postorder(&mut |expanded_node| {
-                expanded_node.dispatch_key_press(
-                    args.clone(),
-                    &self.runtime_context.globals(),
-                    &self.runtime_context,
-                );
-            });
+    pub fn global_dispatch_key_press(&self, args: KeyPress) -> bool {
+        self.dispatch_key_event(args, KEY_PRESS_HANDLERS)
     }
 }
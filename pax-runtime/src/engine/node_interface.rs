@@ -1,11 +1,14 @@
 use std::rc::Rc;
 
 use pax_manifest::UniqueTemplateNodeIdentifier;
-use pax_runtime_api::Property;
 use pax_runtime_api::{borrow, pax_value::ToFromPaxAny, Interpolatable};
+use pax_runtime_api::{Event, Property};
 
 use crate::{
-    api::{math::Space, Window},
+    api::{
+        math::{Point2, Space},
+        Window,
+    },
     ExpandedNode, LayoutProperties, TransformAndBounds,
 };
 
@@ -36,11 +39,43 @@ impl std::fmt::Debug for NodeInterface {
     }
 }
 
+/// The nodes an engine event travels between, e.g. to tell whether a click
+/// landed on a card itself or on one of its children
+pub trait EventTarget {
+    /// The node the event was dispatched to, e.g. the topmost node under a click
+    fn target(&self) -> Option<NodeInterface>;
+
+    /// The node whose handler is receiving the event
+    fn current_target(&self) -> Option<NodeInterface>;
+}
+
+impl<T> EventTarget for Event<T> {
+    fn target(&self) -> Option<NodeInterface> {
+        let target = Rc::clone(self.target_any()?);
+        target.downcast::<ExpandedNode>().ok().map(Into::into)
+    }
+
+    fn current_target(&self) -> Option<NodeInterface> {
+        let current_target = Rc::clone(self.current_target_any()?);
+        current_target
+            .downcast::<ExpandedNode>()
+            .ok()
+            .map(Into::into)
+    }
+}
+
 pub struct NodeLocal;
 
 impl Space for NodeLocal {}
 
 impl NodeInterface {
+    /// The `id` common property of this node
+    pub fn id(&self) -> Option<String> {
+        let common_properties = self.inner.get_common_properties();
+        let id = borrow!(common_properties).id.get();
+        id
+    }
+
     pub fn global_id(&self) -> Option<UniqueTemplateNodeIdentifier> {
         let instance_node = borrow!(self.inner.instance_node);
         let base = instance_node.base();
@@ -81,4 +116,46 @@ impl NodeInterface {
     pub fn is_descendant_of(&self, node: &NodeInterface) -> bool {
         self.inner.is_descendant_of(&node.inner.id)
     }
+
+    /// The nodes rendered directly inside this one, topmost first
+    pub fn children(&self) -> Vec<NodeInterface> {
+        self.inner
+            .children
+            .get()
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// All nodes rendered inside this one, depth first, in the same order as
+    /// [`NodeInterface::children`]
+    pub fn descendants(&self) -> Vec<NodeInterface> {
+        let mut descendants = vec![];
+        for child in self.children() {
+            let grandchildren = child.descendants();
+            descendants.push(child);
+            descendants.extend(grandchildren);
+        }
+        descendants
+    }
+
+    /// The render parents of this node, from its own up to the root
+    pub fn ancestors(&self) -> Vec<NodeInterface> {
+        std::iter::successors(self.render_parent(), NodeInterface::render_parent).collect()
+    }
+
+    /// The smallest axis-aligned box containing this node in window space, as
+    /// its top left and bottom right corners. Accounts for rotation, scale etc.
+    pub fn bounding_box(&self) -> (Point2<Window>, Point2<Window>) {
+        let t_and_b = self.inner.transform_and_bounds.get();
+        let (width, height) = t_and_b.bounds;
+        let corners = [(0.0, 0.0), (width, 0.0), (0.0, height), (width, height)]
+            .map(|(x, y)| t_and_b.transform * Point2::<NodeLocal>::new(x, y));
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in &corners[1..] {
+            min = Point2::new(min.x.min(corner.x), min.y.min(corner.y));
+            max = Point2::new(max.x.max(corner.x), max.y.max(corner.y));
+        }
+        (min, max)
+    }
 }
@@ -285,6 +285,75 @@ fn test_transform_and_bounds_mult() {
     assert!(diff_sum < 1e-4);
 }
 
+/// The size a node asks for before its container lays it out: the `width` / `height`
+/// set on it, falling back to the size reported by the chassis (e.g. for text).
+/// Used by layouts that size their cells to content.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IntrinsicSize {
+    pub width: Option<Size>,
+    pub height: Option<Size>,
+    pub rendered_size: Option<(f64, f64)>,
+}
+
+impl Interpolatable for IntrinsicSize {}
+
+impl IntrinsicSize {
+    /// The size along `axis` in px, if it is known without a container (pixel sizes,
+    /// or no size at all and a chassis-reported size). Percentages depend on the cell
+    /// the node is laid out into, and thus return `None`.
+    pub fn along(&self, axis: Axis) -> Option<f64> {
+        let (size, rendered) = self.split(axis);
+        match size {
+            Some(Size::Pixels(px)) => Some(px.to_float()),
+            Some(_) => None,
+            None => rendered,
+        }
+    }
+
+    /// Like [`IntrinsicSize::along`], resolving percentages against `container`
+    pub fn along_within(&self, container: (f64, f64), axis: Axis) -> Option<f64> {
+        let (size, rendered) = self.split(axis);
+        match size {
+            Some(size) => Some(size.evaluate(container, axis)),
+            None => rendered,
+        }
+    }
+
+    /// The size of the container along `axis` within which this node is `size` px,
+    /// for nodes sized in percentages. Nodes without a size fill their container.
+    pub fn container_for(&self, size: f64, axis: Axis) -> f64 {
+        let (px, percent) = match self.split(axis).0 {
+            Some(Size::Percent(percent)) => (0.0, percent.to_float()),
+            Some(Size::Combined(px, percent)) => (px.to_float(), percent.to_float()),
+            _ => return size,
+        };
+        if percent <= 0.0 {
+            return size;
+        }
+        ((size - px) / (percent / 100.0)).max(0.0)
+    }
+
+    fn split(&self, axis: Axis) -> (&Option<Size>, Option<f64>) {
+        match axis {
+            Axis::X => (&self.width, self.rendered_size.map(|(w, _)| w)),
+            Axis::Y => (&self.height, self.rendered_size.map(|(_, h)| h)),
+        }
+    }
+}
+
+/// Where a node asks to be placed when it is a slot child of a `Grid`, from its
+/// `grid_*` common properties
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GridPlacement {
+    pub row: Option<usize>,
+    pub column: Option<usize>,
+    pub row_span: Option<usize>,
+    pub column_span: Option<usize>,
+    pub area: Option<String>,
+}
+
+impl Interpolatable for GridPlacement {}
+
 impl Interpolatable for LayoutProperties {}
 
 #[derive(Debug, Default, Clone)]
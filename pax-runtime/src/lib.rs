@@ -11,6 +11,7 @@ pub mod properties;
 pub mod rendering;
 pub mod repeat;
 pub mod slot;
+pub mod testing;
 
 pub use crate::component::*;
 pub use crate::conditional::*;
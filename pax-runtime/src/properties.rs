@@ -4,13 +4,14 @@ use pax_manifest::UniqueTemplateNodeIdentifier;
 use pax_message::NativeMessage;
 use pax_runtime_api::pax_value::PaxAny;
 use pax_runtime_api::properties::UntypedProperty;
-use pax_runtime_api::{borrow, borrow_mut, use_RefCell, Store};
+use pax_runtime_api::stores::StoreMap;
+use pax_runtime_api::{borrow, borrow_mut, use_RefCell, DragPayload, Store, StoreError};
 use_RefCell!();
-use std::any::{Any, TypeId};
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::{Rc, Weak};
 
+use crate::constants::{BLUR_HANDLERS, FOCUS_HANDLERS, PRE_RENDER_HANDLERS, TICK_HANDLERS};
 use crate::{ExpandedNode, ExpressionTable, Globals};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -23,6 +24,14 @@ impl ExpandedNodeIdentifier {
     }
 }
 
+/// Builds the payload of a custom event for each handler bound to it, since a
+/// `PaxAny` can't be cloned
+pub type CustomEventPayload = Rc<dyn Fn() -> PaxAny>;
+
+/// A custom event dispatched from the template of a component: the component's
+/// node, the event name and its payload
+type QueuedCustomEvent = (Rc<ExpandedNode>, &'static str, Option<CustomEventPayload>);
+
 /// Shared context for properties pass recursion
 pub struct RuntimeContext {
     next_uid: Cell<ExpandedNodeIdentifier>,
@@ -31,12 +40,54 @@ pub struct RuntimeContext {
     root_node: RefCell<Weak<ExpandedNode>>,
     expression_table: Rc<ExpressionTable>,
     node_cache: RefCell<NodeCache>,
-    queued_custom_events: RefCell<Vec<(Rc<ExpandedNode>, &'static str)>>,
+    queued_custom_events: RefCell<Vec<QueuedCustomEvent>>,
+    /// Nodes whose update dependencies changed since they were last updated,
+    /// shared with the listeners that queue them (see ExpandedNode::recurse_mount)
+    pending_updates: Rc<RefCell<Vec<Weak<ExpandedNode>>>>,
+    /// Set whenever nodes are mounted, unmounted or re-ordered, and cleared
+    /// once the engine has caught up (flattened slot children, occlusion)
+    tree_changed: Cell<bool>,
+    /// Components whose flattened slot children need to be recomputed, by id
+    changed_slot_owners: RefCell<BTreeMap<ExpandedNodeIdentifier, Weak<ExpandedNode>>>,
+    /// The node key events are routed to, if any
+    focused_node: RefCell<Weak<ExpandedNode>>,
+    /// The node mouse move and up events are routed to, if any, regardless
+    /// of what's under the cursor
+    pointer_capture: RefCell<Weak<ExpandedNode>>,
+    /// Progress of an in-app drag and drop gesture
+    drag_state: RefCell<DragState>,
+    /// Stores visible to every node of the app, see `NodeContext::set_app_store`
+    app_stores: StoreMap,
+}
+
+/// Distance in px the cursor has to move with the button pressed before a
+/// drag starts
+pub(crate) const DRAG_THRESHOLD: f64 = 4.0;
+
+#[derive(Clone, Default)]
+pub(crate) enum DragState {
+    #[default]
+    Idle,
+    /// The left button went down over `origin`, but the cursor hasn't moved
+    /// past the drag threshold yet
+    Pressed {
+        origin: Weak<ExpandedNode>,
+        x: f64,
+        y: f64,
+    },
+    /// `@drag_start` on `source` set a payload, and drag events are being dispatched
+    Dragging {
+        source: Weak<ExpandedNode>,
+        payload: DragPayload,
+    },
 }
 
 struct NodeCache {
     eid_to_node: HashMap<ExpandedNodeIdentifier, Rc<ExpandedNode>>,
     uni_to_eid: HashMap<UniqueTemplateNodeIdentifier, Vec<ExpandedNodeIdentifier>>,
+    /// Nodes with tick or pre_render handlers, which are called every frame.
+    /// Ordered by id, and with that roughly parents before children
+    with_frame_handlers: BTreeMap<ExpandedNodeIdentifier, Rc<ExpandedNode>>,
 }
 
 impl NodeCache {
@@ -44,6 +95,7 @@ impl NodeCache {
         Self {
             eid_to_node: Default::default(),
             uni_to_eid: Default::default(),
+            with_frame_handlers: Default::default(),
         }
     }
 
@@ -57,6 +109,15 @@ impl NodeCache {
         if let Some(uni) = uni {
             self.uni_to_eid.entry(uni).or_default().push(node.id);
         }
+        if let Some(ref registry) = borrow!(node.instance_node).base().handler_registry {
+            let handlers = &borrow!(registry).handlers;
+            if [TICK_HANDLERS, PRE_RENDER_HANDLERS]
+                .iter()
+                .any(|name| handlers.get(*name).is_some_and(|h| !h.is_empty()))
+            {
+                self.with_frame_handlers.insert(node.id, Rc::clone(node));
+            }
+        }
     }
 
     // Remove this node from all relevant constant lookup cache structures
@@ -65,6 +126,7 @@ impl NodeCache {
         if let Some(uni) = &borrow!(node.instance_node).base().template_node_identifier {
             self.uni_to_eid.remove(uni);
         }
+        self.with_frame_handlers.remove(&node.id);
     }
 }
 
@@ -78,6 +140,13 @@ impl RuntimeContext {
             root_node: RefCell::new(Weak::new()),
             node_cache: RefCell::new(NodeCache::new()),
             queued_custom_events: Default::default(),
+            pending_updates: Default::default(),
+            tree_changed: Cell::new(false),
+            changed_slot_owners: Default::default(),
+            focused_node: RefCell::new(Weak::new()),
+            pointer_capture: RefCell::new(Weak::new()),
+            drag_state: Default::default(),
+            app_stores: Default::default(),
         }
     }
 
@@ -85,12 +154,85 @@ impl RuntimeContext {
         *borrow_mut!(self.root_node) = Rc::downgrade(root);
     }
 
+    pub fn root_node(&self) -> Option<Rc<ExpandedNode>> {
+        borrow!(self.root_node).upgrade()
+    }
+
     pub fn add_to_cache(&self, node: &Rc<ExpandedNode>) {
         borrow_mut!(self.node_cache).add_to_cache(node);
+        self.mark_tree_changed();
     }
 
     pub fn remove_from_cache(&self, node: &Rc<ExpandedNode>) {
         borrow_mut!(self.node_cache).remove_from_cache(node);
+        self.mark_tree_changed();
+    }
+
+    pub fn mark_tree_changed(&self) {
+        self.tree_changed.set(true);
+    }
+
+    /// Whether the tree changed since this was last called
+    pub fn take_tree_changed(&self) -> bool {
+        self.tree_changed.replace(false)
+    }
+
+    /// Queue used to schedule nodes for an update on the next tick
+    pub fn pending_updates(&self) -> Rc<RefCell<Vec<Weak<ExpandedNode>>>> {
+        Rc::clone(&self.pending_updates)
+    }
+
+    /// Updates all nodes queued since this was last called, including the
+    /// ones queued by doing so (e.g. newly mounted children)
+    pub fn run_pending_updates(self: &Rc<Self>) {
+        loop {
+            let pending = std::mem::take(&mut *borrow_mut!(self.pending_updates));
+            if pending.is_empty() {
+                break;
+            }
+            // a node can be queued several times by changes in between ticks
+            let mut updated = HashSet::new();
+            for node in pending.iter().filter_map(Weak::upgrade) {
+                if *borrow!(node.attached) > 0 && updated.insert(node.id) {
+                    node.update(self);
+                }
+            }
+        }
+    }
+
+    /// Schedules recomputing the flattened slot children of `owner`, once it's mounted
+    /// or the `if`s and `for`s among its slot children changed
+    pub fn mark_slot_children_changed(&self, owner: &Rc<ExpandedNode>) {
+        borrow_mut!(self.changed_slot_owners).insert(owner.id, Rc::downgrade(owner));
+    }
+
+    /// Recomputes the flattened slot children of the mounted components marked with
+    /// [`RuntimeContext::mark_slot_children_changed`] since this was last called, in id order
+    pub fn recompute_flattened_slot_children(&self) {
+        let owners = std::mem::take(&mut *borrow_mut!(self.changed_slot_owners));
+        for owner in owners.values().filter_map(Weak::upgrade) {
+            if *borrow!(owner.attached) > 0 {
+                owner.compute_flattened_slot_children();
+            }
+        }
+    }
+
+    /// Calls the handlers registered under `name` (tick or pre_render) on all
+    /// mounted nodes that have any, in node id order (so parents before the
+    /// children they expanded). All of them run before any node update they cause:
+    /// properties set by a handler are only seen by nodes once the engine updates them.
+    pub fn dispatch_frame_handlers(self: &Rc<Self>, name: &str) {
+        let nodes: Vec<_> = borrow!(self.node_cache)
+            .with_frame_handlers
+            .values()
+            .cloned()
+            .collect();
+        for node in nodes {
+            // might have been unmounted by an earlier handler
+            if *borrow!(node.attached) > 0 {
+                node.dispatch_lifecycle_handlers(name, self);
+            }
+        }
     }
 
     pub fn get_expanded_node_by_eid(&self, id: ExpandedNodeIdentifier) -> Option<Rc<ExpandedNode>> {
@@ -165,8 +307,9 @@ impl RuntimeContext {
                 loop {
                     if let Some(unwrapped_parent) = parent {
                         if let Some(_) = unwrapped_parent.get_clipping_size() {
+                            // clipping is by bounds, even for ancestors that can't be hit
                             ancestral_clipping_bounds_are_satisfied =
-                                (*unwrapped_parent).ray_cast_test(ray);
+                                (*unwrapped_parent).bounds_contain(ray);
                             break;
                         }
                         parent = borrow!(unwrapped_parent.render_parent).upgrade();
@@ -227,21 +370,131 @@ impl RuntimeContext {
         self.expression_table.clone()
     }
 
-    pub fn queue_custom_event(&self, source_expanded_node: Rc<ExpandedNode>, name: &'static str) {
+    /// The node that has keyboard focus, if it's still mounted
+    pub fn focused_node(&self) -> Option<Rc<ExpandedNode>> {
+        borrow!(self.focused_node)
+            .upgrade()
+            .filter(|node| *borrow!(node.attached) > 0)
+    }
+
+    /// Moves keyboard focus to `node`, or clears it, dispatching `@blur` on the
+    /// node that lost focus and then `@focus` on the one that gained it
+    pub fn focus(self: &Rc<Self>, node: Option<Rc<ExpandedNode>>) {
+        let previous = self.focused_node();
+        if previous.as_ref().map(|n| n.id) == node.as_ref().map(|n| n.id) {
+            return;
+        }
+        *borrow_mut!(self.focused_node) = node.as_ref().map(Rc::downgrade).unwrap_or_default();
+        if let Some(previous) = previous {
+            previous.run_handlers(BLUR_HANDLERS, || None, self);
+        }
+        if let Some(node) = node {
+            node.run_handlers(FOCUS_HANDLERS, || None, self);
+        }
+    }
+
+    /// Moves keyboard focus to where the pointer went down: the nearest node at
+    /// or above `hit` that is focusable or already has focus, or nowhere if
+    /// there's none, e.g. when pressing outside of any node
+    pub fn focus_on_press(self: &Rc<Self>, hit: Option<&Rc<ExpandedNode>>) {
+        let focused = self.focused_node();
+        let target = std::iter::successors(hit.cloned(), |node| node.template_parent.upgrade())
+            .find(|node| node.is_focusable() || focused.as_ref().is_some_and(|f| f.id == node.id));
+        self.focus(target);
+    }
+
+    /// Moves keyboard focus to the next node in the Tab order (or the previous
+    /// one if `backwards`), wrapping around at the ends. Nodes with a positive
+    /// `tab_index` come first in ascending order, then the ones with zero or
+    /// none in template order. Returns whether there was a node to move to.
+    pub fn focus_next(self: &Rc<Self>, backwards: bool) -> bool {
+        let Some(root) = borrow!(self.root_node).upgrade() else {
+            return false;
+        };
+        let mut tab_order = Vec::new();
+        collect_tab_order(&root, &mut tab_order);
+        // stable, so equal indices stay in template order
+        tab_order.sort_by_key(|(tab_index, _)| match tab_index {
+            0 => i64::MAX,
+            tab_index => *tab_index,
+        });
+        if tab_order.is_empty() {
+            return false;
+        }
+        let current = self
+            .focused_node()
+            .and_then(|focused| tab_order.iter().position(|(_, n)| n.id == focused.id));
+        let len = tab_order.len();
+        let next = match (current, backwards) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1,
+        };
+        let (_, node) = tab_order.swap_remove(next);
+        self.focus(Some(node));
+        true
+    }
+
+    /// The node that captured the pointer, if it's still mounted
+    pub fn pointer_capture(&self) -> Option<Rc<ExpandedNode>> {
+        borrow!(self.pointer_capture)
+            .upgrade()
+            .filter(|node| *borrow!(node.attached) > 0)
+    }
+
+    /// Routes mouse move and up events to `node` until the next mouse up, or
+    /// stops doing so if `None`
+    pub fn set_pointer_capture(&self, node: Option<&Rc<ExpandedNode>>) {
+        *borrow_mut!(self.pointer_capture) = node.map(Rc::downgrade).unwrap_or_default();
+    }
+
+    /// Stores visible to every node that doesn't have a closer store of the
+    /// same type. Can be filled before the first tick.
+    pub fn app_stores(&self) -> &StoreMap {
+        &self.app_stores
+    }
+
+    pub(crate) fn drag_state(&self) -> DragState {
+        borrow!(self.drag_state).clone()
+    }
+
+    pub(crate) fn set_drag_state(&self, drag_state: DragState) {
+        *borrow_mut!(self.drag_state) = drag_state;
+    }
+
+    pub fn queue_custom_event(
+        &self,
+        source_expanded_node: Rc<ExpandedNode>,
+        name: &'static str,
+        payload: Option<CustomEventPayload>,
+    ) {
         let mut queued_custom_events = borrow_mut!(self.queued_custom_events);
-        queued_custom_events.push((source_expanded_node, name));
+        queued_custom_events.push((source_expanded_node, name, payload));
     }
 
     pub fn flush_custom_events(self: &Rc<Self>) -> Result<(), String> {
         let mut queued_custom_event = borrow_mut!(self.queued_custom_events);
         let to_flush: Vec<_> = std::mem::take(queued_custom_event.as_mut());
-        for (target, ident) in to_flush {
-            target.dispatch_custom_event(ident, self)?;
+        drop(queued_custom_event);
+        for (target, ident, payload) in to_flush {
+            target.dispatch_custom_event(ident, payload.as_ref(), self)?;
         }
         Ok(())
     }
 }
 
+/// The nodes reachable with Tab in the subtree of `node`, with their tab index,
+/// in template order
+fn collect_tab_order(node: &Rc<ExpandedNode>, tab_order: &mut Vec<(i64, Rc<ExpandedNode>)>) {
+    if let Some(tab_index) = node.tab_index() {
+        tab_order.push((tab_index, Rc::clone(node)));
+    }
+    for child in node.children.get().iter() {
+        collect_tab_order(child, tab_order);
+    }
+}
+
 /// Data structure for a single frame of our runtime stack, including
 /// a reference to its parent frame and `properties` for
 /// runtime evaluation, e.g. of Expressions.  `RuntimePropertiesStackFrame`s also track
@@ -252,7 +505,7 @@ impl RuntimeContext {
 
 pub struct RuntimePropertiesStackFrame {
     symbols_within_frame: HashMap<String, UntypedProperty>,
-    local_stores: Rc<RefCell<HashMap<TypeId, Box<dyn Any>>>>,
+    local_stores: StoreMap,
     properties: Rc<RefCell<PaxAny>>,
     parent: Weak<RuntimePropertiesStackFrame>,
 }
@@ -307,29 +560,25 @@ impl RuntimePropertiesStackFrame {
     }
 
     pub fn insert_stack_local_store<T: Store>(&self, store: T) {
-        let type_id = TypeId::of::<T>();
-        borrow_mut!(self.local_stores).insert(type_id, Box::new(store));
+        self.local_stores.insert(store);
+    }
+
+    /// The stores pushed on this frame, leaving out the ones of its ancestors
+    pub(crate) fn own_local_stores(&self) -> &StoreMap {
+        &self.local_stores
     }
 
     pub fn peek_stack_local_store<T: Store, V>(
         self: &Rc<Self>,
         f: impl FnOnce(&mut T) -> V,
-    ) -> Result<V, String> {
+    ) -> Result<V, StoreError> {
         let mut current = Rc::clone(self);
-        let type_id = TypeId::of::<T>();
-
-        while !borrow!(current.local_stores).contains_key(&type_id) {
-            current = current
-                .parent
-                .upgrade()
-                .ok_or_else(|| format!("couldn't find store in local stack"))?;
+        while !current.local_stores.contains::<T>() {
+            current = current.parent.upgrade().ok_or(StoreError::Missing {
+                store: std::any::type_name::<T>(),
+            })?;
         }
-        let v = {
-            let mut stores = borrow_mut!(current.local_stores);
-            let store = stores.get_mut(&type_id).unwrap().downcast_mut().unwrap();
-            f(store)
-        };
-        Ok(v)
+        current.local_stores.with(f)
     }
 
     pub fn resolve_symbol_as_erased_property(&self, symbol: &str) -> Option<UntypedProperty> {
@@ -8,19 +8,24 @@ use pax_manifest::UniqueTemplateNodeIdentifier;
 use pax_message::NativeInterrupt;
 use pax_runtime_api::pax_value::PaxAny;
 use pax_runtime_api::properties::UntypedProperty;
-use pax_runtime_api::{borrow, use_RefCell};
+use pax_runtime_api::{borrow, borrow_mut, use_RefCell, Property};
 use piet::{Color, StrokeStyle};
 
-use crate::api::{Layer, Scroll};
+use crate::api::math::Point2;
+use crate::api::{Layer, Scroll, Timeline};
+use crate::node_interface::NodeLocal;
 
 use crate::{
-    ExpandedNode, ExpressionTable, HandlerRegistry, RuntimeContext, RuntimePropertiesStackFrame,
+    ExpandedNode, ExpandedNodeIdentifier, ExpressionTable, HandlerRegistry, RuntimeContext,
+    RuntimePropertiesStackFrame,
 };
 
 /// Type aliases to make it easier to work with nested Rcs and
 /// RefCells for instance nodes.
 pub type InstanceNodePtr = Rc<dyn InstanceNode>;
 pub type InstanceNodePtrList = RefCell<Vec<InstanceNodePtr>>;
+/// Builds the timelines declared in a component's `@settings`, bound to its properties
+pub type TimelinesFactory = Box<dyn Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, Timeline>>;
 
 pub struct InstantiationArgs {
     pub prototypical_common_properties_factory: Box<
@@ -39,6 +44,7 @@ pub struct InstantiationArgs {
     // Used by RuntimePropertyStackFrame to pull out struct's properties based on their names
     pub properties_scope_factory:
         Option<Box<dyn Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, UntypedProperty>>>,
+    pub timelines_factory: Option<TimelinesFactory>,
 }
 
 #[derive(Clone)]
@@ -81,9 +87,18 @@ pub trait InstanceNode {
         expanded_node: Option<&ExpandedNode>,
     ) -> std::fmt::Result;
 
-    /// Updates the expanded node, recomputing it's properties and possibly updating it's children
+    /// Updates the expanded node, recomputing it's properties and possibly updating it's children.
+    /// Called once after mount, and then on the first tick after one of the properties returned
+    /// by [`InstanceNode::update_dependencies`] (or the node's children) changed.
     fn update(self: Rc<Self>, _expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {}
 
+    /// Properties that [`InstanceNode::update`] reads, subscribed to at mount time so that
+    /// the node is only updated when one of them changed. By default the property the
+    /// node sends native patches from, if any (see [`BaseInstance::set_native_message_property`])
+    fn update_dependencies(&self, expanded_node: &Rc<ExpandedNode>) -> Vec<UntypedProperty> {
+        self.base().native_message_dependencies(expanded_node)
+    }
+
     /// Second lifecycle method during each render loop, occurs after
     /// properties have been computed, but before rendering
     /// Example use-case: perform side-effects to the drawing contexts.
@@ -174,6 +189,15 @@ pub trait InstanceNode {
     ) {
         // no-op for many
     }
+
+    /// Whether `point`, in this node's own coordinate space (px from the top-left
+    /// of its bounds, before its transform), lands on the shape this node draws.
+    /// Used by raycasting when the `hit_test` common property is `HitTest::Shape`.
+    /// `None` (the default) means this node has no shape of its own, and its
+    /// bounding box is used instead.
+    fn hit_test(&self, _expanded_node: &ExpandedNode, _point: Point2<NodeLocal>) -> Option<bool> {
+        None
+    }
 }
 
 pub struct BaseInstance {
@@ -189,8 +213,13 @@ pub struct BaseInstance {
     pub template_node_identifier: Option<UniqueTemplateNodeIdentifier>,
     pub properties_scope_factory:
         Option<Box<dyn Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, UntypedProperty>>>,
+    pub timelines_factory: Option<TimelinesFactory>,
     instance_children: InstanceNodePtrList,
     flags: InstanceFlags,
+    /// For primitives managing native elements: the properties that compute a patch
+    /// for each expanded node and send it as a native message when it changed.
+    /// Since InstanceNode -> ExpandedNode has a one to many relationship, needs to be a hashmap
+    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 pub struct InstanceFlags {
@@ -222,9 +251,47 @@ impl BaseInstance {
             flags,
             template_node_identifier: args.template_node_identifier,
             properties_scope_factory: args.properties_scope_factory,
+            timelines_factory: args.timelines_factory,
+            native_message_props: Default::default(),
         }
     }
 
+    /// Sets the property sending the native patches of `expanded_node`, usually on mount.
+    /// The node is then updated whenever it changed (see [`InstanceNode::update_dependencies`]),
+    /// and should call [`BaseInstance::send_native_messages`] from [`InstanceNode::update`].
+    pub fn set_native_message_property(
+        &self,
+        expanded_node: &ExpandedNode,
+        native_messages: Property<()>,
+    ) {
+        borrow_mut!(self.native_message_props).insert(expanded_node.id, native_messages);
+    }
+
+    /// Computes the native patch of `expanded_node`, sending it if anything changed
+    pub fn send_native_messages(&self, expanded_node: &ExpandedNode) {
+        if let Some(native_messages) = borrow!(self.native_message_props).get(&expanded_node.id) {
+            native_messages.get();
+        }
+    }
+
+    /// Drops the property sending the native patches of `expanded_node`, so that
+    /// none are sent once it's unmounted
+    pub fn remove_native_message_property(&self, expanded_node: &ExpandedNode) {
+        borrow_mut!(self.native_message_props).remove(&expanded_node.id);
+    }
+
+    /// The property sending the native patches of `expanded_node`, empty if it has none
+    pub fn native_message_dependencies(
+        &self,
+        expanded_node: &ExpandedNode,
+    ) -> Vec<UntypedProperty> {
+        borrow!(self.native_message_props)
+            .get(&expanded_node.id)
+            .map(|native_messages| native_messages.untyped())
+            .into_iter()
+            .collect()
+    }
+
     /// Returns a handle to a node-managed HandlerRegistry, a mapping between event types and handlers.
     /// Each node that can handle events is responsible for implementing this; Component instances generate
     /// the necessary code to wire up userland events like `<SomeNode @click=self.handler>`. Primitives must handle
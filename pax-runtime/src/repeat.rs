@@ -3,20 +3,25 @@ use std::iter;
 use std::rc::Rc;
 use_RefCell!();
 
-use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::pax_value::{PaxAny, PaxValue, ToFromPaxAny};
 use pax_runtime_api::properties::UntypedProperty;
 use pax_runtime_api::{borrow, borrow_mut, use_RefCell, ImplToFromPaxAny, Property};
 
 use crate::api::Layer;
 use crate::{
     BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
+    RuntimePropertiesStackFrame,
 };
 
 /// A special "control-flow" primitive associated with the `for` statement.
 /// Repeat allows for nodes to be rendered dynamically per data specified in `source_expression`.
 /// That is: for a `source_expression` of length `n`, `Repeat` will render its
 /// template `n` times, each with an embedded component context (`RepeatItem`)
-/// with an index `i` and a pointer to that relevant datum `source_expression[i]`
+/// with an index `i` and a pointer to that relevant datum `source_expression[i]`.
+/// When a `key` expression is provided (`for item in self.items key=item.id`), children
+/// are reconciled by key: existing children are reused (keeping their state) and only
+/// added/removed entries are mounted/unmounted.  Keys must evaluate to a builtin value
+/// such as a string or a number, else the element's index is used, with a warning.
 pub struct RepeatInstance {
     pub base: BaseInstance,
 }
@@ -30,8 +35,13 @@ pub struct RepeatProperties {
     pub source_expression_range: Option<Property<std::ops::Range<isize>>>,
     pub iterator_i_symbol: Option<String>,
     pub iterator_elem_symbol: Option<String>,
+    pub key_expression: Option<RepeatKeyExpression>,
 }
 
+/// Evaluates the `key` expression for an element, given a stack frame with that element's
+/// `RepeatItem` pushed
+pub type RepeatKeyExpression = Rc<dyn Fn(&Rc<RuntimePropertiesStackFrame>) -> PaxAny>;
+
 impl ImplToFromPaxAny for RepeatItem {}
 
 pub struct RepeatItem {
@@ -39,6 +49,62 @@ pub struct RepeatItem {
     pub i: Property<usize>,
 }
 
+/// The children generated for a single keyed element of the source, retained
+/// across source changes so they can be reused when the key reappears
+struct KeyedChildren {
+    key: RepeatKey,
+    i: Property<usize>,
+    elem: Property<Option<Rc<RefCell<PaxAny>>>>,
+    children: Vec<Rc<ExpandedNode>>,
+}
+
+/// The key children are reconciled by, tagged with its kind so that e.g. the
+/// string `"1"` and the number `1` don't collide
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum RepeatKey {
+    Str(String),
+    /// Any other builtin value, by its debug representation
+    Other(String),
+    /// The index of an element whose key isn't a builtin value
+    Index(usize),
+}
+
+/// The key of an element, if the key expression evaluated to a builtin value
+fn repeat_key(value: PaxAny) -> Option<RepeatKey> {
+    match value {
+        PaxAny::Builtin(PaxValue::String(key)) => Some(RepeatKey::Str(key)),
+        PaxAny::Builtin(key) => Some(RepeatKey::Other(format!("{:?}", key))),
+        PaxAny::Any(_) => None,
+    }
+}
+
+/// Pushes the `RepeatItem` for one element of the source onto `stack`, binding
+/// the iterator symbols (`i` / `elem`) declared in the `for` statement
+fn push_repeat_item(
+    stack: &Rc<RuntimePropertiesStackFrame>,
+    i_symbol: &Option<String>,
+    elem_symbol: &Option<String>,
+    property_i: &Property<usize>,
+    property_elem: &Property<Option<Rc<RefCell<PaxAny>>>>,
+) -> Rc<RuntimePropertiesStackFrame> {
+    let new_repeat_item = Rc::new(RefCell::new(
+        RepeatItem {
+            i: property_i.clone(),
+            elem: property_elem.clone(),
+        }
+        .to_pax_any(),
+    ));
+
+    let mut scope: HashMap<String, UntypedProperty> = HashMap::new();
+    if let Some(ref i_symbol) = i_symbol {
+        scope.insert(i_symbol.clone(), property_i.untyped());
+    }
+    if let Some(ref elem_symbol) = elem_symbol {
+        scope.insert(elem_symbol.clone(), property_elem.untyped());
+    }
+    stack.push(scope, &new_repeat_item)
+}
+
 impl InstanceNode for RepeatInstance {
     fn instantiate(args: InstantiationArgs) -> Rc<Self>
     where
@@ -112,9 +178,15 @@ impl InstanceNode for RepeatInstance {
                 properties.iterator_elem_symbol.clone()
             });
 
+        let key_expression =
+            expanded_node.with_properties_unwrapped(|properties: &mut RepeatProperties| {
+                properties.key_expression.clone()
+            });
+
         let deps = [source_expression.untyped()];
 
         let last_length = Rc::new(RefCell::new(0));
+        let keyed_children: Rc<RefCell<Vec<KeyedChildren>>> = Default::default();
 
         expanded_node
             .children
@@ -124,12 +196,69 @@ impl InstanceNode for RepeatInstance {
                         panic!("ran evaluator after expanded node dropped (repeat elem)")
                     };
                     let source = source_expression.get();
+                    let template_children = cloned_self.base().get_instance_children();
+                    if let Some(ref key_expression) = key_expression {
+                        let mut previous: HashMap<RepeatKey, KeyedChildren> =
+                            borrow_mut!(keyed_children)
+                                .drain(..)
+                                .map(|entry| (entry.key.clone(), entry))
+                                .collect();
+                        let mut next = Vec::with_capacity(source.len());
+                        let mut warned = false;
+                        for (i, elem) in source.iter().enumerate() {
+                            let property_i = Property::new(i);
+                            let property_elem = Property::new(Some(Rc::clone(elem)));
+                            let new_env = push_repeat_item(
+                                &cloned_expanded_node.stack,
+                                &i_symbol,
+                                &elem_symbol,
+                                &property_i,
+                                &property_elem,
+                            );
+                            // elements without a usable key are reconciled by index
+                            let key = repeat_key(key_expression(&new_env)).unwrap_or_else(|| {
+                                if !std::mem::replace(&mut warned, true) {
+                                    log::warn!(
+                                        "repeat key must evaluate to a builtin value, such as a string or a number; falling back to the index"
+                                    );
+                                }
+                                RepeatKey::Index(i)
+                            });
+                            // duplicate keys fall through to fresh children, since the
+                            // first occurrence already claimed the previous entry
+                            let entry = if let Some(existing) = previous.remove(&key) {
+                                existing.i.set(i);
+                                existing.elem.set(Some(Rc::clone(elem)));
+                                existing
+                            } else {
+                                let children = cloned_expanded_node.create_children_detached(
+                                    borrow!(template_children)
+                                        .iter()
+                                        .map(|child| (Rc::clone(child), Rc::clone(&new_env))),
+                                    &cloned_context,
+                                    &Rc::downgrade(&cloned_expanded_node),
+                                );
+                                KeyedChildren {
+                                    key,
+                                    i: property_i,
+                                    elem: property_elem,
+                                    children,
+                                }
+                            };
+                            next.push(entry);
+                        }
+                        let children = next
+                            .iter()
+                            .flat_map(|entry| entry.children.iter().cloned())
+                            .collect();
+                        *borrow_mut!(keyed_children) = next;
+                        return cloned_expanded_node.attach_children(children, &cloned_context);
+                    }
                     let source_len = source.len();
                     if source_len == *borrow!(last_length) {
                         return cloned_expanded_node.children.get();
                     }
                     *borrow_mut!(last_length) = source_len;
-                    let template_children = cloned_self.base().get_instance_children();
                     let children_with_envs = iter::repeat(template_children)
                         .take(source_len)
                         .enumerate()
@@ -146,23 +275,13 @@ impl InstanceNode for RepeatInstance {
                                 &[source_expression.untyped()],
                                 "repeat elem",
                             );
-                            let new_repeat_item = Rc::new(RefCell::new(
-                                RepeatItem {
-                                    i: property_i.clone(),
-                                    elem: property_elem.clone(),
-                                }
-                                .to_pax_any(),
-                            ));
-
-                            let mut scope: HashMap<String, UntypedProperty> = HashMap::new();
-                            if let Some(ref i_symbol) = i_symbol {
-                                scope.insert(i_symbol.clone(), property_i.untyped());
-                            }
-                            if let Some(ref elem_symbol) = elem_symbol {
-                                scope.insert(elem_symbol.clone(), property_elem.untyped());
-                            }
-
-                            let new_env = cloned_expanded_node.stack.push(scope, &new_repeat_item);
+                            let new_env = push_repeat_item(
+                                &cloned_expanded_node.stack,
+                                &i_symbol,
+                                &elem_symbol,
+                                &property_i,
+                                &property_elem,
+                            );
                             borrow!(children)
                                 .clone()
                                 .into_iter()
@@ -1,6 +1,7 @@
 use std::rc::{Rc, Weak};
 use_RefCell!();
 
+use pax_runtime_api::properties::UntypedProperty;
 use pax_runtime_api::{use_RefCell, ImplToFromPaxAny, Numeric, Property};
 
 use crate::api::Layer;
@@ -27,7 +28,8 @@ impl ImplToFromPaxAny for Slot {}
 ///Contains the index value for slot, either a literal or an expression.
 #[derive(Default)]
 pub struct Slot {
-    // HACK: these two properties are being used in update:
+    // HACK: these two properties are being used in update (re-run when index
+    // or the flattened slot children of the containing component change):
     pub index: Property<Numeric>,
     pub last_node_id: Property<usize>,
     // to compute this:
@@ -105,6 +107,19 @@ impl InstanceNode for SlotInstance {
         });
     }
 
+    fn update_dependencies(&self, expanded_node: &Rc<ExpandedNode>) -> Vec<UntypedProperty> {
+        let containing = expanded_node
+            .containing_component
+            .upgrade()
+            .expect("slot to have a containing component");
+        let index = expanded_node
+            .with_properties_unwrapped(|properties: &mut Slot| properties.index.clone());
+        vec![
+            index.untyped(),
+            containing.expanded_and_flattened_slot_children.untyped(),
+        ]
+    }
+
     fn resolve_debug(
         &self,
         f: &mut std::fmt::Formatter,
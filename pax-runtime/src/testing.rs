@@ -0,0 +1,565 @@
+//! Harness for writing `cargo test` assertions against a running [`PaxEngine`].
+//!
+//! A [`TestApp`] owns an engine instantiated from a main component (typically the one
+//! produced by a cartridge's `DefinitionToInstanceTraverser::get_main_component`), lets
+//! tests inject [`NativeInterrupt`]s either at window coordinates or against nodes
+//! looked up by their `id` common property, advance ticks, and then inspect node properties
+//! and the stream of [`NativeMessage`]s the engine emitted.
+//!
+//! ```ignore
+//! let mut app = TestApp::new(main_component, expression_table, (800.0, 600.0));
+//! app.tick();
+//! app.click("increment")?;
+//! app.tick();
+//! assert_eq!(app.with_properties("counter", |c: &mut Counter| c.count.get())?, 1);
+//! ```
+//!
+//! Tests of the runtime itself, which have no cartridge to instantiate, can build
+//! templates out of [`TestNode`]s instead:
+//!
+//! ```ignore
+//! let main = TestNode::new().component(vec![
+//!     TestNode::new().id("leaf").size(50.0, 50.0).on(CLICK_HANDLERS, on_click).leaf(),
+//! ]);
+//! let mut app = TestApp::new(main, ExpressionTable::new(), (200.0, 200.0));
+//! ```
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use pax_message::{
+    ClickInterruptArgs, FormButtonClickArgs, FormCheckboxToggleArgs, FormSliderChangeArgs,
+    FormTextboxChangeArgs, FormTextboxInputArgs, KeyDownInterruptArgs, KeyPressInterruptArgs,
+    KeyUpInterruptArgs, ModifierKeyMessage, MouseButtonMessage, MouseDownInterruptArgs,
+    MouseMoveInterruptArgs, MouseUpInterruptArgs, NativeInterrupt, NativeMessage,
+    TouchEndInterruptArgs, TouchMessage, TouchMoveInterruptArgs, TouchStartInterruptArgs,
+};
+use pax_runtime_api::math::Point2;
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::properties::UntypedProperty;
+use pax_runtime_api::{
+    use_RefCell, CommonProperties, Layer, Platform, Property, Size, Timeline, Window, OS,
+};
+use_RefCell!();
+
+use crate::api::NodeContext;
+use crate::node_interface::{NodeInterface, NodeLocal};
+use crate::{
+    BaseInstance, ComponentInstance, ExpandedNode, ExpressionTable, Handler, HandlerRegistry,
+    InstanceFlags, InstanceNode, InstantiationArgs, PaxEngine, RuntimePropertiesStackFrame,
+    TimelinesFactory,
+};
+
+pub struct TestApp {
+    engine: PaxEngine,
+    native_messages: Vec<NativeMessage>,
+}
+
+impl TestApp {
+    pub fn new(
+        main_component_instance: Rc<ComponentInstance>,
+        expression_table: ExpressionTable,
+        viewport_size: (f64, f64),
+    ) -> Self {
+        let engine = PaxEngine::new(
+            main_component_instance,
+            expression_table,
+            viewport_size,
+            Platform::Unknown,
+            OS::default(),
+        );
+        Self {
+            engine,
+            native_messages: Vec::new(),
+        }
+    }
+
+    pub fn engine(&self) -> &PaxEngine {
+        &self.engine
+    }
+
+    pub fn engine_mut(&mut self) -> &mut PaxEngine {
+        &mut self.engine
+    }
+
+    /// Advances the engine by one tick, recording any emitted native messages
+    pub fn tick(&mut self) {
+        let messages = self.engine.tick();
+        self.native_messages.extend(messages);
+    }
+
+    pub fn tick_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    /// All native messages emitted since the app was created or last drained
+    pub fn native_messages(&self) -> &[NativeMessage] {
+        &self.native_messages
+    }
+
+    pub fn take_native_messages(&mut self) -> Vec<NativeMessage> {
+        std::mem::take(&mut self.native_messages)
+    }
+
+    /// Dispatches a raw interrupt, exactly as a chassis would. Returns whether the
+    /// default behavior of the originating platform event was prevented.
+    pub fn interrupt(&mut self, interrupt: NativeInterrupt) -> bool {
+        self.engine.handle_native_interrupt(&interrupt)
+    }
+
+    pub fn root(&self) -> NodeInterface {
+        Rc::clone(&self.engine.root_node).into()
+    }
+
+    /// All nodes whose `id` common property equals `id`
+    pub fn nodes(&self, id: &str) -> Vec<NodeInterface> {
+        self.expanded_nodes(id)
+            .into_iter()
+            .map(NodeInterface::from)
+            .collect()
+    }
+
+    /// The single node whose `id` common property equals `id`
+    pub fn node(&self, id: &str) -> Result<NodeInterface, String> {
+        self.expanded_node(id).map(NodeInterface::from)
+    }
+
+    pub fn with_properties<T: ToFromPaxAny, V>(
+        &self,
+        id: &str,
+        f: impl FnOnce(&mut T) -> V,
+    ) -> Result<V, String> {
+        let node = self.expanded_node(id)?;
+        node.try_with_properties_unwrapped(f)
+            .ok_or_else(|| format!("node with id `{}` has different properties type", id))
+    }
+
+    /// Left-clicks at the given window coordinates
+    pub fn click_at(&mut self, x: f64, y: f64) -> bool {
+        self.interrupt(NativeInterrupt::Click(ClickInterruptArgs {
+            x,
+            y,
+            button: MouseButtonMessage::Left,
+            modifiers: vec![],
+        }))
+    }
+
+    /// Left-clicks at the center of the node with the given id. As with a real pointer,
+    /// the click is raycast, so it lands on whatever is topmost at that point.
+    pub fn click(&mut self, id: &str) -> Result<bool, String> {
+        let center = self.center_of(id)?;
+        Ok(self.click_at(center.x, center.y))
+    }
+
+    /// Presses the left button at the given window coordinates
+    pub fn mouse_down_at(&mut self, x: f64, y: f64) -> bool {
+        self.interrupt(NativeInterrupt::MouseDown(MouseDownInterruptArgs {
+            x,
+            y,
+            button: MouseButtonMessage::Left,
+            modifiers: vec![],
+        }))
+    }
+
+    /// Moves the cursor to the given window coordinates, with the left button pressed
+    pub fn mouse_move_to(&mut self, x: f64, y: f64) -> bool {
+        self.interrupt(NativeInterrupt::MouseMove(MouseMoveInterruptArgs {
+            x,
+            y,
+            button: MouseButtonMessage::Left,
+            modifiers: vec![],
+        }))
+    }
+
+    /// Releases the left button at the given window coordinates
+    pub fn mouse_up_at(&mut self, x: f64, y: f64) -> bool {
+        self.interrupt(NativeInterrupt::MouseUp(MouseUpInterruptArgs {
+            x,
+            y,
+            button: MouseButtonMessage::Left,
+            modifiers: vec![],
+        }))
+    }
+
+    /// Presses the left button over the center of the node with the given id, drags
+    /// to the center of the node with id `to` and releases it there
+    pub fn drag(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let from = self.center_of(from)?;
+        let to = self.center_of(to)?;
+        self.mouse_down_at(from.x, from.y);
+        self.mouse_move_to(to.x, to.y);
+        self.mouse_up_at(to.x, to.y);
+        Ok(())
+    }
+
+    /// Puts down touches, given as `(identifier, x, y)` in window coordinates
+    pub fn touch_start(&mut self, touches: &[(i64, f64, f64)]) -> bool {
+        self.interrupt(NativeInterrupt::TouchStart(TouchStartInterruptArgs {
+            touches: touch_messages(touches),
+        }))
+    }
+
+    /// Moves touches, given as `(identifier, x, y)` in window coordinates
+    pub fn touch_move(&mut self, touches: &[(i64, f64, f64)]) -> bool {
+        self.interrupt(NativeInterrupt::TouchMove(TouchMoveInterruptArgs {
+            touches: touch_messages(touches),
+        }))
+    }
+
+    /// Lifts touches, given as `(identifier, x, y)` in window coordinates
+    pub fn touch_end(&mut self, touches: &[(i64, f64, f64)]) -> bool {
+        self.interrupt(NativeInterrupt::TouchEnd(TouchEndInterruptArgs {
+            touches: touch_messages(touches),
+        }))
+    }
+
+    pub fn key_down(&mut self, key: &str) -> bool {
+        self.interrupt(NativeInterrupt::KeyDown(KeyDownInterruptArgs {
+            key: key.to_owned(),
+            modifiers: vec![],
+            is_repeat: false,
+        }))
+    }
+
+    pub fn key_up(&mut self, key: &str) -> bool {
+        self.interrupt(NativeInterrupt::KeyUp(KeyUpInterruptArgs {
+            key: key.to_owned(),
+            modifiers: vec![],
+            is_repeat: false,
+        }))
+    }
+
+    pub fn key_press(&mut self, key: &str) -> bool {
+        self.interrupt(NativeInterrupt::KeyPress(KeyPressInterruptArgs {
+            key: key.to_owned(),
+            modifiers: vec![],
+            is_repeat: false,
+        }))
+    }
+
+    /// Presses Tab, or Shift-Tab if `backwards`, moving focus through the Tab order
+    pub fn tab(&mut self, backwards: bool) -> bool {
+        let modifiers = if backwards {
+            vec![ModifierKeyMessage::Shift]
+        } else {
+            vec![]
+        };
+        self.interrupt(NativeInterrupt::KeyDown(KeyDownInterruptArgs {
+            key: "Tab".to_owned(),
+            modifiers,
+            is_repeat: false,
+        }))
+    }
+
+    /// The node that has keyboard focus, if any
+    pub fn focused(&self) -> Option<NodeInterface> {
+        self.engine
+            .runtime_context
+            .focused_node()
+            .map(NodeInterface::from)
+    }
+
+    /// Sends the full down/press/up sequence for `key`
+    pub fn type_key(&mut self, key: &str) {
+        self.key_down(key);
+        self.key_press(key);
+        self.key_up(key);
+    }
+
+    pub fn textbox_input(&mut self, id: &str, text: &str) -> Result<bool, String> {
+        let id = self.native_id(id)?;
+        Ok(
+            self.interrupt(NativeInterrupt::FormTextboxInput(FormTextboxInputArgs {
+                text: text.to_owned(),
+                id,
+            })),
+        )
+    }
+
+    pub fn textbox_change(&mut self, id: &str, text: &str) -> Result<bool, String> {
+        let id = self.native_id(id)?;
+        Ok(
+            self.interrupt(NativeInterrupt::FormTextboxChange(FormTextboxChangeArgs {
+                text: text.to_owned(),
+                id,
+            })),
+        )
+    }
+
+    pub fn slider_change(&mut self, id: &str, value: f64) -> Result<bool, String> {
+        let id = self.native_id(id)?;
+        Ok(
+            self.interrupt(NativeInterrupt::FormSliderChange(FormSliderChangeArgs {
+                id,
+                value,
+            })),
+        )
+    }
+
+    pub fn checkbox_toggle(&mut self, id: &str, state: bool) -> Result<bool, String> {
+        let id = self.native_id(id)?;
+        Ok(self.interrupt(NativeInterrupt::FormCheckboxToggle(
+            FormCheckboxToggleArgs { state, id },
+        )))
+    }
+
+    pub fn button_click(&mut self, id: &str) -> Result<bool, String> {
+        let id = self.native_id(id)?;
+        Ok(self.interrupt(NativeInterrupt::FormButtonClick(FormButtonClickArgs { id })))
+    }
+
+    fn expanded_nodes(&self, id: &str) -> Vec<Rc<ExpandedNode>> {
+        self.engine.runtime_context.get_expanded_nodes_by_id(id)
+    }
+
+    fn expanded_node(&self, id: &str) -> Result<Rc<ExpandedNode>, String> {
+        let mut nodes = self.expanded_nodes(id);
+        match nodes.len() {
+            0 => Err(format!("no node with id `{}`", id)),
+            1 => Ok(nodes.remove(0)),
+            n => Err(format!(
+                "{} nodes with id `{}`, expected exactly one",
+                n, id
+            )),
+        }
+    }
+
+    /// The identifier native elements are addressed by in interrupts
+    fn native_id(&self, id: &str) -> Result<u32, String> {
+        Ok(self.expanded_node(id)?.id.to_u32())
+    }
+
+    fn center_of(&self, id: &str) -> Result<Point2<Window>, String> {
+        let t_and_b = self.expanded_node(id)?.transform_and_bounds.get();
+        let (width, height) = t_and_b.bounds;
+        Ok(t_and_b.transform * Point2::<NodeLocal>::new(width / 2.0, height / 2.0))
+    }
+}
+
+fn touch_messages(touches: &[(i64, f64, f64)]) -> Vec<TouchMessage> {
+    touches
+        .iter()
+        .map(|&(identifier, x, y)| TouchMessage {
+            x,
+            y,
+            identifier,
+            delta_x: 0.0,
+            delta_y: 0.0,
+        })
+        .collect()
+}
+
+type CommonPropertiesSetter = Box<dyn Fn(&Rc<RuntimePropertiesStackFrame>, &mut CommonProperties)>;
+type PropertiesFactory = Box<dyn Fn(&Rc<RuntimePropertiesStackFrame>) -> PaxAny>;
+type ScopeFactory = Box<dyn Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, UntypedProperty>>;
+
+/// Builds the instance nodes of a test template, standing in for the code a
+/// cartridge generates for each template node
+#[derive(Default)]
+pub struct TestNode {
+    common_properties: Vec<CommonPropertiesSetter>,
+    properties: Option<PropertiesFactory>,
+    handlers: HashMap<String, Vec<Handler>>,
+    custom_events: HashMap<String, Option<TypeId>>,
+    children: Option<Vec<Rc<dyn InstanceNode>>>,
+    properties_scope: Option<ScopeFactory>,
+    timelines: Option<TimelinesFactory>,
+}
+
+impl TestNode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(self, id: &str) -> Self {
+        let id = id.to_string();
+        self.common(move |_, common| common.id = Property::new(Some(id.clone())))
+    }
+
+    /// Sets the width and height, in pixels
+    pub fn size(self, width: f64, height: f64) -> Self {
+        self.common(move |_, common| {
+            common.width = Property::new(Some(Size::Pixels(width.into())));
+            common.height = Property::new(Some(Size::Pixels(height.into())));
+        })
+    }
+
+    /// Sets x and y, in pixels
+    pub fn position(self, x: f64, y: f64) -> Self {
+        self.common(move |_, common| {
+            common.x = Property::new(Some(Size::Pixels(x.into())));
+            common.y = Property::new(Some(Size::Pixels(y.into())));
+        })
+    }
+
+    /// Sets any other common properties, possibly from the properties in scope
+    pub fn common(
+        mut self,
+        set: impl Fn(&Rc<RuntimePropertiesStackFrame>, &mut CommonProperties) + 'static,
+    ) -> Self {
+        self.common_properties.push(Box::new(set));
+        self
+    }
+
+    /// The properties of the node, `()` if not set
+    pub fn properties<T: ToFromPaxAny>(
+        mut self,
+        factory: impl Fn(&Rc<RuntimePropertiesStackFrame>) -> T + 'static,
+    ) -> Self {
+        self.properties = Some(Box::new(move |stack| factory(stack).to_pax_any()));
+        self
+    }
+
+    /// Binds an inline handler, like `@event=handler` in a template
+    pub fn on(
+        self,
+        event: &str,
+        handler: fn(Rc<RefCell<PaxAny>>, &NodeContext, Option<PaxAny>),
+    ) -> Self {
+        self.handler(event, Handler::new_inline_handler(handler))
+    }
+
+    /// Like [`TestNode::on`], for a handler downcasting its arguments to `T`
+    pub fn on_with<T: 'static>(
+        self,
+        event: &str,
+        handler: fn(Rc<RefCell<PaxAny>>, &NodeContext, Option<PaxAny>),
+    ) -> Self {
+        self.handler(
+            event,
+            Handler::new_inline_handler(handler).with_args_type(Some(TypeId::of::<T>())),
+        )
+    }
+
+    /// Declares the custom event `event`, like `#[events(event)]` on a component
+    pub fn event(mut self, event: &str) -> Self {
+        self.custom_events.insert(event.to_string(), None);
+        self
+    }
+
+    /// Declares the custom event `event` carrying a `T`, like `#[events(event: T)]`
+    pub fn event_with<T: 'static>(mut self, event: &str) -> Self {
+        self.custom_events
+            .insert(event.to_string(), Some(TypeId::of::<T>()));
+        self
+    }
+
+    pub fn handler(mut self, event: &str, handler: Handler) -> Self {
+        self.handlers
+            .entry(event.to_string())
+            .or_default()
+            .push(handler);
+        self
+    }
+
+    pub fn children(mut self, children: Vec<Rc<dyn InstanceNode>>) -> Self {
+        self.children = Some(children);
+        self
+    }
+
+    /// The properties a component exposes to its template, none if not set
+    pub fn properties_scope(
+        mut self,
+        factory: impl Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, UntypedProperty> + 'static,
+    ) -> Self {
+        self.properties_scope = Some(Box::new(factory));
+        self
+    }
+
+    /// The timelines a component declares in its `@settings`
+    pub fn timelines(
+        mut self,
+        factory: impl Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, Timeline> + 'static,
+    ) -> Self {
+        self.timelines = Some(Box::new(factory));
+        self
+    }
+
+    /// Instantiates a [`TestLeafInstance`], a node without behavior of its own
+    pub fn leaf(self) -> Rc<dyn InstanceNode> {
+        TestLeafInstance::instantiate(self.args(None))
+    }
+
+    /// Instantiates a component with `template` as its template
+    pub fn component(mut self, template: Vec<Rc<dyn InstanceNode>>) -> Rc<ComponentInstance> {
+        if self.properties_scope.is_none() {
+            self.properties_scope = Some(Box::new(|_| HashMap::new()));
+        }
+        ComponentInstance::instantiate(self.args(Some(template)))
+    }
+
+    /// Instantiates any other kind of node, e.g. a `ConditionalInstance`
+    pub fn instantiate<T: InstanceNode>(self) -> Rc<T> {
+        T::instantiate(self.args(None))
+    }
+
+    pub fn args(self, component_template: Option<Vec<Rc<dyn InstanceNode>>>) -> InstantiationArgs {
+        let common_properties = self.common_properties;
+        let properties = self.properties;
+        InstantiationArgs {
+            prototypical_common_properties_factory: Box::new(move |stack, _| {
+                let mut common = CommonProperties::default();
+                for set in &common_properties {
+                    set(&stack, &mut common);
+                }
+                Rc::new(RefCell::new(common))
+            }),
+            prototypical_properties_factory: Box::new(move |stack, _| {
+                let properties = match &properties {
+                    Some(factory) => factory(&stack),
+                    None => ().to_pax_any(),
+                };
+                Rc::new(RefCell::new(properties))
+            }),
+            handler_registry: (!self.handlers.is_empty() || !self.custom_events.is_empty()).then(
+                || {
+                    Rc::new(RefCell::new(HandlerRegistry {
+                        handlers: self.handlers,
+                        custom_events: self.custom_events,
+                    }))
+                },
+            ),
+            children: self.children.map(RefCell::new),
+            component_template: component_template.map(RefCell::new),
+            template_node_identifier: None,
+            properties_scope_factory: self.properties_scope,
+            timelines_factory: self.timelines,
+        }
+    }
+}
+
+/// A canvas node that draws nothing, but can be sized, hit and handle events
+pub struct TestLeafInstance {
+    base: BaseInstance,
+}
+
+impl InstanceNode for TestLeafInstance {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Canvas,
+                    is_component: false,
+                },
+            ),
+        })
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("TestLeaf").finish()
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+}
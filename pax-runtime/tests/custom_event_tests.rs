@@ -0,0 +1,125 @@
+use std::rc::Rc;
+
+use pax_runtime::api::NodeContext;
+use pax_runtime::constants::MOUNT_HANDLERS;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ExpressionTable, InstanceNode};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::use_RefCell;
+use_RefCell!();
+
+mod support;
+use support::{mount, record, taken};
+
+/// `@mount` of the leaf in the template of `Picker`
+fn pick(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    ctx.dispatch_event_with("picked", 7usize).unwrap();
+    ctx.dispatch_event("closed").unwrap();
+    assert!(ctx.dispatch_event_with("unknown", 1usize).is_err());
+}
+
+/// `@mount` of the leaf in the template of `Picker`, dispatching with the wrong payloads
+fn pick_wrongly(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    let errors = [
+        ctx.dispatch_event("picked"),
+        ctx.dispatch_event_with("picked", 7.0),
+        ctx.dispatch_event_with("closed", 7usize),
+    ];
+    record(format!("{:?}", errors));
+}
+
+/// `@mount` of the leaf in the template of `Picker`, when nothing is bound to `picked`
+fn pick_unbound(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    let results = [
+        ctx.dispatch_event_with("picked", 7usize),
+        ctx.dispatch_event_with("picked", 7.0),
+        ctx.dispatch_event("closed"),
+    ];
+    record(format!("{:?}", results));
+}
+
+/// `<Picker @picked=on_picked @picked=on_picked_again @closed=on_closed/>`
+fn on_picked(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let index = usize::from_pax_any(args.expect("picked has a payload")).unwrap();
+    record(format!("picked {}", index));
+}
+
+fn on_picked_again(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let index = usize::from_pax_any(args.expect("picked has a payload")).unwrap();
+    record(format!("picked again {}", index));
+}
+
+fn on_closed(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    assert!(args.is_none());
+    record("closed");
+}
+
+#[test]
+fn custom_events_pass_their_payload_to_each_bound_handler() {
+    let picker = TestNode::new()
+        .event_with::<usize>("picked")
+        .event("closed")
+        .on_with::<usize>("picked", on_picked)
+        .on_with::<usize>("picked", on_picked_again)
+        .on("closed", on_closed)
+        .component(vec![TestNode::new().on(MOUNT_HANDLERS, pick).leaf()]);
+    let main = TestNode::new().component(vec![picker as Rc<dyn InstanceNode>]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (100.0, 100.0));
+
+    // queued when dispatched, handled at the end of the tick
+    assert!(taken().is_empty());
+    app.tick();
+    assert_eq!(taken(), vec!["picked 7", "picked again 7", "closed"]);
+}
+
+#[test]
+fn custom_events_are_only_dispatched_with_their_declared_payload() {
+    let picker = TestNode::new()
+        .event_with::<usize>("picked")
+        .event("closed")
+        .on_with::<usize>("picked", on_picked)
+        .on("closed", on_closed)
+        .component(vec![TestNode::new()
+            .on(MOUNT_HANDLERS, pick_wrongly)
+            .leaf()]);
+    mount(
+        TestNode::new().component(vec![picker as Rc<dyn InstanceNode>]),
+        (100.0, 100.0),
+    );
+
+    // none of the handlers ran
+    let errors = [
+        Err::<(), _>(
+            "custom event \"picked\" carries a payload, dispatch it with dispatch_event_with"
+                .to_string(),
+        ),
+        Err("custom event \"picked\" doesn't carry a f64 payload".to_string()),
+        Err("custom event \"closed\" doesn't carry a usize payload".to_string()),
+    ];
+    assert_eq!(taken(), vec![format!("{:?}", errors)]);
+}
+
+#[test]
+fn custom_events_without_bound_handlers_are_checked_then_dropped() {
+    let picker = TestNode::new()
+        .event_with::<usize>("picked")
+        .event("closed")
+        .on("closed", on_closed)
+        .component(vec![TestNode::new()
+            .on(MOUNT_HANDLERS, pick_unbound)
+            .leaf()]);
+    let main = TestNode::new().component(vec![picker as Rc<dyn InstanceNode>]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (100.0, 100.0));
+    app.tick();
+
+    // the payload is checked against the declared type, not a bound handler
+    let results = [
+        Ok(()),
+        Err("custom event \"picked\" doesn't carry a f64 payload".to_string()),
+        Ok(()),
+    ];
+    assert_eq!(
+        taken(),
+        vec![format!("{:?}", results), "closed".to_string()]
+    );
+}
@@ -0,0 +1,60 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{NodeContext, Property};
+use pax_runtime::constants::MOUNT_HANDLERS;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ConditionalInstance, ConditionalProperties, ExpressionTable, InstanceNode};
+use pax_runtime_api::pax_value::PaxAny;
+use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
+use_RefCell!();
+
+thread_local! {
+    static SOURCE: Property<usize> = Property::new(0);
+    static SEEN: RefCell<Vec<usize>> = RefCell::new(vec![]);
+}
+
+fn subscribe_to_source(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    let source = SOURCE.with(|s| s.clone());
+    ctx.subscribe(&source, |v| SEEN.with(|seen| borrow_mut!(seen).push(v)));
+}
+
+/// `if shown { <Leaf @mount=subscribe_to_source/> }`
+fn build_app(shown: &Property<bool>) -> TestApp {
+    let shown = shown.clone();
+    let conditional = TestNode::new()
+        .properties(move |_| ConditionalProperties {
+            boolean_expression: shown.clone(),
+        })
+        .children(vec![TestNode::new()
+            .on(MOUNT_HANDLERS, subscribe_to_source)
+            .leaf()])
+        .instantiate::<ConditionalInstance>();
+    let main = TestNode::new().component(vec![conditional as Rc<dyn InstanceNode>]);
+    TestApp::new(main, ExpressionTable::new(), (100.0, 100.0))
+}
+
+fn seen() -> Vec<usize> {
+    SEEN.with(|seen| borrow!(seen).clone())
+}
+
+#[test]
+fn node_subscriptions_are_removed_on_unmount() {
+    let shown = Property::new(true);
+    let mut app = build_app(&shown);
+    app.tick();
+
+    SOURCE.with(|s| s.set(1));
+    SOURCE.with(|s| s.set(2));
+    assert_eq!(seen(), vec![1, 2]);
+
+    shown.set(false);
+    app.tick();
+    SOURCE.with(|s| s.set(3));
+    assert_eq!(seen(), vec![1, 2]);
+
+    // mounting again subscribes again
+    shown.set(true);
+    app.tick();
+    SOURCE.with(|s| s.set(4));
+    assert_eq!(seen(), vec![1, 2, 4]);
+}
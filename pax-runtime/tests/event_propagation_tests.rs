@@ -0,0 +1,158 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{Click, Event, EventPhase, NodeContext};
+use pax_runtime::constants::{CAPTURE_HANDLERS_SUFFIX, CLICK_HANDLERS};
+use pax_runtime::node_interface::{EventTarget, NodeInterface};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{Handler, InstanceNode};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::{borrow_mut, use_RefCell};
+use_RefCell!();
+
+mod support;
+use support::{mount, HandlerFn};
+
+/// (handler, phase, target, current_target) of each handler call
+type Received = (
+    &'static str,
+    EventPhase,
+    Option<NodeInterface>,
+    Option<NodeInterface>,
+);
+
+thread_local! {
+    static RECEIVED: RefCell<Vec<Received>> = RefCell::new(vec![]);
+}
+
+fn record(handler: &'static str, args: Option<PaxAny>) -> Event<Click> {
+    let event = Event::<Click>::from_pax_any(args.expect("click has args")).unwrap();
+    RECEIVED.with(|received| {
+        borrow_mut!(received).push((
+            handler,
+            event.phase(),
+            event.target(),
+            event.current_target(),
+        ))
+    });
+    event
+}
+
+fn taken() -> Vec<Received> {
+    RECEIVED.with(|received| std::mem::take(&mut *borrow_mut!(received)))
+}
+
+fn card_capture(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    record("card capture", args);
+}
+
+fn card_capture_and_stop(
+    _properties: Rc<RefCell<PaxAny>>,
+    _ctx: &NodeContext,
+    args: Option<PaxAny>,
+) {
+    record("card capture", args).stop_propagation();
+}
+
+fn card_bubble(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    record("card bubble", args);
+}
+
+fn leaf_click(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    record("leaf", args);
+}
+
+fn leaf_click_and_stop(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    record("leaf", args).stop_propagation();
+}
+
+fn leaf_click_and_prevent_default(
+    _properties: Rc<RefCell<PaxAny>>,
+    _ctx: &NodeContext,
+    args: Option<PaxAny>,
+) {
+    record("leaf", args).prevent_default();
+}
+
+fn main_bubble(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    record("main bubble", args);
+}
+
+fn capture(event: &str) -> String {
+    format!("{}{}", event, CAPTURE_HANDLERS_SUFFIX)
+}
+
+/// `<Card @click.capture=.. @click=.. />` in the template of main, with
+/// `<Leaf @click=.. />` in the template of `Card`
+fn build_app(card_capture: HandlerFn, leaf_click: HandlerFn) -> TestApp {
+    let leaf = TestNode::new()
+        .id("leaf")
+        .size(50.0, 50.0)
+        .on(CLICK_HANDLERS, leaf_click)
+        .leaf();
+    let card = TestNode::new()
+        .id("card")
+        .on(&capture(CLICK_HANDLERS), card_capture)
+        .on(CLICK_HANDLERS, card_bubble)
+        .component(vec![leaf]);
+    let main = TestNode::new()
+        .id("main")
+        .handler(CLICK_HANDLERS, Handler::new_component_handler(main_bubble))
+        .component(vec![card as Rc<dyn InstanceNode>]);
+    mount(main, (200.0, 200.0))
+}
+
+fn handlers(received: &[Received]) -> Vec<(&'static str, EventPhase)> {
+    received.iter().map(|r| (r.0, r.1)).collect()
+}
+
+#[test]
+fn events_are_captured_down_to_the_target_then_bubble_up() {
+    let mut app = build_app(card_capture, leaf_click);
+    let leaf = app.node("leaf").unwrap();
+    let card = app.node("card").unwrap();
+
+    assert!(!app.click("leaf").unwrap());
+    let received = taken();
+    assert_eq!(
+        handlers(&received),
+        vec![
+            ("card capture", EventPhase::Capturing),
+            ("leaf", EventPhase::AtTarget),
+            ("card bubble", EventPhase::Bubbling),
+            ("main bubble", EventPhase::Bubbling),
+        ]
+    );
+    assert!(received.iter().all(|r| r.2.as_ref() == Some(&leaf)));
+    let current_targets: Vec<_> = received.iter().map(|r| r.3.clone().unwrap()).collect();
+    assert_eq!(current_targets[0], card);
+    assert_eq!(current_targets[1], leaf);
+    assert_eq!(current_targets[2], card);
+    assert_eq!(current_targets[3], app.node("main").unwrap());
+}
+
+#[test]
+fn stop_propagation_keeps_events_from_later_nodes() {
+    let mut app = build_app(card_capture, leaf_click_and_stop);
+    assert!(!app.click("leaf").unwrap());
+    assert_eq!(
+        handlers(&taken()),
+        vec![
+            ("card capture", EventPhase::Capturing),
+            ("leaf", EventPhase::AtTarget),
+        ]
+    );
+
+    let mut app = build_app(card_capture_and_stop, leaf_click);
+    app.click("leaf").unwrap();
+    assert_eq!(
+        handlers(&taken()),
+        vec![("card capture", EventPhase::Capturing)]
+    );
+}
+
+#[test]
+fn prevent_default_does_not_stop_propagation() {
+    let mut app = build_app(card_capture, leaf_click_and_prevent_default);
+    assert!(app.click("leaf").unwrap());
+    assert_eq!(taken().len(), 4);
+}
@@ -0,0 +1,189 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{Event, KeyDown, NodeContext, Property};
+use pax_runtime::constants::{BLUR_HANDLERS, FOCUS_HANDLERS, KEY_DOWN_HANDLERS, MOUNT_HANDLERS};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{Handler, InstanceNode};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::use_RefCell;
+use_RefCell!();
+
+mod support;
+use support::{mount, record, square, taken, HandlerFn};
+
+/// Focus and blur events, leaving out the Tab key presses that caused them
+fn taken_focus_events() -> Vec<String> {
+    taken()
+        .into_iter()
+        .filter(|event| !event.ends_with(" Tab"))
+        .collect()
+}
+
+fn focus_on_mount(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    ctx.request_focus().unwrap();
+    assert!(ctx.is_focused());
+}
+
+fn fail_to_focus(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    assert!(ctx.request_focus().is_err());
+}
+
+fn on_focus_a(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    assert!(args.is_none());
+    record("focus a");
+}
+
+fn on_blur_a(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    assert!(args.is_none());
+    record("blur a");
+}
+
+fn on_key_a(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<KeyDown>::from_pax_any(args.unwrap()).unwrap();
+    record(format!("a {}", event.keyboard.key));
+    if event.keyboard.key == "Escape" {
+        ctx.blur();
+        assert!(!ctx.is_focused());
+    }
+}
+
+fn on_key_main(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<KeyDown>::from_pax_any(args.unwrap()).unwrap();
+    record(format!("main {}", event.keyboard.key));
+}
+
+fn leaf(
+    id: &'static str,
+    x: f64,
+    focusable: Option<bool>,
+    tab_index: Option<i64>,
+    handlers: Vec<(&str, HandlerFn)>,
+) -> Rc<dyn InstanceNode> {
+    square(id, x, handlers)
+        .common(move |_, common| {
+            common.focusable = Property::new(focusable);
+            common.tab_index = Property::new(tab_index);
+        })
+        .leaf()
+}
+
+/// A main component with these 10x10 leaves in its template, in order and
+/// 20px apart: `a` (tab_index=0), `b` (focusable=true), `c` (tab_index=1),
+/// `d` (tab_index=-1), and `e`, which isn't focusable
+fn build_app() -> TestApp {
+    let template = vec![
+        leaf(
+            "a",
+            0.0,
+            None,
+            Some(0),
+            vec![
+                (FOCUS_HANDLERS, on_focus_a),
+                (BLUR_HANDLERS, on_blur_a),
+                (KEY_DOWN_HANDLERS, on_key_a),
+            ],
+        ),
+        leaf("b", 20.0, Some(true), None, vec![]),
+        leaf("c", 40.0, None, Some(1), vec![]),
+        leaf(
+            "d",
+            60.0,
+            None,
+            Some(-1),
+            vec![(MOUNT_HANDLERS, focus_on_mount)],
+        ),
+        leaf("e", 80.0, None, None, vec![(MOUNT_HANDLERS, fail_to_focus)]),
+    ];
+    let main = TestNode::new()
+        .handler(
+            KEY_DOWN_HANDLERS,
+            Handler::new_component_handler(on_key_main),
+        )
+        .component(template);
+    mount(main, (100.0, 100.0))
+}
+
+fn assert_focused(app: &TestApp, id: &str) {
+    assert_eq!(app.focused(), Some(app.node(id).unwrap()));
+}
+
+#[test]
+fn tab_moves_focus_through_the_tab_order() {
+    let mut app = build_app();
+    // focused on mount, though it can't be reached with Tab
+    assert_focused(&app, "d");
+
+    app.tab(false);
+    assert_focused(&app, "c");
+    app.tab(false);
+    assert_focused(&app, "a");
+    assert_eq!(taken_focus_events(), vec!["focus a"]);
+    app.tab(false);
+    assert_focused(&app, "b");
+    assert_eq!(taken_focus_events(), vec!["blur a"]);
+    app.tab(false);
+    assert_focused(&app, "c");
+
+    app.tab(true);
+    assert_focused(&app, "b");
+    app.tab(true);
+    assert_focused(&app, "a");
+    assert_eq!(taken_focus_events(), vec!["focus a"]);
+}
+
+#[test]
+fn key_events_go_to_the_focused_node_and_bubble() {
+    let mut app = build_app();
+    taken();
+    app.tab(false);
+    app.tab(false);
+    assert_focused(&app, "a");
+    taken();
+
+    app.key_down("x");
+    assert_eq!(taken(), vec!["a x", "main x"]);
+
+    app.tab(false);
+    taken();
+    app.key_down("y");
+    assert_eq!(taken(), vec!["main y"]);
+}
+
+#[test]
+fn tab_prevents_default_only_when_it_moves_focus() {
+    let mut app = build_app();
+    assert!(app.tab(false));
+    assert!(!app.key_down("x"));
+
+    let mut app = mount(TestNode::new().component(vec![]), (100.0, 100.0));
+    assert!(!app.tab(false));
+}
+
+#[test]
+fn pressing_focuses_and_pressing_elsewhere_blurs() {
+    let mut app = build_app();
+    taken();
+    app.mouse_down_at(5.0, 5.0);
+    assert_focused(&app, "a");
+    app.mouse_down_at(25.0, 5.0);
+    assert_focused(&app, "b");
+    assert_eq!(taken(), vec!["focus a", "blur a"]);
+
+    // `e` isn't focusable, and nothing is under (50, 50)
+    app.mouse_down_at(85.0, 5.0);
+    assert_eq!(app.focused(), None);
+    app.mouse_down_at(5.0, 5.0);
+    app.touch_start(&[(0, 50.0, 50.0)]);
+    assert_eq!(app.focused(), None);
+    assert_eq!(taken(), vec!["focus a", "blur a"]);
+}
+
+#[test]
+fn focused_nodes_can_blur() {
+    let mut app = build_app();
+    app.mouse_down_at(5.0, 5.0);
+    taken();
+    app.key_down("Escape");
+    assert_eq!(app.focused(), None);
+    assert_eq!(taken(), vec!["a Escape", "blur a", "main Escape"]);
+}
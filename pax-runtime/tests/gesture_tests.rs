@@ -0,0 +1,173 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{DoubleTap, Event, LongPress, NodeContext, Pan, Pinch, Rotate, Swipe, Tap};
+use pax_runtime::constants::{
+    DOUBLE_TAP_HANDLERS, LONG_PRESS_HANDLERS, PAN_HANDLERS, PINCH_HANDLERS, ROTATE_HANDLERS,
+    SWIPE_HANDLERS, TAP_HANDLERS,
+};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::use_RefCell;
+use_RefCell!();
+
+mod support;
+use support::{mount, record, taken, HandlerFn};
+
+fn on_tap(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<Tap>::from_pax_any(args.unwrap()).unwrap();
+    record(format!("tap {} {}", event.x, event.y));
+}
+
+fn on_double_tap(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<DoubleTap>::from_pax_any(args.unwrap()).unwrap();
+    record(format!("double_tap {} {}", event.x, event.y));
+}
+
+fn on_long_press(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<LongPress>::from_pax_any(args.unwrap()).unwrap();
+    record(format!("long_press {} {}", event.x, event.y));
+}
+
+fn on_pan(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<Pan>::from_pax_any(args.unwrap()).unwrap();
+    record(format!(
+        "pan {:?} {} {}",
+        event.phase, event.translation_x, event.translation_y
+    ));
+}
+
+fn on_swipe(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<Swipe>::from_pax_any(args.unwrap()).unwrap();
+    record(format!("swipe {:?}", event.direction));
+}
+
+fn on_pinch(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<Pinch>::from_pax_any(args.unwrap()).unwrap();
+    record(format!(
+        "pinch {:?} {:.1} at {} {}",
+        event.phase, event.scale, event.center_x, event.center_y
+    ));
+}
+
+fn on_rotate(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<Rotate>::from_pax_any(args.unwrap()).unwrap();
+    record(format!("rotate {:?} {:.0}", event.phase, event.rotation));
+}
+
+/// A main component with a 100x100 `pad` listening to every gesture
+fn build_app() -> TestApp {
+    let handlers: Vec<(&str, HandlerFn)> = vec![
+        (TAP_HANDLERS, on_tap),
+        (DOUBLE_TAP_HANDLERS, on_double_tap),
+        (LONG_PRESS_HANDLERS, on_long_press),
+        (PAN_HANDLERS, on_pan),
+        (SWIPE_HANDLERS, on_swipe),
+        (PINCH_HANDLERS, on_pinch),
+        (ROTATE_HANDLERS, on_rotate),
+    ];
+    let pad = handlers
+        .into_iter()
+        .fold(
+            TestNode::new().id("pad").size(100.0, 100.0),
+            |node, (event, handler)| node.on(event, handler),
+        )
+        .leaf();
+    mount(TestNode::new().component(vec![pad]), (200.0, 200.0))
+}
+
+#[test]
+fn quick_touches_tap_and_double_tap() {
+    let mut app = build_app();
+    app.touch_start(&[(0, 10.0, 10.0)]);
+    app.touch_end(&[(0, 12.0, 10.0)]);
+    assert_eq!(taken(), vec!["tap 12 10"]);
+
+    app.tick();
+    app.touch_start(&[(1, 14.0, 10.0)]);
+    app.touch_end(&[(1, 14.0, 10.0)]);
+    assert_eq!(taken(), vec!["tap 14 10", "double_tap 14 10"]);
+
+    // too late to make a double tap
+    app.tick_n(30);
+    app.touch_start(&[(2, 14.0, 10.0)]);
+    app.touch_end(&[(2, 14.0, 10.0)]);
+    assert_eq!(taken(), vec!["tap 14 10"]);
+}
+
+#[test]
+fn holding_still_long_presses() {
+    let mut app = build_app();
+    app.touch_start(&[(0, 10.0, 10.0)]);
+    app.tick_n(20);
+    assert!(taken().is_empty());
+    app.tick_n(20);
+    assert_eq!(taken(), vec!["long_press 10 10"]);
+    app.touch_end(&[(0, 10.0, 10.0)]);
+    assert!(taken().is_empty());
+}
+
+#[test]
+fn moving_pans_and_flicking_swipes() {
+    let mut app = build_app();
+    app.touch_start(&[(0, 10.0, 50.0)]);
+    app.tick();
+    app.touch_move(&[(0, 30.0, 50.0)]);
+    app.tick();
+    app.touch_move(&[(0, 60.0, 45.0)]);
+    app.tick();
+    app.touch_end(&[(0, 80.0, 50.0)]);
+    assert_eq!(
+        taken(),
+        vec![
+            "pan Began 20 0",
+            "pan Changed 50 -5",
+            "pan Ended 70 0",
+            "swipe Right"
+        ]
+    );
+
+    // slowly, so it doesn't swipe
+    app.touch_start(&[(1, 10.0, 10.0)]);
+    for y in 1..=10 {
+        app.tick_n(10);
+        app.touch_move(&[(1, 10.0, 10.0 + 5.0 * y as f64)]);
+    }
+    app.tick_n(10);
+    app.touch_end(&[(1, 10.0, 60.0)]);
+    let received = taken();
+    assert_eq!(received.last().unwrap(), "pan Ended 0 50");
+}
+
+#[test]
+fn two_fingers_pinch_and_rotate() {
+    let mut app = build_app();
+    app.touch_start(&[(0, 40.0, 50.0), (1, 60.0, 50.0)]);
+    app.touch_move(&[(0, 30.0, 50.0), (1, 70.0, 50.0)]);
+    assert_eq!(taken(), vec!["pinch Began 2.0 at 50 50", "rotate Began 0"]);
+    app.touch_move(&[(0, 50.0, 40.0), (1, 50.0, 60.0)]);
+    assert_eq!(
+        taken(),
+        vec!["pinch Changed 1.0 at 50 50", "rotate Changed 90"]
+    );
+    app.touch_end(&[(0, 50.0, 40.0)]);
+    assert_eq!(taken(), vec!["pinch Ended 1.0 at 50 50", "rotate Ended 90"]);
+    // the remaining finger doesn't pan or tap
+    app.touch_move(&[(1, 90.0, 90.0)]);
+    app.touch_end(&[(1, 90.0, 90.0)]);
+    assert!(taken().is_empty());
+}
+
+#[test]
+fn mouse_presses_are_recognized_unless_emulated_after_a_touch() {
+    let mut app = build_app();
+    app.mouse_down_at(20.0, 20.0);
+    app.mouse_up_at(20.0, 20.0);
+    assert_eq!(taken(), vec!["tap 20 20"]);
+
+    app.tick_n(30);
+    app.touch_start(&[(0, 50.0, 50.0)]);
+    app.touch_end(&[(0, 50.0, 50.0)]);
+    app.mouse_down_at(50.0, 50.0);
+    app.mouse_up_at(50.0, 50.0);
+    assert_eq!(taken(), vec!["tap 50 50"]);
+}
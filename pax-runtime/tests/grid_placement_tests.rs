@@ -0,0 +1,119 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{NodeContext, Property};
+use pax_runtime::constants::MOUNT_HANDLERS;
+use pax_runtime::testing::TestNode;
+use pax_runtime::{ConditionalInstance, ConditionalProperties, GridPlacement, InstanceNode};
+use pax_runtime_api::pax_value::PaxAny;
+use pax_runtime_api::{borrow_mut, use_RefCell};
+use_RefCell!();
+
+mod support;
+use support::mount;
+
+thread_local! {
+    static PLACEMENTS: RefCell<Vec<Property<Vec<GridPlacement>>>> = RefCell::new(vec![]);
+}
+
+fn on_grid_mount(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    PLACEMENTS
+        .with(|placements| borrow_mut!(placements).push(ctx.slot_children_grid_placements.clone()));
+}
+
+/// Stands in for a `Grid`: a component recording the placements of its slot children
+fn grid(children: Vec<Rc<dyn InstanceNode>>) -> Rc<dyn InstanceNode> {
+    TestNode::new()
+        .on(MOUNT_HANDLERS, on_grid_mount)
+        .children(children)
+        .component(vec![])
+}
+
+#[test]
+fn slot_children_grid_placements_follow_their_common_properties() {
+    let row = Property::new(Some(2));
+    let cp_row = row.clone();
+    let spanning = TestNode::new()
+        .common(move |_, common| {
+            common.grid_row = cp_row.clone();
+            common.grid_column_span = Property::new(Some(2));
+        })
+        .leaf();
+    let named = TestNode::new()
+        .common(|_, common| common.grid_area = Property::new(Some("nav".to_string())))
+        .leaf();
+    let main = TestNode::new().component(vec![grid(vec![spanning, grid(vec![named])])]);
+    mount(main, (100.0, 100.0));
+
+    let placements = PLACEMENTS.with(|placements| std::mem::take(&mut *borrow_mut!(placements)));
+    let [outer, inner] = &placements[..] else {
+        panic!("expected two grids, got {}", placements.len());
+    };
+    // nested grids only see their own slot children
+    assert_eq!(
+        outer.get(),
+        vec![
+            GridPlacement {
+                row: Some(2),
+                column_span: Some(2),
+                ..Default::default()
+            },
+            GridPlacement::default(),
+        ]
+    );
+    assert_eq!(
+        inner.get(),
+        vec![GridPlacement {
+            area: Some("nav".to_string()),
+            ..Default::default()
+        }]
+    );
+
+    row.set(Some(3));
+    assert_eq!(outer.get()[0].row, Some(3));
+}
+
+/// `if shown { children }`
+fn conditional(
+    shown: &Property<bool>,
+    children: Vec<Rc<dyn InstanceNode>>,
+) -> Rc<dyn InstanceNode> {
+    let shown = shown.clone();
+    TestNode::new()
+        .properties(move |_| ConditionalProperties {
+            boolean_expression: shown.clone(),
+        })
+        .children(children)
+        .instantiate::<ConditionalInstance>()
+}
+
+#[test]
+fn slot_children_follow_the_conditionals_among_them() {
+    let outer_shown = Property::new(true);
+    let inner_shown = Property::new(false);
+    let row = |row| {
+        TestNode::new()
+            .common(move |_, common| common.grid_row = Property::new(Some(row)))
+            .leaf()
+    };
+    let nested = conditional(&inner_shown, vec![row(2)]);
+    let main = TestNode::new().component(vec![grid(vec![
+        row(1),
+        conditional(&outer_shown, vec![nested, row(3)]),
+    ])]);
+    let mut app = mount(main, (100.0, 100.0));
+
+    let placements = PLACEMENTS.with(|placements| std::mem::take(&mut *borrow_mut!(placements)));
+    let [placements] = &placements[..] else {
+        panic!("expected one grid, got {}", placements.len());
+    };
+    let rows = || -> Vec<_> { placements.get().iter().map(|p| p.row.unwrap()).collect() };
+    assert_eq!(rows(), vec![1, 3]);
+
+    inner_shown.set(true);
+    app.tick();
+    assert_eq!(rows(), vec![1, 2, 3]);
+
+    outer_shown.set(false);
+    app.tick();
+    assert_eq!(rows(), vec![1]);
+}
@@ -0,0 +1,120 @@
+use std::rc::Rc;
+
+use pax_runtime::api::math::Point2;
+use pax_runtime::api::{HitTest, Layer, NodeContext, Property};
+use pax_runtime::constants::CLICK_HANDLERS;
+use pax_runtime::node_interface::NodeLocal;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs};
+use pax_runtime_api::pax_value::PaxAny;
+use pax_runtime_api::use_RefCell;
+use_RefCell!();
+
+mod support;
+use support::{mount, record, taken, HandlerFn};
+
+/// A node drawing a circle that fills its bounds, or a plain box if not `round`
+struct Disc {
+    base: BaseInstance,
+    round: bool,
+}
+
+impl Disc {
+    fn new(args: InstantiationArgs, round: bool) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Canvas,
+                    is_component: false,
+                },
+            ),
+            round,
+        })
+    }
+}
+
+impl InstanceNode for Disc {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self> {
+        Self::new(args, true)
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("Disc").finish()
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+
+    fn hit_test(&self, expanded_node: &ExpandedNode, point: Point2<NodeLocal>) -> Option<bool> {
+        if !self.round {
+            return None;
+        }
+        let (width, height) = expanded_node.transform_and_bounds.get().bounds;
+        let (dx, dy) = (point.x / width - 0.5, point.y / height - 0.5);
+        Some(dx * dx + dy * dy <= 0.25)
+    }
+}
+
+fn on_disc_click(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, _args: Option<PaxAny>) {
+    record("disc");
+}
+
+fn on_backdrop_click(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, _args: Option<PaxAny>) {
+    record("backdrop");
+}
+
+fn disc(
+    size: f64,
+    round: bool,
+    hit_test: Option<HitTest>,
+    on_click: HandlerFn,
+) -> Rc<dyn InstanceNode> {
+    let args = TestNode::new()
+        .size(size, size)
+        .common(move |_, common| common.hit_test = Property::new(hit_test))
+        .on(CLICK_HANDLERS, on_click)
+        .args(None);
+    Disc::new(args, round)
+}
+
+/// A 100x100 disc on top of a 200x200 backdrop, which is a `Disc` that
+/// isn't round
+fn build_app(disc_hit_test: Option<HitTest>) -> TestApp {
+    let main = TestNode::new().component(vec![
+        disc(100.0, true, disc_hit_test, on_disc_click),
+        disc(200.0, false, None, on_backdrop_click),
+    ]);
+    mount(main, (200.0, 200.0))
+}
+
+#[test]
+fn shapes_are_only_hit_where_they_draw() {
+    let mut app = build_app(None);
+    app.click_at(50.0, 50.0);
+    // in the disc's bounds, but outside the circle
+    app.click_at(5.0, 5.0);
+    assert_eq!(taken(), vec!["disc", "backdrop"]);
+
+    let mut app = build_app(Some(HitTest::Shape));
+    app.click_at(5.0, 5.0);
+    assert_eq!(taken(), vec!["backdrop"]);
+}
+
+#[test]
+fn hit_test_bounds_and_none_override_the_shape() {
+    let mut app = build_app(Some(HitTest::Bounds));
+    app.click_at(5.0, 5.0);
+    assert_eq!(taken(), vec!["disc"]);
+
+    let mut app = build_app(Some(HitTest::None));
+    app.click_at(50.0, 50.0);
+    assert_eq!(taken(), vec!["backdrop"]);
+}
@@ -0,0 +1,66 @@
+use std::rc::Rc;
+
+use pax_runtime::api::Property;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ComponentInstance, ExpressionTable, InstanceNode};
+use pax_runtime_api::pax_value::ImplToFromPaxAny;
+
+#[derive(Default)]
+struct Counter {
+    count: Property<usize>,
+}
+
+impl ImplToFromPaxAny for Counter {}
+
+fn leaf(id: &'static str) -> Rc<dyn InstanceNode> {
+    TestNode::new().id(id).leaf()
+}
+
+/// A `Counter` main component with `template` as its template, the way a cartridge
+/// rebuilds it from a patched manifest
+fn counter(template: Vec<Rc<dyn InstanceNode>>) -> Rc<ComponentInstance> {
+    TestNode::new()
+        .id("counter")
+        .properties(|_| Counter::default())
+        .component(template)
+}
+
+#[test]
+fn reloaded_template_replaces_children() {
+    let mut app = TestApp::new(
+        counter(vec![leaf("a")]),
+        ExpressionTable::new(),
+        (100.0, 100.0),
+    );
+    app.tick();
+    assert!(app.node("a").is_ok());
+    assert!(app.node("b").is_err());
+
+    app.engine_mut()
+        .reload_main_template(counter(vec![leaf("b"), leaf("c")]));
+    app.tick();
+    assert!(app.node("a").is_err());
+    assert!(app.node("b").is_ok());
+    assert!(app.node("c").is_ok());
+}
+
+#[test]
+fn reload_keeps_main_component_state() {
+    let mut app = TestApp::new(
+        counter(vec![leaf("a")]),
+        ExpressionTable::new(),
+        (100.0, 100.0),
+    );
+    app.tick();
+    app.with_properties("counter", |c: &mut Counter| c.count.set(5))
+        .unwrap();
+
+    app.engine_mut()
+        .reload_main_template(counter(vec![leaf("b")]));
+    app.tick();
+    assert_eq!(
+        app.with_properties("counter", |c: &mut Counter| c.count.get())
+            .unwrap(),
+        5
+    );
+}
@@ -0,0 +1,149 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{DragEnd, DragOver, DragStart, DropItem, Event, NodeContext};
+use pax_runtime::constants::{
+    DRAG_END_HANDLERS, DRAG_OVER_HANDLERS, DRAG_START_HANDLERS, DROP_ITEM_HANDLERS,
+    MOUSE_DOWN_HANDLERS, MOUSE_MOVE_HANDLERS, MOUSE_UP_HANDLERS,
+};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::InstanceNode;
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::use_RefCell;
+use_RefCell!();
+
+mod support;
+use support::{mount, record, square, taken, HandlerFn};
+
+fn knob_down(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    ctx.capture_pointer().unwrap();
+    assert!(ctx.has_pointer_capture());
+}
+
+fn knob_move(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, _args: Option<PaxAny>) {
+    record("knob move");
+}
+
+fn knob_up(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, _args: Option<PaxAny>) {
+    record("knob up");
+}
+
+fn target_move(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, _args: Option<PaxAny>) {
+    record("target move");
+}
+
+fn target_up(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, _args: Option<PaxAny>) {
+    record("target up");
+}
+
+fn item_drag_start(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<DragStart>::from_pax_any(args.unwrap()).unwrap();
+    event.drag.payload.set(3usize);
+    record("item drag_start");
+}
+
+fn item_drag_end(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<DragEnd>::from_pax_any(args.unwrap()).unwrap();
+    record(format!(
+        "item drag_end {:?}",
+        event.drag.payload.get::<usize>()
+    ));
+}
+
+fn target_drag_over(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<DragOver>::from_pax_any(args.unwrap()).unwrap();
+    record(format!(
+        "target drag_over {:?}",
+        event.drag.payload.get::<usize>()
+    ));
+}
+
+fn target_drop_item(_properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let event = Event::<DropItem>::from_pax_any(args.unwrap()).unwrap();
+    record(format!(
+        "target drop_item {:?}",
+        event.drag.payload.get::<usize>()
+    ));
+}
+
+fn leaf(id: &'static str, x: f64, handlers: Vec<(&str, HandlerFn)>) -> Rc<dyn InstanceNode> {
+    square(id, x, handlers).leaf()
+}
+
+/// A main component with `item` (drags with a payload), `plain` (no drag
+/// handlers), `knob` (captures the pointer) and `target` side by side
+fn build_app() -> TestApp {
+    let template = vec![
+        leaf(
+            "item",
+            0.0,
+            vec![
+                (DRAG_START_HANDLERS, item_drag_start),
+                (DRAG_END_HANDLERS, item_drag_end),
+            ],
+        ),
+        leaf("plain", 20.0, vec![]),
+        leaf(
+            "knob",
+            40.0,
+            vec![
+                (MOUSE_DOWN_HANDLERS, knob_down),
+                (MOUSE_MOVE_HANDLERS, knob_move),
+                (MOUSE_UP_HANDLERS, knob_up),
+            ],
+        ),
+        leaf(
+            "target",
+            60.0,
+            vec![
+                (MOUSE_MOVE_HANDLERS, target_move),
+                (MOUSE_UP_HANDLERS, target_up),
+                (DRAG_OVER_HANDLERS, target_drag_over),
+                (DROP_ITEM_HANDLERS, target_drop_item),
+            ],
+        ),
+    ];
+    mount(TestNode::new().component(template), (100.0, 100.0))
+}
+
+#[test]
+fn captured_pointer_keeps_receiving_moves_until_released() {
+    let mut app = build_app();
+    app.mouse_down_at(45.0, 5.0);
+    // over target, but the knob has the pointer
+    app.mouse_move_to(65.0, 5.0);
+    app.mouse_up_at(65.0, 5.0);
+    assert_eq!(taken(), vec!["knob move", "knob up"]);
+
+    app.mouse_move_to(65.0, 5.0);
+    assert_eq!(taken(), vec!["target move"]);
+}
+
+#[test]
+fn dragging_carries_the_payload_to_the_drop_target() {
+    let mut app = build_app();
+    app.mouse_down_at(5.0, 5.0);
+    // not past the drag threshold yet
+    app.mouse_move_to(6.0, 6.0);
+    assert!(taken().is_empty());
+
+    app.mouse_move_to(65.0, 5.0);
+    assert_eq!(taken(), vec!["target move", "item drag_start"]);
+    app.mouse_move_to(66.0, 5.0);
+    assert_eq!(taken(), vec!["target move", "target drag_over Some(3)"]);
+    app.mouse_up_at(66.0, 5.0);
+    assert_eq!(
+        taken(),
+        vec![
+            "target up",
+            "target drop_item Some(3)",
+            "item drag_end Some(3)"
+        ]
+    );
+}
+
+#[test]
+fn nodes_without_a_drag_payload_dont_start_a_drag() {
+    let mut app = build_app();
+    app.drag("plain", "target").unwrap();
+    assert_eq!(taken(), vec!["target move", "target up"]);
+}
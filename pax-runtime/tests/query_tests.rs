@@ -0,0 +1,97 @@
+use std::rc::Rc;
+
+use pax_runtime::api::math::Point2;
+use pax_runtime::api::NodeContext;
+use pax_runtime::constants::CLICK_HANDLERS;
+use pax_runtime::node_interface::NodeInterface;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::InstanceNode;
+use pax_runtime_api::pax_value::{ImplToFromPaxAny, PaxAny};
+use pax_runtime_api::use_RefCell;
+use_RefCell!();
+
+mod support;
+use support::{mount, record, taken};
+
+/// Properties of the nodes in the panel, to look them up by type
+#[derive(Default)]
+struct Item;
+
+impl ImplToFromPaxAny for Item {}
+
+fn ids(nodes: Vec<NodeInterface>) -> Vec<String> {
+    nodes
+        .into_iter()
+        .map(|node| node.id().unwrap_or_default())
+        .collect()
+}
+
+fn on_query_click(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    let node = ctx.node().unwrap();
+    let queries = vec![
+        format!("self {:?}", node.id()),
+        format!("under {:?}", ids(ctx.raycast(Point2::new(115.0, 25.0)))),
+        format!("items {:?}", ids(ctx.get_nodes_by_type::<Item>())),
+        format!("by id {:?}", ids(ctx.get_nodes_by_id("b"))),
+    ];
+    queries.into_iter().for_each(record);
+}
+
+fn item(id: &'static str, x: f64, y: f64) -> Rc<dyn InstanceNode> {
+    TestNode::new()
+        .id(id)
+        .position(x, y)
+        .size(30.0, 40.0)
+        .properties(|_| Item)
+        .leaf()
+}
+
+/// A main component with a `panel` component at x = 100 holding items `a`
+/// and `b`, next to a `query` node that runs the queries when clicked
+fn build_app() -> TestApp {
+    let query = TestNode::new()
+        .id("query")
+        .size(50.0, 50.0)
+        .on(CLICK_HANDLERS, on_query_click)
+        .leaf();
+    let panel = TestNode::new()
+        .id("panel")
+        .position(100.0, 0.0)
+        .component(vec![item("a", 10.0, 20.0), item("b", 50.0, 20.0)]);
+    let main = TestNode::new().component(vec![panel as Rc<dyn InstanceNode>, query]);
+    mount(main, (200.0, 200.0))
+}
+
+#[test]
+fn nodes_can_be_walked_and_measured() {
+    let app = build_app();
+    assert_eq!(
+        ids(app.root().descendants()),
+        vec!["panel", "a", "b", "query"]
+    );
+    let panel = app.node("panel").unwrap();
+    assert_eq!(ids(panel.children()), vec!["a", "b"]);
+
+    let a = app.node("a").unwrap();
+    assert_eq!(ids(a.ancestors()), vec!["panel", ""]);
+    assert!(a.is_descendant_of(&panel));
+    let (top_left, bottom_right) = a.bounding_box();
+    assert_eq!((top_left.x, top_left.y), (110.0, 20.0));
+    assert_eq!((bottom_right.x, bottom_right.y), (140.0, 60.0));
+}
+
+#[test]
+fn node_context_queries_work_outside_designtime() {
+    let mut app = build_app();
+    app.click("query").unwrap();
+    assert_eq!(
+        taken(),
+        vec![
+            "self Some(\"query\")",
+            // components themselves aren't hit
+            "under [\"a\"]",
+            "items [\"a\", \"b\"]",
+            "by id [\"b\"]",
+        ]
+    );
+}
@@ -0,0 +1,190 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{Interpolatable, Property};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{
+    ExpressionTable, InstanceNode, RepeatInstance, RepeatItem, RepeatKeyExpression,
+    RepeatProperties, RuntimePropertiesStackFrame,
+};
+use pax_runtime_api::pax_value::{ImplToFromPaxAny, ToFromPaxAny};
+use pax_runtime_api::{borrow, use_RefCell};
+use_RefCell!();
+
+#[derive(Clone, Default)]
+struct Row {
+    id: usize,
+}
+
+impl ImplToFromPaxAny for Row {}
+impl Interpolatable for Row {}
+
+/// Per-node state, standing in for things like textbox focus or a running transition
+#[derive(Default)]
+struct Marker {
+    value: Property<usize>,
+}
+
+impl ImplToFromPaxAny for Marker {}
+
+/// Reads `row.id` from the `RepeatItem` bound to `row` in `stack`
+fn row_id(stack: &Rc<RuntimePropertiesStackFrame>) -> usize {
+    let item = stack.resolve_symbol("row").unwrap();
+    let item = borrow!(item);
+    let elem = RepeatItem::ref_from_pax_any(&item)
+        .unwrap()
+        .elem
+        .get()
+        .unwrap();
+    let elem = borrow!(elem);
+    Row::ref_from_pax_any(&elem).unwrap().id
+}
+
+/// A leaf with id `row-<row.id>` and its own `Marker` state
+fn row_leaf() -> Rc<dyn InstanceNode> {
+    TestNode::new()
+        .common(|stack, common| common.id = Property::new(Some(format!("row-{}", row_id(stack)))))
+        .properties(|_| Marker::default())
+        .leaf()
+}
+
+/// `for row in rows key=<key_expression> { <Leaf/> }`
+fn repeat(
+    rows: Property<Vec<Row>>,
+    key_expression: Option<RepeatKeyExpression>,
+) -> Rc<dyn InstanceNode> {
+    TestNode::new()
+        .properties(move |_| {
+            let cp_rows = rows.clone();
+            let deps = [rows.untyped()];
+            let source = Property::computed(
+                move || {
+                    cp_rows
+                        .get()
+                        .into_iter()
+                        .map(|row| Rc::new(RefCell::new(row.to_pax_any())))
+                        .collect()
+                },
+                &deps,
+            );
+            let key_expression = key_expression.clone();
+            RepeatProperties {
+                source_expression_vec: Some(source),
+                source_expression_range: None,
+                iterator_i_symbol: None,
+                iterator_elem_symbol: Some("row".to_string()),
+                key_expression,
+            }
+        })
+        .children(vec![row_leaf()])
+        .instantiate::<RepeatInstance>()
+}
+
+/// `key=row.id`
+fn by_id() -> Option<RepeatKeyExpression> {
+    Some(Rc::new(|stack| row_id(stack).to_pax_any()))
+}
+
+fn app(rows: &Property<Vec<Row>>, key_expression: Option<RepeatKeyExpression>) -> TestApp {
+    let main = TestNode::new().component(vec![repeat(rows.clone(), key_expression)]);
+    TestApp::new(main, ExpressionTable::new(), (100.0, 100.0))
+}
+
+fn rows(ids: &[usize]) -> Vec<Row> {
+    ids.iter().map(|&id| Row { id }).collect()
+}
+
+fn marker(app: &TestApp, id: &str) -> usize {
+    app.with_properties(id, |m: &mut Marker| m.value.get())
+        .unwrap()
+}
+
+#[test]
+fn keyed_repeat_reuses_children_by_key() {
+    let source = Property::new(rows(&[1, 2, 3]));
+    let mut app = app(&source, by_id());
+    app.tick();
+    app.with_properties("row-2", |m: &mut Marker| m.value.set(7))
+        .unwrap();
+    app.with_properties("row-3", |m: &mut Marker| m.value.set(9))
+        .unwrap();
+
+    source.set(rows(&[3, 2, 4]));
+    app.tick();
+    assert!(app.node("row-1").is_err());
+    assert!(app.node("row-4").is_ok());
+    assert_eq!(marker(&app, "row-2"), 7);
+    assert_eq!(marker(&app, "row-3"), 9);
+    assert_eq!(marker(&app, "row-4"), 0);
+}
+
+#[test]
+fn keyed_repeat_treats_duplicate_keys_as_new_entries() {
+    let source = Property::new(rows(&[1]));
+    let mut app = app(&source, by_id());
+    app.tick();
+    app.with_properties("row-1", |m: &mut Marker| m.value.set(5))
+        .unwrap();
+
+    source.set(rows(&[1, 1]));
+    app.tick();
+    let markers: Vec<usize> = app
+        .nodes("row-1")
+        .iter()
+        .map(|n| n.with_properties(|m: &mut Marker| m.value.get()))
+        .collect();
+    assert_eq!(markers.len(), 2);
+    assert!(markers.contains(&5));
+    assert!(markers.contains(&0));
+}
+
+#[test]
+fn unkeyed_repeat_rebuilds_children_when_length_changes() {
+    let source = Property::new(rows(&[1, 2]));
+    let mut app = app(&source, None);
+    app.tick();
+    app.with_properties("row-2", |m: &mut Marker| m.value.set(7))
+        .unwrap();
+
+    source.set(rows(&[1, 2, 3]));
+    app.tick();
+    assert_eq!(marker(&app, "row-2"), 0);
+}
+
+#[test]
+fn keys_that_arent_builtin_values_fall_back_to_the_index() {
+    let source = Property::new(rows(&[1, 2]));
+    // `key=row`, a struct rather than e.g. a string or a number
+    let by_row: RepeatKeyExpression = Rc::new(|stack| Row { id: row_id(stack) }.to_pax_any());
+    let mut app = app(&source, Some(by_row));
+    app.tick();
+    app.with_properties("row-1", |m: &mut Marker| m.value.set(5))
+        .unwrap();
+
+    source.set(rows(&[1, 2, 3]));
+    app.tick();
+    assert_eq!(marker(&app, "row-1"), 5);
+    assert_eq!(marker(&app, "row-3"), 0);
+}
+
+#[test]
+fn keys_of_different_kinds_dont_collide() {
+    let source = Property::new(rows(&[3, 1, 2]));
+    // the index of row 3 (a struct), a string that reads like it, and a number
+    let mixed: RepeatKeyExpression = Rc::new(|stack| match row_id(stack) {
+        1 => "index 0".to_string().to_pax_any(),
+        2 => 2usize.to_pax_any(),
+        id => Row { id }.to_pax_any(),
+    });
+    let mut app = app(&source, Some(mixed));
+    app.tick();
+    for (id, value) in [("row-3", 5), ("row-1", 7), ("row-2", 9)] {
+        app.with_properties(id, |m: &mut Marker| m.value.set(value))
+            .unwrap();
+    }
+
+    source.set(rows(&[3, 1, 2]));
+    app.tick();
+    assert_eq!(marker(&app, "row-3"), 5);
+    assert_eq!(marker(&app, "row-1"), 7);
+    assert_eq!(marker(&app, "row-2"), 9);
+}
@@ -0,0 +1,145 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{NodeContext, Property, Store, StoreError};
+use pax_runtime::constants::{CLICK_HANDLERS, MOUNT_HANDLERS};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ComponentInstance, ExpressionTable, InstanceNode};
+use pax_runtime_api::pax_value::PaxAny;
+use pax_runtime_api::use_RefCell;
+use_RefCell!();
+
+mod support;
+use support::{record, square, taken, HandlerFn};
+
+#[derive(Clone)]
+struct Theme {
+    color: Property<String>,
+}
+
+impl Store for Theme {}
+
+struct Session;
+
+impl Store for Session {}
+
+fn theme(color: &str) -> Theme {
+    Theme {
+        color: Property::new(color.to_string()),
+    }
+}
+
+fn root_mount(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    ctx.set_app_store(theme("light"));
+}
+
+fn root_provide(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    ctx.provide_store(theme("provided")).unwrap();
+}
+
+fn panel_mount(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    ctx.provide_store(theme("dark")).unwrap();
+}
+
+fn panel_push(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    ctx.push_local_store(theme("pushed"));
+}
+
+fn on_leaf_click(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    let theme = ctx.get_store::<Theme>().map(|theme| theme.color.get());
+    let session = ctx.peek_local_store(|_: &mut Session| ());
+    record(format!("{:?} {:?}", theme, session));
+}
+
+fn leaf(id: &'static str, x: f64) -> Rc<dyn InstanceNode> {
+    square(id, x, vec![(CLICK_HANDLERS, on_leaf_click)]).leaf()
+}
+
+fn component(
+    mount: Option<HandlerFn>,
+    template: Vec<Rc<dyn InstanceNode>>,
+) -> Rc<ComponentInstance> {
+    match mount {
+        Some(mount) => TestNode::new().on(MOUNT_HANDLERS, mount),
+        None => TestNode::new(),
+    }
+    .component(template)
+}
+
+/// An `inner` leaf inside a `panel` component, followed by an `outer` leaf
+/// outside of it. Both record the theme they see when clicked.
+fn build_app(root_mount: Option<HandlerFn>, panel_mount: HandlerFn) -> TestApp {
+    let panel = component(Some(panel_mount), vec![leaf("inner", 0.0)]);
+    let main = component(
+        root_mount,
+        vec![panel as Rc<dyn InstanceNode>, leaf("outer", 50.0)],
+    );
+    TestApp::new(main, ExpressionTable::new(), (100.0, 100.0))
+}
+
+fn click_leaves(app: &mut TestApp) -> Vec<String> {
+    app.click("inner").unwrap();
+    app.click("outer").unwrap();
+    taken()
+}
+
+#[test]
+fn app_stores_can_be_set_before_the_first_tick() {
+    let mut app = build_app(None, panel_mount);
+    let light = theme("light");
+    app.engine()
+        .runtime_context
+        .app_stores()
+        .insert(light.clone());
+    app.tick();
+    let missing = StoreError::Missing {
+        store: std::any::type_name::<Session>(),
+    };
+    assert_eq!(
+        click_leaves(&mut app),
+        vec![
+            format!("Ok(\"dark\") Err({:?})", missing),
+            format!("Ok(\"light\") Err({:?})", missing),
+        ]
+    );
+    assert_eq!(
+        missing.to_string(),
+        "couldn't find store store_tests::Session"
+    );
+
+    // the app store shares its properties with the one that was set
+    light.color.set("white".to_string());
+    let color = app
+        .engine()
+        .runtime_context
+        .app_stores()
+        .with(|theme: &mut Theme| theme.color.get());
+    assert_eq!(color, Ok("white".to_string()));
+}
+
+#[test]
+fn provided_stores_override_app_stores_set_by_the_root() {
+    let mut app = build_app(Some(root_mount), panel_mount);
+    app.tick();
+    let received = click_leaves(&mut app);
+    assert!(received[0].starts_with("Ok(\"dark\") "));
+    assert!(received[1].starts_with("Ok(\"light\") "));
+}
+
+#[test]
+fn app_stores_belong_to_their_engine() {
+    let mut app = build_app(Some(root_mount), panel_mount);
+    app.tick();
+    let mut other = build_app(None, panel_mount);
+    other.tick();
+    let received = click_leaves(&mut other);
+    assert!(received[1].starts_with("Err("));
+}
+
+#[test]
+fn the_closest_store_wins_whether_pushed_or_provided() {
+    let mut app = build_app(Some(root_provide), panel_push);
+    app.tick();
+    let received = click_leaves(&mut app);
+    assert!(received[0].starts_with("Ok(\"pushed\") "));
+    assert!(received[1].starts_with("Ok(\"pushed\") "));
+}
@@ -0,0 +1,42 @@
+//! Fixtures shared by the runtime integration tests
+#![allow(dead_code)]
+
+use std::rc::Rc;
+
+use pax_runtime::api::NodeContext;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ComponentInstance, ExpressionTable};
+use pax_runtime_api::pax_value::PaxAny;
+use pax_runtime_api::{borrow_mut, use_RefCell};
+use_RefCell!();
+
+pub type HandlerFn = fn(Rc<RefCell<PaxAny>>, &NodeContext, Option<PaxAny>);
+
+thread_local! {
+    static RECEIVED: RefCell<Vec<String>> = RefCell::new(vec![]);
+}
+
+/// Logs a handler call, to be checked with [`taken`]
+pub fn record(event: impl Into<String>) {
+    RECEIVED.with(|received| borrow_mut!(received).push(event.into()));
+}
+
+/// The handler calls logged on this thread since the last call
+pub fn taken() -> Vec<String> {
+    RECEIVED.with(|received| std::mem::take(&mut *borrow_mut!(received)))
+}
+
+/// A 10x10 node at (`x`, 0), with `handlers` bound inline
+pub fn square(id: &'static str, x: f64, handlers: Vec<(&str, HandlerFn)>) -> TestNode {
+    let node = TestNode::new().id(id).position(x, 0.0).size(10.0, 10.0);
+    handlers
+        .into_iter()
+        .fold(node, |node, (event, handler)| node.on(event, handler))
+}
+
+/// An app rendering `main` in a viewport of `size`, ticked once
+pub fn mount(main: Rc<ComponentInstance>, size: (f64, f64)) -> TestApp {
+    let mut app = TestApp::new(main, ExpressionTable::new(), size);
+    app.tick();
+    app
+}
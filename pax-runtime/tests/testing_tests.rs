@@ -0,0 +1,106 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{NodeContext, Property, Size};
+use pax_runtime::constants::{CLICK_HANDLERS, KEY_DOWN_HANDLERS};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::ExpressionTable;
+use pax_runtime_api::pax_value::{ImplToFromPaxAny, PaxAny, ToFromPaxAny};
+use pax_runtime_api::{borrow_mut, use_RefCell};
+use_RefCell!();
+
+#[derive(Default)]
+struct Counter {
+    clicks: Property<usize>,
+    keys: Property<Vec<String>>,
+}
+
+impl ImplToFromPaxAny for Counter {}
+
+fn increment(properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, _args: Option<PaxAny>) {
+    let mut properties = borrow_mut!(properties);
+    let counter = Counter::mut_from_pax_any(&mut properties).unwrap();
+    counter.clicks.set(counter.clicks.get() + 1);
+}
+
+fn record_key(properties: Rc<RefCell<PaxAny>>, _ctx: &NodeContext, args: Option<PaxAny>) {
+    let args =
+        pax_runtime::api::Event::<pax_runtime::api::KeyDown>::from_pax_any(args.unwrap()).unwrap();
+    let mut properties = borrow_mut!(properties);
+    let counter = Counter::mut_from_pax_any(&mut properties).unwrap();
+    counter
+        .keys
+        .update(|keys| keys.push(args.keyboard.key.clone()));
+}
+
+/// A main component whose template is a single 50x50 leaf with id `leaf`
+fn build_app() -> TestApp {
+    let main = TestNode::new()
+        .id("main")
+        .properties(|_| Counter::default())
+        .component(vec![TestNode::new()
+            .id("leaf")
+            .size(50.0, 50.0)
+            .on(CLICK_HANDLERS, increment)
+            .on(KEY_DOWN_HANDLERS, record_key)
+            .leaf()]);
+    TestApp::new(main, ExpressionTable::new(), (200.0, 200.0))
+}
+
+fn clicks(app: &TestApp) -> usize {
+    app.with_properties("main", |c: &mut Counter| c.clicks.get())
+        .unwrap()
+}
+
+#[test]
+fn click_at_coordinates_hits_topmost_node() {
+    let mut app = build_app();
+    app.tick();
+    app.click_at(10.0, 10.0);
+    app.tick();
+    assert_eq!(clicks(&app), 1);
+
+    // outside of the leaf's bounds, nothing handles the click
+    app.click_at(150.0, 150.0);
+    app.tick();
+    assert_eq!(clicks(&app), 1);
+}
+
+#[test]
+fn click_by_id_targets_node_center() {
+    let mut app = build_app();
+    app.tick();
+    app.click("leaf").unwrap();
+    app.click("leaf").unwrap();
+    app.tick_n(2);
+    assert_eq!(clicks(&app), 2);
+    assert!(app.click("missing").is_err());
+}
+
+#[test]
+fn key_events_are_dispatched_to_nodes() {
+    let mut app = build_app();
+    app.tick();
+    app.type_key("a");
+    app.key_down("b");
+    app.tick();
+    let keys = app
+        .with_properties("main", |c: &mut Counter| c.keys.get())
+        .unwrap();
+    assert_eq!(keys, vec!["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn node_lookup_and_properties() {
+    let mut app = build_app();
+    app.tick();
+    let leaf = app.node("leaf").unwrap();
+    assert_eq!(
+        leaf.layout_properties().width,
+        Some(Size::Pixels(50.into()))
+    );
+    assert!(app.node("main").unwrap().is_of_type::<Counter>());
+    assert!(app
+        .with_properties("leaf", |c: &mut Counter| c.clicks.get())
+        .is_err());
+    assert!(app.native_messages().is_empty());
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use pax_runtime::api::{Keyframe, NodeContext, Property, Timeline};
+use pax_runtime::constants::MOUNT_HANDLERS;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::ExpressionTable;
+use pax_runtime_api::pax_value::PaxAny;
+use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
+use_RefCell!();
+
+thread_local! {
+    static OPACITY: Property<f64> = Property::new(0.0);
+    static FOUND: RefCell<Option<Timeline>> = RefCell::new(None);
+}
+
+fn find_intro(_properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    FOUND.with(|found| *borrow_mut!(found) = ctx.timeline("intro"));
+}
+
+/// A component with `@timeline intro (autoplay) { opacity: [0ms: 0.0, 100ms: 1.0] }`,
+/// and `<Leaf @mount=find_intro/>` in its template
+fn build_app() -> TestApp {
+    let main = TestNode::new()
+        .timelines(|_| {
+            let intro = Timeline::new();
+            OPACITY.with(|opacity| {
+                intro.track(
+                    opacity,
+                    vec![
+                        Keyframe::new(Duration::ZERO, 0.0),
+                        Keyframe::new(Duration::from_millis(100), 1.0),
+                    ],
+                )
+            });
+            intro.play();
+            HashMap::from([("intro".to_string(), intro)])
+        })
+        .component(vec![TestNode::new().on(MOUNT_HANDLERS, find_intro).leaf()]);
+    TestApp::new(main, ExpressionTable::new(), (100.0, 100.0))
+}
+
+fn opacity() -> f64 {
+    OPACITY.with(|opacity| opacity.get())
+}
+
+#[test]
+fn declared_timelines_are_built_on_mount_and_found_from_the_template() {
+    let mut app = build_app();
+    app.engine_mut().tick_at(1000.0);
+    let intro = FOUND
+        .with(|found| borrow!(found).clone())
+        .expect("intro timeline");
+    assert!(intro.is_playing());
+
+    app.engine_mut().tick_at(1050.0);
+    assert_eq!(opacity(), 0.5);
+    app.engine_mut().tick_at(1200.0);
+    assert_eq!(opacity(), 1.0);
+    assert!(!intro.is_playing());
+
+    // handles found through the node context control the same timeline
+    intro.seek(Duration::from_millis(25));
+    assert_eq!(opacity(), 0.25);
+}
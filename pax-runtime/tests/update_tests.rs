@@ -0,0 +1,155 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{Layer, Property};
+use pax_runtime::constants::{PRE_RENDER_HANDLERS, TICK_HANDLERS};
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{
+    BaseInstance, ConditionalInstance, ConditionalProperties, ExpandedNode, ExpressionTable,
+    InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
+};
+use pax_runtime_api::properties::{properties_leaked_by, UntypedProperty};
+use std::cell::Cell;
+
+mod support;
+use support::{record, taken};
+
+thread_local! {
+    static SOURCE: Property<usize> = Property::new(0);
+    static UPDATES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts its updates, and depends on `SOURCE`
+struct Counting {
+    base: BaseInstance,
+}
+
+impl InstanceNode for Counting {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Canvas,
+                    is_component: false,
+                },
+            ),
+        })
+    }
+
+    fn update(self: Rc<Self>, _expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
+        SOURCE.with(|s| s.get());
+        UPDATES.with(|u| u.set(u.get() + 1));
+    }
+
+    fn update_dependencies(&self, _expanded_node: &Rc<ExpandedNode>) -> Vec<UntypedProperty> {
+        vec![SOURCE.with(|s| s.untyped())]
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("Counting").finish()
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+}
+
+/// `if shown { <Counting/> }`
+fn build_app(shown: &Property<bool>) -> TestApp {
+    let shown = shown.clone();
+    let conditional = TestNode::new()
+        .properties(move |_| ConditionalProperties {
+            boolean_expression: shown.clone(),
+        })
+        .children(vec![TestNode::new().instantiate::<Counting>()])
+        .instantiate::<ConditionalInstance>();
+    let main = TestNode::new().component(vec![conditional as Rc<dyn InstanceNode>]);
+    TestApp::new(main, ExpressionTable::new(), (100.0, 100.0))
+}
+
+fn updates() -> usize {
+    UPDATES.with(|u| u.get())
+}
+
+#[test]
+fn nodes_are_only_updated_when_dependencies_change() {
+    let shown = Property::new(true);
+    let mut app = build_app(&shown);
+    app.tick();
+    assert_eq!(updates(), 1);
+
+    // idle ticks don't visit the node
+    app.tick();
+    app.tick();
+    assert_eq!(updates(), 1);
+
+    // several changes between ticks result in a single update
+    SOURCE.with(|s| s.set(1));
+    SOURCE.with(|s| s.set(2));
+    app.tick();
+    assert_eq!(updates(), 2);
+
+    // unmounted nodes are no longer updated
+    shown.set(false);
+    app.tick();
+    SOURCE.with(|s| s.set(3));
+    app.tick();
+    assert_eq!(updates(), 2);
+}
+
+#[test]
+fn unmounted_nodes_leave_no_properties_behind() {
+    let shown = Property::new(false);
+    let mut app = build_app(&shown);
+    app.tick();
+    // thread locals are initialized on first use, which would show up as a leak
+    SOURCE.with(|_| ());
+    let leaked = properties_leaked_by(|| {
+        shown.set(true);
+        app.tick();
+        shown.set(false);
+        app.tick();
+    });
+    assert!(leaked.nodes.is_empty(), "{}", leaked.to_json());
+}
+
+#[test]
+fn frame_handlers_run_before_the_updates_they_cause() {
+    // the first handler changes `SOURCE`, which the `Counting` node before it depends on
+    let first = TestNode::new().on(TICK_HANDLERS, |_, _, _| {
+        record(format!("first tick {}", updates()));
+        SOURCE.with(|s| s.set(s.get() + 1));
+    });
+    let second = TestNode::new()
+        .on(TICK_HANDLERS, |_, _, _| {
+            record(format!("second tick {}", updates()))
+        })
+        .on(PRE_RENDER_HANDLERS, |_, _, _| {
+            record(format!("second pre_render {}", updates()))
+        });
+    let main = TestNode::new().component(vec![
+        TestNode::new().instantiate::<Counting>() as Rc<dyn InstanceNode>,
+        first.leaf(),
+        second.leaf(),
+    ]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (100.0, 100.0));
+    app.tick();
+    taken();
+
+    let before = updates();
+    app.tick();
+    assert_eq!(
+        taken(),
+        vec![
+            format!("first tick {}", before),
+            format!("second tick {}", before),
+            format!("second pre_render {}", before + 1),
+        ]
+    );
+}
@@ -1,24 +1,17 @@
-use pax_message::{AnyCreatePatch, CheckboxPatch};
+use pax_message::{AnyCreatePatch, CheckboxPatch, NativeInterrupt};
 use pax_runtime::api::{Layer, Property};
 use pax_runtime::{
-    BaseInstance, ExpandedNode, ExpandedNodeIdentifier, InstanceFlags, InstanceNode,
-    InstantiationArgs, RuntimeContext,
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
 use_RefCell!();
 use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
 use pax_std::primitives::Checkbox;
-use std::collections::HashMap;
 use std::rc::Rc;
 
 use crate::patch_if_needed;
 
 pub struct CheckboxInstance {
     base: BaseInstance,
-    // Properties that listen to Text property changes, and computes
-    // a patch in the case that they have changed + sends it as a native
-    // message to the chassi. Since InstanceNode -> ExpandedNode has a one
-    // to many relationship, needs to be a hashmap
-    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 impl InstanceNode for CheckboxInstance {
@@ -36,16 +29,12 @@ impl InstanceNode for CheckboxInstance {
                     is_component: false,
                 },
             ),
-            native_message_props: Default::default(),
         })
     }
 
     fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
         //trigger computation of property that computes + sends native message update
-        borrow!(self.native_message_props)
-            .get(&expanded_node.id)
-            .unwrap()
-            .get();
+        self.base.send_native_messages(expanded_node);
     }
 
     fn handle_mount(
@@ -73,8 +62,8 @@ impl InstanceNode for CheckboxInstance {
             .cloned()
             .chain([expanded_node.transform_and_bounds.untyped()])
             .collect();
-        borrow_mut!(self.native_message_props).insert(
-            expanded_node.id,
+        self.base.set_native_message_property(
+            expanded_node,
             Property::computed(
                 move || {
                     let Some(expanded_node) = weak_self_ref.upgrade() else {
@@ -120,7 +109,7 @@ impl InstanceNode for CheckboxInstance {
         let id = expanded_node.id.clone();
         context.enqueue_native_message(pax_message::NativeMessage::CheckboxDelete(id.to_u32()));
         // Reset so that native_message sending updates while unmounted
-        borrow_mut!(self.native_message_props).remove(&id);
+        self.base.remove_native_message_property(expanded_node);
     }
 
     fn base(&self) -> &BaseInstance {
@@ -134,4 +123,15 @@ impl InstanceNode for CheckboxInstance {
     ) -> std::fmt::Result {
         f.debug_struct("Checkbox").finish_non_exhaustive()
     }
+
+    fn handle_native_interrupt(
+        &self,
+        expanded_node: &Rc<ExpandedNode>,
+        interrupt: &NativeInterrupt,
+    ) {
+        if let NativeInterrupt::FormCheckboxToggle(args) = interrupt {
+            expanded_node
+                .with_properties_unwrapped(|props: &mut Checkbox| props.checked.set(args.state));
+        }
+    }
 }
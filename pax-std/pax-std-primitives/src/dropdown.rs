@@ -1,24 +1,17 @@
 use pax_message::{AnyCreatePatch, DropdownPatch, NativeInterrupt};
 use pax_runtime::api::{use_RefCell, Layer, Property};
 use pax_runtime::{
-    BaseInstance, ExpandedNode, ExpandedNodeIdentifier, InstanceFlags, InstanceNode,
-    InstantiationArgs, RuntimeContext,
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
 use_RefCell!();
-use pax_runtime_api::{borrow, borrow_mut};
+use pax_runtime_api::borrow_mut;
 use pax_std::primitives::Dropdown;
-use std::collections::HashMap;
 use std::rc::Rc;
 
 use crate::patch_if_needed;
 
 pub struct DropdownInstance {
     base: BaseInstance,
-    // Properties that listen to Text property changes, and computes
-    // a patch in the case that they have changed + sends it as a native
-    // message to the chassi. Since InstanceNode -> ExpandedNode has a one
-    // to many relationship, needs to be a hashmap
-    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 impl InstanceNode for DropdownInstance {
@@ -36,16 +29,12 @@ impl InstanceNode for DropdownInstance {
                     is_component: false,
                 },
             ),
-            native_message_props: Default::default(),
         })
     }
 
     fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
         //trigger computation of property that computes + sends native message update
-        borrow!(self.native_message_props)
-            .get(&expanded_node.id)
-            .unwrap()
-            .get();
+        self.base.send_native_messages(expanded_node);
     }
 
     fn handle_mount(
@@ -76,8 +65,8 @@ impl InstanceNode for DropdownInstance {
             .cloned()
             .chain([expanded_node.transform_and_bounds.untyped()])
             .collect();
-        borrow_mut!(self.native_message_props).insert(
-            id,
+        self.base.set_native_message_property(
+            expanded_node,
             Property::computed(
                 move || {
                     let Some(expanded_node) = weak_self_ref.upgrade() else {
@@ -149,7 +138,7 @@ impl InstanceNode for DropdownInstance {
         let id = expanded_node.id.clone();
         context.enqueue_native_message(pax_message::NativeMessage::DropdownDelete(id.to_u32()));
         // Reset so that native_message sending updates while unmounted
-        borrow_mut!(self.native_message_props).remove(&id);
+        self.base.remove_native_message_property(expanded_node);
     }
 
     fn base(&self) -> &BaseInstance {
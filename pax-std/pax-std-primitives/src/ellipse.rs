@@ -1,5 +1,7 @@
-use kurbo::{Rect, Shape};
+use kurbo::{Point, Rect, Shape};
+use pax_runtime::api::math::Point2;
 use pax_runtime::api::{Fill, Layer, RenderContext};
+use pax_runtime::node_interface::NodeLocal;
 use pax_runtime::BaseInstance;
 use pax_runtime_api::{borrow, use_RefCell};
 use pax_std::primitives::Ellipse;
@@ -79,6 +81,29 @@ impl InstanceNode for EllipseInstance {
         });
     }
 
+    fn hit_test(&self, expanded_node: &ExpandedNode, point: Point2<NodeLocal>) -> Option<bool> {
+        let (width, height) = expanded_node.transform_and_bounds.get().bounds;
+        let ellipse = kurbo::Ellipse::from_rect(Rect::from_points((0.0, 0.0), (width, height)));
+        let point = Point::new(point.x, point.y);
+        expanded_node.with_properties_unwrapped(|properties: &mut Ellipse| {
+            let filled = match properties.fill.get() {
+                Fill::Solid(color) => color.to_rgba_0_1()[3] > 0.0,
+                _ => true,
+            };
+            let stroke_width = properties
+                .stroke
+                .get()
+                .width
+                .get()
+                .expect_pixels()
+                .to_float();
+            Some(
+                (filled && ellipse.contains(point))
+                    || crate::stroke_contains(&ellipse.to_path(0.1), point, stroke_width),
+            )
+        })
+    }
+
     fn resolve_debug(
         &self,
         f: &mut std::fmt::Formatter,
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::iter;
 use std::rc::Rc;
 
@@ -7,8 +6,7 @@ use kurbo::{Affine, BezPath};
 use pax_message::{AnyCreatePatch, FramePatch};
 use pax_runtime::api::{Layer, Property, RenderContext};
 use pax_runtime::{
-    BaseInstance, ExpandedNode, ExpandedNodeIdentifier, InstanceFlags, InstanceNode,
-    InstantiationArgs, RuntimeContext,
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
 use_RefCell!();
 use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
@@ -23,11 +21,6 @@ use pax_std::primitives::Frame;
 /// to [`Frame`], since `[Frame]` creates a clipping mask.
 pub struct FrameInstance {
     base: BaseInstance,
-    // Properties that listen to Text property changes, and computes
-    // a patch in the case that they have changed + sends it as a native
-    // message to the chassi. Since InstanceNode -> ExpandedNode has a one
-    // to many relationship, needs to be a hashmap
-    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 impl InstanceNode for FrameInstance {
@@ -45,16 +38,12 @@ impl InstanceNode for FrameInstance {
                     is_component: false,
                 },
             ),
-            native_message_props: Default::default(),
         })
     }
 
     fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
         //trigger computation of property that computes + sends native message update
-        borrow!(self.native_message_props)
-            .get(&expanded_node.id)
-            .unwrap()
-            .get();
+        self.base.send_native_messages(expanded_node);
     }
 
     fn handle_pre_render(
@@ -144,8 +133,8 @@ impl InstanceNode for FrameInstance {
             .cloned()
             .chain([expanded_node.transform_and_bounds.untyped()])
             .collect();
-        borrow_mut!(self.native_message_props).insert(
-            id,
+        self.base.set_native_message_property(
+            expanded_node,
             Property::computed(
                 move || {
                     let Some(expanded_node) = weak_self_ref.upgrade() else {
@@ -189,7 +178,7 @@ impl InstanceNode for FrameInstance {
         let id = expanded_node.id.clone();
         context.enqueue_native_message(pax_message::NativeMessage::FrameDelete(id.to_u32()));
         // Reset so that native_message sending updates while unmounted
-        borrow_mut!(self.native_message_props).remove(&id);
+        self.base.remove_native_message_property(expanded_node);
     }
 
     fn resolve_debug(
@@ -1,7 +1,6 @@
-use pax_runtime::{api::Property, api::RenderContext, ExpandedNodeIdentifier};
+use pax_runtime::{api::Property, api::RenderContext};
 use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
 use pax_std::{primitives::Image, types::ImageFit};
-use std::collections::HashMap;
 
 use_RefCell!();
 use pax_message::ImagePatch;
@@ -15,11 +14,6 @@ use crate::patch_if_needed;
 /// by `size`, transformed by `transform`
 pub struct ImageInstance {
     base: BaseInstance,
-    // Properties that listen to Image property changes, and computes
-    // a patch in the case that they have changed + sends it as a native
-    // message to the chassi. Since InstanceNode -> ExpandedNode has a one
-    // to many relationship, needs to be a hashmap
-    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 impl InstanceNode for ImageInstance {
@@ -37,16 +31,12 @@ impl InstanceNode for ImageInstance {
                     is_component: false,
                 },
             ),
-            native_message_props: Default::default(),
         })
     }
 
     fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
         //trigger computation of property that computes + sends native message update
-        borrow!(self.native_message_props)
-            .get(&expanded_node.id)
-            .unwrap()
-            .get();
+        self.base.send_native_messages(expanded_node);
     }
 
     fn handle_mount(
@@ -69,8 +59,8 @@ impl InstanceNode for ImageInstance {
             .cloned()
             .chain([expanded_node.transform_and_bounds.untyped()])
             .collect();
-        borrow_mut!(self.native_message_props).insert(
-            expanded_node.id,
+        self.base.set_native_message_property(
+            expanded_node,
             Property::computed(
                 move || {
                     let Some(expanded_node) = weak_self_ref.upgrade() else {
@@ -99,9 +89,8 @@ impl InstanceNode for ImageInstance {
     }
 
     fn handle_unmount(&self, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
-        let id = expanded_node.id.clone();
         // Reset so that native_message stops sending updates while unmounted
-        borrow_mut!(self.native_message_props).remove(&id);
+        self.base.remove_native_message_property(expanded_node);
     }
 
     fn render(
@@ -13,6 +13,18 @@ pub mod slider;
 pub mod text;
 pub mod textbox;
 
+use kurbo::{BezPath, ParamCurveNearest, Point};
+
+/// Whether `point` is within half of `width` from the outline of `path`, i.e. on
+/// the stroke drawn along it
+fn stroke_contains(path: &BezPath, point: Point, width: f64) -> bool {
+    let half_width_sq = (width / 2.0).powi(2);
+    width > f64::EPSILON
+        && path
+            .segments()
+            .any(|segment| segment.nearest(point, 0.1).distance_sq <= half_width_sq)
+}
+
 fn patch_if_needed<T: PartialEq + Clone>(
     old_state: &mut Option<T>,
     patch: &mut Option<T>,
@@ -1,6 +1,8 @@
-use kurbo::BezPath;
+use kurbo::{BezPath, Shape};
 
+use pax_runtime::api::math::Point2;
 use pax_runtime::api::{Layer, RenderContext};
+use pax_runtime::node_interface::NodeLocal;
 use pax_runtime::{
     BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
@@ -29,7 +31,7 @@ impl InstanceNode for PathInstance {
                 args,
                 InstanceFlags {
                     invisible_to_slot: false,
-                    invisible_to_raycasting: true,
+                    invisible_to_raycasting: false,
                     layer: Layer::Canvas,
                     is_component: false,
                 },
@@ -61,13 +63,6 @@ impl InstanceNode for PathInstance {
         });
     }
 
-    fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
-        // NOTE: do not update children here,
-        // we know that all of the expanded and flattened children
-        // are the same as the once being rendered
-        expanded_node.compute_flattened_slot_children();
-    }
-
     fn render(
         &self,
         expanded_node: &ExpandedNode,
@@ -77,50 +72,10 @@ impl InstanceNode for PathInstance {
         let layer_id = format!("{}", borrow!(expanded_node.occlusion_id));
 
         expanded_node.with_properties_unwrapped(|properties: &mut Path| {
-            let mut bez_path = BezPath::new();
-
             let bounds = expanded_node.transform_and_bounds.get().bounds;
-
-            let elems = properties.elements.get();
-            let mut itr_elems = elems.iter();
-
-            if let Some(elem) = itr_elems.next() {
-                if let &PathElement::Point(x, y) = elem {
-                    bez_path.move_to(Point { x, y }.to_kurbo_point(bounds));
-                } else {
-                    log::warn!("path must start with point");
-                    return;
-                }
-            }
-
-            while let Some(elem) = itr_elems.next() {
-                match elem {
-                    &PathElement::Point(x, y) => {
-                        bez_path.move_to(Point { x, y }.to_kurbo_point(bounds));
-                    }
-                    &PathElement::Line => {
-                        let Some(&PathElement::Point(x, y)) = itr_elems.next() else {
-                            log::warn!("line expects to be followed by a point");
-                            return;
-                        };
-                        bez_path.line_to(Point { x, y }.to_kurbo_point(bounds));
-                    }
-                    &PathElement::Curve(h_x, h_y) => {
-                        let Some(&PathElement::Point(x, y)) = itr_elems.next() else {
-                            log::warn!("curve expects to be followed by a point");
-                            return;
-                        };
-                        bez_path.quad_to(
-                            Point { x: h_x, y: h_y }.to_kurbo_point(bounds),
-                            Point { x, y }.to_kurbo_point(bounds),
-                        );
-                    }
-                    &PathElement::Close => {
-                        bez_path.close_path();
-                    }
-                    PathElement::Empty => (), //no-op
-                }
-            }
+            let Some(bez_path) = build_path(&properties.elements.get(), bounds) else {
+                return;
+            };
 
             let tab = expanded_node.transform_and_bounds.get();
             let transformed_bez_path = Into::<kurbo::Affine>::into(tab.transform) * bez_path;
@@ -153,6 +108,28 @@ impl InstanceNode for PathInstance {
         });
     }
 
+    fn hit_test(&self, expanded_node: &ExpandedNode, point: Point2<NodeLocal>) -> Option<bool> {
+        let bounds = expanded_node.transform_and_bounds.get().bounds;
+        let point = kurbo::Point::new(point.x, point.y);
+        expanded_node.with_properties_unwrapped(|properties: &mut Path| {
+            let Some(bez_path) = build_path(&properties.elements.get(), bounds) else {
+                return Some(false);
+            };
+            let filled = properties.fill.get().to_rgba_0_1()[3] > 0.0;
+            let stroke_width = properties
+                .stroke
+                .get()
+                .width
+                .get()
+                .expect_pixels()
+                .to_float();
+            Some(
+                (filled && bez_path.contains(point))
+                    || crate::stroke_contains(&bez_path, point, stroke_width),
+            )
+        })
+    }
+
     fn base(&self) -> &BaseInstance {
         &self.base
     }
@@ -165,3 +142,49 @@ impl InstanceNode for PathInstance {
         f.debug_struct("Path").finish()
     }
 }
+
+/// The path drawn for `elements` within `bounds`, or `None` if they don't
+/// describe a valid path
+fn build_path(elements: &[PathElement], bounds: (f64, f64)) -> Option<BezPath> {
+    let mut bez_path = BezPath::new();
+    let mut itr_elems = elements.iter();
+
+    if let Some(elem) = itr_elems.next() {
+        if let &PathElement::Point(x, y) = elem {
+            bez_path.move_to(Point { x, y }.to_kurbo_point(bounds));
+        } else {
+            log::warn!("path must start with point");
+            return None;
+        }
+    }
+
+    while let Some(elem) = itr_elems.next() {
+        match elem {
+            &PathElement::Point(x, y) => {
+                bez_path.move_to(Point { x, y }.to_kurbo_point(bounds));
+            }
+            &PathElement::Line => {
+                let Some(&PathElement::Point(x, y)) = itr_elems.next() else {
+                    log::warn!("line expects to be followed by a point");
+                    return None;
+                };
+                bez_path.line_to(Point { x, y }.to_kurbo_point(bounds));
+            }
+            &PathElement::Curve(h_x, h_y) => {
+                let Some(&PathElement::Point(x, y)) = itr_elems.next() else {
+                    log::warn!("curve expects to be followed by a point");
+                    return None;
+                };
+                bez_path.quad_to(
+                    Point { x: h_x, y: h_y }.to_kurbo_point(bounds),
+                    Point { x, y }.to_kurbo_point(bounds),
+                );
+            }
+            &PathElement::Close => {
+                bez_path.close_path();
+            }
+            PathElement::Empty => (), //no-op
+        }
+    }
+    Some(bez_path)
+}
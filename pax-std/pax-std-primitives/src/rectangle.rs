@@ -1,4 +1,6 @@
-use kurbo::{RoundedRect, Shape};
+use kurbo::{Point, RoundedRect, Shape};
+use pax_runtime::api::math::Point2;
+use pax_runtime::node_interface::NodeLocal;
 use pax_runtime::{api::Fill, BaseInstance};
 use pax_runtime_api::{borrow, use_RefCell};
 use piet::{LinearGradient, RadialGradient};
@@ -95,6 +97,25 @@ impl InstanceNode for RectangleInstance {
         });
     }
 
+    fn hit_test(&self, expanded_node: &ExpandedNode, point: Point2<NodeLocal>) -> Option<bool> {
+        let (width, height) = expanded_node.transform_and_bounds.get().bounds;
+        let point = Point::new(point.x, point.y);
+        expanded_node.with_properties_unwrapped(|properties: &mut Rectangle| {
+            let rect = RoundedRect::new(0.0, 0.0, width, height, &properties.corner_radii.get());
+            let stroke_width = properties
+                .stroke
+                .get()
+                .width
+                .get()
+                .expect_pixels()
+                .to_float();
+            Some(
+                rect.contains(point)
+                    || crate::stroke_contains(&rect.to_path(0.1), point, stroke_width),
+            )
+        })
+    }
+
     fn resolve_debug(
         &self,
         f: &mut std::fmt::Formatter,
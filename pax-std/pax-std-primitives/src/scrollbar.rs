@@ -1,9 +1,8 @@
 use core::option::Option::Some;
-use pax_runtime::{BaseInstance, ExpandedNodeIdentifier, InstanceFlags, RuntimeContext};
+use pax_runtime::{BaseInstance, InstanceFlags, RuntimeContext};
 use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
 use pax_std::primitives::Scrollbar;
 use_RefCell!();
-use std::collections::HashMap;
 use std::rc::Rc;
 
 use pax_message::{AnyCreatePatch, ScrollerPatch};
@@ -20,11 +19,6 @@ use crate::patch_if_needed;
 /// transformed `Group` surrounding its contents.
 pub struct ScrollbarInstance {
     base: BaseInstance,
-    // Properties that listen to Text property changes, and computes
-    // a patch in the case that they have changed + sends it as a native
-    // message to the chassi. Since InstanceNode -> ExpandedNode has a one
-    // to many relationship, needs to be a hashmap
-    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 impl InstanceNode for ScrollbarInstance {
@@ -42,15 +36,11 @@ impl InstanceNode for ScrollbarInstance {
                     is_component: false,
                 },
             ),
-            native_message_props: Default::default(),
         })
     }
     fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
         //trigger computation of property that computes + sends native message update
-        borrow!(self.native_message_props)
-            .get(&expanded_node.id)
-            .unwrap()
-            .get();
+        self.base.send_native_messages(expanded_node);
     }
 
     fn handle_mount(
@@ -81,8 +71,8 @@ impl InstanceNode for ScrollbarInstance {
             .cloned()
             .chain([expanded_node.transform_and_bounds.untyped()])
             .collect();
-        borrow_mut!(self.native_message_props).insert(
-            expanded_node.id,
+        self.base.set_native_message_property(
+            expanded_node,
             Property::computed(
                 move || {
                     let Some(expanded_node) = weak_self_ref.upgrade() else {
@@ -148,7 +138,7 @@ impl InstanceNode for ScrollbarInstance {
         let id = expanded_node.id.to_u32();
         context.enqueue_native_message(pax_message::NativeMessage::ScrollerDelete(id));
         // Reset so that native_message sending updates while unmounted
-        borrow_mut!(self.native_message_props).remove(&expanded_node.id);
+        self.base.remove_native_message_property(expanded_node);
     }
     fn base(&self) -> &BaseInstance {
         &self.base
@@ -1,13 +1,11 @@
 use pax_message::{AnyCreatePatch, TextPatch};
 use pax_runtime::api::{Layer, Property, RenderContext};
 use pax_runtime::{
-    BaseInstance, ExpandedNode, ExpandedNodeIdentifier, InstanceFlags, InstanceNode,
-    InstantiationArgs, RuntimeContext,
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
 use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
 use pax_std::primitives::Text;
 use_RefCell!();
-use std::collections::HashMap;
 use std::rc::Rc;
 #[cfg(feature = "designtime")]
 use {
@@ -20,11 +18,6 @@ use crate::patch_if_needed;
 
 pub struct TextInstance {
     base: BaseInstance,
-    // Properties that listen to Text property changes, and computes
-    // a patch in the case that they have changed + sends it as a native
-    // message to the chassi. Since InstanceNode -> ExpandedNode has a one
-    // to many relationship, needs to be a hashmap
-    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 impl InstanceNode for TextInstance {
@@ -42,16 +35,12 @@ impl InstanceNode for TextInstance {
                     is_component: false,
                 },
             ),
-            native_message_props: Default::default(),
         })
     }
 
     fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
         //trigger computation of property that computes + sends native message update
-        borrow!(self.native_message_props)
-            .get(&expanded_node.id)
-            .unwrap()
-            .get();
+        self.base.send_native_messages(expanded_node);
     }
 
     fn render(
@@ -107,8 +96,8 @@ impl InstanceNode for TextInstance {
             .chain([expanded_node.transform_and_bounds.untyped()])
             .collect();
 
-        borrow_mut!(self.native_message_props).insert(
-            expanded_node.id,
+        self.base.set_native_message_property(
+            expanded_node,
             Property::computed(
                 move || {
                     let Some(expanded_node) = weak_self_ref.upgrade() else {
@@ -182,7 +171,7 @@ impl InstanceNode for TextInstance {
         let id = expanded_node.id.to_u32();
         context.enqueue_native_message(pax_message::NativeMessage::TextDelete(id));
         // Reset so that native_message sending updates while unmounted
-        borrow_mut!(self.native_message_props).remove(&expanded_node.id);
+        self.base.remove_native_message_property(expanded_node);
     }
 
     fn resolve_debug(
@@ -1,24 +1,17 @@
-use pax_message::{AnyCreatePatch, TextboxPatch};
+use pax_message::{AnyCreatePatch, NativeInterrupt, TextboxPatch};
 use pax_runtime::api::{use_RefCell, Layer, Property};
 use pax_runtime::{
-    BaseInstance, ExpandedNode, ExpandedNodeIdentifier, InstanceFlags, InstanceNode,
-    InstantiationArgs, RuntimeContext,
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
 use_RefCell!();
-use pax_runtime_api::{borrow, borrow_mut};
+use pax_runtime_api::borrow_mut;
 use pax_std::primitives::Textbox;
-use std::collections::HashMap;
 use std::rc::Rc;
 
 use crate::patch_if_needed;
 
 pub struct TextboxInstance {
     base: BaseInstance,
-    // Properties that listen to Text property changes, and computes
-    // a patch in the case that they have changed + sends it as a native
-    // message to the chassi. Since InstanceNode -> ExpandedNode has a one
-    // to many relationship, needs to be a hashmap
-    native_message_props: RefCell<HashMap<ExpandedNodeIdentifier, Property<()>>>,
 }
 
 impl InstanceNode for TextboxInstance {
@@ -36,16 +29,12 @@ impl InstanceNode for TextboxInstance {
                     is_component: false,
                 },
             ),
-            native_message_props: Default::default(),
         })
     }
 
     fn update(self: Rc<Self>, expanded_node: &Rc<ExpandedNode>, _context: &Rc<RuntimeContext>) {
         //trigger computation of property that computes + sends native message update
-        borrow!(self.native_message_props)
-            .get(&expanded_node.id)
-            .unwrap()
-            .get();
+        self.base.send_native_messages(expanded_node);
     }
 
     fn handle_mount(
@@ -61,6 +50,13 @@ impl InstanceNode for TextboxInstance {
             occlusion_layer_id: 0,
         }));
 
+        // the native textbox takes focus along with this node, blurring the node that had it
+        let focus_on_mount = expanded_node
+            .with_properties_unwrapped(|properties: &mut Textbox| properties.focus_on_mount.get());
+        if focus_on_mount {
+            context.focus(Some(Rc::clone(expanded_node)));
+        }
+
         // send update message when relevant properties change
         let weak_self_ref = Rc::downgrade(&expanded_node);
         let context = Rc::clone(context);
@@ -74,8 +70,8 @@ impl InstanceNode for TextboxInstance {
             .cloned()
             .chain([expanded_node.transform_and_bounds.untyped()])
             .collect();
-        borrow_mut!(self.native_message_props).insert(
-            id,
+        self.base.set_native_message_property(
+            expanded_node,
             Property::computed(
                 move || {
                     let Some(expanded_node) = weak_self_ref.upgrade() else {
@@ -152,7 +148,7 @@ impl InstanceNode for TextboxInstance {
         let id = expanded_node.id.clone();
         context.enqueue_native_message(pax_message::NativeMessage::TextboxDelete(id.to_u32()));
         // Reset so that native_message sending updates while unmounted
-        borrow_mut!(self.native_message_props).remove(&id);
+        self.base.remove_native_message_property(expanded_node);
     }
 
     fn base(&self) -> &BaseInstance {
@@ -166,4 +162,15 @@ impl InstanceNode for TextboxInstance {
     ) -> std::fmt::Result {
         f.debug_struct("Textbox").finish_non_exhaustive()
     }
+
+    fn handle_native_interrupt(
+        &self,
+        expanded_node: &Rc<ExpandedNode>,
+        interrupt: &NativeInterrupt,
+    ) {
+        if let NativeInterrupt::FormTextboxInput(args) = interrupt {
+            expanded_node
+                .with_properties_unwrapped(|props: &mut Textbox| props.text.set(args.text.clone()));
+        }
+    }
 }
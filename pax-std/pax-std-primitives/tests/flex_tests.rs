@@ -0,0 +1,104 @@
+use std::rc::Rc;
+
+use pax_runtime::api::{NodeContext, Property, Size};
+use pax_runtime::constants::MOUNT_HANDLERS;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ExpressionTable, InstanceNode};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_runtime_api::{borrow_mut, use_RefCell};
+use pax_std::components::Flex;
+use pax_std::types::StackerCell;
+use_RefCell!();
+
+fn on_flex_mount(properties: Rc<RefCell<PaxAny>>, ctx: &NodeContext, _args: Option<PaxAny>) {
+    let mut properties = borrow_mut!(properties);
+    Flex::mut_from_pax_any(&mut properties)
+        .unwrap()
+        .on_mount(ctx);
+}
+
+/// A 200x100 `Flex` with `children` in its slot, mounted the way its `@mount` handler is
+fn build_app(flex: Flex, children: Vec<Rc<dyn InstanceNode>>) -> TestApp {
+    let flex = TestNode::new()
+        .id("flex")
+        .size(200.0, 100.0)
+        .properties(move |_| flex.clone())
+        .on(MOUNT_HANDLERS, on_flex_mount)
+        .children(children)
+        .component(vec![]);
+    let main = TestNode::new().component(vec![flex as Rc<dyn InstanceNode>]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (200.0, 100.0));
+    app.tick();
+    app
+}
+
+fn child(width: Option<Size>) -> Rc<dyn InstanceNode> {
+    TestNode::new()
+        .common(move |_, common| common.width = Property::new(width))
+        .leaf()
+}
+
+/// (x, width) of each cell
+fn cells(app: &TestApp) -> Vec<(f64, f64)> {
+    app.with_properties("flex", |flex: &mut Flex| flex._cell_specs.get())
+        .unwrap()
+        .iter()
+        .map(|cell: &StackerCell| (cell.x_px, cell.width_px))
+        .collect()
+}
+
+#[test]
+fn children_without_a_size_fill_the_space_left_by_the_others() {
+    let flex = Flex {
+        shrink: Property::new(vec![0.0, 0.0, 1.0]),
+        ..Default::default()
+    };
+    let app = build_app(
+        flex,
+        vec![
+            child(Some(Size::Percent(25.into()))),
+            child(Some(Size::Pixels(50.into()))),
+            child(None),
+        ],
+    );
+    // the 25% child renders at 50px in a cell of the Flex's width
+    assert_eq!(
+        cells(&app),
+        vec![(0.0, 200.0), (50.0, 50.0), (100.0, 100.0)]
+    );
+    let heights: Vec<f64> = app
+        .with_properties("flex", |flex: &mut Flex| flex._cell_specs.get())
+        .unwrap()
+        .iter()
+        .map(|cell| cell.height_px)
+        .collect();
+    assert_eq!(heights, vec![100.0; 3]);
+}
+
+#[test]
+fn percentage_children_follow_grow_and_max_sizes() {
+    let grow = Property::new(vec![]);
+    let max_sizes = Property::new(vec![]);
+    let flex = Flex {
+        grow: grow.clone(),
+        max_sizes: max_sizes.clone(),
+        ..Default::default()
+    };
+    let mut app = build_app(
+        flex,
+        vec![
+            child(Some(Size::Percent(25.into()))),
+            child(Some(Size::Pixels(50.into()))),
+        ],
+    );
+    assert_eq!(cells(&app), vec![(0.0, 200.0), (50.0, 50.0)]);
+
+    // grown to 150px, which is 25% of a 600px cell
+    grow.set(vec![1.0]);
+    app.tick();
+    assert_eq!(cells(&app), vec![(0.0, 600.0), (150.0, 50.0)]);
+
+    max_sizes.set(vec![Some(Size::Pixels(100.into()))]);
+    app.tick();
+    assert_eq!(cells(&app), vec![(0.0, 400.0), (100.0, 50.0)]);
+}
@@ -0,0 +1,36 @@
+use std::rc::Rc;
+
+use pax_runtime::api::Property;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ExpressionTable, InstanceNode};
+use pax_std::primitives::Textbox;
+use pax_std_primitives::textbox::TextboxInstance;
+
+/// Equivalent of `<Textbox id=textbox focus_on_mount=true/>`, 100x20
+fn build_app() -> TestApp {
+    let textbox = TestNode::new()
+        .id("textbox")
+        .size(100.0, 20.0)
+        .properties(|_| Textbox {
+            focus_on_mount: Property::new(true),
+            ..Default::default()
+        })
+        .instantiate::<TextboxInstance>();
+    let main = TestNode::new().component(vec![textbox as Rc<dyn InstanceNode>]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (200.0, 200.0));
+    app.tick();
+    app
+}
+
+#[test]
+fn textboxes_focused_on_mount_have_focus() {
+    let mut app = build_app();
+    let textbox = app.node("textbox").unwrap();
+    assert_eq!(app.focused(), Some(textbox.clone()));
+
+    // pressing the textbox keeps focus on it, though it isn't focusable
+    app.mouse_down_at(50.0, 10.0);
+    assert_eq!(app.focused(), Some(textbox));
+    app.mouse_down_at(50.0, 100.0);
+    assert_eq!(app.focused(), None);
+}
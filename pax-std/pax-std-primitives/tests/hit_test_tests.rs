@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use pax_runtime::api::math::Point2;
+use pax_runtime::api::{Color, Fill, Numeric, Property, Size, Stroke};
+use pax_runtime::node_interface::NodeInterface;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ExpressionTable, InstanceNode};
+use pax_runtime_api::pax_value::ToFromPaxAny;
+use pax_std::primitives::{Ellipse, Path, Rectangle};
+use pax_std::types::{PathElement, RectangleCornerRadii};
+use pax_std_primitives::ellipse::EllipseInstance;
+use pax_std_primitives::path::PathInstance;
+use pax_std_primitives::rectangle::RectangleInstance;
+
+/// A 100x100 `shape` at (50, 50) in a 200x200 viewport
+fn build_app<T: InstanceNode + 'static, P: ToFromPaxAny + Clone>(properties: P) -> TestApp {
+    let shape = TestNode::new()
+        .id("shape")
+        .position(50.0, 50.0)
+        .size(100.0, 100.0)
+        .properties(move |_| properties.clone())
+        .instantiate::<T>();
+    let main = TestNode::new().component(vec![shape as Rc<dyn InstanceNode>]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (200.0, 200.0));
+    app.tick();
+    app
+}
+
+/// Whether a ray at (x, y), relative to the top left of the shape, hits it
+fn hits(app: &TestApp, x: f64, y: f64) -> bool {
+    let ray = Point2::new(x + 50.0, y + 50.0);
+    let hit = app
+        .engine()
+        .runtime_context
+        .get_topmost_element_beneath_ray(ray);
+    hit.map(NodeInterface::from) == app.node("shape").ok()
+}
+
+fn stroke(width: f64) -> Property<Stroke> {
+    Property::new(Stroke {
+        color: Property::new(Color::BLACK),
+        width: Property::new(Size::Pixels(width.into())),
+    })
+}
+
+fn ellipse(fill: Color, stroke_width: f64) -> TestApp {
+    build_app::<EllipseInstance, _>(Ellipse {
+        fill: Property::new(Fill::Solid(fill)),
+        stroke: stroke(stroke_width),
+    })
+}
+
+fn triangle(fill: Color, stroke_width: f64) -> TestApp {
+    let point = |x: f64, y: f64| PathElement::Point(Size::Pixels(x.into()), Size::Pixels(y.into()));
+    build_app::<PathInstance, _>(Path {
+        elements: Property::new(vec![
+            point(0.0, 0.0),
+            PathElement::Line,
+            point(100.0, 0.0),
+            PathElement::Line,
+            point(0.0, 100.0),
+            PathElement::Close,
+        ]),
+        fill: Property::new(fill),
+        stroke: stroke(stroke_width),
+    })
+}
+
+#[test]
+fn ellipses_are_hit_inside_the_ellipse_only() {
+    let app = ellipse(Color::RED, 0.0);
+    assert!(hits(&app, 50.0, 50.0));
+    assert!(hits(&app, 50.0, 2.0));
+    // in the bounds, but past the curve
+    assert!(!hits(&app, 5.0, 5.0));
+    assert!(!hits(&app, 95.0, 95.0));
+}
+
+#[test]
+fn transparent_ellipses_are_only_hit_on_their_stroke() {
+    let app = ellipse(Color::TRANSPARENT, 0.0);
+    assert!(!hits(&app, 50.0, 50.0));
+
+    let app = ellipse(Color::TRANSPARENT, 10.0);
+    assert!(!hits(&app, 50.0, 50.0));
+    assert!(hits(&app, 50.0, 2.0));
+    // half of the stroke is drawn outside of the bounds
+    assert!(hits(&app, 50.0, -3.0));
+    assert!(!hits(&app, 50.0, -8.0));
+}
+
+#[test]
+fn rectangles_are_not_hit_outside_rounded_corners() {
+    let app = build_app::<RectangleInstance, _>(Rectangle::default());
+    assert!(hits(&app, 3.0, 3.0));
+
+    let corner_radii = RectangleCornerRadii {
+        top_left: Property::new(Numeric::F64(40.0)),
+        ..Default::default()
+    };
+    let app = build_app::<RectangleInstance, _>(Rectangle {
+        corner_radii: Property::new(corner_radii),
+        ..Default::default()
+    });
+    assert!(!hits(&app, 3.0, 3.0));
+    assert!(hits(&app, 20.0, 20.0));
+    assert!(hits(&app, 97.0, 3.0));
+    assert!(hits(&app, 97.0, 97.0));
+}
+
+#[test]
+fn filled_paths_are_hit_inside_and_stroked_paths_on_the_stroke() {
+    let app = triangle(Color::RED, 0.0);
+    assert!(hits(&app, 20.0, 20.0));
+    // in the bounds, but on the other side of the diagonal
+    assert!(!hits(&app, 80.0, 80.0));
+
+    let app = triangle(Color::TRANSPARENT, 0.0);
+    assert!(!hits(&app, 20.0, 20.0));
+
+    let app = triangle(Color::TRANSPARENT, 10.0);
+    assert!(!hits(&app, 20.0, 20.0));
+    assert!(hits(&app, 50.0, 2.0));
+    assert!(hits(&app, 52.0, 52.0));
+    assert!(!hits(&app, 60.0, 60.0));
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use pax_runtime::api::Property;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ExpressionTable, InstanceNode, RuntimePropertiesStackFrame};
+use pax_runtime_api::pax_value::{ImplToFromPaxAny, ToFromPaxAny};
+use pax_runtime_api::{borrow, use_RefCell};
+use pax_std::primitives::{Checkbox, Textbox};
+use pax_std_primitives::checkbox::CheckboxInstance;
+use pax_std_primitives::textbox::TextboxInstance;
+use_RefCell!();
+
+#[derive(Default)]
+struct Form {
+    name: Property<String>,
+    agreed: Property<bool>,
+}
+
+impl ImplToFromPaxAny for Form {}
+
+/// Resolves `self.<symbol>` the same way generated cartridges do for a
+/// `ValueDefinition::DoubleBinding`
+fn bound<T: pax_runtime_api::properties::PropertyValue>(
+    stack_frame: &Rc<RuntimePropertiesStackFrame>,
+    symbol: &str,
+) -> Property<T> {
+    let untyped_property = stack_frame
+        .resolve_symbol_as_erased_property(symbol)
+        .expect("failed to resolve identifier");
+    Property::new_from_untyped(untyped_property.clone())
+}
+
+/// Equivalent of `<Textbox id=textbox bind:text=self.name/>` and
+/// `<Checkbox id=checkbox bind:checked=self.agreed/>` inside a `Form` component
+fn build_app() -> TestApp {
+    let textbox = TestNode::new()
+        .id("textbox")
+        .size(100.0, 20.0)
+        .properties(|stack_frame| Textbox {
+            text: bound(stack_frame, "name"),
+            ..Default::default()
+        })
+        .instantiate::<TextboxInstance>();
+    let checkbox = TestNode::new()
+        .id("checkbox")
+        .position(0.0, 30.0)
+        .size(20.0, 20.0)
+        .properties(|stack_frame| Checkbox {
+            checked: bound(stack_frame, "agreed"),
+        })
+        .instantiate::<CheckboxInstance>();
+    let main = TestNode::new()
+        .id("form")
+        .properties(|_| Form {
+            name: Property::new("initial".to_string()),
+            agreed: Property::new(false),
+        })
+        .properties_scope(|props| {
+            let props = borrow!(props);
+            let form = Form::ref_from_pax_any(&props).unwrap();
+            let mut scope = HashMap::new();
+            scope.insert("name".to_string(), form.name.untyped());
+            scope.insert("agreed".to_string(), form.agreed.untyped());
+            scope
+        })
+        .component(vec![
+            textbox as Rc<dyn InstanceNode>,
+            checkbox as Rc<dyn InstanceNode>,
+        ]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (200.0, 200.0));
+    app.tick();
+    app
+}
+
+fn form<V>(app: &TestApp, f: impl FnOnce(&mut Form) -> V) -> V {
+    app.with_properties("form", f).unwrap()
+}
+
+fn text(app: &TestApp) -> String {
+    app.with_properties("textbox", |textbox: &mut Textbox| textbox.text.get())
+        .unwrap()
+}
+
+fn checked(app: &TestApp) -> bool {
+    app.with_properties("checkbox", |checkbox: &mut Checkbox| checkbox.checked.get())
+        .unwrap()
+}
+
+#[test]
+fn bound_properties_start_with_parent_values() {
+    let app = build_app();
+    assert_eq!(text(&app), "initial");
+    assert!(!checked(&app));
+}
+
+#[test]
+fn native_input_flows_back_to_parent() {
+    let mut app = build_app();
+    app.textbox_input("textbox", "edited").unwrap();
+    app.checkbox_toggle("checkbox", true).unwrap();
+    app.tick();
+    assert_eq!(form(&app, |form| form.name.get()), "edited");
+    assert!(form(&app, |form| form.agreed.get()));
+}
+
+#[test]
+fn parent_changes_flow_to_controls() {
+    let mut app = build_app();
+    form(&app, |form| {
+        form.name.set("from parent".to_string());
+        form.agreed.set(true);
+    });
+    app.tick();
+    assert_eq!(text(&app), "from parent");
+    assert!(checked(&app));
+}
@@ -0,0 +1,39 @@
+use std::rc::Rc;
+
+use pax_runtime::api::borrow;
+use pax_runtime::testing::{TestApp, TestNode};
+use pax_runtime::{ExpressionTable, InstanceNode};
+use pax_std::primitives::{Text, Textbox};
+use pax_std_primitives::text::TextInstance;
+use pax_std_primitives::textbox::TextboxInstance;
+
+/// Equivalent of `<Textbox id=textbox/><Text id=text/>`
+fn build_app() -> TestApp {
+    let textbox = TestNode::new()
+        .id("textbox")
+        .size(100.0, 20.0)
+        .properties(|_| Textbox::default())
+        .instantiate::<TextboxInstance>();
+    let text = TestNode::new()
+        .id("text")
+        .size(100.0, 20.0)
+        .properties(|_| Text::default())
+        .instantiate::<TextInstance>();
+    let main = TestNode::new().component(vec![textbox as Rc<dyn InstanceNode>, text]);
+    let mut app = TestApp::new(main, ExpressionTable::new(), (200.0, 200.0));
+    app.tick();
+    app
+}
+
+#[test]
+fn native_nodes_have_no_dependencies_once_unmounted() {
+    let app = build_app();
+    let context = &app.engine().runtime_context;
+    for id in ["textbox", "text"] {
+        let node = context.get_expanded_nodes_by_id(id).pop().unwrap();
+        let instance = Rc::clone(&*borrow!(node.instance_node));
+        assert_eq!(instance.update_dependencies(&node).len(), 1);
+        Rc::clone(&node).recurse_unmount(context);
+        assert!(instance.update_dependencies(&node).is_empty());
+    }
+}
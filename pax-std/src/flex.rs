@@ -0,0 +1,515 @@
+use crate::primitives::*;
+use crate::types::{FlexAlign, FlexJustify, StackerCell, StackerDirection};
+use pax_engine::api::Numeric;
+use pax_engine::api::{Property, Size, Transform2D};
+use pax_engine::*;
+use pax_runtime::api::{Axis, NodeContext};
+
+/// Flex lays out a series of nodes along a main axis (a row or a column), sizing each
+/// cell to its content (the child's own `width` / `height`, with percentages relative to
+/// the Flex, or the size reported for e.g. text, else the whole Flex) and then distributing
+/// leftover space by `grow` / `shrink` weights.
+/// Cells can wrap onto multiple lines, and are placed along the main axis per `justify`
+/// and along the cross axis per `align`.  Per-cell settings (`grow`, `shrink`, `min_sizes`,
+/// `max_sizes`) are indexed by slot child, falling back to defaults when out-of-bounds.
+#[pax]
+#[custom(Default)]
+#[inlined(
+    for (cell_spec, i) in self._cell_specs {
+        <Group
+            transform={Transform2D::translate((cell_spec.x_px)px, (cell_spec.y_px)px)}
+            width={(cell_spec.width_px)px}
+            height={(cell_spec.height_px)px}
+        >
+            slot(i)
+        </Group>
+    }
+
+    @settings {
+        @mount: on_mount
+    }
+
+)]
+pub struct Flex {
+    pub direction: Property<crate::types::StackerDirection>,
+    pub justify: Property<crate::types::FlexJustify>,
+    pub align: Property<crate::types::FlexAlign>,
+    pub wrap: Property<bool>,
+    /// Space between cells along the main axis, and between lines when wrapping
+    pub gap: Property<Size>,
+    pub padding: Property<Size>,
+
+    /// Share of leftover main-axis space each cell grows by (default 0)
+    pub grow: Property<Vec<f64>>,
+    /// Share of overflowing main-axis space each cell shrinks by, scaled by its size (default 1)
+    pub shrink: Property<Vec<f64>>,
+    /// Main-axis lower bounds for each cell; None-values leave the cell unconstrained
+    pub min_sizes: Property<Vec<Option<Size>>>,
+    /// Main-axis upper bounds for each cell; None-values leave the cell unconstrained
+    pub max_sizes: Property<Vec<Option<Size>>>,
+
+    pub _cell_specs: Property<Vec<StackerCell>>,
+}
+
+impl Default for Flex {
+    fn default() -> Self {
+        Self {
+            direction: Property::new(StackerDirection::Horizontal),
+            justify: Property::new(FlexJustify::Start),
+            align: Property::new(FlexAlign::Stretch),
+            wrap: Property::new(false),
+            gap: Property::new(Size::Pixels(Numeric::I32(0))),
+            padding: Property::new(Size::Pixels(Numeric::I32(0))),
+            grow: Property::new(vec![]),
+            shrink: Property::new(vec![]),
+            min_sizes: Property::new(vec![]),
+            max_sizes: Property::new(vec![]),
+            _cell_specs: Property::new(vec![]),
+        }
+    }
+}
+
+impl Flex {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let bound = ctx.bounds_self.clone();
+        let slot_children_sizes = ctx.slot_children_sizes.clone();
+        let direction = self.direction.clone();
+        let justify = self.justify.clone();
+        let align = self.align.clone();
+        let wrap = self.wrap.clone();
+        let gap = self.gap.clone();
+        let padding = self.padding.clone();
+        let grow = self.grow.clone();
+        let shrink = self.shrink.clone();
+        let min_sizes = self.min_sizes.clone();
+        let max_sizes = self.max_sizes.clone();
+
+        let deps = [
+            bound.untyped(),
+            slot_children_sizes.untyped(),
+            direction.untyped(),
+            justify.untyped(),
+            align.untyped(),
+            wrap.untyped(),
+            gap.untyped(),
+            padding.untyped(),
+            grow.untyped(),
+            shrink.untyped(),
+            min_sizes.untyped(),
+            max_sizes.untyped(),
+        ];
+
+        //NOTE: replace with is needed since the for loop already has a connection to the prop
+        self._cell_specs.replace_with(Property::computed_with_name(
+            move || {
+                let bounds = bound.get();
+                let direction = direction.get();
+                let (main_axis, cross_axis) = match direction {
+                    StackerDirection::Horizontal => (Axis::X, Axis::Y),
+                    StackerDirection::Vertical => (Axis::Y, Axis::X),
+                };
+                let padding = padding.get();
+                let padding_main = padding.evaluate(bounds, main_axis);
+                let padding_cross = padding.evaluate(bounds, cross_axis);
+                let inner_main = (along(bounds, main_axis) - 2.0 * padding_main).max(0.0);
+                let inner_cross = (along(bounds, cross_axis) - 2.0 * padding_cross).max(0.0);
+                let inner_bounds = match direction {
+                    StackerDirection::Horizontal => (inner_main, inner_cross),
+                    StackerDirection::Vertical => (inner_cross, inner_main),
+                };
+
+                let grow = grow.get();
+                let shrink = shrink.get();
+                let min_sizes = min_sizes.get();
+                let max_sizes = max_sizes.get();
+                let sizes = slot_children_sizes.get();
+                let items = sizes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, size)| {
+                        let bound_at = |sizes: &Vec<Option<Size>>| {
+                            sizes
+                                .get(i)
+                                .cloned()
+                                .flatten()
+                                .map(|s| s.evaluate(inner_bounds, main_axis))
+                        };
+                        FlexItem {
+                            // children without a size fill the Flex, until shrunk
+                            main: size
+                                .along_within(inner_bounds, main_axis)
+                                .or(Some(inner_main)),
+                            cross: size.along_within(inner_bounds, cross_axis),
+                            grow: grow.get(i).copied().unwrap_or(0.0),
+                            shrink: shrink.get(i).copied().unwrap_or(1.0),
+                            min: bound_at(&min_sizes).unwrap_or(0.0),
+                            max: bound_at(&max_sizes).unwrap_or(f64::INFINITY),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let rects = layout_flex(
+                    &items,
+                    inner_main,
+                    inner_cross,
+                    gap.get().evaluate(bounds, main_axis),
+                    wrap.get(),
+                    justify.get(),
+                    align.get(),
+                );
+
+                rects
+                    .into_iter()
+                    .zip(sizes.iter())
+                    .map(|(rect, size)| {
+                        // Children sized in percentages are rendered relative to their cell,
+                        // so the cell is sized for them to span the laid out rect
+                        let main_size = size.container_for(rect.main_size, main_axis);
+                        let cross_size = size.container_for(rect.cross_size, cross_axis);
+                        match direction {
+                            StackerDirection::Horizontal => StackerCell {
+                                x_px: padding_main + rect.main_offset,
+                                y_px: padding_cross + rect.cross_offset,
+                                width_px: main_size,
+                                height_px: cross_size,
+                            },
+                            StackerDirection::Vertical => StackerCell {
+                                x_px: padding_cross + rect.cross_offset,
+                                y_px: padding_main + rect.main_offset,
+                                width_px: cross_size,
+                                height_px: main_size,
+                            },
+                        }
+                    })
+                    .collect()
+            },
+            &deps,
+            "flex _cell_specs",
+        ));
+    }
+}
+
+fn along(bounds: (f64, f64), axis: Axis) -> f64 {
+    match axis {
+        Axis::X => bounds.0,
+        Axis::Y => bounds.1,
+    }
+}
+
+/// A slot child as seen by the flex algorithm, in main / cross axis terms
+struct FlexItem {
+    /// Content size along the main axis, if known
+    main: Option<f64>,
+    /// Content size along the cross axis, if known
+    cross: Option<f64>,
+    grow: f64,
+    shrink: f64,
+    min: f64,
+    max: f64,
+}
+
+impl FlexItem {
+    fn clamp(&self, size: f64) -> f64 {
+        size.min(self.max).max(self.min)
+    }
+}
+
+/// A laid out cell, in main / cross axis terms relative to the padded content box
+#[derive(Debug, PartialEq)]
+struct FlexRect {
+    main_offset: f64,
+    cross_offset: f64,
+    main_size: f64,
+    cross_size: f64,
+}
+
+fn layout_flex(
+    items: &[FlexItem],
+    inner_main: f64,
+    inner_cross: f64,
+    gap: f64,
+    wrap: bool,
+    justify: FlexJustify,
+    align: FlexAlign,
+) -> Vec<FlexRect> {
+    let hypothetical: Vec<f64> = items
+        .iter()
+        .map(|item| item.clamp(item.main.unwrap_or(0.0)))
+        .collect();
+
+    // Break into lines: a line ends when the next cell (plus gap) would overflow it
+    let mut lines: Vec<std::ops::Range<usize>> = vec![];
+    let mut start = 0;
+    let mut used = 0.0;
+    for (i, size) in hypothetical.iter().enumerate() {
+        let needed = if i == start { *size } else { used + gap + size };
+        if wrap && i > start && needed > inner_main {
+            lines.push(start..i);
+            start = i;
+            used = *size;
+        } else {
+            used = needed;
+        }
+    }
+    if start < items.len() {
+        lines.push(start..items.len());
+    }
+
+    // Lines are as tall as their tallest known cell; lines without any known cross size
+    // split whatever cross space is left. A single line fills the cross axis.
+    let known_cross: Vec<Option<f64>> = lines
+        .iter()
+        .map(|line| {
+            items[line.clone()]
+                .iter()
+                .filter_map(|item| item.cross)
+                .reduce(f64::max)
+        })
+        .collect();
+    let line_cross: Vec<f64> = if lines.len() == 1 {
+        vec![inner_cross]
+    } else {
+        let unknown = known_cross.iter().filter(|c| c.is_none()).count();
+        let known: f64 = known_cross.iter().flatten().sum();
+        let gaps = gap * (lines.len() - 1) as f64;
+        let share = ((inner_cross - known - gaps) / unknown.max(1) as f64).max(0.0);
+        known_cross.iter().map(|c| c.unwrap_or(share)).collect()
+    };
+
+    let mut rects = Vec::with_capacity(items.len());
+    let mut cross_offset = 0.0;
+    for (line, cross_size) in lines.into_iter().zip(line_cross) {
+        let line_items = &items[line.clone()];
+        let sizes = resolve_flexible_lengths(line_items, &hypothetical[line], inner_main, gap);
+        let count = sizes.len() as f64;
+        let free = (inner_main - sizes.iter().sum::<f64>() - gap * (count - 1.0)).max(0.0);
+        let (leading, between) = match justify {
+            FlexJustify::Start => (0.0, 0.0),
+            FlexJustify::End => (free, 0.0),
+            FlexJustify::Center => (free / 2.0, 0.0),
+            FlexJustify::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+            FlexJustify::SpaceBetween => (0.0, 0.0),
+            FlexJustify::SpaceAround => (free / count / 2.0, free / count),
+            FlexJustify::SpaceEvenly => (free / (count + 1.0), free / (count + 1.0)),
+        };
+
+        let mut main_offset = leading;
+        for (item, main_size) in line_items.iter().zip(sizes) {
+            // without a known cross size, a cell can only take the size of its line
+            let item_cross = match (&align, item.cross) {
+                (FlexAlign::Stretch, _) | (_, None) => cross_size,
+                (_, Some(cross)) => cross,
+            };
+            let item_cross_offset = match align {
+                FlexAlign::Start | FlexAlign::Stretch => 0.0,
+                FlexAlign::End => cross_size - item_cross,
+                FlexAlign::Center => (cross_size - item_cross) / 2.0,
+            };
+            rects.push(FlexRect {
+                main_offset,
+                cross_offset: cross_offset + item_cross_offset,
+                main_size,
+                cross_size: item_cross,
+            });
+            main_offset += main_size + gap + between;
+        }
+        cross_offset += cross_size + gap;
+    }
+    rects
+}
+
+/// Grows (or shrinks) the cells of a line to fill `inner_main`, by their grow weights
+/// (or shrink weights scaled by size), freezing cells as they hit their min / max
+fn resolve_flexible_lengths(
+    items: &[FlexItem],
+    hypothetical: &[f64],
+    inner_main: f64,
+    gap: f64,
+) -> Vec<f64> {
+    let mut sizes = hypothetical.to_vec();
+    let mut frozen = vec![false; items.len()];
+    let gaps = gap * (items.len().max(1) - 1) as f64;
+    loop {
+        let free = inner_main - gaps - sizes.iter().sum::<f64>();
+        if free.abs() < f64::EPSILON {
+            break;
+        }
+        let weights: Vec<f64> = items
+            .iter()
+            .zip(&sizes)
+            .zip(&frozen)
+            .map(|((item, size), frozen)| match (frozen, free > 0.0) {
+                (true, _) => 0.0,
+                (false, true) => item.grow,
+                (false, false) => item.shrink * size,
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            break;
+        }
+        let mut clamped = false;
+        for (i, item) in items.iter().enumerate() {
+            if weights[i] == 0.0 {
+                continue;
+            }
+            let target = sizes[i] + free * weights[i] / total;
+            sizes[i] = item.clamp(target);
+            if sizes[i] != target {
+                frozen[i] = true;
+                clamped = true;
+            }
+        }
+        if !clamped {
+            break;
+        }
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(main: Option<f64>, cross: Option<f64>) -> FlexItem {
+        FlexItem {
+            main,
+            cross,
+            grow: 0.0,
+            shrink: 1.0,
+            min: 0.0,
+            max: f64::INFINITY,
+        }
+    }
+
+    fn main_sizes(rects: &[FlexRect]) -> Vec<(f64, f64)> {
+        rects.iter().map(|r| (r.main_offset, r.main_size)).collect()
+    }
+
+    #[test]
+    fn sizes_cells_to_content_and_justifies_leftover_space() {
+        let items = [item(Some(20.0), None), item(Some(30.0), None)];
+        let start = layout_flex(
+            &items,
+            100.0,
+            10.0,
+            10.0,
+            false,
+            FlexJustify::Start,
+            FlexAlign::Stretch,
+        );
+        assert_eq!(main_sizes(&start), vec![(0.0, 20.0), (30.0, 30.0)]);
+        assert_eq!(start[0].cross_size, 10.0);
+
+        let between = layout_flex(
+            &items,
+            100.0,
+            10.0,
+            10.0,
+            false,
+            FlexJustify::SpaceBetween,
+            FlexAlign::Stretch,
+        );
+        assert_eq!(main_sizes(&between), vec![(0.0, 20.0), (70.0, 30.0)]);
+    }
+
+    #[test]
+    fn grows_by_weight_up_to_max() {
+        let mut a = item(Some(10.0), None);
+        a.grow = 1.0;
+        a.max = 20.0;
+        let mut b = item(Some(10.0), None);
+        b.grow = 1.0;
+        let rects = layout_flex(
+            &[a, b],
+            100.0,
+            10.0,
+            0.0,
+            false,
+            FlexJustify::Start,
+            FlexAlign::Stretch,
+        );
+        assert_eq!(main_sizes(&rects), vec![(0.0, 20.0), (20.0, 80.0)]);
+    }
+
+    #[test]
+    fn shrinks_overflowing_cells_down_to_min() {
+        let mut a = item(Some(100.0), None);
+        a.min = 90.0;
+        let b = item(Some(100.0), None);
+        let rects = layout_flex(
+            &[a, b],
+            150.0,
+            10.0,
+            0.0,
+            false,
+            FlexJustify::Start,
+            FlexAlign::Stretch,
+        );
+        assert_eq!(main_sizes(&rects), vec![(0.0, 90.0), (90.0, 60.0)]);
+    }
+
+    #[test]
+    fn only_lines_without_known_cross_sizes_share_leftover_space() {
+        let items = [
+            item(Some(60.0), Some(0.0)),
+            item(Some(60.0), None),
+            item(Some(60.0), Some(20.0)),
+        ];
+        let rects = layout_flex(
+            &items,
+            100.0,
+            100.0,
+            10.0,
+            true,
+            FlexJustify::Start,
+            FlexAlign::Stretch,
+        );
+        let cross: Vec<(f64, f64)> = rects
+            .iter()
+            .map(|r| (r.cross_offset, r.cross_size))
+            .collect();
+        assert_eq!(cross, vec![(0.0, 0.0), (10.0, 60.0), (80.0, 20.0)]);
+    }
+
+    #[test]
+    fn wraps_onto_lines_and_aligns_on_cross_axis() {
+        let items = [
+            item(Some(40.0), Some(10.0)),
+            item(Some(40.0), Some(20.0)),
+            item(Some(40.0), Some(10.0)),
+        ];
+        let rects = layout_flex(
+            &items,
+            100.0,
+            100.0,
+            10.0,
+            true,
+            FlexJustify::Start,
+            FlexAlign::Center,
+        );
+        assert_eq!(
+            rects,
+            vec![
+                FlexRect {
+                    main_offset: 0.0,
+                    cross_offset: 5.0,
+                    main_size: 40.0,
+                    cross_size: 10.0
+                },
+                FlexRect {
+                    main_offset: 50.0,
+                    cross_offset: 0.0,
+                    main_size: 40.0,
+                    cross_size: 20.0
+                },
+                FlexRect {
+                    main_offset: 0.0,
+                    cross_offset: 30.0,
+                    main_size: 40.0,
+                    cross_size: 10.0
+                },
+            ]
+        );
+    }
+}
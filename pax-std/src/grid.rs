@@ -0,0 +1,486 @@
+use std::collections::HashSet;
+
+use crate::primitives::*;
+use crate::types::{GridTrack, StackerCell};
+use pax_engine::api::Numeric;
+use pax_engine::api::{Property, Size, Transform2D};
+use pax_engine::*;
+use pax_runtime::api::{Axis, NodeContext};
+use pax_runtime::GridPlacement;
+
+/// Grid lays out its slot children on a two-dimensional grid of row and column tracks,
+/// each sized in pixels, percent, fractions of the leftover space (`fr`) or to fit its
+/// content (auto).  Children are placed by their `grid_row` / `grid_column` / span common
+/// properties, or by naming one of the grid's `areas` in `grid_area`; unplaced children
+/// fill the first free spot, row by row.
+#[pax]
+#[custom(Default)]
+#[inlined(
+    for (cell_spec, i) in self._cell_specs {
+        <Group
+            transform={Transform2D::translate((cell_spec.x_px)px, (cell_spec.y_px)px)}
+            width={(cell_spec.width_px)px}
+            height={(cell_spec.height_px)px}
+        >
+            slot(i)
+        </Group>
+    }
+
+    @settings {
+        @mount: on_mount
+    }
+
+)]
+pub struct Grid {
+    pub rows: Property<Vec<GridTrack>>,
+    pub columns: Property<Vec<GridTrack>>,
+    pub row_gap: Property<Size>,
+    pub column_gap: Property<Size>,
+    /// Named areas, one string per row with one whitespace-separated name per column,
+    /// e.g. `["header header", "nav main"]`.  Use `.` for columns that belong to no area.
+    pub areas: Property<Vec<String>>,
+
+    pub _cell_specs: Property<Vec<StackerCell>>,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            rows: Property::new(vec![]),
+            columns: Property::new(vec![]),
+            row_gap: Property::new(Size::Pixels(Numeric::I32(0))),
+            column_gap: Property::new(Size::Pixels(Numeric::I32(0))),
+            areas: Property::new(vec![]),
+            _cell_specs: Property::new(vec![]),
+        }
+    }
+}
+
+impl Grid {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let bound = ctx.bounds_self.clone();
+        let slot_children_sizes = ctx.slot_children_sizes.clone();
+        let slot_children_grid_placements = ctx.slot_children_grid_placements.clone();
+        let rows = self.rows.clone();
+        let columns = self.columns.clone();
+        let row_gap = self.row_gap.clone();
+        let column_gap = self.column_gap.clone();
+        let areas = self.areas.clone();
+
+        let deps = [
+            bound.untyped(),
+            slot_children_sizes.untyped(),
+            slot_children_grid_placements.untyped(),
+            rows.untyped(),
+            columns.untyped(),
+            row_gap.untyped(),
+            column_gap.untyped(),
+            areas.untyped(),
+        ];
+        self._cell_specs.replace_with(Property::computed_with_name(
+            move || {
+                let bounds = bound.get();
+                let items: Vec<GridItem> = slot_children_sizes
+                    .get()
+                    .iter()
+                    .zip(slot_children_grid_placements.get())
+                    .map(|(size, placement)| GridItem {
+                        width: size.along(Axis::X),
+                        height: size.along(Axis::Y),
+                        ..placement.into()
+                    })
+                    .collect();
+                layout_grid(
+                    &items,
+                    &rows.get(),
+                    &columns.get(),
+                    &areas.get(),
+                    bounds,
+                    row_gap.get().evaluate(bounds, Axis::Y),
+                    column_gap.get().evaluate(bounds, Axis::X),
+                )
+            },
+            &deps,
+            "grid cells",
+        ));
+    }
+}
+
+/// A slot child, as placed on the grid
+#[derive(Debug, Clone, PartialEq)]
+struct GridItem {
+    /// 1-based, 0 to auto-place
+    row: usize,
+    /// 1-based, 0 to auto-place
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    area: String,
+    /// Content size, if known
+    width: Option<f64>,
+    height: Option<f64>,
+}
+
+impl Default for GridItem {
+    fn default() -> Self {
+        Self {
+            row: 0,
+            column: 0,
+            row_span: 1,
+            column_span: 1,
+            area: String::new(),
+            width: None,
+            height: None,
+        }
+    }
+}
+
+impl From<GridPlacement> for GridItem {
+    fn from(placement: GridPlacement) -> Self {
+        Self {
+            row: placement.row.unwrap_or(0),
+            column: placement.column.unwrap_or(0),
+            row_span: placement.row_span.unwrap_or(1),
+            column_span: placement.column_span.unwrap_or(1),
+            area: placement.area.unwrap_or_default(),
+            width: None,
+            height: None,
+        }
+    }
+}
+
+/// The tracks a cell covers, 0-based
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Placement {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+}
+
+fn layout_grid(
+    items: &[GridItem],
+    rows: &[GridTrack],
+    columns: &[GridTrack],
+    areas: &[String],
+    bounds: (f64, f64),
+    row_gap: f64,
+    column_gap: f64,
+) -> Vec<StackerCell> {
+    let area_columns = areas
+        .iter()
+        .map(|row| row.split_whitespace().count())
+        .max()
+        .unwrap_or(0);
+    let placements = place_items(items, areas, columns.len().max(area_columns).max(1));
+
+    let row_count = placements
+        .iter()
+        .map(|p| p.row + p.row_span)
+        .chain([rows.len(), areas.len()])
+        .max()
+        .unwrap_or(0);
+    let column_count = placements
+        .iter()
+        .map(|p| p.column + p.column_span)
+        .chain([columns.len(), area_columns])
+        .max()
+        .unwrap_or(0);
+
+    let row_sizes = size_tracks(
+        rows,
+        row_count,
+        bounds.1,
+        row_gap,
+        &items
+            .iter()
+            .zip(&placements)
+            .map(|(item, p)| (p.row, p.row_span, item.height))
+            .collect::<Vec<_>>(),
+    );
+    let column_sizes = size_tracks(
+        columns,
+        column_count,
+        bounds.0,
+        column_gap,
+        &items
+            .iter()
+            .zip(&placements)
+            .map(|(item, p)| (p.column, p.column_span, item.width))
+            .collect::<Vec<_>>(),
+    );
+
+    let extent = |sizes: &[f64], gap: f64, start: usize, span: usize| {
+        let offset: f64 = sizes[..start].iter().map(|s| s + gap).sum();
+        let length = sizes[start..start + span].iter().sum::<f64>() + gap * (span - 1) as f64;
+        (offset, length)
+    };
+    placements
+        .iter()
+        .map(|p| {
+            let (y_px, height_px) = extent(&row_sizes, row_gap, p.row, p.row_span);
+            let (x_px, width_px) = extent(&column_sizes, column_gap, p.column, p.column_span);
+            StackerCell {
+                x_px,
+                y_px,
+                width_px,
+                height_px,
+            }
+        })
+        .collect()
+}
+
+/// Resolves every item to the tracks it covers: named areas and fully specified cells
+/// first, then the rest into the first free spot, row by row, within `column_count`
+fn place_items(items: &[GridItem], areas: &[String], column_count: usize) -> Vec<Placement> {
+    let mut occupied = HashSet::new();
+    let occupy = |occupied: &mut HashSet<(usize, usize)>, p: &Placement| {
+        for r in p.row..p.row + p.row_span {
+            for c in p.column..p.column + p.column_span {
+                occupied.insert((r, c));
+            }
+        }
+    };
+
+    let mut placements: Vec<Option<Placement>> = items
+        .iter()
+        .map(|item| {
+            let placement = find_area(areas, &item.area).or_else(|| {
+                (item.row > 0 && item.column > 0).then(|| Placement {
+                    row: item.row - 1,
+                    column: item.column - 1,
+                    row_span: item.row_span.max(1),
+                    column_span: item.column_span.max(1),
+                })
+            });
+            if let Some(p) = &placement {
+                occupy(&mut occupied, p);
+            }
+            placement
+        })
+        .collect();
+
+    for (item, placement) in items.iter().zip(placements.iter_mut()) {
+        if placement.is_some() {
+            continue;
+        }
+        let (row_span, column_span) = (item.row_span.max(1), item.column_span.max(1));
+        let free = |occupied: &HashSet<(usize, usize)>, row: usize, column: usize| {
+            (row..row + row_span)
+                .all(|r| (column..column + column_span).all(|c| !occupied.contains(&(r, c))))
+        };
+        let (row, column) = match (item.row.checked_sub(1), item.column.checked_sub(1)) {
+            (Some(row), _) => (row, (0..).find(|&c| free(&occupied, row, c)).unwrap()),
+            (None, Some(column)) => ((0..).find(|&r| free(&occupied, r, column)).unwrap(), column),
+            (None, None) => {
+                let last_column = column_count.max(column_span) - column_span;
+                (0..)
+                    .flat_map(|r| (0..=last_column).map(move |c| (r, c)))
+                    .find(|&(r, c)| free(&occupied, r, c))
+                    .unwrap()
+            }
+        };
+        let p = Placement {
+            row,
+            column,
+            row_span,
+            column_span,
+        };
+        occupy(&mut occupied, &p);
+        *placement = Some(p);
+    }
+    placements.into_iter().map(Option::unwrap).collect()
+}
+
+/// The bounding box of all occurrences of `name` in `areas`, if any
+fn find_area(areas: &[String], name: &str) -> Option<Placement> {
+    if name.is_empty() || name == "." {
+        return None;
+    }
+    let cells: Vec<(usize, usize)> = areas
+        .iter()
+        .enumerate()
+        .flat_map(|(r, row)| {
+            row.split_whitespace()
+                .enumerate()
+                .filter(|(_, n)| *n == name)
+                .map(move |(c, _)| (r, c))
+        })
+        .collect();
+    let first_row = cells.iter().map(|(r, _)| *r).min()?;
+    let last_row = cells.iter().map(|(r, _)| *r).max()?;
+    let first_column = cells.iter().map(|(_, c)| *c).min()?;
+    let last_column = cells.iter().map(|(_, c)| *c).max()?;
+    Some(Placement {
+        row: first_row,
+        column: first_column,
+        row_span: last_row - first_row + 1,
+        column_span: last_column - first_column + 1,
+    })
+}
+
+/// Sizes `count` tracks (beyond `tracks`, implicit tracks are auto) given the
+/// (start, span, content size) of the cells placed along this axis
+fn size_tracks(
+    tracks: &[GridTrack],
+    count: usize,
+    available: f64,
+    gap: f64,
+    cells: &[(usize, usize, Option<f64>)],
+) -> Vec<f64> {
+    let track = |i: usize| tracks.get(i).cloned().unwrap_or_default();
+    let is_auto = |i: usize| matches!(track(i), GridTrack::Auto);
+    let mut sizes: Vec<f64> = (0..count)
+        .map(|i| match track(i) {
+            GridTrack::Px(px) => px,
+            GridTrack::Percent(percent) => available * percent / 100.0,
+            GridTrack::Fr(_) | GridTrack::Auto => 0.0,
+        })
+        .collect();
+
+    // Auto tracks fit their single-track cells, then grow evenly to fit cells spanning them
+    for &(start, _, content) in cells.iter().filter(|(_, span, _)| *span == 1) {
+        if let (true, Some(content)) = (is_auto(start), content) {
+            sizes[start] = sizes[start].max(content);
+        }
+    }
+    for &(start, span, content) in cells.iter().filter(|(_, span, _)| *span > 1) {
+        let Some(content) = content else {
+            continue;
+        };
+        let autos: Vec<usize> = (start..start + span).filter(|&i| is_auto(i)).collect();
+        let current = sizes[start..start + span].iter().sum::<f64>() + gap * (span - 1) as f64;
+        if !autos.is_empty() && content > current {
+            let extra = (content - current) / autos.len() as f64;
+            for i in autos {
+                sizes[i] += extra;
+            }
+        }
+    }
+
+    let gaps = gap * count.saturating_sub(1) as f64;
+    let leftover = (available - gaps - sizes.iter().sum::<f64>()).max(0.0);
+    let fractions: Vec<(usize, f64)> = (0..count)
+        .filter_map(|i| match track(i) {
+            GridTrack::Fr(fr) => Some((i, fr)),
+            _ => None,
+        })
+        .collect();
+    let total_fr: f64 = fractions.iter().map(|(_, fr)| fr).sum();
+    if total_fr > 0.0 {
+        for (i, fr) in fractions {
+            sizes[i] = leftover * fr / total_fr;
+        }
+    } else {
+        let autos: Vec<usize> = (0..count).filter(|&i| is_auto(i)).collect();
+        for &i in &autos {
+            sizes[i] += leftover / autos.len() as f64;
+        }
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(row: usize, column: usize) -> GridItem {
+        GridItem {
+            row,
+            column,
+            ..Default::default()
+        }
+    }
+
+    fn rects(cells: &[StackerCell]) -> Vec<(f64, f64, f64, f64)> {
+        cells
+            .iter()
+            .map(|c| (c.x_px, c.y_px, c.width_px, c.height_px))
+            .collect()
+    }
+
+    #[test]
+    fn sizes_fixed_fractional_and_auto_tracks() {
+        let mut content = item(1, 2);
+        content.width = Some(30.0);
+        let cells = layout_grid(
+            &[item(1, 1), content, item(1, 3), item(1, 4)],
+            &[GridTrack::Px(20.0)],
+            &[
+                GridTrack::Px(10.0),
+                GridTrack::Auto,
+                GridTrack::Percent(20.0),
+                GridTrack::Fr(1.0),
+            ],
+            &[],
+            (200.0, 100.0),
+            0.0,
+            10.0,
+        );
+        assert_eq!(
+            rects(&cells),
+            vec![
+                (0.0, 0.0, 10.0, 20.0),
+                (20.0, 0.0, 30.0, 20.0),
+                (60.0, 0.0, 40.0, 20.0),
+                (110.0, 0.0, 90.0, 20.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn auto_places_around_spanning_cells() {
+        let mut wide = item(1, 1);
+        wide.column_span = 2;
+        let cells = layout_grid(
+            &[wide, item(0, 0), item(0, 0), item(0, 2)],
+            &[
+                GridTrack::Px(10.0),
+                GridTrack::Px(10.0),
+                GridTrack::Px(10.0),
+            ],
+            &[GridTrack::Fr(1.0), GridTrack::Fr(1.0), GridTrack::Fr(1.0)],
+            &[],
+            (30.0, 30.0),
+            0.0,
+            0.0,
+        );
+        assert_eq!(
+            rects(&cells),
+            vec![
+                (0.0, 0.0, 20.0, 10.0),
+                (20.0, 0.0, 10.0, 10.0),
+                (0.0, 10.0, 10.0, 10.0),
+                (10.0, 10.0, 10.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn places_cells_by_named_area() {
+        let areas = ["header header".to_string(), "nav main".to_string()];
+        let mut header = item(0, 0);
+        header.area = "header".to_string();
+        let mut main = item(0, 0);
+        main.area = "main".to_string();
+        let cells = layout_grid(
+            &[main, header],
+            &[GridTrack::Px(10.0), GridTrack::Fr(1.0)],
+            &[GridTrack::Px(20.0), GridTrack::Fr(1.0)],
+            &areas,
+            (100.0, 100.0),
+            5.0,
+            5.0,
+        );
+        assert_eq!(
+            rects(&cells),
+            vec![(25.0, 15.0, 75.0, 85.0), (0.0, 0.0, 100.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn spanning_content_grows_auto_tracks_evenly() {
+        let sizes = size_tracks(&[], 2, 0.0, 10.0, &[(0, 1, Some(20.0)), (0, 2, Some(50.0))]);
+        assert_eq!(sizes, vec![30.0, 10.0]);
+    }
+}
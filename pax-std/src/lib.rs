@@ -1,11 +1,17 @@
 pub mod types;
 
+#[allow(unused_imports)]
+pub mod flex;
+#[allow(unused_imports)]
+pub mod grid;
 #[allow(unused_imports)]
 pub mod scroller;
 #[allow(unused_imports)]
 pub mod stacker;
 
 pub mod components {
+    pub use super::flex::*;
+    pub use super::grid::*;
     pub use super::scroller::*;
     pub use super::stacker::*;
 }
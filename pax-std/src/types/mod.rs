@@ -22,6 +22,43 @@ pub enum StackerDirection {
     Horizontal,
 }
 
+/// How a `Flex` distributes leftover space along its main axis
+#[pax]
+pub enum FlexJustify {
+    #[default]
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+/// How a `Flex` places each cell along its cross axis, within the cell's line
+#[pax]
+pub enum FlexAlign {
+    Start,
+    End,
+    Center,
+    #[default]
+    Stretch,
+}
+
+/// The size of a row or column of a `Grid`
+#[pax]
+pub enum GridTrack {
+    /// A fixed size in pixels
+    Px(f64),
+    /// A percentage of the grid's width (columns) or height (rows)
+    Percent(f64),
+    /// A share of the space left over once all other tracks are sized
+    Fr(f64),
+    /// Sized to the largest cell placed in this track; auto tracks stretch to
+    /// fill leftover space when there are no `Fr` tracks
+    #[default]
+    Auto,
+}
+
 #[pax]
 pub enum SidebarDirection {
     Left,
@@ -41,6 +41,7 @@ PACKAGES = [
     "pax-std",
     "pax-std/pax-std-primitives",
     "pax-lang",
+    "pax-paxel-interp",
 ]
 
 # Compile ts to js and css for the web chassi